@@ -0,0 +1,98 @@
+//! Benchmarks for the relationship resolution phase
+//!
+//! Indexes a generated project without resolving it, then times
+//! `resolve_cross_file_relationships` on one thread and on every core.
+
+use codanna::SimpleIndexer;
+use codanna::config::Settings;
+use criterion::{BatchSize, BenchmarkId, Criterion, criterion_group, criterion_main};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tempfile::TempDir;
+
+const MODULES: usize = 60;
+
+/// Rust modules that call functions and methods of the next module
+fn write_project(root: &Path) -> Vec<PathBuf> {
+    let src = root.join("src");
+    fs::create_dir_all(&src).unwrap();
+
+    let mut lib = String::new();
+    let mut files = vec![src.join("lib.rs")];
+    for n in 0..MODULES {
+        let next = (n + 1) % MODULES;
+        lib.push_str(&format!("pub mod module_{n};\n"));
+        let source = format!(
+            "use crate::module_{next}::{{helper_{next}, Widget{next}}};\n\n\
+             pub struct Widget{n};\n\n\
+             impl Widget{n} {{\n    \
+                 pub fn new() -> Self {{ Widget{n} }}\n    \
+                 pub fn render(&self) -> usize {{ helper_{n}() }}\n\
+             }}\n\n\
+             pub fn helper_{n}() -> usize {{ {n} }}\n\n\
+             pub fn run_{n}() -> usize {{\n    \
+                 let widget = Widget{next}::new();\n    \
+                 widget.render() + helper_{next}()\n\
+             }}\n"
+        );
+        let path = src.join(format!("module_{n}.rs"));
+        fs::write(&path, source).unwrap();
+        files.push(path);
+    }
+    fs::write(src.join("lib.rs"), lib).unwrap();
+    files
+}
+
+/// A fresh index of `files` with every relationship still pending
+fn indexed_without_resolution(
+    root: &Path,
+    files: &[PathBuf],
+    threads: usize,
+) -> (TempDir, SimpleIndexer) {
+    let index_dir = TempDir::new().unwrap();
+    let mut settings = Settings {
+        index_path: index_dir.path().to_path_buf(),
+        workspace_root: Some(root.to_path_buf()),
+        ..Settings::default()
+    };
+    settings.indexing.parallel_threads = threads;
+
+    let mut indexer = SimpleIndexer::with_settings(Arc::new(settings));
+    for file in files {
+        indexer.index_file_no_resolve(file).unwrap();
+    }
+    (index_dir, indexer)
+}
+
+fn bench_resolution(c: &mut Criterion) {
+    let project = TempDir::new().unwrap();
+    let files = write_project(project.path());
+    let cores = std::thread::available_parallelism().map_or(1, usize::from);
+
+    let mut group = c.benchmark_group("resolve_cross_file_relationships");
+    group.sample_size(10);
+    let mut thread_counts = vec![1, cores];
+    thread_counts.dedup();
+    for threads in thread_counts {
+        group.bench_with_input(
+            BenchmarkId::new("threads", threads),
+            &threads,
+            |b, &threads| {
+                b.iter_batched(
+                    || indexed_without_resolution(project.path(), &files, threads),
+                    |(index_dir, mut indexer)| {
+                        indexer.resolve_cross_file_relationships().unwrap();
+                        // Dropped after the measurement
+                        (index_dir, indexer)
+                    },
+                    BatchSize::PerIteration,
+                );
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_resolution);
+criterion_main!(benches);
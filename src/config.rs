@@ -110,6 +110,14 @@ pub struct Settings {
     #[serde(default)]
     pub server: ServerConfig,
 
+    /// 依存ライブラリのインデックス設定
+    #[serde(default)]
+    pub dependencies: DependenciesConfig,
+
+    /// クエリデーモン設定
+    #[serde(default)]
+    pub daemon: DaemonConfig,
+
     /// マルチホップクエリ用のAIガイダンス設定
     #[serde(default)]
     pub guidance: GuidanceConfig,
@@ -143,6 +151,19 @@ pub struct IndexingConfig {
     #[serde(default = "default_max_retry_attempts")]
     pub max_retry_attempts: u32,
 
+    /// 別プロセスがインデックスに書き込み中のとき、書き込みロックを待つ秒数
+    /// 0 の場合は待たずにエラーで終了
+    #[serde(default = "default_lock_timeout_secs")]
+    pub lock_timeout_secs: u64,
+
+    /// 解決待ちの関係を保持するメモリの目安（メガバイト単位）
+    /// ソフトリミット: 100ファイルのバッチが終わるたびに推定サイズを確認し、
+    /// 超えていればディスクへ退避して、全ファイルの処理後に読み戻して解決する
+    /// バッチの途中では上限を超えることがあり、型ごとのメソッド表は推定に含まない
+    /// 0 の場合は上限なし
+    #[serde(default)]
+    pub memory_limit_mb: usize,
+
     /// プロジェクトルートディレクトリ（デフォルトはワークスペースルート）
     /// gitignore解決とモジュールパス計算に使用
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -296,6 +317,53 @@ pub struct ServerConfig {
     pub watch_interval: u64,
 }
 
+/// 依存ライブラリ設定
+///
+/// `codanna deps index` が対象とするエコシステムを保持します。
+/// 依存ライブラリは読み取り専用の別レイヤーにインデックスされ、
+/// プロジェクト内で見つからないシンボルの検索時にのみ参照されます。
+///
+/// # 使用例
+///
+/// ```
+/// use codanna::config::DependenciesConfig;
+///
+/// let config = DependenciesConfig::default();
+/// assert!(config.ecosystems.contains(&"cargo".to_string()));
+/// ```
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct DependenciesConfig {
+    /// 対象エコシステム: "cargo", "npm", "python", "go"
+    #[serde(default = "default_dependency_ecosystems")]
+    pub ecosystems: Vec<String>,
+}
+
+/// クエリデーモン設定
+///
+/// `codanna retrieve` が利用するバックグラウンドデーモンの設定を保持します。
+/// デーモンはインデックスを一度だけ読み込んで保持し、`.codanna/daemon.sock`
+/// 経由でクエリに応答します。起動中のデーモンはこの設定に関係なく利用されます。
+///
+/// # 使用例
+///
+/// ```
+/// use codanna::config::DaemonConfig;
+///
+/// let config = DaemonConfig::default();
+/// assert!(!config.enabled);
+/// assert_eq!(config.idle_timeout_secs, 600);
+/// ```
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct DaemonConfig {
+    /// デーモンが見つからない場合にバックグラウンドで自動起動する
+    #[serde(default = "default_false")]
+    pub enabled: bool,
+
+    /// この秒数だけリクエストがなければデーモンを終了する（0で無効、デフォルト: 600）
+    #[serde(default = "default_daemon_idle_timeout_secs")]
+    pub idle_timeout_secs: u64,
+}
+
 /// AIガイダンス設定
 ///
 /// マルチホップクエリのためのAIガイダンスシステムの設定を保持します。
@@ -377,6 +445,9 @@ fn default_tantivy_heap_mb() -> usize {
 fn default_max_retry_attempts() -> u32 {
     3 // Exponential backoff: 100ms, 200ms, 400ms
 }
+fn default_lock_timeout_secs() -> u64 {
+    30
+}
 fn default_true() -> bool {
     true
 }
@@ -404,6 +475,17 @@ fn default_bind_address() -> String {
 fn default_watch_interval() -> u64 {
     5
 }
+fn default_daemon_idle_timeout_secs() -> u64 {
+    600
+}
+fn default_dependency_ecosystems() -> Vec<String> {
+    vec![
+        "cargo".to_string(),
+        "npm".to_string(),
+        "python".to_string(),
+        "go".to_string(),
+    ]
+}
 
 impl Default for Settings {
     fn default() -> Self {
@@ -419,6 +501,8 @@ impl Default for Settings {
             semantic_search: SemanticSearchConfig::default(),
             file_watch: FileWatchConfig::default(),
             server: ServerConfig::default(),
+            dependencies: DependenciesConfig::default(),
+            daemon: DaemonConfig::default(),
             guidance: GuidanceConfig::default(),
         }
     }
@@ -430,6 +514,8 @@ impl Default for IndexingConfig {
             parallel_threads: default_parallel_threads(),
             tantivy_heap_mb: default_tantivy_heap_mb(),
             max_retry_attempts: default_max_retry_attempts(),
+            lock_timeout_secs: default_lock_timeout_secs(),
+            memory_limit_mb: 0,
             project_root: None,
             ignore_patterns: vec![
                 "target/**".to_string(),
@@ -470,6 +556,23 @@ impl Default for FileWatchConfig {
     }
 }
 
+impl Default for DaemonConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            idle_timeout_secs: default_daemon_idle_timeout_secs(),
+        }
+    }
+}
+
+impl Default for DependenciesConfig {
+    fn default() -> Self {
+        Self {
+            ecosystems: default_dependency_ecosystems(),
+        }
+    }
+}
+
 impl Default for ServerConfig {
     fn default() -> Self {
         Self {
@@ -907,6 +1010,14 @@ impl Settings {
             } else if line.starts_with("max_retry_attempts = ") {
                 result.push_str("\n# Retry attempts for transient file system errors\n");
                 result.push_str("# Exponential backoff: 100ms, 200ms, 400ms delays\n");
+            } else if line.starts_with("lock_timeout_secs = ") {
+                result.push_str(
+                    "\n# Seconds to wait while another codanna process writes the index (0 = fail at once)\n",
+                );
+            } else if line.starts_with("memory_limit_mb = ") {
+                result.push_str(
+                    "\n# Megabytes of relationships held for resolution before they spill to disk (0 = no limit)\n",
+                );
             } else if line.starts_with("ignore_patterns = ") {
                 result.push_str("\n# Additional patterns to ignore during indexing\n");
             } else if line.starts_with("indexed_paths = ") {
@@ -978,10 +1089,28 @@ impl Settings {
                 result.push_str("\n# HTTP server bind address (only used when mode = \"http\" or --http flag)\n");
             } else if line.starts_with("watch_interval = ") {
                 result.push_str("\n# Watch interval for stdio mode in seconds (how often to check for file changes)\n");
+            } else if line == "[dependencies]" {
+                result.push_str("\n[dependencies]\n");
+                result.push_str("# Ecosystems indexed by `codanna deps index` into a separate read-only layer\n");
+                result.push_str(
+                    "# Library symbols are only used when a lookup finds nothing in the project\n",
+                );
+                result.push_str("# Supported: \"cargo\", \"npm\", \"python\", \"go\"\n");
+                prev_line_was_section = true;
+                continue;
+            } else if line == "[daemon]" {
+                result.push_str("\n[daemon]\n");
+                result.push_str("# Keep the index loaded in a background process for `codanna retrieve`\n");
+                result.push_str("# When enabled, a query that finds no daemon starts one for the next call\n");
+                result.push_str("# A running daemon is used either way; `codanna daemon stop` shuts it down\n");
+                prev_line_was_section = true;
+                continue;
+            } else if line.starts_with("idle_timeout_secs = ") {
+                result.push_str("\n# Seconds without queries before the daemon exits (0 keeps it running)\n");
             } else if line.starts_with("[languages.") {
                 if !in_languages_section {
                     result.push_str("\n# Language-specific settings\n");
-                    result.push_str("# Currently supported: Rust, Python, PHP, TypeScript, Go, C, C++, CSharp, Gdscript\n");
+                    result.push_str("# Currently supported: Rust, Python, PHP, TypeScript, Go, C, C++, CSharp, Gdscript, Protobuf\n");
                     in_languages_section = true;
                 }
                 result.push('\n');
@@ -0,0 +1,555 @@
+//! Background daemon that keeps the index loaded between `codanna retrieve` calls
+//!
+//! Every `codanna retrieve` invocation loads Tantivy, the symbol cache and, with
+//! semantic search enabled, the embeddings before it answers a single query.
+//! Scripts and slash commands that run many queries in a row spend most of
+//! their time there. The daemon loads the index once, listens on
+//! `.codanna/daemon.sock` and answers each query with the output and exit code
+//! the command would have produced in-process.
+//!
+//! The CLI forwards a query only when a daemon answers on the socket and loads
+//! the index itself otherwise, so a missing, outdated or crashed daemon never
+//! makes a query fail. With `[daemon] enabled = true`, a query that found no
+//! daemon starts one in the background for the next call. The daemon reloads
+//! the index after a writer saves it, exits when `settings.toml` or the
+//! `codanna` version changes, and shuts down after `idle_timeout_secs` without
+//! requests.
+
+use crate::config::Settings;
+use crate::indexing::IndexWriteLock;
+use crate::storage::IndexPersistence;
+use crate::{IndexResult, SimpleIndexer};
+use fs4::fs_std::FileExt;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, Write};
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, mpsc};
+use std::time::{Duration, Instant, SystemTime};
+
+/// File name of the socket, next to the index directory
+pub const SOCKET_FILE: &str = "daemon.sock";
+
+/// Lock held by the daemon that owns the socket
+const LOCK_FILE: &str = "daemon.lock";
+
+/// Version a client and the daemon must share to talk to each other
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// How long the daemon waits for a connected client to send its request
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Message sent by a client, one per connection
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "request", rename_all = "snake_case")]
+pub enum Request {
+    /// Run `codanna <args>` against the loaded index from `cwd`
+    Query {
+        version: String,
+        cwd: PathBuf,
+        args: Vec<String>,
+    },
+    Status,
+    Stop,
+}
+
+/// Answer of the daemon to a [`Request`]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "response", rename_all = "snake_case")]
+pub enum Response {
+    Output {
+        stdout: String,
+        stderr: String,
+        exit_code: i32,
+    },
+    Status(DaemonStatus),
+    Stopping,
+    /// The daemon cannot answer; the client runs the query itself
+    Rejected {
+        reason: String,
+    },
+}
+
+/// State reported by `codanna daemon status`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DaemonStatus {
+    pub pid: u32,
+    pub version: String,
+    pub index_path: PathBuf,
+    /// Symbols in the loaded index, `None` before an index exists
+    pub symbols: Option<usize>,
+    pub requests: u64,
+    pub uptime_secs: u64,
+    pub idle_timeout_secs: u64,
+}
+
+/// Socket of the daemon serving the index at `index_path`
+///
+/// The default index at `.codanna/index` gives `.codanna/daemon.sock`.
+pub fn socket_path(index_path: &Path) -> PathBuf {
+    match index_path.parent() {
+        Some(parent) => parent.join(SOCKET_FILE),
+        None => PathBuf::from(SOCKET_FILE),
+    }
+}
+
+/// Send one request and wait for its response
+pub fn request(
+    socket: &Path,
+    request: &Request,
+    timeout: Option<Duration>,
+) -> io::Result<Response> {
+    let mut stream = UnixStream::connect(socket)?;
+    stream.set_read_timeout(timeout)?;
+    serde_json::to_writer(&mut stream, request)?;
+    stream.shutdown(std::net::Shutdown::Write)?;
+    Ok(serde_json::from_reader(&stream)?)
+}
+
+/// Status of the daemon listening on `socket`, if one answers
+pub fn status(socket: &Path) -> Option<DaemonStatus> {
+    match request(socket, &Request::Status, Some(READ_TIMEOUT)) {
+        Ok(Response::Status(status)) => Some(status),
+        _ => None,
+    }
+}
+
+/// Run `codanna <args>` through the daemon and copy its output to ours
+///
+/// Returns the exit code of the query, or `None` when no daemon answered and
+/// the caller has to run the query in-process.
+pub fn forward_query(socket: &Path, args: &[String]) -> Option<i32> {
+    let query = Request::Query {
+        version: VERSION.to_string(),
+        cwd: std::env::current_dir().ok()?,
+        args: args.to_vec(),
+    };
+    match request(socket, &query, None) {
+        Ok(Response::Output {
+            stdout,
+            stderr,
+            exit_code,
+        }) => {
+            // A closed pipe on our side (`| head`) is not a failed query
+            let _ = io::stderr().write_all(stderr.as_bytes());
+            let _ = io::stdout().write_all(stdout.as_bytes());
+            let _ = io::stdout().flush();
+            Some(exit_code)
+        }
+        _ => None,
+    }
+}
+
+/// Start `codanna daemon run` detached from the calling terminal
+pub fn spawn(config_file: Option<&Path>) -> io::Result<()> {
+    let mut command = Command::new(std::env::current_exe()?);
+    if let Some(path) = config_file {
+        command.arg("--config").arg(path);
+    }
+    command
+        .args(["daemon", "run"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        // Own process group, so Ctrl-C in the caller's terminal leaves it running
+        .process_group(0)
+        .spawn()?;
+    Ok(())
+}
+
+/// Serve queries on the socket of `settings.index_path` until idle or stopped
+///
+/// `run_query` runs the parsed arguments of one `codanna` invocation against the
+/// loaded index and returns its exit code; whatever it writes to stdout and
+/// stderr is sent back to the client. `settings_file` is the configuration the
+/// settings were loaded from; the daemon exits once it changes. Returns
+/// `Ok(false)` when another daemon already serves the socket.
+pub fn serve<F>(
+    settings: Arc<Settings>,
+    settings_file: Option<PathBuf>,
+    mut run_query: F,
+) -> io::Result<bool>
+where
+    F: FnMut(&SimpleIndexer, &[String]) -> i32,
+{
+    // Queries run from the client's directory, so the index path must not
+    // depend on ours
+    let mut owned = (*settings).clone();
+    owned.index_path = std::path::absolute(&settings.index_path)?;
+    let settings = Arc::new(owned);
+    let socket = std::path::absolute(socket_path(&settings.index_path))?;
+
+    let Some(listener) = Listener::bind(&socket)? else {
+        return Ok(false);
+    };
+    let idle_timeout = Duration::from_secs(settings.daemon.idle_timeout_secs);
+    let mut daemon = Daemon {
+        settings_stamp: settings_file.as_deref().and_then(modified),
+        settings_file,
+        settings,
+        indexer: None,
+        index_stamp: None,
+        started: Instant::now(),
+        requests: 0,
+    };
+
+    // Accept on a separate thread so the idle timeout can interrupt the wait
+    let (sender, connections) = mpsc::channel();
+    let accepting = listener.socket.try_clone()?;
+    std::thread::spawn(move || {
+        for stream in accepting.incoming() {
+            if sender.send(stream).is_err() {
+                break;
+            }
+        }
+    });
+
+    loop {
+        let stream = if idle_timeout.is_zero() {
+            connections.recv().map_err(|_| ())
+        } else {
+            connections.recv_timeout(idle_timeout).map_err(|_| ())
+        };
+        let Ok(Ok(stream)) = stream else {
+            break;
+        };
+        let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+        let Ok(request) = serde_json::from_reader::<_, Request>(&stream) else {
+            continue;
+        };
+        let (response, stop) = daemon.handle(request, &mut run_query);
+        let _ = serde_json::to_writer(&stream, &response);
+        if stop {
+            break;
+        }
+    }
+    Ok(true)
+}
+
+/// Bound socket, removed again when the daemon exits
+struct Listener {
+    socket: UnixListener,
+    path: PathBuf,
+    _lock: File,
+}
+
+impl Listener {
+    fn bind(path: &Path) -> io::Result<Option<Self>> {
+        let dir = path.parent().unwrap_or(Path::new("."));
+        std::fs::create_dir_all(dir)?;
+        let lock = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(dir.join(LOCK_FILE))?;
+        if !lock.try_lock_exclusive()? {
+            return Ok(None);
+        }
+
+        // Holding the lock, a socket file left behind is one of a daemon that died
+        match std::fs::remove_file(path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+        Ok(Some(Self {
+            socket: UnixListener::bind(path)?,
+            path: path.to_path_buf(),
+            _lock: lock,
+        }))
+    }
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+struct Daemon {
+    settings: Arc<Settings>,
+    settings_file: Option<PathBuf>,
+    settings_stamp: Option<SystemTime>,
+    indexer: Option<SimpleIndexer>,
+    index_stamp: Option<SystemTime>,
+    started: Instant,
+    requests: u64,
+}
+
+impl Daemon {
+    /// Answer one request; `true` asks the daemon to exit afterwards
+    fn handle<F>(&mut self, request: Request, run_query: &mut F) -> (Response, bool)
+    where
+        F: FnMut(&SimpleIndexer, &[String]) -> i32,
+    {
+        let rejected = |reason: String| Response::Rejected { reason };
+        self.requests += 1;
+
+        let (version, cwd, args) = match request {
+            Request::Query { version, cwd, args } => (version, cwd, args),
+            Request::Status => {
+                let _ = self.refresh();
+                return (Response::Status(self.status()), false);
+            }
+            Request::Stop => return (Response::Stopping, true),
+        };
+
+        // Let the next query start a daemon that matches the client
+        if version != VERSION {
+            return (rejected(format!("daemon runs codanna {VERSION}")), true);
+        }
+        if self.settings_file.as_deref().and_then(modified) != self.settings_stamp {
+            return (rejected("settings.toml changed".to_string()), true);
+        }
+        if let Err(e) = self.refresh() {
+            return (rejected(format!("could not load index: {e}")), false);
+        }
+        let Some(indexer) = &self.indexer else {
+            return (rejected("no index to load".to_string()), false);
+        };
+        if std::env::current_dir().ok().as_ref() != Some(&cwd) {
+            if let Err(e) = std::env::set_current_dir(&cwd) {
+                return (rejected(format!("{}: {e}", cwd.display())), false);
+            }
+        }
+
+        let output = capture_output(|| {
+            let query = std::panic::AssertUnwindSafe(|| run_query(indexer, &args));
+            std::panic::catch_unwind(query).unwrap_or(101)
+        });
+        match output {
+            Ok((stdout, stderr, exit_code)) => (
+                Response::Output {
+                    stdout,
+                    stderr,
+                    exit_code,
+                },
+                false,
+            ),
+            Err(e) => (rejected(format!("could not capture output: {e}")), false),
+        }
+    }
+
+    /// Load the index again if a writer saved it since the last load
+    fn refresh(&mut self) -> IndexResult<()> {
+        let index_path = &self.settings.index_path;
+        let stamp = index_stamp(index_path);
+        if self.indexer.is_some() {
+            // While a writer runs, keep answering from the generation already
+            // loaded and pick up its result once it saved everything
+            if stamp == self.index_stamp || IndexWriteLock::held_elsewhere(index_path) {
+                return Ok(());
+            }
+        }
+
+        let persistence = IndexPersistence::new(index_path.clone());
+        self.indexer = None;
+        if persistence.exists() {
+            let indexer =
+                persistence.load_with_settings_lazy(self.settings.clone(), false, false)?;
+            self.indexer = Some(indexer);
+        }
+        self.index_stamp = stamp;
+        Ok(())
+    }
+
+    fn status(&self) -> DaemonStatus {
+        DaemonStatus {
+            pid: std::process::id(),
+            version: VERSION.to_string(),
+            index_path: self.settings.index_path.clone(),
+            symbols: self.indexer.as_ref().map(|indexer| indexer.symbol_count()),
+            requests: self.requests,
+            uptime_secs: self.started.elapsed().as_secs(),
+            idle_timeout_secs: self.settings.daemon.idle_timeout_secs,
+        }
+    }
+}
+
+/// Latest save of the index: Tantivy commits and the index metadata
+fn index_stamp(index_path: &Path) -> Option<SystemTime> {
+    let tantivy = modified(&index_path.join("tantivy").join("meta.json"));
+    let metadata = modified(&index_path.join("index.meta"));
+    tantivy.max(metadata)
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Run `f` with the process's stdout and stderr sent to temporary files
+///
+/// Retrieve commands print through `println!`, `eprintln!` and
+/// `OutputManager`, so the file descriptors themselves are redirected. The
+/// daemon answers one request at a time, which keeps this process-wide swap
+/// safe.
+fn capture_output(f: impl FnOnce() -> i32) -> io::Result<(String, String, i32)> {
+    let mut stdout = tempfile::tempfile()?;
+    let mut stderr = tempfile::tempfile()?;
+    let exit_code = {
+        let _stdout = Redirect::new(libc::STDOUT_FILENO, &stdout)?;
+        let _stderr = Redirect::new(libc::STDERR_FILENO, &stderr)?;
+        f()
+    };
+    Ok((read_all(&mut stdout)?, read_all(&mut stderr)?, exit_code))
+}
+
+fn read_all(file: &mut File) -> io::Result<String> {
+    let mut bytes = Vec::new();
+    file.rewind()?;
+    file.read_to_end(&mut bytes)?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// File descriptor pointed at a file until dropped
+struct Redirect {
+    fd: RawFd,
+    saved: OwnedFd,
+}
+
+impl Redirect {
+    fn new(fd: RawFd, target: &File) -> io::Result<Self> {
+        flush_std();
+        // SAFETY: dup only duplicates `fd`; the copy is owned by `saved`
+        let saved = unsafe { libc::dup(fd) };
+        if saved < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: `saved` is a freshly duplicated descriptor nobody else owns
+        let saved = unsafe { OwnedFd::from_raw_fd(saved) };
+        // SAFETY: both descriptors are open for the duration of the call
+        if unsafe { libc::dup2(target.as_raw_fd(), fd) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Self { fd, saved })
+    }
+}
+
+impl Drop for Redirect {
+    fn drop(&mut self) {
+        flush_std();
+        // SAFETY: restores the descriptor saved in `new`, which is still open
+        unsafe { libc::dup2(self.saved.as_raw_fd(), self.fd) };
+    }
+}
+
+fn flush_std() {
+    let _ = io::stdout().flush();
+    let _ = io::stderr().flush();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn wait_for_daemon(socket: &Path) -> DaemonStatus {
+        let started = Instant::now();
+        loop {
+            if let Some(status) = status(socket) {
+                return status;
+            }
+            assert!(
+                started.elapsed() < Duration::from_secs(30),
+                "daemon did not start"
+            );
+            std::thread::sleep(Duration::from_millis(50));
+        }
+    }
+
+    #[test]
+    fn test_query_runs_against_loaded_index() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        fs::write(root.join("lib.rs"), "pub fn answer() -> u32 { 42 }\n").unwrap();
+        let settings = Arc::new(Settings {
+            index_path: root.join("index"),
+            workspace_root: Some(root.clone()),
+            ..Settings::default()
+        });
+        let mut indexer = SimpleIndexer::with_settings(settings.clone());
+        indexer
+            .index_files(vec![root.join("lib.rs")], false, false)
+            .unwrap();
+        indexer.finish_index_journal().unwrap();
+        IndexPersistence::new(root.join("index"))
+            .save(&indexer)
+            .unwrap();
+        drop(indexer);
+
+        let daemon = std::thread::spawn(move || {
+            serve(settings, None, |indexer, args| {
+                // Written to the descriptor, which the test harness does not capture
+                let found = indexer.find_symbols_by_name(&args[0], None).len();
+                writeln!(io::stdout(), "{} matches {found}", args[0]).unwrap();
+                if found > 0 { 0 } else { 3 }
+            })
+        });
+        let socket = root.join(SOCKET_FILE);
+        let status = wait_for_daemon(&socket);
+        assert_eq!(status.pid, std::process::id());
+        assert!(status.symbols.unwrap() > 0);
+
+        // A second daemon for the same socket steps aside
+        let settings = Arc::new(Settings {
+            index_path: root.join("index"),
+            ..Settings::default()
+        });
+        assert!(!serve(settings, None, |_, _| 0).unwrap());
+
+        // Same directory as the test process, which other tests share
+        let cwd = std::env::current_dir().unwrap();
+        let query = |name: &str| Request::Query {
+            version: VERSION.to_string(),
+            cwd: cwd.clone(),
+            args: vec![name.to_string()],
+        };
+        match request(&socket, &query("answer"), None).unwrap() {
+            Response::Output {
+                stdout, exit_code, ..
+            } => {
+                assert_eq!(stdout, "answer matches 1\n");
+                assert_eq!(exit_code, 0);
+            }
+            other => panic!("unexpected response: {other:?}"),
+        }
+        match request(&socket, &query("missing"), None).unwrap() {
+            Response::Output { exit_code, .. } => assert_eq!(exit_code, 3),
+            other => panic!("unexpected response: {other:?}"),
+        }
+
+        let outdated = Request::Query {
+            version: "0.0.0".to_string(),
+            cwd: cwd.clone(),
+            args: Vec::new(),
+        };
+        assert!(matches!(
+            request(&socket, &outdated, None).unwrap(),
+            Response::Rejected { .. }
+        ));
+        // An outdated client shuts the daemon down for a matching one to start
+        assert!(daemon.join().unwrap().unwrap());
+        assert!(!socket.exists());
+        assert!(forward_query(&socket, &["answer".to_string()]).is_none());
+    }
+
+    #[test]
+    fn test_daemon_exits_when_idle() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let mut settings = Settings {
+            index_path: root.join("index"),
+            ..Settings::default()
+        };
+        settings.daemon.idle_timeout_secs = 1;
+
+        let started = Instant::now();
+        assert!(serve(Arc::new(settings), None, |_, _| 0).unwrap());
+        assert!(started.elapsed() >= Duration::from_secs(1));
+        assert!(!root.join(SOCKET_FILE).exists());
+    }
+}
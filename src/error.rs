@@ -133,6 +133,26 @@ pub enum IndexError {
     #[error("Index appears to be corrupted: {reason}")]
     IndexCorrupted { reason: String },
 
+    /// スキーマ不一致エラー - 移行できない形式で書かれたインデックス
+    #[error(
+        "Index schema v{found} cannot be read by this version of codanna (schema v{expected}): {reason}; the index needs a reindex"
+    )]
+    SchemaMismatch {
+        found: u32,
+        expected: u32,
+        reason: String,
+    },
+
+    /// 書き込みロックエラー - 別のプロセスがインデックスに書き込み中
+    #[error(
+        "Index at '{path}' is being written by another process ({holder}); gave up after {waited_secs}s"
+    )]
+    IndexLocked {
+        path: PathBuf,
+        holder: String,
+        waited_secs: u64,
+    },
+
     /// 一般的なエラー - 既存の動作を保持するため
     #[error("{0}")]
     General(String),
@@ -175,6 +195,8 @@ impl IndexError {
             Self::TransactionFailed { .. } => "TRANSACTION_FAILED",
             Self::MutexPoisoned => "MUTEX_POISONED",
             Self::IndexCorrupted { .. } => "INDEX_CORRUPTED",
+            Self::SchemaMismatch { .. } => "SCHEMA_MISMATCH",
+            Self::IndexLocked { .. } => "INDEX_LOCKED",
             Self::General(_) => "GENERAL_ERROR",
         }
         .to_string()
@@ -217,10 +239,18 @@ impl IndexError {
                 "Run 'codanna index --force' to rebuild from scratch",
                 "Check for disk errors or filesystem corruption",
             ],
+            Self::SchemaMismatch { .. } => vec![
+                "Run 'codanna index --force' to rebuild the index with the current schema",
+                "Or query it with the codanna release that created it",
+            ],
             Self::LoadError { .. } | Self::PersistenceError { .. } => vec![
                 "The index will be loaded from Tantivy on next start",
                 "Run 'codanna index --force' if you continue to have issues",
             ],
+            Self::IndexLocked { .. } => vec![
+                "Wait for the other codanna process to finish writing, then retry",
+                "Raise indexing.lock_timeout_secs in settings.toml to wait longer",
+            ],
             Self::FileRead { .. } => vec![
                 "Check that the file exists and you have read permissions",
                 "Ensure the file is not locked by another process",
@@ -10,6 +10,7 @@ use std::sync::Arc;
 use tokio::sync::{RwLock, mpsc};
 
 use crate::config::Settings;
+use crate::indexing::IndexWriteLock;
 use crate::mcp::notifications::{FileChangeEvent, NotificationBroadcaster};
 use crate::{IndexError, IndexResult, SimpleIndexer};
 
@@ -175,19 +176,8 @@ impl ConfigFileWatcher {
 
         // Index new directories
         let mut indexer = self.indexer.write().await;
-        for path in &added {
-            eprintln!("Indexing new directory: {}", path.display());
-            match indexer.index_directory(path, false, false) {
-                Ok(stats) => {
-                    eprintln!(
-                        "  ✓ Indexed {} files, {} symbols",
-                        stats.files_indexed, stats.symbols_found
-                    );
-                }
-                Err(e) => {
-                    eprintln!("  ✗ Failed to index {}: {e}", path.display());
-                }
-            }
+        if !index_directories(&mut indexer, &added)? {
+            return Ok(());
         }
         drop(indexer); // Release write lock
 
@@ -248,20 +238,7 @@ impl ConfigFileWatcher {
 
             // Index new directories
             let mut indexer = self.indexer.write().await;
-            for path in &added {
-                eprintln!("Indexing new directory: {}", path.display());
-                match indexer.index_directory(path, false, false) {
-                    Ok(stats) => {
-                        eprintln!(
-                            "  ✓ Indexed {} files, {} symbols",
-                            stats.files_indexed, stats.symbols_found
-                        );
-                    }
-                    Err(e) => {
-                        eprintln!("  ✗ Failed to index {}: {e}", path.display());
-                    }
-                }
-            }
+            index_directories(&mut indexer, &added)?;
         }
 
         if !removed.is_empty() {
@@ -289,3 +266,35 @@ impl ConfigFileWatcher {
         Ok(())
     }
 }
+
+/// Index directories added to the config under the index write lock
+///
+/// Returns false without indexing when another process is writing the index;
+/// the startup sync of the next codanna command indexes them instead.
+fn index_directories(indexer: &mut SimpleIndexer, paths: &[PathBuf]) -> IndexResult<bool> {
+    let index_dir = indexer.index_dir();
+    let Some(_lock) = IndexWriteLock::try_acquire(&index_dir)? else {
+        eprintln!(
+            "Index is being written by another process ({}); new directories are indexed by the next codanna command",
+            IndexWriteLock::holder(&index_dir).unwrap_or_else(|| "unknown process".to_string())
+        );
+        return Ok(false);
+    };
+
+    for path in paths {
+        eprintln!("Indexing new directory: {}", path.display());
+        match indexer.index_directory(path, false, false) {
+            Ok(stats) => {
+                eprintln!(
+                    "  ✓ Indexed {} files, {} symbols",
+                    stats.files_indexed, stats.symbols_found
+                );
+            }
+            Err(e) => {
+                eprintln!("  ✗ Failed to index {}: {e}", path.display());
+            }
+        }
+    }
+    indexer.release_writer()?;
+    Ok(true)
+}
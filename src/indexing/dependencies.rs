@@ -0,0 +1,662 @@
+//! Dependency source discovery and the read-only dependency index layer
+//!
+//! Calls into library code cannot be resolved against the project index and
+//! are dropped as external. This module finds the sources of the project's
+//! dependencies on disk and indexes them into a separate Tantivy index under
+//! `<index_path>/dependencies`. Lookups fall back to that layer only when the
+//! project index has no match, so library symbols never show up in project
+//! search results.
+//!
+//! Supported ecosystems:
+//! - Cargo: registry sources for the packages in `Cargo.lock`
+//! - npm: packages installed in `node_modules`
+//! - Python: packages in the site-packages of the project's virtualenv
+//! - Go: modules required by `go.mod`, read from the module cache
+
+use crate::Settings;
+use crate::error::{IndexError, IndexResult};
+use crate::indexing::{FileWalker, IndexStats, SimpleIndexer};
+use crate::storage::DocumentIndex;
+use crate::symbol::context::{SymbolContext, SymbolRelationships};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Directory of the dependency layer, relative to the index path
+pub const LAYER_DIR: &str = "dependencies";
+
+/// Manifest listing the sources indexed into the layer
+const MANIFEST_FILE: &str = "sources.json";
+
+/// Directories inside a package that never contain library API
+const SKIPPED_DIRS: &[&str] = &[
+    "tests",
+    "test",
+    "testdata",
+    "examples",
+    "benches",
+    "__pycache__",
+    "node_modules",
+];
+
+/// Python distributions that are tooling rather than libraries
+const SKIPPED_PYTHON_PACKAGES: &[&str] = &["pip", "setuptools", "wheel", "_distutils_hack"];
+
+/// Files larger than this are usually generated or bundled code
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+
+/// Package ecosystem a dependency comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Ecosystem {
+    Cargo,
+    Npm,
+    Python,
+    Go,
+}
+
+impl Ecosystem {
+    /// All supported ecosystems
+    pub const ALL: [Ecosystem; 4] = [
+        Ecosystem::Cargo,
+        Ecosystem::Npm,
+        Ecosystem::Python,
+        Ecosystem::Go,
+    ];
+
+    /// Name used in configuration and output
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Ecosystem::Cargo => "cargo",
+            Ecosystem::Npm => "npm",
+            Ecosystem::Python => "python",
+            Ecosystem::Go => "go",
+        }
+    }
+
+    /// Parse an ecosystem name, accepting common aliases
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "cargo" | "rust" => Some(Ecosystem::Cargo),
+            "npm" | "node" | "javascript" | "typescript" => Some(Ecosystem::Npm),
+            "python" | "pip" | "pypi" => Some(Ecosystem::Python),
+            "go" | "golang" => Some(Ecosystem::Go),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Ecosystem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+/// A dependency whose sources were found on disk
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DependencySource {
+    pub ecosystem: Ecosystem,
+    pub name: String,
+    pub version: String,
+    /// Package directory, or a single module file for flat Python packages
+    pub path: PathBuf,
+}
+
+/// Find dependency sources for the project rooted at `root`
+///
+/// Dependencies whose sources are not present locally (not yet downloaded,
+/// no virtualenv, ...) are silently left out.
+pub fn discover(root: &Path, ecosystems: &[Ecosystem]) -> Vec<DependencySource> {
+    let mut sources = Vec::new();
+    for ecosystem in ecosystems {
+        let found = match ecosystem {
+            Ecosystem::Cargo => discover_cargo(root),
+            Ecosystem::Npm => discover_npm(root),
+            Ecosystem::Python => discover_python(root),
+            Ecosystem::Go => discover_go(root),
+        };
+        sources.extend(found);
+    }
+    sources
+}
+
+/// Registry packages `(name, version)` pinned by a `Cargo.lock`
+///
+/// Path and git dependencies are skipped: path dependencies are part of the
+/// workspace and git checkouts do not have a stable location.
+pub fn parse_cargo_lock(content: &str) -> Vec<(String, String)> {
+    let Ok(lock) = content.parse::<toml::Table>() else {
+        return Vec::new();
+    };
+    let Some(packages) = lock.get("package").and_then(|p| p.as_array()) else {
+        return Vec::new();
+    };
+
+    packages
+        .iter()
+        .filter_map(|package| {
+            let source = package.get("source")?.as_str()?;
+            if !source.starts_with("registry+") && !source.starts_with("sparse+") {
+                return None;
+            }
+            let name = package.get("name")?.as_str()?;
+            let version = package.get("version")?.as_str()?;
+            Some((name.to_string(), version.to_string()))
+        })
+        .collect()
+}
+
+fn discover_cargo(root: &Path) -> Vec<DependencySource> {
+    let Ok(content) = std::fs::read_to_string(root.join("Cargo.lock")) else {
+        return Vec::new();
+    };
+    let Some(cargo_home) = std::env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".cargo")))
+    else {
+        return Vec::new();
+    };
+
+    // One directory per registry, e.g. `index.crates.io-6f17d22bba15001f`
+    let registries: Vec<PathBuf> = read_dir_paths(&cargo_home.join("registry").join("src"));
+
+    parse_cargo_lock(&content)
+        .into_iter()
+        .filter_map(|(name, version)| {
+            let dir_name = format!("{name}-{version}");
+            let path = registries
+                .iter()
+                .map(|registry| registry.join(&dir_name))
+                .find(|path| path.is_dir())?;
+            Some(DependencySource {
+                ecosystem: Ecosystem::Cargo,
+                name,
+                version,
+                path,
+            })
+        })
+        .collect()
+}
+
+fn discover_npm(root: &Path) -> Vec<DependencySource> {
+    let node_modules = root.join("node_modules");
+    let mut packages = Vec::new();
+
+    for entry in read_dir_paths(&node_modules) {
+        let Some(name) = file_name(&entry) else {
+            continue;
+        };
+        if name.starts_with('.') {
+            continue;
+        }
+        if name.starts_with('@') {
+            // Scoped packages live one level deeper
+            for scoped in read_dir_paths(&entry) {
+                if let Some(inner) = file_name(&scoped) {
+                    packages.push((format!("{name}/{inner}"), scoped));
+                }
+            }
+        } else {
+            packages.push((name.to_string(), entry));
+        }
+    }
+
+    packages
+        .into_iter()
+        .filter(|(_, path)| path.is_dir())
+        .map(|(name, path)| DependencySource {
+            ecosystem: Ecosystem::Npm,
+            version: npm_package_version(&path).unwrap_or_default(),
+            name,
+            path,
+        })
+        .collect()
+}
+
+fn npm_package_version(package_dir: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(package_dir.join("package.json")).ok()?;
+    let manifest: serde_json::Value = serde_json::from_str(&content).ok()?;
+    manifest
+        .get("version")
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+}
+
+/// Split a `.dist-info` directory name into distribution name and version
+pub fn parse_dist_info(dir_name: &str) -> Option<(String, String)> {
+    let stem = dir_name.strip_suffix(".dist-info")?;
+    // Names are normalized to use underscores, so the first dash ends the name
+    let (name, version) = stem.split_once('-')?;
+    Some((name.to_string(), version.to_string()))
+}
+
+fn discover_python(root: &Path) -> Vec<DependencySource> {
+    let venv = std::env::var_os("VIRTUAL_ENV")
+        .map(PathBuf::from)
+        .into_iter()
+        .chain([root.join(".venv"), root.join("venv")])
+        .find(|path| path.is_dir());
+    let Some(venv) = venv else {
+        return Vec::new();
+    };
+
+    let mut site_packages: Vec<PathBuf> = read_dir_paths(&venv.join("lib"))
+        .into_iter()
+        .filter(|path| file_name(path).is_some_and(|name| name.starts_with("python")))
+        .map(|path| path.join("site-packages"))
+        .collect();
+    // Windows layout
+    site_packages.push(venv.join("Lib").join("site-packages"));
+
+    let mut sources = Vec::new();
+    for dir in site_packages.into_iter().filter(|dir| dir.is_dir()) {
+        for dist_info in read_dir_paths(&dir) {
+            let Some((name, version)) = file_name(&dist_info).and_then(parse_dist_info) else {
+                continue;
+            };
+            let top_level = std::fs::read_to_string(dist_info.join("top_level.txt"))
+                .map(|content| {
+                    content
+                        .lines()
+                        .map(str::trim)
+                        .filter(|line| !line.is_empty())
+                        .map(str::to_string)
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_else(|_| vec![name.to_lowercase()]);
+
+            for module in top_level {
+                if SKIPPED_PYTHON_PACKAGES.contains(&module.as_str()) {
+                    continue;
+                }
+                let package_dir = dir.join(&module);
+                let module_file = dir.join(format!("{module}.py"));
+                let path = if package_dir.is_dir() {
+                    package_dir
+                } else if module_file.is_file() {
+                    module_file
+                } else {
+                    continue;
+                };
+                sources.push(DependencySource {
+                    ecosystem: Ecosystem::Python,
+                    name: module,
+                    version: version.clone(),
+                    path,
+                });
+            }
+        }
+    }
+    sources
+}
+
+/// Modules `(path, version)` required by a `go.mod`
+pub fn parse_go_mod(content: &str) -> Vec<(String, String)> {
+    let mut requires = Vec::new();
+    let mut in_block = false;
+
+    for line in content.lines() {
+        let line = line.split("//").next().unwrap_or("").trim();
+        if in_block {
+            if line == ")" {
+                in_block = false;
+                continue;
+            }
+        } else if line == "require (" {
+            in_block = true;
+            continue;
+        }
+
+        let spec = if in_block {
+            line
+        } else if let Some(rest) = line.strip_prefix("require ") {
+            rest.trim()
+        } else {
+            continue;
+        };
+
+        let mut parts = spec.split_whitespace();
+        if let (Some(path), Some(version)) = (parts.next(), parts.next()) {
+            requires.push((path.to_string(), version.to_string()));
+        }
+    }
+    requires
+}
+
+/// Escape a module path or version the way the Go module cache does
+///
+/// Upper-case letters become `!` followed by the lower-case letter.
+pub fn escape_go_module_path(path: &str) -> String {
+    let mut escaped = String::with_capacity(path.len());
+    for c in path.chars() {
+        if c.is_ascii_uppercase() {
+            escaped.push('!');
+            escaped.push(c.to_ascii_lowercase());
+        } else {
+            escaped.push(c);
+        }
+    }
+    escaped
+}
+
+fn discover_go(root: &Path) -> Vec<DependencySource> {
+    let Ok(content) = std::fs::read_to_string(root.join("go.mod")) else {
+        return Vec::new();
+    };
+    let mod_cache = std::env::var_os("GOMODCACHE")
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("GOPATH")
+                .and_then(|gopath| std::env::split_paths(&gopath).next())
+                .map(|gopath| gopath.join("pkg").join("mod"))
+        })
+        .or_else(|| dirs::home_dir().map(|home| home.join("go").join("pkg").join("mod")));
+    let Some(mod_cache) = mod_cache else {
+        return Vec::new();
+    };
+
+    parse_go_mod(&content)
+        .into_iter()
+        .filter_map(|(module, version)| {
+            let path = mod_cache.join(format!(
+                "{}@{}",
+                escape_go_module_path(&module),
+                escape_go_module_path(&version)
+            ));
+            path.is_dir().then_some(DependencySource {
+                ecosystem: Ecosystem::Go,
+                name: module,
+                version,
+                path,
+            })
+        })
+        .collect()
+}
+
+fn read_dir_paths(dir: &Path) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.path()).collect())
+        .unwrap_or_default();
+    paths.sort();
+    paths
+}
+
+fn file_name(path: &Path) -> Option<&str> {
+    path.file_name().and_then(|name| name.to_str())
+}
+
+/// Source files of a dependency that are worth indexing
+pub fn source_files(settings: Arc<Settings>, source: &DependencySource) -> Vec<PathBuf> {
+    FileWalker::new(settings)
+        .with_ignore_files(false)
+        .walk(&source.path)
+        .filter(|file| {
+            let relative = file.strip_prefix(&source.path).unwrap_or(file);
+            !relative.components().any(|component| {
+                component
+                    .as_os_str()
+                    .to_str()
+                    .is_some_and(|name| SKIPPED_DIRS.contains(&name))
+            })
+        })
+        .filter(|file| {
+            std::fs::metadata(file)
+                .map(|meta| meta.len() <= MAX_FILE_BYTES)
+                .unwrap_or(false)
+        })
+        .collect()
+}
+
+/// Absolute directory of the dependency layer
+pub fn layer_path(settings: &Settings) -> PathBuf {
+    let index_base = match &settings.workspace_root {
+        Some(workspace_root) => workspace_root.join(&settings.index_path),
+        None => settings.index_path.clone(),
+    };
+    index_base.join(LAYER_DIR)
+}
+
+/// Settings for the indexer that writes the dependency layer
+fn layer_settings(settings: &Settings) -> Settings {
+    let mut layer = settings.clone();
+    layer.index_path = settings.index_path.join(LAYER_DIR);
+    // Library docs are only looked up by name; embeddings would dwarf the project
+    layer.semantic_search.enabled = false;
+    layer
+}
+
+/// Rebuild the dependency layer from `sources`
+///
+/// The previous layer is removed first. The project index is not touched.
+pub fn build_layer(
+    settings: &Settings,
+    sources: &[DependencySource],
+    progress: bool,
+) -> IndexResult<IndexStats> {
+    let path = layer_path(settings);
+    if path.exists() {
+        std::fs::remove_dir_all(&path).map_err(|e| {
+            IndexError::General(format!(
+                "Failed to remove dependency layer {}: {e}",
+                path.display()
+            ))
+        })?;
+    }
+
+    let layer_settings = Arc::new(layer_settings(settings));
+    let files: Vec<PathBuf> = sources
+        .iter()
+        .flat_map(|source| source_files(layer_settings.clone(), source))
+        .collect();
+
+    let mut indexer = SimpleIndexer::with_settings(layer_settings);
+    let stats = indexer.index_files(files, progress, true)?;
+
+    let manifest = serde_json::to_string_pretty(sources)
+        .map_err(|e| IndexError::General(format!("Failed to serialize sources: {e}")))?;
+    std::fs::write(path.join(MANIFEST_FILE), manifest)
+        .map_err(|e| IndexError::General(format!("Failed to write dependency manifest: {e}")))?;
+    indexer.finish_index_journal()?;
+
+    Ok(stats)
+}
+
+/// Read-only view of a built dependency layer
+pub struct DependencyLayer {
+    index: DocumentIndex,
+    sources: Vec<DependencySource>,
+    workspace_root: Option<PathBuf>,
+}
+
+impl DependencyLayer {
+    /// Open the layer if `codanna deps index` has built one
+    pub fn open(settings: &Settings) -> Option<Self> {
+        let path = layer_path(settings);
+        let tantivy_path = path.join("tantivy");
+        if !tantivy_path.join("meta.json").exists() {
+            return None;
+        }
+
+        let index = DocumentIndex::new(tantivy_path, settings).ok()?;
+        let sources = std::fs::read_to_string(path.join(MANIFEST_FILE))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        Some(Self {
+            index,
+            sources,
+            workspace_root: settings.workspace_root.clone(),
+        })
+    }
+
+    /// Dependencies indexed into the layer
+    pub fn sources(&self) -> &[DependencySource] {
+        &self.sources
+    }
+
+    /// Number of symbols in the layer
+    pub fn symbol_count(&self) -> usize {
+        self.index.count_symbols().unwrap_or(0)
+    }
+
+    /// Find library symbols by exact name
+    ///
+    /// Relationships are left empty: the layer only answers what a library
+    /// symbol looks like, not who uses it.
+    pub fn find_symbols_by_name(
+        &self,
+        name: &str,
+        language_filter: Option<&str>,
+    ) -> Vec<SymbolContext> {
+        self.index
+            .find_symbols_by_name(name, language_filter)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|symbol| {
+                let file_path = self
+                    .index
+                    .get_file_path(symbol.file_id)
+                    .ok()
+                    .flatten()
+                    .unwrap_or_default();
+                SymbolContext {
+                    symbol,
+                    file_path,
+                    relationships: SymbolRelationships::default(),
+                }
+            })
+            .collect()
+    }
+
+    /// Dependency that a layer file path belongs to
+    pub fn source_for_path(&self, file_path: &str) -> Option<&DependencySource> {
+        let path = Path::new(file_path);
+        let absolute = match &self.workspace_root {
+            Some(root) if path.is_relative() => root.join(path),
+            _ => path.to_path_buf(),
+        };
+        self.sources
+            .iter()
+            .find(|source| absolute.starts_with(&source.path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_cargo_lock_keeps_registry_packages() {
+        let lock = r#"
+version = 4
+
+[[package]]
+name = "my-app"
+version = "0.1.0"
+
+[[package]]
+name = "serde"
+version = "1.0.200"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "forked"
+version = "0.2.0"
+source = "git+https://github.com/example/forked#abc"
+"#;
+        assert_eq!(
+            parse_cargo_lock(lock),
+            vec![("serde".to_string(), "1.0.200".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_parse_go_mod_single_and_block_requires() {
+        let go_mod = "module example.com/app\n\ngo 1.22\n\nrequire github.com/pkg/errors v0.9.1\n\nrequire (\n\tgithub.com/BurntSushi/toml v1.3.2 // indirect\n\tgolang.org/x/sync v0.7.0\n)\n";
+        assert_eq!(
+            parse_go_mod(go_mod),
+            vec![
+                ("github.com/pkg/errors".to_string(), "v0.9.1".to_string()),
+                (
+                    "github.com/BurntSushi/toml".to_string(),
+                    "v1.3.2".to_string()
+                ),
+                ("golang.org/x/sync".to_string(), "v0.7.0".to_string()),
+            ]
+        );
+        assert_eq!(
+            escape_go_module_path("github.com/BurntSushi/toml"),
+            "github.com/!burnt!sushi/toml"
+        );
+    }
+
+    #[test]
+    fn test_parse_dist_info() {
+        assert_eq!(
+            parse_dist_info("typing_extensions-4.12.2.dist-info"),
+            Some(("typing_extensions".to_string(), "4.12.2".to_string()))
+        );
+        assert_eq!(parse_dist_info("requests"), None);
+    }
+
+    #[test]
+    fn test_discover_npm_and_python_layouts() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+
+        let lodash = root.join("node_modules/lodash");
+        std::fs::create_dir_all(&lodash).unwrap();
+        std::fs::write(lodash.join("package.json"), r#"{"version":"4.17.21"}"#).unwrap();
+        std::fs::create_dir_all(root.join("node_modules/@types/node")).unwrap();
+        std::fs::create_dir_all(root.join("node_modules/.bin")).unwrap();
+
+        let site = root.join(".venv/lib/python3.12/site-packages");
+        std::fs::create_dir_all(site.join("requests")).unwrap();
+        std::fs::create_dir_all(site.join("requests-2.32.0.dist-info")).unwrap();
+        std::fs::write(
+            site.join("requests-2.32.0.dist-info/top_level.txt"),
+            "requests\n",
+        )
+        .unwrap();
+        std::fs::write(site.join("six.py"), "").unwrap();
+        std::fs::create_dir_all(site.join("six-1.16.0.dist-info")).unwrap();
+
+        let sources = discover(root, &[Ecosystem::Npm, Ecosystem::Python]);
+        let names: Vec<(&str, &str)> = sources
+            .iter()
+            .map(|s| (s.name.as_str(), s.version.as_str()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("@types/node", ""),
+                ("lodash", "4.17.21"),
+                ("requests", "2.32.0"),
+                ("six", "1.16.0"),
+            ]
+        );
+        assert!(sources[3].path.ends_with("six.py"));
+    }
+
+    #[test]
+    fn test_source_files_skip_tests_and_nested_packages() {
+        let temp = TempDir::new().unwrap();
+        let package = temp.path().join("demo");
+        std::fs::create_dir_all(package.join("tests")).unwrap();
+        std::fs::create_dir_all(package.join("src")).unwrap();
+        std::fs::write(package.join(".gitignore"), "src\n").unwrap();
+        std::fs::write(package.join("src/lib.rs"), "pub fn api() {}").unwrap();
+        std::fs::write(package.join("tests/it.rs"), "fn t() {}").unwrap();
+
+        let source = DependencySource {
+            ecosystem: Ecosystem::Cargo,
+            name: "demo".to_string(),
+            version: "1.0.0".to_string(),
+            path: package.clone(),
+        };
+        let files = source_files(Arc::new(Settings::default()), &source);
+        assert_eq!(files, vec![package.join("src/lib.rs")]);
+    }
+}
@@ -0,0 +1,698 @@
+//! Index integrity checks and targeted repairs behind `codanna doctor`
+//!
+//! The index is spread over several stores that are written at different
+//! times: Tantivy documents, the symbol cache (`symbol_cache.bin`), the
+//! semantic vectors with their `metadata.json`, and the index metadata with
+//! its counts. [`check`] cross-checks them and reports each problem with the
+//! [`Repair`] that fixes it; [`repair`] applies those repairs so a damaged
+//! index does not need a full `--force` reindex.
+
+use crate::indexing::lock::IndexWriteLock;
+use crate::indexing::transaction::IndexJournal;
+use crate::semantic::{SemanticMetadata, SemanticVectorStorage};
+use crate::storage::IndexMetadata;
+use crate::storage::symbol_cache::SymbolHashCache;
+use crate::{IndexError, IndexResult, SimpleIndexer, SymbolId};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Number of paths or IDs listed in a problem's detail
+const SAMPLE_SIZE: usize = 3;
+
+/// Check and repair passes `codanna doctor --fix` makes before giving up
+pub const MAX_REPAIR_PASSES: usize = 3;
+
+/// A targeted fix for one kind of problem, in the order they are applied
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Repair {
+    /// Roll back and finish the run the journal describes
+    ResumeRun,
+    /// Remove indexed files that no longer exist on disk
+    RemoveMissingFiles,
+    /// Delete relationships whose source or target symbol is gone
+    DropDanglingRelationships,
+    /// Rewrite the semantic vectors without symbols that are gone
+    DropOrphanEmbeddings,
+    /// Embed doc comments that have no vector
+    ReEmbed,
+    /// Rebuild `symbol_cache.bin` from Tantivy
+    RebuildSymbolCache,
+    /// Rewrite the index metadata from the current counts
+    SaveMetadata,
+}
+
+impl Repair {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ResumeRun => "resume the interrupted indexing run",
+            Self::RemoveMissingFiles => "remove deleted files from the index",
+            Self::DropDanglingRelationships => "drop dangling relationships",
+            Self::DropOrphanEmbeddings => "drop orphaned embeddings",
+            Self::ReEmbed => "embed missing doc comments",
+            Self::RebuildSymbolCache => "rebuild the symbol cache",
+            Self::SaveMetadata => "rewrite the index metadata",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Ok,
+    Problem,
+    Skipped,
+}
+
+/// Result of one cross-check
+#[derive(Debug, Clone, Serialize)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repair: Option<Repair>,
+}
+
+impl Check {
+    fn ok(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Ok,
+            detail: detail.into(),
+            repair: None,
+        }
+    }
+
+    fn problem(name: &'static str, detail: impl Into<String>, repair: Repair) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Problem,
+            detail: detail.into(),
+            repair: Some(repair),
+        }
+    }
+
+    fn skipped(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Skipped,
+            detail: detail.into(),
+            repair: None,
+        }
+    }
+}
+
+/// Everything [`check`] found, with what [`repair`] needs to fix it
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DoctorReport {
+    pub symbols: usize,
+    pub files: usize,
+    pub relationships: usize,
+    pub checks: Vec<Check>,
+    #[serde(skip)]
+    missing_files: Vec<String>,
+    #[serde(skip)]
+    dangling_symbols: Vec<SymbolId>,
+    #[serde(skip)]
+    live_symbols: HashSet<SymbolId>,
+}
+
+impl DoctorReport {
+    pub fn is_healthy(&self) -> bool {
+        self.checks
+            .iter()
+            .all(|check| check.status != CheckStatus::Problem)
+    }
+
+    /// Repairs the problems call for, in the order they must be applied
+    pub fn repairs(&self) -> Vec<Repair> {
+        let mut repairs: Vec<Repair> = self.checks.iter().filter_map(|c| c.repair).collect();
+        repairs.sort();
+        repairs.dedup();
+        repairs
+    }
+}
+
+/// Cross-check the stores of the index `indexer` has open
+pub fn check(indexer: &SimpleIndexer) -> IndexResult<DoctorReport> {
+    let index_dir = indexer.index_dir();
+    let document_index = indexer.document_index();
+    let tantivy_error =
+        |operation: &str, e: crate::storage::StorageError| IndexError::TantivyError {
+            operation: operation.to_string(),
+            cause: e.to_string(),
+        };
+
+    let symbol_total = document_index
+        .count_symbols()
+        .map_err(|e| tantivy_error("count_symbols", e))?;
+    let symbols = document_index
+        .get_all_symbols(symbol_total)
+        .map_err(|e| tantivy_error("get_all_symbols", e))?;
+    let files = document_index
+        .query_file_info()
+        .map_err(|e| tantivy_error("query_file_info", e))?;
+    let relationships = document_index
+        .query_relationships()
+        .map_err(|e| tantivy_error("query_relationships", e))?;
+
+    let mut report = DoctorReport {
+        symbols: symbols.len(),
+        files: files.len(),
+        relationships: relationships.len(),
+        live_symbols: symbols.iter().map(|s| s.id).collect(),
+        ..Default::default()
+    };
+    report.checks.push(Check::ok(
+        "tantivy",
+        format!(
+            "{} symbols, {} files, {} relationships",
+            report.symbols, report.files, report.relationships
+        ),
+    ));
+
+    // Journal of an interrupted run
+    let journal = IndexJournal::new(&index_dir);
+    report.checks.push(match journal.read() {
+        Ok(None) => Check::ok("journal", "no interrupted run"),
+        Ok(Some(_)) if IndexWriteLock::held_elsewhere(&index_dir) => {
+            Check::skipped("journal", "indexing run in progress in another process")
+        }
+        Ok(Some(run)) => Check::problem(
+            "journal",
+            format!(
+                "interrupted run: {} files committed, {} never reached",
+                run.committed.len(),
+                run.pending.len()
+            ),
+            Repair::ResumeRun,
+        ),
+        Err(e) => Check::problem(
+            "journal",
+            format!("unreadable journal: {e}"),
+            Repair::ResumeRun,
+        ),
+    });
+
+    // Index metadata counts
+    report.checks.push(match IndexMetadata::load(&index_dir) {
+        Ok(metadata)
+            if metadata.symbol_count as usize == report.symbols
+                && metadata.file_count as usize == report.files =>
+        {
+            Check::ok("metadata", "counts match")
+        }
+        Ok(metadata) => Check::problem(
+            "metadata",
+            format!(
+                "records {} symbols and {} files, index has {} and {}",
+                metadata.symbol_count, metadata.file_count, report.symbols, report.files
+            ),
+            Repair::SaveMetadata,
+        ),
+        Err(e) => Check::problem("metadata", e.to_string(), Repair::SaveMetadata),
+    });
+
+    report.checks.push(check_symbol_cache(
+        indexer,
+        &index_dir,
+        &report.live_symbols,
+    ));
+
+    // Relationship endpoints
+    let mut dangling: HashSet<SymbolId> = HashSet::new();
+    let mut dangling_count = 0;
+    for (from, to, _) in &relationships {
+        let mut is_dangling = false;
+        for id in [from, to] {
+            if !report.live_symbols.contains(id) {
+                dangling.insert(*id);
+                is_dangling = true;
+            }
+        }
+        if is_dangling {
+            dangling_count += 1;
+        }
+    }
+    report.dangling_symbols = dangling.into_iter().collect();
+    report.dangling_symbols.sort_by_key(|id| id.value());
+    report.checks.push(if dangling_count == 0 {
+        Check::ok("relationships", "all endpoints exist")
+    } else {
+        Check::problem(
+            "relationships",
+            format!(
+                "{dangling_count} relationships point to {} missing symbols",
+                report.dangling_symbols.len()
+            ),
+            Repair::DropDanglingRelationships,
+        )
+    });
+
+    // Files deleted since they were indexed
+    let workspace_root = indexer.settings().workspace_root.clone();
+    report.missing_files = files
+        .iter()
+        .map(|(_, path, _, _)| path)
+        .filter(|path| {
+            let on_disk = match &workspace_root {
+                Some(root) => root.join(path),
+                None => Path::new(path).to_path_buf(),
+            };
+            !on_disk.exists()
+        })
+        .cloned()
+        .collect();
+    report.missing_files.sort();
+    report.checks.push(if report.missing_files.is_empty() {
+        Check::ok("files", "all indexed files exist on disk")
+    } else {
+        Check::problem(
+            "files",
+            format!(
+                "{} indexed files no longer exist ({})",
+                report.missing_files.len(),
+                sample(&report.missing_files)
+            ),
+            Repair::RemoveMissingFiles,
+        )
+    });
+
+    report.checks.extend(check_semantic(
+        &index_dir.join("semantic"),
+        &symbols,
+        &report.live_symbols,
+    ));
+
+    Ok(report)
+}
+
+/// `symbol_cache.bin` must open, hold what a rebuild would write, and only
+/// point to indexed symbols
+fn check_symbol_cache(
+    indexer: &SimpleIndexer,
+    index_dir: &Path,
+    live_symbols: &HashSet<SymbolId>,
+) -> Check {
+    const NAME: &str = "symbol cache";
+
+    let cache_path = index_dir.join("symbol_cache.bin");
+    if !cache_path.exists() {
+        return Check::problem(
+            NAME,
+            "symbol_cache.bin is missing",
+            Repair::RebuildSymbolCache,
+        );
+    }
+    let cache = match SymbolHashCache::open(&cache_path) {
+        Ok(cache) => cache,
+        Err(e) => {
+            return Check::problem(
+                NAME,
+                format!("symbol_cache.bin is unreadable: {e}"),
+                Repair::RebuildSymbolCache,
+            );
+        }
+    };
+
+    // The cache is built from the same capped listing
+    let cached_symbols = indexer.get_all_symbols();
+    if cache.symbol_count() != cached_symbols.len() {
+        return Check::problem(
+            NAME,
+            format!(
+                "holds {} symbols, index has {}",
+                cache.symbol_count(),
+                cached_symbols.len()
+            ),
+            Repair::RebuildSymbolCache,
+        );
+    }
+
+    let names: HashSet<&str> = cached_symbols.iter().map(|s| s.name.as_ref()).collect();
+    let stale = names
+        .iter()
+        .flat_map(|name| cache.lookup_candidates(name, 64))
+        .filter(|id| !live_symbols.contains(id))
+        .count();
+    if stale > 0 {
+        return Check::problem(
+            NAME,
+            format!("{stale} entries point to symbols that are no longer indexed"),
+            Repair::RebuildSymbolCache,
+        );
+    }
+
+    Check::ok(NAME, format!("{} symbols", cache.symbol_count()))
+}
+
+/// Stored vectors must match `metadata.json` and the indexed symbols
+fn check_semantic(
+    semantic_dir: &Path,
+    symbols: &[crate::Symbol],
+    live_symbols: &HashSet<SymbolId>,
+) -> Vec<Check> {
+    const NAME: &str = "semantic";
+
+    if !SemanticMetadata::exists(semantic_dir) {
+        return vec![Check::skipped(NAME, "no semantic data")];
+    }
+    let metadata = match SemanticMetadata::load(semantic_dir) {
+        Ok(metadata) => metadata,
+        Err(e) => {
+            return vec![Check::problem(
+                NAME,
+                format!("metadata.json is unreadable: {e}"),
+                Repair::DropOrphanEmbeddings,
+            )];
+        }
+    };
+    let stored = match SemanticVectorStorage::open(semantic_dir).and_then(|mut s| s.load_all()) {
+        Ok(stored) => stored,
+        Err(e) => {
+            return vec![Check::problem(
+                NAME,
+                format!("vector storage is unreadable: {e}"),
+                Repair::ReEmbed,
+            )];
+        }
+    };
+
+    let mut checks = Vec::new();
+    if stored.len() != metadata.embedding_count {
+        checks.push(Check::problem(
+            NAME,
+            format!(
+                "metadata.json records {} embeddings, storage holds {}",
+                metadata.embedding_count,
+                stored.len()
+            ),
+            Repair::DropOrphanEmbeddings,
+        ));
+    }
+
+    let embedded: HashSet<SymbolId> = stored.iter().map(|(id, _)| *id).collect();
+    let orphaned = embedded
+        .iter()
+        .filter(|id| !live_symbols.contains(id))
+        .count();
+    if orphaned > 0 {
+        checks.push(Check::problem(
+            NAME,
+            format!("{orphaned} embeddings belong to symbols that are no longer indexed"),
+            Repair::DropOrphanEmbeddings,
+        ));
+    }
+
+    let unembedded = symbols
+        .iter()
+        .filter(|s| {
+            s.doc_comment
+                .as_deref()
+                .is_some_and(|doc| !doc.trim().is_empty())
+        })
+        .filter(|s| !embedded.contains(&s.id))
+        .count();
+    if unembedded > 0 {
+        checks.push(Check::problem(
+            NAME,
+            format!("{unembedded} documented symbols have no embedding"),
+            Repair::ReEmbed,
+        ));
+    }
+
+    if checks.is_empty() {
+        checks.push(Check::ok(
+            NAME,
+            format!("{} embeddings ({})", stored.len(), metadata.model_name),
+        ));
+    }
+    checks
+}
+
+/// Apply the repairs `report` calls for
+///
+/// Returns a line per repair describing what it did. The caller saves the
+/// index afterwards, which also covers [`Repair::SaveMetadata`].
+pub fn repair(indexer: &mut SimpleIndexer, report: &DoctorReport) -> IndexResult<Vec<String>> {
+    let mut done = Vec::new();
+    for repair in report.repairs() {
+        let outcome = match repair {
+            Repair::ResumeRun => match indexer.recover_interrupted_run()? {
+                Some(recovery) => {
+                    let remaining = recovery.remaining.len();
+                    if remaining > 0 {
+                        indexer.index_files(recovery.remaining, false, false)?;
+                    }
+                    format!(
+                        "rolled back {} files and indexed {remaining}",
+                        recovery.rolled_back
+                    )
+                }
+                None => "nothing to resume".to_string(),
+            },
+            Repair::RemoveMissingFiles => {
+                let removed = indexer.remove_files(&report.missing_files)?;
+                format!("removed {removed} files")
+            }
+            Repair::DropDanglingRelationships => {
+                drop_relationships(indexer, &report.dangling_symbols)?;
+                format!(
+                    "dropped relationships of {} missing symbols",
+                    report.dangling_symbols.len()
+                )
+            }
+            Repair::DropOrphanEmbeddings => {
+                let dropped = if indexer.has_semantic_search() {
+                    indexer.drop_orphaned_embeddings()
+                } else {
+                    rewrite_semantic_storage(
+                        &indexer.index_dir().join("semantic"),
+                        &report.live_symbols,
+                    )?
+                };
+                format!("dropped {dropped} embeddings")
+            }
+            Repair::ReEmbed => {
+                if !indexer.has_semantic_search() {
+                    return Err(IndexError::General(
+                        "Re-embedding needs the embedding model; enable semantic search in settings.toml".to_string(),
+                    ));
+                }
+                let embedded = indexer.embed_missing_doc_comments()?;
+                format!("embedded {embedded} doc comments")
+            }
+            Repair::RebuildSymbolCache => {
+                indexer.build_symbol_cache()?;
+                "rebuilt".to_string()
+            }
+            Repair::SaveMetadata => "rewritten on save".to_string(),
+        };
+        done.push(format!("{}: {outcome}", repair.as_str()));
+    }
+    Ok(done)
+}
+
+fn drop_relationships(indexer: &mut SimpleIndexer, symbols: &[SymbolId]) -> IndexResult<()> {
+    if symbols.is_empty() {
+        return Ok(());
+    }
+    let tantivy_error =
+        |operation: &str, e: crate::storage::StorageError| IndexError::TantivyError {
+            operation: operation.to_string(),
+            cause: e.to_string(),
+        };
+    let document_index = indexer.document_index();
+    document_index
+        .start_batch()
+        .map_err(|e| tantivy_error("start_batch", e))?;
+    for id in symbols {
+        document_index
+            .delete_relationships_for_symbol(*id)
+            .map_err(|e| tantivy_error("delete_relationships_for_symbol", e))?;
+    }
+    document_index
+        .commit_batch()
+        .map_err(|e| tantivy_error("commit_batch", e))
+}
+
+/// Rewrite the stored vectors keeping only `live_symbols`, without loading
+/// the embedding model
+///
+/// Returns the number of vectors dropped, superseded copies included.
+pub(crate) fn rewrite_semantic_storage(
+    semantic_dir: &Path,
+    live_symbols: &HashSet<SymbolId>,
+) -> IndexResult<usize> {
+    let semantic_error = |e: crate::semantic::SemanticSearchError| {
+        IndexError::General(format!("Failed to rewrite semantic data: {e}"))
+    };
+
+    let mut metadata = SemanticMetadata::load(semantic_dir).map_err(semantic_error)?;
+    let mut storage = SemanticVectorStorage::open(semantic_dir).map_err(semantic_error)?;
+    let before = storage.embedding_count();
+    let kept = storage
+        .retain(|id| live_symbols.contains(&id))
+        .map_err(semantic_error)?;
+    drop(storage);
+    metadata.update(kept);
+    metadata.save(semantic_dir).map_err(semantic_error)?;
+
+    // Language mappings are keyed by symbol ID as well
+    let languages_path = semantic_dir.join("languages.json");
+    if let Ok(json) = std::fs::read_to_string(&languages_path) {
+        if let Ok(mut languages) = serde_json::from_str::<HashMap<u32, String>>(&json) {
+            languages
+                .retain(|id, _| SymbolId::new(*id).is_some_and(|id| live_symbols.contains(&id)));
+            let json = serde_json::to_string(&languages)
+                .map_err(|e| IndexError::General(format!("Failed to serialize languages: {e}")))?;
+            std::fs::write(&languages_path, json).map_err(|e| IndexError::FileWrite {
+                path: languages_path.clone(),
+                source: e,
+            })?;
+        }
+    }
+
+    Ok(before.saturating_sub(kept))
+}
+
+fn sample(items: &[String]) -> String {
+    let mut listed = items
+        .iter()
+        .take(SAMPLE_SIZE)
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(", ");
+    if items.len() > SAMPLE_SIZE {
+        listed.push_str(", ...");
+    }
+    listed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Settings;
+    use std::fs;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    fn indexed(root: &Path) -> SimpleIndexer {
+        let settings = Arc::new(Settings {
+            index_path: root.join("index"),
+            workspace_root: Some(root.to_path_buf()),
+            ..Settings::default()
+        });
+        let mut indexer = SimpleIndexer::with_settings(settings);
+        let files = vec![root.join("util.py"), root.join("app.py")];
+        indexer.index_files(files, false, false).unwrap();
+        indexer.finish_index_journal().unwrap();
+        crate::storage::IndexPersistence::new(root.join("index"))
+            .save(&indexer)
+            .unwrap();
+        indexer
+    }
+
+    fn problems(report: &DoctorReport) -> Vec<&'static str> {
+        report
+            .checks
+            .iter()
+            .filter(|c| c.status == CheckStatus::Problem)
+            .map(|c| c.name)
+            .collect()
+    }
+
+    #[test]
+    fn test_deleted_file_is_found_and_removed() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join("util.py"), "def helper():\n    return 1\n").unwrap();
+        fs::write(
+            root.join("app.py"),
+            "from util import helper\n\ndef run():\n    helper()\n",
+        )
+        .unwrap();
+        let mut indexer = indexed(root);
+
+        let report = check(&indexer).unwrap();
+        assert!(report.is_healthy(), "{:?}", report.checks);
+
+        fs::remove_file(root.join("util.py")).unwrap();
+        let report = check(&indexer).unwrap();
+        assert_eq!(problems(&report), vec!["files"]);
+        assert_eq!(report.repairs(), vec![Repair::RemoveMissingFiles]);
+
+        repair(&mut indexer, &report).unwrap();
+        let report = check(&indexer).unwrap();
+        // run() still calls the removed helper()
+        assert_eq!(problems(&report), vec!["metadata", "relationships"]);
+
+        repair(&mut indexer, &report).unwrap();
+        crate::storage::IndexPersistence::new(root.join("index"))
+            .save(&indexer)
+            .unwrap();
+        let report = check(&indexer).unwrap();
+        assert!(report.is_healthy(), "{:?}", report.checks);
+        assert!(indexer.find_symbols_by_name("helper", None).is_empty());
+    }
+
+    #[test]
+    fn test_stale_symbol_cache_is_rebuilt() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join("util.py"), "def helper():\n    return 1\n").unwrap();
+        fs::write(root.join("app.py"), "def run():\n    return 2\n").unwrap();
+        let mut indexer = indexed(root);
+
+        fs::remove_file(root.join("index").join("symbol_cache.bin")).unwrap();
+        let report = check(&indexer).unwrap();
+        assert_eq!(report.repairs(), vec![Repair::RebuildSymbolCache]);
+
+        repair(&mut indexer, &report).unwrap();
+        assert!(check(&indexer).unwrap().is_healthy());
+    }
+
+    #[test]
+    fn test_orphaned_embeddings_are_dropped_from_disk() {
+        use crate::vector::VectorDimension;
+
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join("util.py"), "def helper():\n    return 1\n").unwrap();
+        fs::write(root.join("app.py"), "def run():\n    return 2\n").unwrap();
+        let mut indexer = indexed(root);
+
+        let live = indexer.find_symbols_by_name("helper", None)[0].id;
+        let orphan = SymbolId::new(9999).unwrap();
+        let semantic_dir = root.join("index").join("semantic");
+        fs::create_dir_all(&semantic_dir).unwrap();
+        let mut storage =
+            SemanticVectorStorage::new(&semantic_dir, VectorDimension::new(4).unwrap()).unwrap();
+        storage
+            .save_batch(&[(live, vec![1.0; 4]), (orphan, vec![0.5; 4])])
+            .unwrap();
+        SemanticMetadata::new("test".to_string(), 4, 2)
+            .save(&semantic_dir)
+            .unwrap();
+
+        let report = check(&indexer).unwrap();
+        assert_eq!(report.repairs(), vec![Repair::DropOrphanEmbeddings]);
+
+        repair(&mut indexer, &report).unwrap();
+        assert!(check(&indexer).unwrap().is_healthy());
+        let stored = SemanticVectorStorage::open(&semantic_dir)
+            .unwrap()
+            .load_all()
+            .unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].0, live);
+        assert_eq!(
+            SemanticMetadata::load(&semantic_dir)
+                .unwrap()
+                .embedding_count,
+            1
+        );
+    }
+}
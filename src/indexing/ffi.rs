@@ -0,0 +1,664 @@
+//! Cross-language FFI linking
+//!
+//! Rust functions exported through PyO3, wasm-bindgen, napi-rs, JNI or
+//! `extern "C"` are called from other languages under a foreign-visible name,
+//! so ordinary resolution never connects the two sides. After relationship
+//! resolution the indexer links each export to
+//!
+//! - declarations of it in a caller language: Kotlin/Java `external`/`native`
+//!   functions, C# `[DllImport]`/`[LibraryImport]` methods, and functions in
+//!   `.pyi`/`.d.ts` stubs
+//! - call sites in a caller language that stayed unresolved and whose callee
+//!   comes from an import (or, for C and cgo, from the flat linker namespace)
+//!
+//! with `Calls` relationships whose context records the binding mechanism,
+//! e.g. `ffi:pyo3`.
+
+use crate::parsing::Import;
+use crate::parsing::route::{arguments, keyword_argument, string_literal};
+use crate::parsing::test_detection::file_name;
+use crate::{Symbol, SymbolId, SymbolKind};
+use std::collections::{HashMap, HashSet};
+
+/// Annotations that can mark a Rust function as exported
+pub const EXPORT_ANNOTATIONS: [&str; 7] = [
+    "pyfunction",
+    "pymodule",
+    "wasm_bindgen",
+    "napi",
+    "no_mangle",
+    "export_name",
+    "unsafe",
+];
+
+/// Context key recording the binding mechanism of a linked call
+pub const CONTEXT_KEY: &str = "ffi";
+
+/// How a Rust function is made callable from another language
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FfiMechanism {
+    /// `#[pyfunction]` called from Python
+    PyO3,
+    /// `#[wasm_bindgen]` called from JavaScript/TypeScript
+    WasmBindgen,
+    /// `#[napi]` called from Node.js
+    Napi,
+    /// `Java_<class>_<method>` symbols behind `external`/`native` declarations
+    Jni,
+    /// Other `#[no_mangle]`/`#[export_name]` symbols
+    ExternC,
+}
+
+impl FfiMechanism {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::PyO3 => "pyo3",
+            Self::WasmBindgen => "wasm-bindgen",
+            Self::Napi => "napi",
+            Self::Jni => "jni",
+            Self::ExternC => "extern-c",
+        }
+    }
+
+    /// Languages that call Rust through this mechanism
+    pub fn caller_languages(self) -> &'static [&'static str] {
+        match self {
+            Self::PyO3 => &["python"],
+            Self::WasmBindgen | Self::Napi => &["typescript", "javascript"],
+            Self::Jni => &["kotlin", "java"],
+            Self::ExternC => &["c", "cpp", "go", "csharp"],
+        }
+    }
+
+    pub fn accepts(self, language: &str) -> bool {
+        self.caller_languages().contains(&language)
+    }
+}
+
+/// Whether code in `language` can call Rust through any mechanism
+pub fn is_caller_language(language: &str) -> bool {
+    [
+        FfiMechanism::PyO3,
+        FfiMechanism::WasmBindgen,
+        FfiMechanism::Jni,
+        FfiMechanism::ExternC,
+    ]
+    .iter()
+    .any(|mechanism| mechanism.accepts(language))
+}
+
+/// A Rust function as seen from another language
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FfiExport {
+    pub symbol_id: SymbolId,
+    /// Name foreign code calls it by
+    pub name: String,
+    /// Declaring class of a JNI export, e.g. `com.example.Native`
+    pub class: Option<String>,
+    pub mechanism: FfiMechanism,
+}
+
+/// Value of `key = value` in attribute arguments, without quotes
+fn named_value(args: &str, key: &str) -> Option<String> {
+    let value = keyword_argument(&arguments(args), key)?;
+    string_literal(value).or_else(|| {
+        let ident = value.trim();
+        (!ident.is_empty() && ident.chars().all(|c| c.is_alphanumeric() || c == '_'))
+            .then(|| ident.to_string())
+    })
+}
+
+/// `sum_numbers` -> `sumNumbers`, the name napi-rs exports functions under
+fn camel_case(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+    let mut upper = false;
+    for c in name.chars() {
+        if c == '_' && !result.is_empty() {
+            upper = true;
+        } else if upper {
+            result.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// Split a JNI symbol into class and method
+///
+/// `Java_com_example_Native_nativeAdd` is `com.example.Native` and
+/// `nativeAdd`; `_1` escapes an underscore and `__` starts the overload
+/// signature.
+pub fn parse_jni_symbol(symbol: &str) -> Option<(String, String)> {
+    let mangled = symbol.strip_prefix("Java_")?;
+    let mangled = mangled.split("__").next().unwrap_or(mangled);
+    let mut qualified = String::with_capacity(mangled.len());
+    let mut chars = mangled.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '_' {
+            qualified.push(c);
+            continue;
+        }
+        match chars.peek() {
+            Some('1') => {
+                chars.next();
+                qualified.push('_');
+            }
+            Some('2') => {
+                chars.next();
+                qualified.push(';');
+            }
+            Some('3') => {
+                chars.next();
+                qualified.push('[');
+            }
+            _ => qualified.push('.'),
+        }
+    }
+    let (class, method) = qualified.rsplit_once('.')?;
+    (!class.is_empty() && !method.is_empty()).then(|| (class.to_string(), method.to_string()))
+}
+
+/// Export of an unmangled linker symbol: JNI if it follows the JNI naming scheme
+fn linker_export(symbol: &Symbol, name: String) -> FfiExport {
+    match parse_jni_symbol(&name) {
+        Some((class, method)) => FfiExport {
+            symbol_id: symbol.id,
+            name: method,
+            class: Some(class),
+            mechanism: FfiMechanism::Jni,
+        },
+        None => FfiExport {
+            symbol_id: symbol.id,
+            name,
+            class: None,
+            mechanism: FfiMechanism::ExternC,
+        },
+    }
+}
+
+/// Foreign-visible exports of a Rust function, read from its attributes
+pub fn exports_of(symbol: &Symbol) -> Vec<FfiExport> {
+    if !matches!(symbol.kind, SymbolKind::Function | SymbolKind::Method) {
+        return Vec::new();
+    }
+    let rust_name: &str = &symbol.name;
+    // `#[pyo3(name = "...")]` renames a `#[pyfunction]`
+    let pyo3_name = symbol
+        .annotations
+        .iter()
+        .filter(|a| a.short_name() == "pyo3")
+        .find_map(|a| named_value(a.arguments.as_deref().unwrap_or_default(), "name"));
+
+    let export = |name: String, mechanism| FfiExport {
+        symbol_id: symbol.id,
+        name,
+        class: None,
+        mechanism,
+    };
+    let mut exports = Vec::new();
+    for annotation in &symbol.annotations {
+        let args = annotation.arguments.as_deref().unwrap_or_default();
+        match annotation.short_name() {
+            "pyfunction" => {
+                let name = named_value(args, "name")
+                    .or_else(|| pyo3_name.clone())
+                    .unwrap_or_else(|| rust_name.to_string());
+                exports.push(export(name, FfiMechanism::PyO3));
+            }
+            "wasm_bindgen" => {
+                let name = named_value(args, "js_name").unwrap_or_else(|| rust_name.to_string());
+                exports.push(export(name, FfiMechanism::WasmBindgen));
+            }
+            "napi" => {
+                let name = named_value(args, "js_name").unwrap_or_else(|| camel_case(rust_name));
+                exports.push(export(name, FfiMechanism::Napi));
+            }
+            "no_mangle" => exports.push(linker_export(symbol, rust_name.to_string())),
+            "export_name" => {
+                if let Some(name) = string_literal(args) {
+                    exports.push(linker_export(symbol, name));
+                }
+            }
+            // Edition 2024 spells these `#[unsafe(no_mangle)]` and `#[unsafe(export_name = "..")]`
+            "unsafe" => {
+                let inner = args.trim();
+                if inner == "no_mangle" {
+                    exports.push(linker_export(symbol, rust_name.to_string()));
+                } else if let Some(name) = named_value(inner, "export_name") {
+                    exports.push(linker_export(symbol, name));
+                }
+            }
+            _ => {}
+        }
+    }
+    exports
+}
+
+/// Name of the Python extension module a `#[pymodule]` function defines
+pub fn python_module_name(symbol: &Symbol) -> Option<String> {
+    let module = symbol
+        .annotations
+        .iter()
+        .find(|a| a.short_name() == "pymodule")?;
+    let renamed = |annotation: &crate::parsing::Annotation| {
+        named_value(annotation.arguments.as_deref().unwrap_or_default(), "name")
+    };
+    let name = renamed(module)
+        .or_else(|| {
+            symbol
+                .annotations
+                .iter()
+                .filter(|a| a.short_name() == "pyo3")
+                .find_map(renamed)
+        })
+        .unwrap_or_else(|| symbol.name.to_string());
+    Some(name)
+}
+
+/// Whether `symbol` declares a foreign function bound through `mechanism`
+pub fn is_foreign_declaration(symbol: &Symbol, mechanism: FfiMechanism) -> bool {
+    let Some(language) = symbol.language_id.map(|id| id.as_str()) else {
+        return false;
+    };
+    if !mechanism.accepts(language)
+        || !matches!(symbol.kind, SymbolKind::Function | SymbolKind::Method)
+    {
+        return false;
+    }
+    let path = symbol.file_path.as_ref();
+    match mechanism {
+        FfiMechanism::Jni => symbol.signature.as_deref().is_some_and(|signature| {
+            signature
+                .split(|c: char| !c.is_alphanumeric())
+                .any(|word| word == "external" || word == "native")
+        }),
+        FfiMechanism::ExternC => symbol.annotations.iter().any(|a| {
+            matches!(
+                a.short_name(),
+                "DllImport" | "DllImportAttribute" | "LibraryImport" | "LibraryImportAttribute"
+            )
+        }),
+        FfiMechanism::PyO3 => path.ends_with(".pyi"),
+        FfiMechanism::WasmBindgen | FfiMechanism::Napi => path.ends_with(".d.ts"),
+    }
+}
+
+/// Whether a JNI declaration belongs to the export's class
+///
+/// The class is matched by its short name against the declaring file
+/// (`Native.kt`) or by its package against the module path.
+fn declares_jni_class(symbol: &Symbol, class: &str) -> bool {
+    let (package, short) = class.rsplit_once('.').unwrap_or(("", class));
+    let stem = file_name(&symbol.file_path)
+        .split('.')
+        .next()
+        .unwrap_or_default();
+    stem == short
+        || symbol.module_path.as_deref().is_some_and(|module| {
+            let module = module.replace("::", ".");
+            module == class || (!package.is_empty() && module == package)
+        })
+}
+
+/// An unresolved call in a caller language, kept for FFI linking
+#[derive(Debug, Clone)]
+pub struct ForeignCall {
+    pub from_ids: Vec<SymbolId>,
+    pub language: &'static str,
+    pub file_id: crate::FileId,
+    /// Called name as recorded, possibly qualified
+    pub name: String,
+    pub receiver: Option<String>,
+    pub metadata: Option<crate::relationship::RelationshipMetadata>,
+}
+
+impl ForeignCall {
+    /// Called name without its qualifier
+    pub fn short_name(&self) -> &str {
+        self.name
+            .rsplit(['.', ':'])
+            .next()
+            .unwrap_or(self.name.as_str())
+    }
+
+    /// Receiver or qualifier the name was called on (`mylib` in `mylib.add`)
+    fn qualifier(&self) -> Option<&str> {
+        self.receiver.as_deref().or_else(|| {
+            let short = self.short_name();
+            let prefix = self.name[..self.name.len() - short.len()].trim_end_matches(['.', ':']);
+            (!prefix.is_empty()).then_some(prefix)
+        })
+    }
+}
+
+/// Last segment of an import path (`./pkg/mylib` -> `mylib`, `a.b` -> `b`)
+fn import_tail(path: &str) -> &str {
+    path.rsplit(['.', '/', ':'])
+        .find(|segment| !segment.is_empty())
+        .unwrap_or(path)
+}
+
+/// Exports of the indexed Rust code, by foreign-visible name
+#[derive(Debug, Default)]
+pub struct ExportTable {
+    by_name: HashMap<String, Vec<FfiExport>>,
+    python_modules: HashSet<String>,
+}
+
+impl ExportTable {
+    /// Collect the exports of `symbols`, ignoring non-Rust symbols
+    pub fn new(symbols: &[Symbol]) -> Self {
+        let mut table = Self::default();
+        let mut seen = HashSet::new();
+        for symbol in symbols {
+            if symbol.language_id.map(|id| id.as_str()) != Some("rust")
+                || !seen.insert(symbol.id.value())
+            {
+                continue;
+            }
+            if let Some(module) = python_module_name(symbol) {
+                table.python_modules.insert(module);
+            }
+            for export in exports_of(symbol) {
+                table
+                    .by_name
+                    .entry(export.name.clone())
+                    .or_default()
+                    .push(export);
+            }
+        }
+        table
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_name.is_empty()
+    }
+
+    pub fn exports(&self) -> impl Iterator<Item = &FfiExport> {
+        self.by_name.values().flatten()
+    }
+
+    /// Mechanism through which code in `language` calls the export `symbol_id`
+    pub fn mechanism(&self, symbol_id: SymbolId, language: &str) -> Option<FfiMechanism> {
+        self.exports()
+            .find(|e| e.symbol_id == symbol_id && e.mechanism.accepts(language))
+            .map(|e| e.mechanism)
+    }
+
+    /// Whether any export could be called by this name
+    pub fn has_name(&self, name: &str) -> bool {
+        self.by_name.contains_key(name)
+    }
+
+    /// Declarations of `export` among same-named symbols of other languages
+    pub fn declarations<'a>(
+        &self,
+        export: &FfiExport,
+        candidates: &'a [Symbol],
+    ) -> Vec<&'a Symbol> {
+        let declarations: Vec<&Symbol> = candidates
+            .iter()
+            .filter(|s| is_foreign_declaration(s, export.mechanism))
+            .collect();
+        // Several classes may declare a native method of the same name
+        match &export.class {
+            Some(class) if declarations.len() > 1 => {
+                let matching: Vec<&Symbol> = declarations
+                    .iter()
+                    .copied()
+                    .filter(|s| declares_jni_class(s, class))
+                    .collect();
+                if matching.is_empty() {
+                    declarations
+                } else {
+                    matching
+                }
+            }
+            _ => declarations,
+        }
+    }
+
+    /// Export an unresolved call reaches, if exactly one matches
+    ///
+    /// The callee must be in scope through an import of the calling file:
+    /// imported by name, or called on an imported module or namespace. PyO3
+    /// imports must also name a `#[pymodule]` when the index has one. C and
+    /// C++ share the linker's flat namespace, and cgo calls go through `C.`.
+    pub fn match_call(&self, call: &ForeignCall, imports: &[Import]) -> Option<&FfiExport> {
+        let name = call.short_name();
+        let qualifier = call.qualifier();
+        let imported: Vec<&Import> = imports
+            .iter()
+            .filter(|import| {
+                let scope = qualifier.unwrap_or(name);
+                import.alias.as_deref() == Some(scope) || import_tail(&import.path) == scope
+            })
+            .collect();
+
+        let mut matches = self.by_name.get(name)?.iter().filter(|export| {
+            if !export.mechanism.accepts(call.language) {
+                return false;
+            }
+            match export.mechanism {
+                FfiMechanism::Jni => false,
+                FfiMechanism::ExternC => match call.language {
+                    "c" | "cpp" => qualifier.is_none(),
+                    "go" => qualifier == Some("C"),
+                    _ => false,
+                },
+                FfiMechanism::PyO3 => imported.iter().any(|import| {
+                    self.python_modules.is_empty()
+                        || import
+                            .path
+                            .split('.')
+                            .any(|segment| self.python_modules.contains(segment))
+                }),
+                FfiMechanism::WasmBindgen | FfiMechanism::Napi => !imported.is_empty(),
+            }
+        });
+        let first = matches.next()?;
+        matches
+            .all(|other| other.symbol_id == first.symbol_id)
+            .then_some(first)
+    }
+}
+
+/// Append `ffi:<mechanism>` to a relationship context
+pub fn with_mechanism(context: Option<&str>, mechanism: FfiMechanism) -> String {
+    let entry = format!("{CONTEXT_KEY}:{}", mechanism.as_str());
+    match context {
+        Some(context) if !context.is_empty() => format!("{context},{entry}"),
+        _ => entry,
+    }
+}
+
+/// Binding mechanism recorded in a relationship context, e.g. `pyo3`
+pub fn mechanism_in(context: &str) -> Option<&str> {
+    context
+        .split(',')
+        .find_map(|part| part.trim().strip_prefix(CONTEXT_KEY)?.strip_prefix(':'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::{Annotation, LanguageId};
+    use crate::{FileId, Range};
+
+    fn symbol(id: u32, name: &str, language: &'static str, annotations: &[&str]) -> Symbol {
+        let mut symbol = Symbol::new(
+            SymbolId::new(id).unwrap(),
+            name,
+            SymbolKind::Function,
+            FileId::new(1).unwrap(),
+            Range::new(1, 0, 3, 0),
+        )
+        .with_language_id(LanguageId::new(language));
+        symbol.annotations = annotations
+            .iter()
+            .filter_map(|a| Annotation::parse(a))
+            .collect();
+        symbol
+    }
+
+    fn call(language: &'static str, name: &str, receiver: Option<&str>) -> ForeignCall {
+        ForeignCall {
+            from_ids: vec![SymbolId::new(99).unwrap()],
+            language,
+            file_id: FileId::new(2).unwrap(),
+            name: name.to_string(),
+            receiver: receiver.map(str::to_string),
+            metadata: None,
+        }
+    }
+
+    fn import(path: &str, alias: Option<&str>) -> Import {
+        Import {
+            path: path.to_string(),
+            alias: alias.map(str::to_string),
+            file_id: FileId::new(2).unwrap(),
+            is_glob: false,
+            is_type_only: false,
+        }
+    }
+
+    #[test]
+    fn test_exports_from_attributes() {
+        let names = |s: &Symbol| -> Vec<(String, &'static str)> {
+            exports_of(s)
+                .into_iter()
+                .map(|e| (e.name, e.mechanism.as_str()))
+                .collect()
+        };
+
+        let py = symbol(
+            1,
+            "add_numbers",
+            "rust",
+            &["pyfunction", "pyo3(name = \"add\")"],
+        );
+        assert_eq!(names(&py), vec![("add".to_string(), "pyo3")]);
+
+        let napi = symbol(2, "sum_numbers", "rust", &["napi"]);
+        assert_eq!(names(&napi), vec![("sumNumbers".to_string(), "napi")]);
+
+        let wasm = symbol(3, "greet", "rust", &["wasm_bindgen(js_name = sayHello)"]);
+        assert_eq!(names(&wasm), vec![("sayHello".to_string(), "wasm-bindgen")]);
+
+        let c = symbol(4, "codec_encode", "rust", &["unsafe(no_mangle)"]);
+        assert_eq!(names(&c), vec![("codec_encode".to_string(), "extern-c")]);
+
+        let jni = symbol(
+            5,
+            "Java_com_example_Native_nativeAdd",
+            "rust",
+            &["no_mangle"],
+        );
+        let export = &exports_of(&jni)[0];
+        assert_eq!(export.mechanism, FfiMechanism::Jni);
+        assert_eq!(export.name, "nativeAdd");
+        assert_eq!(export.class.as_deref(), Some("com.example.Native"));
+    }
+
+    #[test]
+    fn test_parse_jni_symbol() {
+        assert_eq!(
+            parse_jni_symbol("Java_org_my_1app_Bridge_run__I"),
+            Some(("org.my_app.Bridge".to_string(), "run".to_string()))
+        );
+        assert_eq!(parse_jni_symbol("codec_encode"), None);
+    }
+
+    #[test]
+    fn test_call_matching_requires_import_evidence() {
+        let table = ExportTable::new(&[
+            symbol(1, "core", "rust", &["pymodule"]),
+            symbol(2, "add", "rust", &["pyfunction"]),
+            symbol(3, "sum_numbers", "rust", &["napi"]),
+            symbol(4, "codec_encode", "rust", &["no_mangle"]),
+        ]);
+
+        let module_call = call("python", "add", Some("core"));
+        assert!(
+            table
+                .match_call(&module_call, &[import("core", None)])
+                .is_some()
+        );
+        let from_import = call("python", "add", None);
+        assert!(
+            table
+                .match_call(&from_import, &[import("core.add", None)])
+                .is_some()
+        );
+        // `add` from some other package is not the extension module's `add`
+        assert!(
+            table
+                .match_call(&from_import, &[import("utils.add", None)])
+                .is_none()
+        );
+        assert!(table.match_call(&from_import, &[]).is_none());
+
+        let ts = call("typescript", "sumNumbers", None);
+        assert!(
+            table
+                .match_call(&ts, &[import("./native", Some("sumNumbers"))])
+                .is_some()
+        );
+        assert!(table.match_call(&ts, &[]).is_none());
+
+        assert!(
+            table
+                .match_call(&call("c", "codec_encode", None), &[])
+                .is_some()
+        );
+        assert!(
+            table
+                .match_call(&call("go", "codec_encode", Some("C")), &[])
+                .is_some()
+        );
+        assert!(
+            table
+                .match_call(&call("python", "codec_encode", None), &[])
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_mechanism_context() {
+        let context = with_mechanism(Some("receiver:corelib,static:false"), FfiMechanism::PyO3);
+        assert_eq!(context, "receiver:corelib,static:false,ffi:pyo3");
+        assert_eq!(mechanism_in(&context), Some("pyo3"));
+        assert_eq!(with_mechanism(None, FfiMechanism::Jni), "ffi:jni");
+        assert_eq!(mechanism_in("function_call"), None);
+    }
+
+    #[test]
+    fn test_declarations() {
+        let table = ExportTable::new(&[symbol(
+            1,
+            "Java_com_example_Native_nativeAdd",
+            "rust",
+            &["no_mangle"],
+        )]);
+        let export = table.exports().next().unwrap().clone();
+
+        let mut external = symbol(10, "nativeAdd", "kotlin", &[]);
+        external.signature = Some("external fun nativeAdd(a: Int, b: Int): Int".into());
+        external.file_path = "app/src/main/kotlin/com/example/Native.kt".into();
+        let mut other = external.clone();
+        other.id = SymbolId::new(11).unwrap();
+        other.file_path = "app/src/main/kotlin/com/example/Legacy.kt".into();
+        let mut plain = symbol(12, "nativeAdd", "kotlin", &[]);
+        plain.signature = Some("fun nativeAdd(a: Int, b: Int): Int".into());
+
+        let candidates = [external, other, plain];
+        let ids: Vec<u32> = table
+            .declarations(&export, &candidates)
+            .iter()
+            .map(|s| s.id.value())
+            .collect();
+        assert_eq!(ids, vec![10]);
+    }
+}
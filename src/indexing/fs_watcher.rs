@@ -14,6 +14,7 @@ use thiserror::Error;
 use tokio::sync::{RwLock, mpsc};
 use tokio::time::{Duration, sleep};
 
+use crate::indexing::IndexWriteLock;
 use crate::mcp::notifications::{FileChangeEvent, NotificationBroadcaster};
 use crate::{IndexError, IndexResult, SimpleIndexer};
 
@@ -226,6 +227,8 @@ impl FileSystemWatcher {
 
         // 5. Set up debouncing state
         let mut pending_changes: HashMap<PathBuf, Instant> = HashMap::new();
+        let mut pending_removals: HashSet<PathBuf> = HashSet::new();
+        let mut lock_wait_reported = false;
         let debounce_duration = Duration::from_millis(self.debounce_ms);
 
         // 6. Subscribe to broadcast notifications if broadcaster is available
@@ -256,44 +259,11 @@ impl FileSystemWatcher {
                                             pending_changes.insert(path.clone(), Instant::now());
                                         }
                                         EventKind::Remove(_) => {
-                                            // File was deleted - remove it from index immediately
+                                            // File was deleted - remove it from index on the next pass
                                             let path_display = path.display();
                                             eprintln!("Detected deletion of indexed file: {path_display}");
-                                            eprintln!("  Removing from index...");
-
-                                            // Convert absolute path to relative path for the index
-                                            let relative_path = if path.is_absolute() {
-                                                if let Ok(cwd) = std::env::current_dir() {
-                                                    match path.strip_prefix(&cwd) {
-                                                        Ok(rel) => rel.to_path_buf(),
-                                                        Err(_) => path.clone(),
-                                                    }
-                                                } else {
-                                                    path.clone()
-                                                }
-                                            } else {
-                                                path.clone()
-                                            };
-
-                                            let relative_display = relative_path.display();
-                                            eprintln!("  Using relative path: {relative_display}");
-
-                                            let mut indexer = self.indexer.write().await;
-                                            if let Err(e) = indexer.remove_file(&relative_path) {
-                                                eprintln!("  ✗ Failed to remove from index: {e}");
-                                            } else {
-                                                eprintln!("  ✓ Removed from index successfully");
-
-                                                // Send notification to MCP clients
-                                                if let Some(ref broadcaster) = self.broadcaster {
-                                                    if self.mcp_debug {
-                                                        eprintln!("DEBUG: Sending FileDeleted notification for: {}", path.display());
-                                                    }
-                                                    broadcaster.send(FileChangeEvent::FileDeleted {
-                                                        path: path.clone(),
-                                                    });
-                                                }
-                                            }
+                                            pending_changes.remove(path);
+                                            pending_removals.insert(path.clone());
                                         }
                                         _ => {} // Ignore other event types
                                     }
@@ -321,50 +291,37 @@ impl FileSystemWatcher {
                         }
                     });
 
-                    // Process debounced files
-                    for path in files_to_process {
-                        eprintln!("Detected change in indexed file: {}", path.display());
-                        eprintln!("  Re-indexing...");
-
-                        eprintln!("  Using absolute path for file reading: {}", path.display());
-
-                        let mut indexer = self.indexer.write().await;
-                        match indexer.index_file(&path) {
-                            Ok(result) => {
-                                use crate::IndexingResult;
-                                match result {
-                                    IndexingResult::Indexed(_) => {
-                                        eprintln!("  ✓ Re-indexed successfully (file updated)");
-
-                                        // CRITICAL: Save semantic search data after re-indexing
-                                        if indexer.has_semantic_search() {
-                                            let semantic_path = self.index_path.join("semantic");
-                                            if let Err(e) = indexer.save_semantic_search(&semantic_path) {
-                                                eprintln!("  ✗ Failed to save semantic search after re-indexing: {e}");
-                                            } else {
-                                                eprintln!("  ✓ Semantic search saved successfully");
-                                            }
-                                        }
-
-                                        // Send notification if broadcaster is available
-                                        if let Some(ref broadcaster) = self.broadcaster {
-                                            if self.mcp_debug {
-                                                eprintln!("DEBUG: FileSystemWatcher sending notification for: {}", path.display());
-                                            }
-                                            broadcaster.send(FileChangeEvent::FileReindexed {
-                                                path: path.clone(),
-                                            });
-                                        } else if self.mcp_debug {
-                                            eprintln!("DEBUG: No broadcaster available to send notification");
-                                        }
-                                    }
-                                    IndexingResult::Cached(_) => {
-                                        eprintln!("  ✓ File unchanged (hash match, skipped)");
-                                    }
+                    // Another process writing the index keeps the changes
+                    // pending until it has saved its own
+                    if !files_to_process.is_empty() || !pending_removals.is_empty() {
+                        match IndexWriteLock::try_acquire(&self.index_path) {
+                            Ok(Some(_lock)) => {
+                                lock_wait_reported = false;
+                                for path in pending_removals.drain() {
+                                    self.remove_deleted_file(&path).await;
+                                }
+                                for path in files_to_process {
+                                    self.reindex_changed_file(&path).await;
+                                }
+                                if let Err(e) = self.indexer.read().await.release_writer() {
+                                    eprintln!("  ✗ Failed to release index writer: {e}");
                                 }
                             }
-                            Err(e) => {
-                                eprintln!("  ✗ Re-index failed: {e}");
+                            result => {
+                                if !lock_wait_reported {
+                                    match result {
+                                        Err(e) => eprintln!("  ✗ Could not lock the index for writing: {e}"),
+                                        _ => eprintln!(
+                                            "Index is being written by another process ({}); changes wait until it finishes",
+                                            IndexWriteLock::holder(&self.index_path)
+                                                .unwrap_or_else(|| "unknown process".to_string())
+                                        ),
+                                    }
+                                    lock_wait_reported = true;
+                                }
+                                for path in files_to_process {
+                                    pending_changes.insert(path, now);
+                                }
                             }
                         }
                     }
@@ -453,6 +410,102 @@ impl FileSystemWatcher {
         Ok(())
     }
 
+    /// Remove a deleted file from the index; the caller holds the write lock
+    async fn remove_deleted_file(&self, path: &Path) {
+        let path_display = path.display();
+        eprintln!("Removing deleted file from index: {path_display}");
+
+        // Convert absolute path to relative path for the index
+        let relative_path = if path.is_absolute() {
+            if let Ok(cwd) = std::env::current_dir() {
+                match path.strip_prefix(&cwd) {
+                    Ok(rel) => rel.to_path_buf(),
+                    Err(_) => path.to_path_buf(),
+                }
+            } else {
+                path.to_path_buf()
+            }
+        } else {
+            path.to_path_buf()
+        };
+
+        let relative_display = relative_path.display();
+        eprintln!("  Using relative path: {relative_display}");
+
+        let mut indexer = self.indexer.write().await;
+        if let Err(e) = indexer.remove_file(&relative_path) {
+            eprintln!("  ✗ Failed to remove from index: {e}");
+        } else {
+            eprintln!("  ✓ Removed from index successfully");
+
+            // Send notification to MCP clients
+            if let Some(ref broadcaster) = self.broadcaster {
+                if self.mcp_debug {
+                    eprintln!(
+                        "DEBUG: Sending FileDeleted notification for: {}",
+                        path.display()
+                    );
+                }
+                broadcaster.send(FileChangeEvent::FileDeleted {
+                    path: path.to_path_buf(),
+                });
+            }
+        }
+    }
+
+    /// Re-index a changed file; the caller holds the write lock
+    async fn reindex_changed_file(&self, path: &Path) {
+        eprintln!("Detected change in indexed file: {}", path.display());
+        eprintln!("  Re-indexing...");
+
+        eprintln!("  Using absolute path for file reading: {}", path.display());
+
+        let mut indexer = self.indexer.write().await;
+        match indexer.index_file(path) {
+            Ok(result) => {
+                use crate::IndexingResult;
+                match result {
+                    IndexingResult::Indexed(_) => {
+                        eprintln!("  ✓ Re-indexed successfully (file updated)");
+
+                        // CRITICAL: Save semantic search data after re-indexing
+                        if indexer.has_semantic_search() {
+                            let semantic_path = self.index_path.join("semantic");
+                            if let Err(e) = indexer.save_semantic_search(&semantic_path) {
+                                eprintln!(
+                                    "  ✗ Failed to save semantic search after re-indexing: {e}"
+                                );
+                            } else {
+                                eprintln!("  ✓ Semantic search saved successfully");
+                            }
+                        }
+
+                        // Send notification if broadcaster is available
+                        if let Some(ref broadcaster) = self.broadcaster {
+                            if self.mcp_debug {
+                                eprintln!(
+                                    "DEBUG: FileSystemWatcher sending notification for: {}",
+                                    path.display()
+                                );
+                            }
+                            broadcaster.send(FileChangeEvent::FileReindexed {
+                                path: path.to_path_buf(),
+                            });
+                        } else if self.mcp_debug {
+                            eprintln!("DEBUG: No broadcaster available to send notification");
+                        }
+                    }
+                    IndexingResult::Cached(_) => {
+                        eprintln!("  ✓ File unchanged (hash match, skipped)");
+                    }
+                }
+            }
+            Err(e) => {
+                eprintln!("  ✗ Re-index failed: {e}");
+            }
+        }
+    }
+
     /// Compute minimal set of directories to watch
     ///
     /// Given a list of file paths, returns the unique parent directories
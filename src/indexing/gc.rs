@@ -0,0 +1,353 @@
+//! Compaction behind `codanna index gc`
+//!
+//! Incremental updates leave garbage in every store of the index. Tantivy keeps
+//! deleted documents in their segments until a merge drops them, and import
+//! documents can outlive the file that declared them. The vector files only
+//! grow: a changed embedding is appended as a new copy, and vectors of removed
+//! symbols stay until the file is rewritten. [`collect`] removes all of it and
+//! reports the bytes each store gave back.
+//!
+//! Vector IDs are symbol IDs. Compaction keeps every surviving vector under its
+//! symbol's ID and collapses superseded copies into the latest one, so the
+//! `vector_id` fields of the Tantivy documents still point at the right vector.
+
+use crate::indexing::doctor;
+use crate::semantic::SemanticMetadata;
+use crate::storage::StorageError;
+use crate::vector::{MmapVectorStorage, SegmentOrdinal};
+use crate::{FileId, IndexError, IndexResult, SimpleIndexer, SymbolId};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// Bytes one store took before and after [`collect`]
+#[derive(Debug, Clone, Serialize)]
+pub struct StoreReport {
+    pub store: &'static str,
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+    pub detail: String,
+}
+
+impl StoreReport {
+    pub fn reclaimed(&self) -> u64 {
+        self.bytes_before.saturating_sub(self.bytes_after)
+    }
+}
+
+/// What [`collect`] removed, store by store
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GcReport {
+    pub stores: Vec<StoreReport>,
+    /// Files whose import documents were left behind
+    pub orphaned_import_files: usize,
+    /// Vectors of removed symbols and superseded copies
+    pub dropped_vectors: usize,
+}
+
+impl GcReport {
+    pub fn reclaimed(&self) -> u64 {
+        self.stores.iter().map(StoreReport::reclaimed).sum()
+    }
+}
+
+/// Compact every store of the index `indexer` has open
+///
+/// The caller holds the index write lock. Symbols, files and relationships
+/// stay as they are; only data nothing refers to is removed.
+pub fn collect(indexer: &mut SimpleIndexer) -> IndexResult<GcReport> {
+    let index_dir = indexer.index_dir();
+    let mut report = GcReport::default();
+
+    let tantivy = compact_tantivy(indexer, &index_dir, &mut report)?;
+    report.stores.push(tantivy);
+
+    let live_symbols = live_symbols(indexer)?;
+    let semantic_dir = index_dir.join("semantic");
+    if SemanticMetadata::exists(&semantic_dir) {
+        let bytes_before = dir_size(&semantic_dir);
+        let dropped = doctor::rewrite_semantic_storage(&semantic_dir, &live_symbols)?;
+        // A later save writes the embeddings held in memory back to disk
+        indexer.drop_orphaned_embeddings();
+        report.dropped_vectors += dropped;
+        report.stores.push(StoreReport {
+            store: "semantic",
+            bytes_before,
+            bytes_after: dir_size(&semantic_dir),
+            detail: format!("dropped {dropped} vectors"),
+        });
+    }
+
+    let vector_dir = index_dir.join("vectors");
+    if vector_dir.is_dir() {
+        let bytes_before = dir_size(&vector_dir);
+        let dropped = compact_vector_segments(&vector_dir, &live_symbols)?;
+        report.dropped_vectors += dropped;
+        report.stores.push(StoreReport {
+            store: "vectors",
+            bytes_before,
+            bytes_after: dir_size(&vector_dir),
+            detail: format!("dropped {dropped} vectors"),
+        });
+    }
+
+    let cache_path = index_dir.join("symbol_cache.bin");
+    if cache_path.exists() {
+        let bytes_before = file_size(&cache_path);
+        indexer.build_symbol_cache()?;
+        report.stores.push(StoreReport {
+            store: "symbol cache",
+            bytes_before,
+            bytes_after: file_size(&cache_path),
+            detail: format!("rebuilt for {} symbols", live_symbols.len()),
+        });
+    }
+
+    Ok(report)
+}
+
+/// Delete import documents of files that are gone, then merge the segments
+fn compact_tantivy(
+    indexer: &SimpleIndexer,
+    index_dir: &Path,
+    report: &mut GcReport,
+) -> IndexResult<StoreReport> {
+    let tantivy_dir = index_dir.join("tantivy");
+    let bytes_before = dir_size(&tantivy_dir);
+    let document_index = indexer.document_index();
+
+    let files: HashSet<FileId> = document_index
+        .query_file_info()
+        .map_err(|e| tantivy_error("query_file_info", e))?
+        .into_iter()
+        .map(|(file_id, ..)| file_id)
+        .collect();
+    let mut orphaned: Vec<FileId> = document_index
+        .query_import_file_ids()
+        .map_err(|e| tantivy_error("query_import_file_ids", e))?
+        .into_iter()
+        .filter(|file_id| !files.contains(file_id))
+        .collect();
+    orphaned.sort_by_key(|file_id| file_id.value());
+    if !orphaned.is_empty() {
+        document_index
+            .start_batch()
+            .map_err(|e| tantivy_error("start_batch", e))?;
+        for file_id in &orphaned {
+            document_index
+                .delete_imports_for_file(*file_id)
+                .map_err(|e| tantivy_error("delete_imports_for_file", e))?;
+        }
+        document_index
+            .commit_batch()
+            .map_err(|e| tantivy_error("commit_batch", e))?;
+    }
+    report.orphaned_import_files = orphaned.len();
+
+    let segments = document_index
+        .merge_segments()
+        .map_err(|e| tantivy_error("merge_segments", e))?;
+
+    let mut detail = if segments > 1 {
+        format!("merged {segments} segments")
+    } else {
+        "one segment".to_string()
+    };
+    if !orphaned.is_empty() {
+        detail.push_str(&format!(
+            ", removed imports of {} deleted files",
+            orphaned.len()
+        ));
+    }
+    Ok(StoreReport {
+        store: "tantivy",
+        bytes_before,
+        bytes_after: dir_size(&tantivy_dir),
+        detail,
+    })
+}
+
+fn live_symbols(indexer: &SimpleIndexer) -> IndexResult<HashSet<SymbolId>> {
+    let document_index = indexer.document_index();
+    let total = document_index
+        .count_symbols()
+        .map_err(|e| tantivy_error("count_symbols", e))?;
+    Ok(document_index
+        .get_all_symbols(total)
+        .map_err(|e| tantivy_error("get_all_symbols", e))?
+        .into_iter()
+        .map(|symbol| symbol.id)
+        .collect())
+}
+
+/// Rewrite each `segment_N.vec` of the vector engine without removed symbols
+fn compact_vector_segments(
+    vector_dir: &Path,
+    live_symbols: &HashSet<SymbolId>,
+) -> IndexResult<usize> {
+    let storage_error = |e: crate::vector::VectorStorageError| {
+        IndexError::General(format!("Failed to compact vector storage: {e}"))
+    };
+
+    let mut dropped = 0;
+    for entry in std::fs::read_dir(vector_dir).map_err(|e| IndexError::FileRead {
+        path: vector_dir.to_path_buf(),
+        source: e,
+    })? {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        let name = entry.file_name();
+        let Some(ordinal) = name
+            .to_str()
+            .and_then(|name| name.strip_prefix("segment_")?.strip_suffix(".vec"))
+            .and_then(|ordinal| ordinal.parse().ok())
+        else {
+            continue;
+        };
+
+        let mut storage = MmapVectorStorage::open(vector_dir, SegmentOrdinal::new(ordinal))
+            .map_err(storage_error)?;
+        let before = storage.vector_count();
+        let kept = storage
+            .compact(|vector_id| {
+                SymbolId::new(vector_id.get())
+                    .filter(|id| live_symbols.contains(id))
+                    .map(|_| vector_id)
+            })
+            .map_err(storage_error)?;
+        dropped += before.saturating_sub(kept);
+    }
+    Ok(dropped)
+}
+
+fn dir_size(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| entry.metadata().ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+fn file_size(path: &Path) -> u64 {
+    std::fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+fn tantivy_error(operation: &str, e: StorageError) -> IndexError {
+    IndexError::TantivyError {
+        operation: operation.to_string(),
+        cause: e.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Settings;
+    use crate::semantic::SemanticVectorStorage;
+    use crate::storage::IndexPersistence;
+    use crate::vector::VectorDimension;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    fn indexed(root: &Path) -> SimpleIndexer {
+        let settings = Settings {
+            index_path: root.join(".codanna/index"),
+            workspace_root: Some(root.to_path_buf()),
+            ..Settings::default()
+        };
+        let mut indexer = SimpleIndexer::with_settings(Arc::new(settings));
+        for (name, content) in [
+            ("a.rs", "use std::fmt;\n\npub fn alpha() {}\n"),
+            ("b.rs", "use std::io;\n\npub fn beta() {}\n"),
+        ] {
+            let path = root.join(name);
+            std::fs::write(&path, content).unwrap();
+            indexer.index_file(&path).unwrap();
+        }
+        IndexPersistence::new(indexer.index_dir())
+            .save(&indexer)
+            .unwrap();
+        indexer
+    }
+
+    #[test]
+    fn test_gc_merges_segments_and_drops_orphans() {
+        let temp = TempDir::new().unwrap();
+        let mut indexer = indexed(temp.path());
+        let index_dir = indexer.index_dir();
+
+        // Rewrite a file a few times so segments pile up with deleted documents
+        let path = temp.path().join("a.rs");
+        for n in 0..3 {
+            std::fs::write(&path, format!("use std::fmt;\n\npub fn alpha{n}() {{}}\n")).unwrap();
+            indexer.index_file(&path).unwrap();
+        }
+        let symbols = indexer.get_all_symbols();
+        let live = symbols[0].id;
+
+        // Vectors of a symbol that is gone, plus a superseded copy of a live one
+        let semantic_dir = index_dir.join("semantic");
+        let mut storage =
+            SemanticVectorStorage::new(&semantic_dir, VectorDimension::new(2).unwrap()).unwrap();
+        let gone = SymbolId::new(9_999).unwrap();
+        storage
+            .save_batch(&[(live, vec![0.0, 1.0]), (gone, vec![1.0, 0.0])])
+            .unwrap();
+        storage.save_batch(&[(live, vec![0.5, 0.5])]).unwrap();
+        drop(storage);
+        SemanticMetadata::new("AllMiniLML6V2".to_string(), 2, 3)
+            .save(&semantic_dir)
+            .unwrap();
+
+        // Import documents whose file is no longer indexed
+        let document_index = indexer.document_index();
+        document_index.start_batch().unwrap();
+        document_index
+            .store_import(&crate::parsing::Import {
+                path: "std::env".to_string(),
+                alias: None,
+                file_id: FileId::new(4_242).unwrap(),
+                is_glob: false,
+                is_type_only: false,
+            })
+            .unwrap();
+        document_index.commit_batch().unwrap();
+
+        let report = collect(&mut indexer).unwrap();
+        // Re-indexing a.rs dropped the imports of its earlier file IDs
+        assert_eq!(report.orphaned_import_files, 1);
+        assert_eq!(report.dropped_vectors, 2);
+        let tantivy = &report.stores[0];
+        assert_eq!(tantivy.store, "tantivy");
+        assert!(tantivy.bytes_after < tantivy.bytes_before);
+
+        let document_index = indexer.document_index();
+        assert!(
+            !document_index
+                .query_import_file_ids()
+                .unwrap()
+                .contains(&FileId::new(4_242).unwrap())
+        );
+        for (file_id, ..) in document_index.query_file_info().unwrap() {
+            assert_eq!(
+                document_index.get_imports_for_file(file_id).unwrap().len(),
+                1
+            );
+        }
+        assert_eq!(document_index.merge_segments().unwrap(), 1);
+        assert_eq!(indexer.get_all_symbols().len(), symbols.len());
+
+        let mut storage = SemanticVectorStorage::open(&semantic_dir).unwrap();
+        assert_eq!(storage.load_all().unwrap(), vec![(live, vec![0.5, 0.5])]);
+        assert_eq!(
+            SemanticMetadata::load(&semantic_dir)
+                .unwrap()
+                .embedding_count,
+            1
+        );
+    }
+}
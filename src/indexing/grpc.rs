@@ -0,0 +1,400 @@
+//! gRPC service linking
+//!
+//! RPCs declared in `.proto` schemas are implemented and called through code
+//! generated per language, so nothing in the sources names the schema
+//! directly. After relationship resolution the indexer links each RPC to
+//!
+//! - server implementations: Go, Rust (tonic) and TypeScript/JavaScript
+//!   methods named after the RPC whose signature takes its request message
+//!   (or the generated `<Service>_<Rpc>Server` stream)
+//! - client call sites: calls of the RPC's method name in files that create
+//!   the service client (`<Service>Client`, `createClient(<Service>, ...)`)
+//!
+//! Implementations get `Implements` relationships and call sites `Calls`
+//! relationships, with `grpc:server` or `grpc:client` in the context.
+//! Generated code (`*.pb.go`, `*_grpc.pb.go`, `*_pb.ts`, ...) is skipped.
+
+use crate::indexing::ffi::ForeignCall;
+use crate::parsing::protobuf::rpc_messages;
+use crate::parsing::test_detection::file_name;
+use crate::{Symbol, SymbolId, SymbolKind};
+use std::collections::HashMap;
+
+/// Context key recording the side of a linked RPC
+pub const CONTEXT_KEY: &str = "grpc";
+
+/// Languages with server and client code generation that codanna links
+pub const LINKED_LANGUAGES: [&str; 4] = ["go", "rust", "typescript", "javascript"];
+
+/// An RPC declared in a schema
+#[derive(Debug, Clone)]
+pub struct RpcDefinition {
+    pub symbol_id: SymbolId,
+    pub service: String,
+    pub name: String,
+    pub input: String,
+    pub output: String,
+}
+
+impl RpcDefinition {
+    /// Build from a `protobuf` Method symbol inside a service
+    pub fn from_symbol(symbol: &Symbol) -> Option<Self> {
+        if symbol.kind != SymbolKind::Method {
+            return None;
+        }
+        let (input, output) = rpc_messages(symbol.signature.as_deref()?)?;
+        let mut path = symbol.module_path.as_deref()?.rsplit('.');
+        let name = path.next()?;
+        let service = path.next()?;
+        Some(Self {
+            symbol_id: symbol.id,
+            service: service.to_string(),
+            name: name.to_string(),
+            input: input.to_string(),
+            output: output.to_string(),
+        })
+    }
+
+    /// Names the generated code gives this RPC in a language
+    ///
+    /// Go keeps the schema name, tonic uses snake_case and the TypeScript
+    /// generators use lowerCamelCase (grpc-js also keeps the schema name).
+    pub fn method_names(&self, language: &str) -> Vec<String> {
+        match language {
+            "go" => vec![self.name.clone()],
+            "rust" => vec![snake_case(&self.name)],
+            "typescript" | "javascript" => {
+                let camel = lower_camel_case(&self.name);
+                if camel == self.name {
+                    vec![camel]
+                } else {
+                    vec![camel, self.name.clone()]
+                }
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// `GetHTTPStatus` -> `get_http_status`, as prost/tonic name methods
+pub fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut out = String::with_capacity(name.len() + 4);
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() {
+            let prev = i.checked_sub(1).map(|p| chars[p]);
+            let next = chars.get(i + 1);
+            let boundary = prev.is_some_and(|p| p.is_lowercase() || p.is_ascii_digit())
+                || (prev.is_some_and(char::is_uppercase) && next.is_some_and(|n| n.is_lowercase()));
+            if boundary && !out.ends_with('_') {
+                out.push('_');
+            }
+            out.extend(c.to_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// `CreateOrder` -> `createOrder`
+fn lower_camel_case(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Whether a path looks like protoc/buf output rather than hand-written code
+pub fn is_generated_file(path: &str) -> bool {
+    let name = file_name(path);
+    name.contains(".pb.")
+        || name.contains("_pb.")
+        || name.contains("_grpc_pb")
+        || name.contains("_connect.")
+}
+
+/// Whether `text` contains `word` as a whole identifier
+fn mentions(text: &str, word: &str) -> bool {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    text.match_indices(word).any(|(start, _)| {
+        let before = text[..start].chars().next_back();
+        let after = text[start + word.len()..].chars().next();
+        !before.is_some_and(is_ident) && !after.is_some_and(is_ident)
+    })
+}
+
+/// RPCs of the indexed schemas, by generated method name
+#[derive(Debug, Default)]
+pub struct RpcTable {
+    rpcs: Vec<RpcDefinition>,
+    by_method: HashMap<String, Vec<usize>>,
+}
+
+impl RpcTable {
+    /// Collect RPCs from `protobuf` Method symbols
+    pub fn new(symbols: &[Symbol]) -> Self {
+        let mut table = Self::default();
+        for rpc in symbols.iter().filter_map(RpcDefinition::from_symbol) {
+            let index = table.rpcs.len();
+            for language in LINKED_LANGUAGES {
+                for name in rpc.method_names(language) {
+                    let entry = table.by_method.entry(name).or_default();
+                    if !entry.contains(&index) {
+                        entry.push(index);
+                    }
+                }
+            }
+            table.rpcs.push(rpc);
+        }
+        table
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rpcs.is_empty()
+    }
+
+    pub fn rpcs(&self) -> &[RpcDefinition] {
+        &self.rpcs
+    }
+
+    /// Whether some RPC is generated under this method name
+    pub fn has_method(&self, name: &str) -> bool {
+        self.by_method.contains_key(name)
+    }
+
+    /// RPCs generated as `name` in `language`
+    fn candidates(&self, name: &str, language: &str) -> Vec<&RpcDefinition> {
+        self.by_method
+            .get(name)
+            .into_iter()
+            .flatten()
+            .map(|&index| &self.rpcs[index])
+            .filter(|rpc| rpc.method_names(language).iter().any(|n| n == name))
+            .collect()
+    }
+
+    /// Narrow to a single RPC, using the service names mentioned in the file
+    fn unique<'a>(
+        candidates: Vec<&'a RpcDefinition>,
+        file_text: &str,
+    ) -> Option<&'a RpcDefinition> {
+        if candidates.len() <= 1 {
+            return candidates.into_iter().next();
+        }
+        let mut in_file = candidates
+            .into_iter()
+            .filter(|rpc| file_text.contains(rpc.service.as_str()));
+        let first = in_file.next()?;
+        in_file.next().is_none().then_some(first)
+    }
+
+    /// The RPC a symbol implements on the server side, if any
+    ///
+    /// `file_text` is the source of the symbol's file, used to tell apart
+    /// services that declare RPCs with the same name and request.
+    pub fn implemented_by(
+        &self,
+        symbol: &Symbol,
+        file_path: &str,
+        file_text: &str,
+    ) -> Option<&RpcDefinition> {
+        if !matches!(symbol.kind, SymbolKind::Method | SymbolKind::Function)
+            || is_generated_file(file_path)
+        {
+            return None;
+        }
+        let language = symbol.language_id?.as_str();
+        let signature = symbol.signature.as_deref()?;
+        // Generated clients take call options (Go) or `&mut self` (tonic)
+        if signature.contains("CallOption") || signature.contains("&mut self") {
+            return None;
+        }
+        let candidates = self
+            .candidates(&symbol.name, language)
+            .into_iter()
+            .filter(|rpc| {
+                mentions(signature, &rpc.input)
+                    || signature.contains(&format!("{}_{}Server", rpc.service, rpc.name))
+            })
+            .collect();
+        Self::unique(candidates, file_text)
+    }
+
+    /// The RPC a call site invokes through a generated client, if any
+    ///
+    /// The caller's file must create or hold the service client.
+    pub fn match_call(&self, call: &ForeignCall, file_text: &str) -> Option<&RpcDefinition> {
+        let candidates = self
+            .candidates(call.short_name(), call.language)
+            .into_iter()
+            .filter(|rpc| {
+                file_text.contains(&format!("{}Client", rpc.service))
+                    || file_text.contains(&format!("Client({}", rpc.service))
+            })
+            .collect();
+        Self::unique(candidates, file_text)
+    }
+}
+
+/// Append the RPC side (`server` or `client`) to a relationship context
+pub fn with_role(context: Option<&str>, role: &str) -> String {
+    let entry = format!("{CONTEXT_KEY}:{role}");
+    match context {
+        Some(context) if !context.is_empty() => format!("{context},{entry}"),
+        _ => entry,
+    }
+}
+
+/// RPC side recorded in a relationship context, e.g. `client`
+pub fn role_in(context: &str) -> Option<&str> {
+    context
+        .split(',')
+        .find_map(|part| part.trim().strip_prefix(CONTEXT_KEY)?.strip_prefix(':'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::LanguageId;
+    use crate::{FileId, Range};
+
+    fn symbol(id: u32, name: &str, kind: SymbolKind, language: &'static str) -> Symbol {
+        Symbol::new(
+            SymbolId::new(id).unwrap(),
+            name,
+            kind,
+            FileId::new(1).unwrap(),
+            Range::new(1, 0, 3, 0),
+        )
+        .with_language_id(LanguageId::new(language))
+    }
+
+    fn rpc(id: u32, service: &str, name: &str, input: &str, output: &str) -> Symbol {
+        symbol(id, name, SymbolKind::Method, "protobuf")
+            .with_module_path(format!("orders.v1.{service}.{name}"))
+            .with_signature(format!("rpc {name}({input}) returns ({output})"))
+    }
+
+    fn table() -> RpcTable {
+        RpcTable::new(&[
+            rpc(
+                1,
+                "OrderService",
+                "CreateOrder",
+                "CreateOrderRequest",
+                "Order",
+            ),
+            rpc(2, "OrderService", "Upload", "stream Chunk", "UploadReply"),
+            rpc(
+                3,
+                "AuditService",
+                "CreateOrder",
+                "CreateOrderRequest",
+                "Empty",
+            ),
+        ])
+    }
+
+    fn call(language: &'static str, name: &str) -> ForeignCall {
+        ForeignCall {
+            from_ids: vec![SymbolId::new(99).unwrap()],
+            language,
+            file_id: FileId::new(2).unwrap(),
+            name: name.to_string(),
+            receiver: Some("client".to_string()),
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_method_names() {
+        assert_eq!(snake_case("CreateOrder"), "create_order");
+        assert_eq!(snake_case("GetHTTPStatus"), "get_http_status");
+        assert_eq!(snake_case("ListV2Items"), "list_v2_items");
+
+        let table = table();
+        assert!(table.has_method("CreateOrder"));
+        assert!(table.has_method("create_order"));
+        assert!(table.has_method("createOrder"));
+        assert!(!table.has_method("create"));
+    }
+
+    #[test]
+    fn test_server_implementations() {
+        let table = table();
+
+        let go = symbol(10, "CreateOrder", SymbolKind::Method, "go").with_signature(
+            "func (s *server) CreateOrder(ctx context.Context, req *pb.CreateOrderRequest) (*pb.Order, error)",
+        );
+        let go_file = "pb.RegisterOrderServiceServer(grpcServer, &server{})";
+        let found = table
+            .implemented_by(&go, "server/orders.go", go_file)
+            .unwrap();
+        assert_eq!(
+            (found.service.as_str(), found.symbol_id.value()),
+            ("OrderService", 1)
+        );
+
+        // Same name and request in two services and no service in the file
+        assert!(table.implemented_by(&go, "server/orders.go", "").is_none());
+        // Generated code and clients are not implementations
+        assert!(
+            table
+                .implemented_by(&go, "gen/orders_grpc.pb.go", go_file)
+                .is_none()
+        );
+        let client = symbol(11, "CreateOrder", SymbolKind::Method, "go").with_signature(
+            "func (c *orderServiceClient) CreateOrder(ctx context.Context, in *CreateOrderRequest, opts ...grpc.CallOption) (*Order, error)",
+        );
+        assert!(
+            table
+                .implemented_by(&client, "client.go", go_file)
+                .is_none()
+        );
+
+        let rust = symbol(12, "upload", SymbolKind::Method, "rust").with_signature(
+            "async fn upload(&self, request: Request<Streaming<Chunk>>) -> Result<Response<UploadReply>, Status>",
+        );
+        let found = table.implemented_by(&rust, "src/server.rs", "").unwrap();
+        assert_eq!(found.name, "Upload");
+
+        // A same-named helper that does not take the request is not linked
+        let helper = symbol(13, "upload", SymbolKind::Function, "rust")
+            .with_signature("fn upload(path: &Path) -> io::Result<()>");
+        assert!(table.implemented_by(&helper, "src/files.rs", "").is_none());
+    }
+
+    #[test]
+    fn test_client_calls() {
+        let table = table();
+
+        let go_file = "client := pb.NewOrderServiceClient(conn)";
+        let found = table
+            .match_call(&call("go", "CreateOrder"), go_file)
+            .unwrap();
+        assert_eq!(found.service, "OrderService");
+
+        let ts_file = "const client = createClient(OrderService, transport);";
+        let found = table.match_call(&call("typescript", "createOrder"), ts_file);
+        assert_eq!(found.map(|rpc| rpc.symbol_id.value()), Some(1));
+
+        // The file never creates a client for the service
+        assert!(table.match_call(&call("rust", "upload"), "").is_none());
+        // tonic methods are snake_case
+        assert!(
+            table
+                .match_call(&call("rust", "Upload"), "OrderServiceClient::connect")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_context_role() {
+        let context = with_role(Some("receiver:client"), "client");
+        assert_eq!(context, "receiver:client,grpc:client");
+        assert_eq!(role_in(&context), Some("client"));
+        assert_eq!(role_in("receiver:x"), None);
+    }
+}
@@ -0,0 +1,190 @@
+//! Advisory lock that lets one process write an index at a time
+//!
+//! Several processes can open the same index directory: `codanna index` run
+//! from a script, `codanna serve --watch` re-indexing changed files through
+//! `FileSystemWatcher` or `IndexWatcher`, and the startup sync of any command.
+//! Tantivy only guards its own segments; the symbol cache, the semantic vectors
+//! and the index metadata are written next to them, so two interleaved writers
+//! leave stores that disagree with each other.
+//!
+//! Every writer holds an [`IndexWriteLock`] from before its first write until
+//! the index is saved. Readers never take it and keep serving the last
+//! committed Tantivy generation. The lock is an OS file lock on `index.lock`,
+//! released by the kernel when its holder exits, so a crashed writer never
+//! leaves a stale lock behind.
+
+use crate::{IndexError, IndexResult};
+use fs4::fs_std::FileExt;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, Write};
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// File name of the lock inside the index directory
+pub const LOCK_FILE: &str = "index.lock";
+
+/// How often a waiting writer retries the lock
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Process recorded in the lock file while it holds the lock
+#[derive(Debug, Serialize, Deserialize)]
+struct LockHolder {
+    pid: u32,
+    command: String,
+    since: u64,
+}
+
+/// Exclusive right to write the index, released on drop
+#[derive(Debug)]
+pub struct IndexWriteLock {
+    file: File,
+}
+
+impl IndexWriteLock {
+    /// Take the lock, waiting up to `timeout` for the current writer to finish
+    pub fn acquire(index_dir: &Path, timeout: Duration) -> IndexResult<Self> {
+        let started = Instant::now();
+        loop {
+            if let Some(lock) = Self::try_acquire(index_dir)? {
+                return Ok(lock);
+            }
+            if started.elapsed() >= timeout {
+                return Err(IndexError::IndexLocked {
+                    path: index_dir.to_path_buf(),
+                    holder: Self::holder(index_dir).unwrap_or_else(|| "unknown process".into()),
+                    waited_secs: timeout.as_secs(),
+                });
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    /// Take the lock if no other writer holds it
+    pub fn try_acquire(index_dir: &Path) -> IndexResult<Option<Self>> {
+        let path = index_dir.join(LOCK_FILE);
+        let lock_error = |source: std::io::Error| IndexError::FileWrite {
+            path: path.clone(),
+            source,
+        };
+
+        std::fs::create_dir_all(index_dir).map_err(lock_error)?;
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .map_err(lock_error)?;
+        if !file.try_lock_exclusive().map_err(lock_error)? {
+            return Ok(None);
+        }
+
+        let holder = LockHolder {
+            pid: std::process::id(),
+            command: std::env::args().collect::<Vec<_>>().join(" "),
+            since: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+        };
+        let json = serde_json::to_string(&holder).map_err(std::io::Error::other);
+        json.and_then(|json| {
+            file.set_len(0)?;
+            file.rewind()?;
+            file.write_all(json.as_bytes())
+        })
+        .map_err(lock_error)?;
+
+        Ok(Some(Self { file }))
+    }
+
+    /// Whether a writer in another process holds the lock right now
+    ///
+    /// An index journal found while this is true belongs to a run in
+    /// progress, not to an interrupted one.
+    pub fn held_elsewhere(index_dir: &Path) -> bool {
+        match Self::try_acquire(index_dir) {
+            Ok(None) => {
+                Self::read_holder(index_dir).is_none_or(|holder| holder.pid != std::process::id())
+            }
+            _ => false,
+        }
+    }
+
+    /// Description of the process holding the lock, as it recorded itself
+    pub fn holder(index_dir: &Path) -> Option<String> {
+        let holder = Self::read_holder(index_dir)?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        Some(format!(
+            "pid {}, `{}`, writing for {}s",
+            holder.pid,
+            holder.command,
+            now.saturating_sub(holder.since)
+        ))
+    }
+
+    fn read_holder(index_dir: &Path) -> Option<LockHolder> {
+        let mut json = String::new();
+        File::open(index_dir.join(LOCK_FILE))
+            .ok()?
+            .read_to_string(&mut json)
+            .ok()?;
+        serde_json::from_str(&json).ok()
+    }
+}
+
+impl Drop for IndexWriteLock {
+    fn drop(&mut self) {
+        // Closing the file releases the lock; clear the holder first so it is
+        // never read back for a process that no longer writes
+        let _ = self.file.set_len(0);
+        let _ = FileExt::unlock(&self.file);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_second_writer_waits_then_gives_up() {
+        let dir = TempDir::new().unwrap();
+        let lock = IndexWriteLock::try_acquire(dir.path()).unwrap().unwrap();
+        assert!(IndexWriteLock::try_acquire(dir.path()).unwrap().is_none());
+        assert!(
+            IndexWriteLock::holder(dir.path())
+                .unwrap()
+                .starts_with(&format!("pid {}", std::process::id()))
+        );
+        // Held by this process, so no run is in progress elsewhere
+        assert!(!IndexWriteLock::held_elsewhere(dir.path()));
+
+        let started = Instant::now();
+        let err = IndexWriteLock::acquire(dir.path(), Duration::from_millis(300)).unwrap_err();
+        assert!(started.elapsed() >= Duration::from_millis(300));
+        assert!(matches!(err, IndexError::IndexLocked { .. }));
+
+        drop(lock);
+        assert!(IndexWriteLock::holder(dir.path()).is_none());
+        let _lock = IndexWriteLock::acquire(dir.path(), Duration::ZERO).unwrap();
+    }
+
+    #[test]
+    fn test_waiting_writer_gets_lock_when_released() {
+        let dir = TempDir::new().unwrap();
+        let lock = IndexWriteLock::try_acquire(dir.path()).unwrap().unwrap();
+        let releaser = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(200));
+            drop(lock);
+        });
+
+        let lock = IndexWriteLock::acquire(dir.path(), Duration::from_secs(10));
+        releaser.join().unwrap();
+        assert!(lock.is_ok());
+    }
+}
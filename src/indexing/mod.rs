@@ -7,8 +7,16 @@
 //! - [`SimpleIndexer`]: メインのインデクサー実装
 //! - [`FileWalker`]: ファイルシステムの探索
 //! - [`FileSystemWatcher`]: ファイル変更の監視
-//! - [`IndexTransaction`]: トランザクション管理
+//! - [`IndexJournal`]: 中断したインデックス作成を再開するための先行書き込みジャーナル
+//! - [`IndexWriteLock`]: 複数プロセスからの同時書き込みを防ぐ `index.lock` の排他ロック
 //! - [`ConfigFileWatcher`]: 設定ファイルの監視
+//! - [`ffi`]: 言語間 FFI 呼び出しのリンク
+//! - [`grpc`]: `.proto` の RPC と実装・クライアント呼び出しのリンク
+//! - [`dependencies`]: `codanna deps` による依存ライブラリの読み取り専用レイヤー
+//! - [`doctor`]: 各ストアの整合性検査と、`codanna doctor --fix` による個別の修復
+//! - [`gc`]: `codanna index gc` によるセグメントの統合と、不要になったベクトル・インポートの削除
+//! - [`spill`]: `indexing.memory_limit_mb` を超えた解決待ちの関係のディスク退避
+//! - [`unresolved`]: 解決できなかった参照の集計、`codanna stats --unresolved` で表示
 //!
 //! # 使用例
 //!
@@ -19,25 +27,39 @@
 //! ```
 
 pub mod config_watcher;
+pub mod dependencies;
+pub mod doctor;
+pub mod ffi;
 pub mod file_info;
 pub mod fs_watcher;
+pub mod gc;
+pub mod grpc;
+pub mod lock;
 pub mod progress;
 pub mod retry;
 pub mod simple;
+pub mod spill;
 pub mod transaction;
+pub mod type_methods;
+pub mod unresolved;
 pub mod walker;
 
 #[cfg(test)]
 pub mod import_resolution_proof;
 
 pub use config_watcher::ConfigFileWatcher;
+pub use dependencies::{DependencyLayer, DependencySource, Ecosystem};
 pub use file_info::{FileInfo, calculate_hash, get_utc_timestamp};
 pub use fs_watcher::{FileSystemWatcher, WatchError};
+pub use lock::IndexWriteLock;
 pub use progress::IndexStats;
 pub use retry::{
     WindowsIoRetryClass, backoff_with_jitter_ms, is_windows_transient_io_error, is_writer_killed,
     normalized_heap_bytes, windows_error_retry_class,
 };
 pub use simple::SimpleIndexer;
-pub use transaction::{FileTransaction, IndexTransaction};
+#[allow(deprecated)]
+pub use transaction::FileTransaction;
+pub use transaction::{IndexJournal, IndexTransaction, InterruptedRun, RunRecovery};
+pub use unresolved::{UnresolvedReason, UnresolvedReference, UnresolvedSummary};
 pub use walker::FileWalker;
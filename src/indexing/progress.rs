@@ -18,6 +18,18 @@ pub struct IndexStats {
     /// Time elapsed during indexing
     pub elapsed: Duration,
 
+    /// Batches of files committed together
+    pub batches: usize,
+
+    /// Relationships written to disk to stay under `indexing.memory_limit_mb`
+    pub relationships_spilled: usize,
+
+    /// Spill chunks written, one per batch that crossed the limit
+    pub spill_chunks: usize,
+
+    /// Compressed bytes of all spill chunks
+    pub spill_bytes: u64,
+
     /// Errors encountered (limited to first N errors)
     pub errors: Vec<(PathBuf, String)>,
 
@@ -58,6 +70,16 @@ impl IndexStats {
         println!("  Symbols found: {}", self.symbols_found);
         println!("  Time elapsed: {:.2}s", self.elapsed.as_secs_f64());
 
+        if self.relationships_spilled > 0 {
+            println!(
+                "  Spilled to disk: {} relationships in {} of {} batches ({:.1} MB)",
+                self.relationships_spilled,
+                self.spill_chunks,
+                self.batches,
+                self.spill_bytes as f64 / 1_000_000.0
+            );
+        }
+
         if self.files_indexed > 0 {
             let files_per_sec = self.files_indexed as f64 / self.elapsed.as_secs_f64();
             println!("  Performance: {files_per_sec:.0} files/second");
@@ -89,6 +111,10 @@ mod tests {
         stats.files_failed = 2;
         stats.symbols_found = 1500;
         stats.elapsed = Duration::from_secs(5);
+        stats.batches = 3;
+        stats.relationships_spilled = 12_000;
+        stats.spill_chunks = 2;
+        stats.spill_bytes = 480_000;
 
         // Should not panic
         stats.display();
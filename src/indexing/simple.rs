@@ -1,6 +1,13 @@
 //! Tantivy-only implementation of SimpleIndexer
 //! This version uses Tantivy as the single source of truth for all data
 
+use crate::indexing::dependencies::DependencyLayer;
+use crate::indexing::ffi::{self, ExportTable, FfiExport, ForeignCall};
+use crate::indexing::grpc::{self, RpcTable};
+use crate::indexing::spill::{SpillStore, SpilledFile, SpilledRelationship};
+use crate::indexing::transaction::{IndexJournal, RunRecovery};
+use crate::indexing::type_methods::{self, TypeMethods};
+use crate::indexing::unresolved::{UnresolvedReason, UnresolvedReference, UnresolvedSummary};
 use crate::indexing::{
     FileWalker, IndexStats, IndexTransaction, calculate_hash, get_utc_timestamp,
 };
@@ -11,15 +18,17 @@ use crate::parsing::{LanguageId, MethodCall, ParserFactory, get_registry};
 use crate::relationship::RelationshipMetadata;
 use crate::semantic::SimpleSemanticSearch;
 use crate::storage::{DocumentIndex, SearchResult};
-use crate::types::SymbolCounter;
+use crate::types::{StableIdAssigner, SymbolCounter, SymbolRef};
 use crate::vector::{EmbeddingGenerator, VectorSearchEngine, create_symbol_text};
 use crate::{
     FileId, IndexError, IndexResult, RelationKind, Relationship, Settings, Symbol, SymbolId,
     SymbolKind,
 };
+use dashmap::DashMap;
+use rayon::prelude::*;
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, OnceLock};
 
 /// Debug print macro that respects the debug setting
 macro_rules! debug_print {
@@ -62,6 +71,17 @@ struct UnresolvedRelationship {
     metadata: Option<RelationshipMetadata>,
 }
 
+/// Outcome of resolving one file's relationships, stored in file order
+#[derive(Default)]
+struct FileResolution {
+    relationships: Vec<(SymbolId, SymbolId, Relationship)>,
+    unresolved: Vec<UnresolvedReference>,
+    foreign_calls: Vec<ForeignCall>,
+    rpc_calls: Vec<ForeignCall>,
+    resolved: usize,
+    skipped: usize,
+}
+
 /// The main indexer struct that handles parsing and indexing of source code
 pub struct SimpleIndexer {
     parser_factory: ParserFactory,
@@ -78,6 +98,10 @@ pub struct SimpleIndexer {
         std::collections::HashMap<FileId, std::collections::HashMap<String, crate::SymbolKind>>,
     /// Method calls with rich receiver information for enhanced resolution
     method_calls_by_file: std::collections::HashMap<FileId, Vec<crate::parsing::MethodCall>>,
+    /// Which types define which methods, for resolving calls by receiver type
+    type_methods: TypeMethods,
+    /// Relationship targets resolution could not bind, counted per file
+    unresolved: UnresolvedSummary,
     /// Optional vector search engine
     vector_engine: Option<Arc<Mutex<VectorSearchEngine>>>,
     /// Optional embedding generator
@@ -92,6 +116,10 @@ pub struct SimpleIndexer {
     file_behaviors: std::collections::HashMap<FileId, Box<dyn crate::parsing::LanguageBehavior>>,
     /// Indexed directory paths (canonicalized) to track which directories are currently indexed
     indexed_paths: std::collections::HashSet<std::path::PathBuf>,
+    /// Read-only dependency layer, opened on first lookup once it has been built
+    dependency_layer: OnceLock<DependencyLayer>,
+    /// Write-ahead journal of the indexing run in progress
+    journal: IndexJournal,
 }
 
 impl Default for SimpleIndexer {
@@ -137,6 +165,8 @@ impl SimpleIndexer {
             variable_types: std::collections::HashMap::new(),
             trait_symbols_by_file: std::collections::HashMap::new(),
             method_calls_by_file: std::collections::HashMap::new(),
+            type_methods: TypeMethods::new(),
+            unresolved: UnresolvedSummary::new(),
             vector_engine: None,
             embedding_generator: None,
             pending_embeddings: Vec::new(),
@@ -144,6 +174,8 @@ impl SimpleIndexer {
             file_languages: std::collections::HashMap::new(),
             file_behaviors: std::collections::HashMap::new(),
             indexed_paths: std::collections::HashSet::new(),
+            dependency_layer: OnceLock::new(),
+            journal: IndexJournal::new(&index_base),
         };
 
         // Try to load symbol cache for fast lookups
@@ -181,6 +213,8 @@ impl SimpleIndexer {
             variable_types: std::collections::HashMap::new(),
             trait_symbols_by_file: std::collections::HashMap::new(),
             method_calls_by_file: std::collections::HashMap::new(),
+            type_methods: TypeMethods::new(),
+            unresolved: UnresolvedSummary::new(),
             vector_engine: None,
             embedding_generator: None,
             pending_embeddings: Vec::new(),
@@ -188,6 +222,8 @@ impl SimpleIndexer {
             file_languages: std::collections::HashMap::new(),
             file_behaviors: std::collections::HashMap::new(),
             indexed_paths: std::collections::HashSet::new(),
+            dependency_layer: OnceLock::new(),
+            journal: IndexJournal::new(&index_base),
         };
 
         // Resolution system now handled through LanguageBehavior:
@@ -288,6 +324,70 @@ impl SimpleIndexer {
         }
     }
 
+    /// Drop embeddings whose symbol is no longer indexed
+    ///
+    /// Returns how many were dropped; 0 when semantic search is not enabled.
+    pub fn drop_orphaned_embeddings(&mut self) -> usize {
+        let Some(semantic) = &self.semantic_search else {
+            return 0;
+        };
+        let mut semantic = semantic.lock().unwrap();
+        let orphaned: Vec<SymbolId> = semantic
+            .symbol_ids()
+            .into_iter()
+            .filter(|id| matches!(self.document_index.find_symbol_by_id(*id), Ok(None)))
+            .collect();
+        semantic.remove_embeddings(&orphaned);
+        orphaned.len()
+    }
+
+    /// Embed the doc comments of indexed symbols that have no embedding
+    ///
+    /// Returns how many symbols were embedded.
+    pub fn embed_missing_doc_comments(&mut self) -> IndexResult<usize> {
+        let Some(semantic) = &self.semantic_search else {
+            return Err(IndexError::General(
+                "Semantic search is not enabled".to_string(),
+            ));
+        };
+        let mut semantic = semantic.lock().unwrap();
+        let embedded: std::collections::HashSet<SymbolId> =
+            semantic.symbol_ids().into_iter().collect();
+
+        let total = self.document_index.count_symbols().unwrap_or(0);
+        let symbols =
+            self.document_index
+                .get_all_symbols(total)
+                .map_err(|e| IndexError::TantivyError {
+                    operation: "get_all_symbols".to_string(),
+                    cause: e.to_string(),
+                })?;
+
+        let mut count = 0;
+        for symbol in symbols {
+            let Some(doc) = &symbol.doc_comment else {
+                continue;
+            };
+            if embedded.contains(&symbol.id) || doc.trim().is_empty() {
+                continue;
+            }
+            let language = symbol
+                .language_id
+                .map(|lang_id| lang_id.as_str())
+                .unwrap_or("unknown");
+            semantic
+                .index_doc_comment_with_language(symbol.id, doc, language)
+                .map_err(|e| {
+                    IndexError::General(format!(
+                        "Failed to embed doc comment for symbol {}: {e}",
+                        symbol.name
+                    ))
+                })?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
     /// Save semantic search data to the given path
     pub fn save_semantic_search(
         &self,
@@ -394,24 +494,109 @@ impl SimpleIndexer {
         Ok(())
     }
 
-    /// Begin a transaction (compatibility method)
-    /// With Tantivy, transactions are handled internally by the batch system
+    /// Begin a batch transaction
+    ///
+    /// Files added to it are recorded in the journal when the batch commits.
     pub fn begin_transaction(&self) -> IndexTransaction {
-        // Return a dummy transaction for compatibility
-        IndexTransaction::new(&())
+        IndexTransaction::default()
     }
 
-    /// Commit a transaction (compatibility method)
-    /// With Tantivy, this just commits the current batch
+    /// Commit the current batch and record its files in the journal
     pub fn commit_transaction(&mut self, mut transaction: IndexTransaction) -> IndexResult<()> {
+        self.commit_tantivy_batch()?;
+        self.journal
+            .commit(&mut transaction)
+            .map_err(|e| self.journal_write_error(e))
+    }
+
+    /// Abandon a batch transaction
+    ///
+    /// Its files are left out of the journal, so an interrupted run indexes
+    /// them again when it resumes.
+    pub fn rollback_transaction(&mut self, mut transaction: IndexTransaction) {
         transaction.complete();
-        self.commit_tantivy_batch()
     }
 
-    /// Rollback a transaction (compatibility method)
-    /// With Tantivy, uncommitted changes are automatically discarded
-    pub fn rollback_transaction(&mut self, _transaction: IndexTransaction) {
-        // No-op - Tantivy automatically discards uncommitted changes
+    /// Remove the journal once the run's results are saved
+    ///
+    /// Called by `IndexPersistence::save` after the metadata and the
+    /// semantic vectors are written. The journal of a run that did not get
+    /// to resolve its relationships stays for the next start to resume.
+    pub fn finish_index_journal(&self) -> IndexResult<()> {
+        self.journal
+            .finish()
+            .map_err(|e| self.journal_write_error(e))
+    }
+
+    /// Whether the journal of an indexing run is still in the index directory
+    ///
+    /// Either the run was interrupted or it is still going on in another
+    /// process; only the holder of the index write lock can tell them apart.
+    pub fn has_index_journal(&self) -> bool {
+        self.journal.exists()
+    }
+
+    /// Drop the Tantivy writer kept between batches so another process can write
+    ///
+    /// Long-running writers such as the file watchers call this once their
+    /// changes are committed, before they release the index write lock.
+    pub fn release_writer(&self) -> IndexResult<()> {
+        self.document_index
+            .release_writer()
+            .map_err(|e| IndexError::TantivyError {
+                operation: "release_writer".to_string(),
+                cause: e.to_string(),
+            })
+    }
+
+    /// Check the stores against the journal of an interrupted run and repair them
+    ///
+    /// Returns `None` when the last run completed. Otherwise the files of
+    /// committed batches are removed again from Tantivy and the semantic
+    /// vectors, unless their relationships were resolved and there are no
+    /// vectors that still had to be saved. Embeddings of symbols that are no
+    /// longer indexed are dropped and the symbol cache is rebuilt from
+    /// Tantivy. The rolled-back files and the files the run never reached are
+    /// returned in [`RunRecovery::remaining`]; index them and save the index
+    /// to complete the run.
+    pub fn recover_interrupted_run(&mut self) -> IndexResult<Option<RunRecovery>> {
+        let run = self.journal.read().map_err(|e| IndexError::FileRead {
+            path: self.journal.path().to_path_buf(),
+            source: e,
+        })?;
+        let Some(run) = run else {
+            return Ok(None);
+        };
+
+        let mut recovery = RunRecovery::default();
+        let roll_back = !run.resolved || self.has_semantic_search();
+        if roll_back {
+            let stored: Vec<String> = run
+                .committed
+                .iter()
+                .filter_map(|path| self.storage_path(path).to_str().map(String::from))
+                .collect();
+            recovery.rolled_back = self.remove_files(&stored)?;
+            recovery.remaining.extend(run.committed);
+        }
+        recovery.remaining.extend(run.pending);
+        recovery.remaining.retain(|path| path.exists());
+
+        recovery.dropped_embeddings = self.drop_orphaned_embeddings();
+
+        // remove_files already rebuilt the cache; otherwise it may predate the crash
+        if recovery.rolled_back == 0 {
+            self.build_symbol_cache()?;
+        }
+
+        Ok(Some(recovery))
+    }
+
+    fn journal_write_error(&self, source: std::io::Error) -> IndexError {
+        IndexError::FileWrite {
+            path: self.journal.path().to_path_buf(),
+            source,
+        }
     }
 
     /// Get the data for persistence (compatibility method)
@@ -473,6 +658,18 @@ impl SimpleIndexer {
         }
     }
 
+    /// Path as stored in the index: relative to the workspace root when under it
+    ///
+    /// Zero-cost: we only work with references, no allocations
+    fn storage_path<'a>(&self, path: &'a Path) -> &'a Path {
+        if path.is_absolute() {
+            if let Some(workspace_root) = &self.settings.workspace_root {
+                return path.strip_prefix(workspace_root).unwrap_or(path);
+            }
+        }
+        path
+    }
+
     fn index_file_internal(
         &mut self,
         path: impl AsRef<Path>,
@@ -481,16 +678,7 @@ impl SimpleIndexer {
         let path = path.as_ref();
 
         // Normalize path relative to workspace_root for consistent storage
-        // Zero-cost: we only work with references, no allocations
-        let normalized_path = if path.is_absolute() {
-            if let Some(workspace_root) = &self.settings.workspace_root {
-                path.strip_prefix(workspace_root).unwrap_or(path)
-            } else {
-                path
-            }
-        } else {
-            path
-        };
+        let normalized_path = self.storage_path(path);
 
         let path_str = normalized_path
             .to_str()
@@ -532,6 +720,19 @@ impl SimpleIndexer {
                     cause: e.to_string(),
                 })?;
 
+            // Imports are stored by file ID and the new content gets a new one
+            self.document_index
+                .delete_imports_for_file(file_id)
+                .map_err(|e| IndexError::TantivyError {
+                    operation: "delete_imports_for_file".to_string(),
+                    cause: e.to_string(),
+                })?;
+
+            // Drop receiver data and unresolved counts from the old content
+            self.method_calls_by_file.remove(&file_id);
+            self.type_methods.remove_file(file_id);
+            self.unresolved.remove_file(path_str);
+
             // Remove embeddings for the old symbols if semantic search is enabled
             if let Some(symbol_ids) = symbols_to_remove {
                 if let Some(semantic) = &self.semantic_search {
@@ -573,6 +774,60 @@ impl SimpleIndexer {
             eprintln!("  Querying index for path: '{path_str}'");
         }
 
+        if !self.remove_file_uncommitted(path_str)? {
+            return Ok(());
+        }
+
+        // Commit the changes to persist them
+        self.document_index
+            .commit_batch()
+            .map_err(|e| IndexError::TantivyError {
+                operation: "commit after removal".to_string(),
+                cause: e.to_string(),
+            })?;
+        if self.settings.debug {
+            eprintln!("  Changes committed to index");
+        }
+
+        // Rebuild symbol cache after file removal to remove stale entries
+        if let Err(e) = self.build_symbol_cache() {
+            eprintln!("Warning: Failed to rebuild symbol cache after file removal: {e}");
+        }
+
+        Ok(())
+    }
+
+    /// Remove several files in one batch
+    ///
+    /// `paths` are paths as stored in the index. Commits once and rebuilds
+    /// the symbol cache once, instead of once per file as [`Self::remove_file`]
+    /// does. Returns how many of the files were indexed.
+    pub fn remove_files(&mut self, paths: &[String]) -> IndexResult<usize> {
+        let mut removed = 0;
+        for path in paths {
+            if self.remove_file_uncommitted(path)? {
+                removed += 1;
+            }
+        }
+        if removed == 0 {
+            return Ok(0);
+        }
+
+        self.document_index
+            .commit_batch()
+            .map_err(|e| IndexError::TantivyError {
+                operation: "commit after removal".to_string(),
+                cause: e.to_string(),
+            })?;
+        self.build_symbol_cache()?;
+        Ok(removed)
+    }
+
+    /// Delete a file's documents in the open batch and drop its in-memory state
+    ///
+    /// `path_str` is the path as stored in the index. Returns `false` when
+    /// the file is not indexed. The caller commits the batch.
+    fn remove_file_uncommitted(&mut self, path_str: &str) -> IndexResult<bool> {
         // Get the FileId for this file path
         let file_info =
             self.document_index
@@ -615,13 +870,17 @@ impl SimpleIndexer {
                     cause: e.to_string(),
                 })?;
 
+            self.method_calls_by_file.remove(&file_id);
+            self.type_methods.remove_file(file_id);
+            self.unresolved.remove_file(path_str);
+
             symbols
         } else {
             // File not in index, nothing to remove
             if self.settings.debug {
                 eprintln!("  File not found in index: {path_str}");
             }
-            return Ok(());
+            return Ok(false);
         };
 
         // Remove ALL documents for this file from Tantivy
@@ -645,23 +904,7 @@ impl SimpleIndexer {
             eprintln!("  Removed {symbol_count} symbols from {path_str}");
         }
 
-        // Commit the changes to persist them
-        self.document_index
-            .commit_batch()
-            .map_err(|e| IndexError::TantivyError {
-                operation: "commit after removal".to_string(),
-                cause: e.to_string(),
-            })?;
-        if self.settings.debug {
-            eprintln!("  Changes committed to index");
-        }
-
-        // Rebuild symbol cache after file removal to remove stale entries
-        if let Err(e) = self.build_symbol_cache() {
-            eprintln!("Warning: Failed to rebuild symbol cache after file removal: {e}");
-        }
-
-        Ok(())
+        Ok(true)
     }
 
     /// Read file content and calculate its hash
@@ -976,6 +1219,7 @@ impl SimpleIndexer {
         // Build symbol map for relationship resolution
         let mut symbol_map = std::collections::HashMap::new();
 
+        let mut configured = Vec::with_capacity(symbols.len());
         for mut symbol in symbols {
             // Track trait symbols
             trait_symbols.insert(symbol.name.to_string(), symbol.kind);
@@ -983,15 +1227,34 @@ impl SimpleIndexer {
             // Set the language_id on the symbol
             symbol.language_id = Some(language_id);
 
-            // Capture name and ID before configuring
-            let name = symbol.name.to_string();
-            let id = symbol.id;
+            // Map name to ID (captured before configuring) for relationship resolution
+            symbol_map.insert(symbol.name.to_string(), symbol.id);
 
             self.configure_symbol(&mut symbol, module_path, behavior);
+            symbol.is_test = symbol.is_test || behavior.is_test_symbol(&symbol, path_str);
+            configured.push(symbol);
+        }
+
+        let mut stable_ids = StableIdAssigner::new();
+        assign_stable_ids(&mut configured, path_str, &mut stable_ids);
+
+        // Routes are read from the configured symbols (annotations, ranges)
+        let routes = behavior.extract_routes(content, path_str, &configured);
+
+        for symbol in configured {
             self.store_symbol(symbol, path_str)?;
+        }
 
-            // Map name to ID for relationship resolution
-            symbol_map.insert(name, id);
+        for route in routes {
+            self.store_route(
+                route,
+                file_id,
+                path_str,
+                module_path,
+                language_id,
+                symbol_counter,
+                &mut stable_ids,
+            )?;
         }
 
         // Store trait symbols for this file
@@ -1000,6 +1263,70 @@ impl SimpleIndexer {
         Ok(symbol_map)
     }
 
+    /// Store an HTTP route as a `Route` symbol linked to its handler
+    ///
+    /// Handlers defined in the same file are linked directly; the others are
+    /// resolved by name once all files are indexed.
+    fn store_route(
+        &mut self,
+        route: crate::parsing::RouteEntry,
+        file_id: FileId,
+        path_str: &str,
+        module_path: &Option<String>,
+        language_id: LanguageId,
+        symbol_counter: &mut SymbolCounter,
+        stable_ids: &mut StableIdAssigner,
+    ) -> IndexResult<()> {
+        let route_id = symbol_counter.next_id();
+        let name = route.name();
+        let mut symbol = crate::Symbol::new(
+            route_id,
+            name.as_str(),
+            crate::SymbolKind::Route,
+            file_id,
+            crate::Range::new(route.line, 0, route.line, 0),
+        )
+        .with_signature(route.signature())
+        .with_visibility(crate::Visibility::Public)
+        .with_language_id(language_id);
+        if let Some(module_path) = module_path {
+            symbol = symbol.with_module_path(module_path.as_str());
+        }
+        symbol.stable_id = Some(
+            stable_ids.assign(
+                language_id.as_str(),
+                symbol
+                    .module_path
+                    .as_deref()
+                    .filter(|path| !path.is_empty())
+                    .unwrap_or(path_str),
+                &symbol.name,
+                symbol.kind,
+            ),
+        );
+        self.store_symbol(symbol, path_str)?;
+
+        let metadata = RelationshipMetadata::new()
+            .at_position(route.line, 0)
+            .with_context(format!("framework:{}", route.framework));
+        match (route.handler_id, route.handler) {
+            (Some(handler_id), _) => self.add_relationship_internal(
+                route_id,
+                handler_id,
+                Relationship::new(RelationKind::Routes).with_metadata(metadata),
+            ),
+            (None, Some(handler)) => self.add_relationships_by_name(
+                Some(route_id),
+                &name,
+                &handler,
+                file_id,
+                RelationKind::Routes,
+                Some(metadata),
+            ),
+            (None, None) => Ok(()),
+        }
+    }
+
     /// Configure a symbol with module path and visibility
     fn configure_symbol(
         &self,
@@ -1264,6 +1591,15 @@ impl SimpleIndexer {
             // For TypeScript: TypeScriptBehavior tracks interface implementations
             // This replaces the old TraitResolver.add_trait_impl() functionality
             behavior.add_trait_impl(type_name.to_string(), trait_name.to_string(), file_id);
+            if let Some(language_id) = self.file_languages.get(&file_id).copied() {
+                self.type_methods.add_parent(
+                    language_id,
+                    file_id,
+                    type_name,
+                    trait_name,
+                    "implements",
+                );
+            }
             let from_id = symbol_map.get(type_name).copied();
             self.add_relationships_by_name(
                 from_id,
@@ -1284,6 +1620,15 @@ impl SimpleIndexer {
                 derived_type,
                 base_type
             );
+            if let Some(language_id) = self.file_languages.get(&file_id).copied() {
+                self.type_methods.add_parent(
+                    language_id,
+                    file_id,
+                    derived_type,
+                    base_type,
+                    "extends",
+                );
+            }
             let from_id = symbol_map.get(derived_type).copied();
             self.add_relationships_by_name(
                 from_id,
@@ -1349,7 +1694,7 @@ impl SimpleIndexer {
             defines.len(),
             file_id
         );
-        for (definer_name, method_name, _range) in defines {
+        for (definer_name, method_name, range) in defines {
             debug_print!(
                 self,
                 "Processing define: {} defines {}",
@@ -1377,6 +1722,16 @@ impl SimpleIndexer {
                     }
                 }
             }
+            // Remember the definition site so receiver-typed calls can name this method
+            if let Some(language_id) = self.file_languages.get(&file_id).copied() {
+                self.type_methods.add_method(
+                    language_id,
+                    file_id,
+                    definer_name,
+                    method_name,
+                    range,
+                );
+            }
             let from_id = symbol_map.get(definer_name).copied();
             self.add_relationships_by_name(
                 from_id,
@@ -1623,6 +1978,15 @@ impl SimpleIndexer {
                 // This is a catch-all for general references
                 true
             }
+            Routes | RoutedBy => {
+                // HTTP routes are handled by functions, methods or controller classes
+                let handler = |k: &crate::SymbolKind| matches!(k, Function | Method | Class);
+                match rel_kind {
+                    Routes => from_kind == Route && handler(&to_kind),
+                    RoutedBy => handler(&from_kind) && to_kind == Route,
+                    _ => unreachable!(),
+                }
+            }
         }
     }
 
@@ -1651,13 +2015,15 @@ impl SimpleIndexer {
             RelationKind::Uses => Some(RelationKind::UsedBy),
             RelationKind::Defines => Some(RelationKind::DefinedIn),
             RelationKind::References => Some(RelationKind::ReferencedBy),
+            RelationKind::Routes => Some(RelationKind::RoutedBy),
             // Don't create reverse for already-reverse relationships
             RelationKind::ImplementedBy
             | RelationKind::ExtendedBy
             | RelationKind::CalledBy
             | RelationKind::UsedBy
             | RelationKind::DefinedIn
-            | RelationKind::ReferencedBy => None,
+            | RelationKind::ReferencedBy
+            | RelationKind::RoutedBy => None,
         };
 
         if let Some(reverse_kind) = reverse_kind {
@@ -1739,6 +2105,19 @@ impl SimpleIndexer {
             .and_then(|symbols| symbols.first().map(|s| s.id))
     }
 
+    /// Get the dependency layer built by `codanna deps index`, if any
+    ///
+    /// Lookups should only consult it when the project index has no match.
+    pub fn dependency_layer(&self) -> Option<&DependencyLayer> {
+        if let Some(layer) = self.dependency_layer.get() {
+            return Some(layer);
+        }
+        if let Some(layer) = DependencyLayer::open(&self.settings) {
+            let _ = self.dependency_layer.set(layer);
+        }
+        self.dependency_layer.get()
+    }
+
     pub fn find_symbols_by_name(&self, name: &str, language_filter: Option<&str>) -> Vec<Symbol> {
         // For now, still use Tantivy for full symbol retrieval
         // Cache only helps with ID lookups
@@ -1770,6 +2149,24 @@ impl SimpleIndexer {
             })
     }
 
+    /// Get a symbol by numeric or stable ID, as written after `symbol_id:`
+    pub fn get_symbol_by_ref(&self, symbol: &SymbolRef) -> Option<Symbol> {
+        match symbol {
+            SymbolRef::Id(id) => self.get_symbol(*id),
+            SymbolRef::Stable(stable_id) => self
+                .document_index
+                .find_symbol_by_stable_id(stable_id)
+                .ok()
+                .flatten()
+                .map(|mut symbol| {
+                    if let Some(language_id) = self.file_languages.get(&symbol.file_id) {
+                        symbol.language_id = Some(*language_id);
+                    }
+                    symbol
+                }),
+        }
+    }
+
     /// Get reference to symbol cache if available
     pub fn symbol_cache(&self) -> Option<&crate::storage::symbol_cache::ConcurrentSymbolCache> {
         self.symbol_cache.as_ref().map(|arc| arc.as_ref())
@@ -2053,66 +2450,542 @@ impl SimpleIndexer {
         self.document_index.count_relationships().unwrap_or(0)
     }
 
+    /// Relationship targets resolution could not bind, counted per file
+    pub fn unresolved_summary(&self) -> &UnresolvedSummary {
+        &self.unresolved
+    }
+
+    /// Restore the unresolved counts saved with the index metadata
+    pub fn set_unresolved_summary(&mut self, summary: UnresolvedSummary) {
+        self.unresolved = summary;
+    }
+
     pub fn get_file_path(&self, file_id: FileId) -> Option<String> {
         self.document_index.get_file_path(file_id).ok().flatten()
     }
 
-    /// Get all indexed file paths - used by file watcher
-    pub fn get_all_indexed_paths(&self) -> Vec<PathBuf> {
+    /// Look up the file ID for an indexed path.
+    ///
+    /// Accepts either the stored workspace-relative path or an absolute path
+    /// inside the workspace root.
+    pub fn get_file_id_by_path(&self, path: &str) -> Option<FileId> {
+        let normalized = self.normalize_indexed_path(Path::new(path));
         self.document_index
-            .get_all_indexed_paths()
-            .unwrap_or_else(|e| {
-                eprintln!("Warning: Failed to get indexed paths: {e}");
-                Vec::new()
-            })
+            .get_file_info(&normalized)
+            .ok()
+            .flatten()
+            .map(|(file_id, _)| file_id)
     }
 
-    /// Track a directory as indexed (stores canonicalized path)
-    pub fn add_indexed_path(&mut self, dir_path: &Path) -> IndexResult<()> {
-        let canonical = dir_path.canonicalize().map_err(|e| IndexError::FileRead {
-            path: dir_path.to_path_buf(),
-            source: e,
-        })?;
-
-        // Skip if this path is already covered by an existing indexed directory
-        if self
-            .indexed_paths
-            .iter()
-            .any(|existing| canonical.starts_with(existing))
-        {
-            return Ok(());
+    /// Build the nested symbol outline of an indexed file.
+    ///
+    /// Parents come from `Defines` relationships, `ScopeContext` parent
+    /// information and range containment (see [`crate::symbol::outline`]).
+    pub fn get_file_outline(&self, path: &str) -> Option<crate::symbol::outline::FileOutline> {
+        let path = path.strip_prefix("./").unwrap_or(path);
+        let file_id = self.get_file_id_by_path(path)?;
+        let symbols = self.get_symbols_by_file(file_id);
+
+        let mut defined_by = std::collections::HashMap::new();
+        for symbol in &symbols {
+            let defined = self
+                .document_index
+                .get_relationships_from(symbol.id, RelationKind::Defines)
+                .unwrap_or_default();
+            for (_, to_id, _) in defined {
+                defined_by.entry(to_id).or_insert(symbol.id);
+            }
         }
 
-        // Remove any tracked paths that are descendants of the new canonical path
-        self.indexed_paths
-            .retain(|existing| !existing.starts_with(canonical.as_path()));
-
-        self.indexed_paths.insert(canonical);
-        Ok(())
+        Some(crate::symbol::outline::build_outline(
+            &self.normalize_indexed_path(Path::new(path)),
+            file_id.value(),
+            symbols,
+            &defined_by,
+        ))
     }
 
-    /// Get all currently tracked indexed directories
-    pub fn get_indexed_paths(&self) -> &std::collections::HashSet<PathBuf> {
-        &self.indexed_paths
+    /// Find the innermost symbol containing a `file:line[:column]` position.
+    ///
+    /// With `include_edges`, relationships recorded on that line by the
+    /// enclosing definition (calls, type uses, ...) are returned as well,
+    /// ordered by their distance to the requested column.
+    pub fn find_symbol_at(
+        &self,
+        position: &crate::symbol::position::SourcePosition,
+        include_edges: bool,
+    ) -> Option<crate::symbol::position::SymbolAtPosition> {
+        use crate::symbol::position::{PositionEdge, SymbolAtPosition, innermost_symbol_at};
+
+        let path = position
+            .file_path
+            .strip_prefix("./")
+            .unwrap_or(&position.file_path);
+        let file_id = self.get_file_id_by_path(path)?;
+        let symbols = self.get_symbols_by_file(file_id);
+
+        let line = position.zero_based_line();
+        let column = position.zero_based_column();
+        let symbol = innermost_symbol_at(&symbols, line, column)?.clone();
+
+        let mut edges = Vec::new();
+        if include_edges {
+            // Relationships are recorded by definitions, not by parameters or locals
+            let owner = innermost_symbol_at(&symbols, line, None).unwrap_or(&symbol);
+            for kind in [
+                RelationKind::Calls,
+                RelationKind::Uses,
+                RelationKind::References,
+                RelationKind::Implements,
+                RelationKind::Extends,
+                RelationKind::Defines,
+            ] {
+                let relationships = self
+                    .document_index
+                    .get_relationships_from(owner.id, kind)
+                    .unwrap_or_default();
+                for (_, to_id, rel) in relationships {
+                    let Some(meta) = rel.metadata else { continue };
+                    if meta.line != Some(line) {
+                        continue;
+                    }
+                    if let Some(target) = self.get_symbol(to_id) {
+                        edges.push(PositionEdge {
+                            kind,
+                            target_id: target.id.value(),
+                            target_name: target.name.to_string(),
+                            target_kind: target.kind,
+                            target_file_path: target.file_path.to_string(),
+                            line: line + 1,
+                            column: meta.column.map(|c| c + 1),
+                        });
+                    }
+                }
+            }
+            if let Some(column) = position.column {
+                edges.sort_by_key(|e| e.column.map_or(u16::MAX, |c| c.abs_diff(column)));
+            } else {
+                edges.sort_by_key(|e| e.column);
+            }
+        }
+
+        Some(SymbolAtPosition {
+            position: position.clone(),
+            symbol,
+            edges,
+        })
     }
 
-    /// Sync indexed paths with config (settings.toml is source of truth)
+    /// Compute the change impact of a parsed unified diff.
     ///
-    /// Compares stored indexed_paths with current config.
-    /// - Indexes new directories in config
-    /// - Removes symbols from directories not in config
-    ///
-    /// Returns (added_count, removed_count, files_indexed, symbols_found)
-    pub fn sync_with_config(
-        &mut self,
-        stored_paths: Option<Vec<PathBuf>>,
+    /// Each changed line is attributed to the innermost symbol containing it;
+    /// every symbol of a deleted file counts as changed. The impact radius of
+    /// the changed symbols is collected with [`Self::get_impact_radius`] and
+    /// the result is grouped by file.
+    pub fn diff_impact(
+        &self,
+        diffs: &[crate::symbol::impact::FileDiff],
+        max_depth: usize,
+    ) -> crate::symbol::impact::DiffImpact {
+        use crate::symbol::impact::{DiffImpact, FileImpact, ImpactEntry, ImpactKind};
+        use crate::symbol::outline::is_outline_symbol;
+        use crate::symbol::position::innermost_symbol_at;
+        use crate::symbol::trace::match_trace_path;
+        use std::collections::{BTreeMap, HashMap, HashSet};
+
+        let entry = |symbol: &Symbol, impact: ImpactKind| ImpactEntry {
+            impact,
+            symbol_id: symbol.id.value(),
+            name: symbol.name.to_string(),
+            kind: symbol.kind,
+            start_line: symbol.range.start_line + 1,
+            end_line: symbol.range.end_line + 1,
+            via: Vec::new(),
+        };
+
+        let mut indexed_paths: Option<Vec<PathBuf>> = None;
+        let mut unindexed_files = Vec::new();
+        let mut changed: Vec<Symbol> = Vec::new();
+
+        for diff in diffs {
+            // Diff paths are repository-relative; fall back to a unique suffix match
+            let file_id = self.get_file_id_by_path(&diff.path).or_else(|| {
+                let indexed = indexed_paths.get_or_insert_with(|| self.get_all_indexed_paths());
+                match_trace_path(&diff.path, indexed)
+                    .and_then(|(path, _)| self.get_file_id_by_path(&path.to_string_lossy()))
+            });
+            let Some(file_id) = file_id else {
+                unindexed_files.push(diff.path.clone());
+                continue;
+            };
+
+            let symbols = self.get_symbols_by_file(file_id);
+            if diff.deleted {
+                changed.extend(symbols.into_iter().filter(is_outline_symbol));
+                continue;
+            }
+            for line in &diff.lines {
+                if let Some(symbol) = innermost_symbol_at(&symbols, line.saturating_sub(1), None) {
+                    if !changed.iter().any(|s| s.id == symbol.id) {
+                        changed.push(symbol.clone());
+                    }
+                }
+            }
+        }
+
+        let changed_ids: HashSet<SymbolId> = changed.iter().map(|s| s.id).collect();
+        let mut impacted: HashMap<SymbolId, Vec<String>> = HashMap::new();
+        for symbol in &changed {
+            for id in self.get_impact_radius(symbol.id, Some(max_depth)) {
+                if !changed_ids.contains(&id) {
+                    let via = impacted.entry(id).or_default();
+                    let name = symbol.name.to_string();
+                    if !via.contains(&name) {
+                        via.push(name);
+                    }
+                }
+            }
+        }
+
+        let mut grouped: BTreeMap<String, Vec<ImpactEntry>> = BTreeMap::new();
+        for symbol in &changed {
+            grouped
+                .entry(symbol.file_path.to_string())
+                .or_default()
+                .push(entry(symbol, ImpactKind::Changed));
+        }
+        for (id, via) in impacted {
+            if let Some(symbol) = self.get_symbol(id) {
+                let mut impacted_entry = entry(&symbol, ImpactKind::Impacted);
+                impacted_entry.via = via;
+                grouped
+                    .entry(symbol.file_path.to_string())
+                    .or_default()
+                    .push(impacted_entry);
+            }
+        }
+
+        let files = grouped
+            .into_iter()
+            .map(|(file_path, symbols)| FileImpact { file_path, symbols })
+            .collect();
+        DiffImpact::new(max_depth, files, unindexed_files)
+    }
+
+    /// Find the tests that reach a symbol through calls.
+    ///
+    /// Callers are walked backwards breadth-first up to `max_depth` calls;
+    /// the walk stops at symbols flagged as tests, which are reported with
+    /// the functions between them and the symbol. A test symbol reports
+    /// itself at depth 0.
+    pub fn find_tests_for(
+        &self,
+        symbol_id: SymbolId,
+        max_depth: usize,
+    ) -> Option<crate::symbol::test_map::TestsForSymbol> {
+        use crate::symbol::test_map::{TestEntry, TestsForSymbol};
+        use std::collections::HashSet;
+
+        let target = self.get_symbol(symbol_id)?;
+        let entry = |symbol: &Symbol, depth: usize, via: &[String]| TestEntry {
+            symbol_id: symbol.id.value(),
+            name: symbol.name.to_string(),
+            file_path: symbol.file_path.to_string(),
+            line: symbol.range.start_line + 1,
+            depth,
+            via: via.to_vec(),
+        };
+
+        let mut tests = Vec::new();
+        if target.is_test {
+            tests.push(entry(&target, 0, &[]));
+        }
+
+        // Each frontier item carries the functions between it and the target
+        let mut visited: HashSet<SymbolId> = HashSet::from([symbol_id]);
+        let mut frontier: Vec<(SymbolId, Vec<String>)> = vec![(symbol_id, Vec::new())];
+        for depth in 1..=max_depth {
+            let mut next = Vec::new();
+            for (id, via) in &frontier {
+                for caller in self.get_calling_functions(*id) {
+                    if !visited.insert(caller.id) {
+                        continue;
+                    }
+                    if caller.is_test {
+                        tests.push(entry(&caller, depth, via));
+                    } else {
+                        let mut caller_via = vec![caller.name.to_string()];
+                        caller_via.extend(via.iter().cloned());
+                        next.push((caller.id, caller_via));
+                    }
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            frontier = next;
+        }
+
+        Some(TestsForSymbol::new(
+            target.id.value(),
+            target.name.to_string(),
+            target.file_path.to_string(),
+            max_depth,
+            tests,
+        ))
+    }
+
+    /// List indexed HTTP routes with their handler symbols.
+    ///
+    /// `method` keeps routes accepting that method (including `ANY` routes);
+    /// `path` is a concrete path such as `/orders/42`, matched against the
+    /// route patterns, or a substring of the pattern. `language` filters by
+    /// the language of the file registering the route.
+    pub fn find_routes(
+        &self,
+        method: Option<&str>,
+        path: Option<&str>,
+        language: Option<&str>,
+    ) -> crate::symbol::routes::RouteTable {
+        use crate::parsing::RouteEntry;
+        use crate::symbol::routes::{RouteHandler, RouteInfo, RouteTable};
+
+        let routes = self
+            .document_index
+            .find_symbols_by_kind(SymbolKind::Route, None, 100_000)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|symbol| {
+                language.is_none_or(|lang| symbol.language_id.is_some_and(|id| id.as_str() == lang))
+            })
+            .filter_map(|symbol| {
+                let entry = RouteEntry::from_symbol(&symbol)?;
+                let handler_symbol = self
+                    .document_index
+                    .get_relationships_from(symbol.id, RelationKind::Routes)
+                    .ok()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .find_map(|(_, to_id, _)| self.get_symbol(to_id))
+                    .map(|handler| RouteHandler {
+                        symbol_id: handler.id.value(),
+                        name: handler.name.to_string(),
+                        file_path: handler.file_path.to_string(),
+                        line: handler.range.start_line + 1,
+                    });
+                Some(RouteInfo {
+                    symbol_id: symbol.id.value(),
+                    method: entry.method,
+                    path: entry.path,
+                    handler: entry.handler,
+                    framework: entry.framework,
+                    file_path: symbol.file_path.to_string(),
+                    line: symbol.range.start_line + 1,
+                    handler_symbol,
+                })
+            })
+            .filter(|route| method.is_none_or(|m| route.matches_method(m)))
+            .filter(|route| path.is_none_or(|p| route.matches_path(p)))
+            .collect();
+
+        RouteTable::new(routes)
+    }
+
+    /// Map parsed stack trace frames to indexed symbols.
+    ///
+    /// A frame is resolved by position when its file matches an indexed file
+    /// (exactly, or by a unique path suffix whose symbol agrees with the frame
+    /// name); otherwise by its qualified function name. Resolved frames carry
+    /// up to `max_callers` callers. Frames that resolve to nothing are outside
+    /// the index.
+    pub fn resolve_stack_trace(
+        &self,
+        frames: Vec<crate::symbol::trace::StackFrame>,
+        max_callers: usize,
+    ) -> Vec<crate::symbol::trace::ResolvedFrame> {
+        use crate::symbol::position::SourcePosition;
+        use crate::symbol::trace::{
+            FrameCaller, FrameMatch, ResolvedFrame, is_frame_kind, match_trace_path,
+            qualifier_score, short_name,
+        };
+
+        // Loaded on the first frame whose path is not stored verbatim
+        let mut indexed_paths: Option<Vec<PathBuf>> = None;
+
+        frames
+            .into_iter()
+            .map(|frame| {
+                let segments = frame.name_segments();
+                let mut matched: Option<(Symbol, FrameMatch)> = None;
+                let mut candidates = Vec::new();
+
+                if let (Some(path), Some(line)) = (frame.file_path.as_deref(), frame.line) {
+                    let path = path.strip_prefix("./").unwrap_or(path);
+                    let file = if self.get_file_id_by_path(path).is_some() {
+                        Some((path.to_string(), true))
+                    } else {
+                        let indexed =
+                            indexed_paths.get_or_insert_with(|| self.get_all_indexed_paths());
+                        match_trace_path(path, indexed)
+                            .map(|(p, exact)| (p.to_string_lossy().into_owned(), exact))
+                    };
+
+                    if let Some((file_path, exact)) = file {
+                        let position = SourcePosition {
+                            file_path,
+                            line,
+                            column: None,
+                        };
+                        matched = self
+                            .find_symbol_at(&position, false)
+                            .map(|found| found.symbol)
+                            // A loose path match must agree with the frame name
+                            .filter(|symbol| {
+                                exact
+                                    || segments
+                                        .last()
+                                        .is_none_or(|name| short_name(&symbol.name) == name)
+                            })
+                            .map(|symbol| (symbol, FrameMatch::Position));
+                    }
+                }
+
+                if matched.is_none() {
+                    if let Some((name, qualifiers)) = segments.split_last() {
+                        // Python stores methods as `Class.method`
+                        let mut found = self.find_symbols_by_name(name, None);
+                        if let Some(owner) = qualifiers.last() {
+                            found.extend(
+                                self.find_symbols_by_name(&format!("{owner}.{name}"), None),
+                            );
+                        }
+                        found.sort_by_key(|s| s.id.value());
+                        found.dedup_by_key(|s| s.id);
+
+                        let scored: Vec<(usize, Symbol)> = found
+                            .into_iter()
+                            .filter(|s| is_frame_kind(s.kind))
+                            .map(|s| {
+                                let parent = self
+                                    .document_index
+                                    .get_relationships_to(s.id, RelationKind::Defines)
+                                    .unwrap_or_default()
+                                    .into_iter()
+                                    .find_map(|(from, _, _)| self.get_symbol(from));
+                                let score = qualifier_score(
+                                    &s,
+                                    qualifiers,
+                                    parent.as_ref().map(|p| &*p.name),
+                                    frame.file_name(),
+                                );
+                                (score, s)
+                            })
+                            .collect();
+
+                        let best = scored.iter().map(|(score, _)| *score).max();
+                        let mut top: Vec<Symbol> = scored
+                            .into_iter()
+                            .filter(|(score, _)| Some(*score) == best)
+                            .map(|(_, s)| s)
+                            .collect();
+                        if top.len() == 1 {
+                            matched = top.pop().map(|s| (s, FrameMatch::Name));
+                        } else {
+                            candidates = top.iter().take(10).map(|s| s.id.value()).collect();
+                        }
+                    }
+                }
+
+                let callers = matched
+                    .as_ref()
+                    .map(|(symbol, _)| {
+                        let mut seen = std::collections::HashSet::new();
+                        self.get_calling_functions(symbol.id)
+                            .into_iter()
+                            .filter(|caller| seen.insert(caller.id))
+                            .take(max_callers)
+                            .map(|caller| FrameCaller {
+                                symbol_id: caller.id.value(),
+                                name: caller.name.to_string(),
+                                kind: caller.kind,
+                                file_path: caller.file_path.to_string(),
+                                line: caller.range.start_line + 1,
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+
+                let (symbol, matched_by) = matched.unzip();
+                ResolvedFrame {
+                    frame,
+                    in_index: symbol.is_some(),
+                    matched_by,
+                    symbol,
+                    callers,
+                    candidates,
+                }
+            })
+            .collect()
+    }
+
+    /// Convert a path to the workspace-relative form used as the storage key.
+    pub fn normalize_indexed_path(&self, path: &Path) -> String {
+        let normalized = match &self.settings.workspace_root {
+            Some(workspace_root) if path.is_absolute() => {
+                path.strip_prefix(workspace_root).unwrap_or(path)
+            }
+            _ => path,
+        };
+        normalized.to_string_lossy().into_owned()
+    }
+
+    /// Get all indexed file paths - used by file watcher
+    pub fn get_all_indexed_paths(&self) -> Vec<PathBuf> {
+        self.document_index
+            .get_all_indexed_paths()
+            .unwrap_or_else(|e| {
+                eprintln!("Warning: Failed to get indexed paths: {e}");
+                Vec::new()
+            })
+    }
+
+    /// Track a directory as indexed (stores canonicalized path)
+    pub fn add_indexed_path(&mut self, dir_path: &Path) -> IndexResult<()> {
+        let canonical = dir_path.canonicalize().map_err(|e| IndexError::FileRead {
+            path: dir_path.to_path_buf(),
+            source: e,
+        })?;
+
+        // Skip if this path is already covered by an existing indexed directory
+        if self
+            .indexed_paths
+            .iter()
+            .any(|existing| canonical.starts_with(existing))
+        {
+            return Ok(());
+        }
+
+        // Remove any tracked paths that are descendants of the new canonical path
+        self.indexed_paths
+            .retain(|existing| !existing.starts_with(canonical.as_path()));
+
+        self.indexed_paths.insert(canonical);
+        Ok(())
+    }
+
+    /// Get all currently tracked indexed directories
+    pub fn get_indexed_paths(&self) -> &std::collections::HashSet<PathBuf> {
+        &self.indexed_paths
+    }
+
+    /// Directories added to and removed from config since the index stored `stored_paths`
+    fn config_path_changes(
+        stored_paths: &[PathBuf],
         config_paths: &[PathBuf],
-        progress: bool,
-    ) -> IndexResult<(usize, usize, usize, usize)> {
+    ) -> (Vec<PathBuf>, Vec<PathBuf>) {
         // Convert to sets for comparison (canonicalized)
         let stored_set: std::collections::HashSet<PathBuf> = stored_paths
-            .unwrap_or_default()
-            .into_iter()
+            .iter()
             .filter_map(|p| p.canonicalize().ok())
             .collect();
 
@@ -2127,6 +3000,32 @@ impl SimpleIndexer {
         // Find removed paths (in stored but not in config)
         let removed_paths: Vec<PathBuf> = stored_set.difference(&config_set).cloned().collect();
 
+        (new_paths, removed_paths)
+    }
+
+    /// Whether [`sync_with_config`](Self::sync_with_config) would change the index
+    pub fn needs_sync(stored_paths: Option<&[PathBuf]>, config_paths: &[PathBuf]) -> bool {
+        let (new_paths, removed_paths) =
+            Self::config_path_changes(stored_paths.unwrap_or_default(), config_paths);
+        !new_paths.is_empty() || !removed_paths.is_empty()
+    }
+
+    /// Sync indexed paths with config (settings.toml is source of truth)
+    ///
+    /// Compares stored indexed_paths with current config.
+    /// - Indexes new directories in config
+    /// - Removes symbols from directories not in config
+    ///
+    /// Returns (added_count, removed_count, files_indexed, symbols_found)
+    pub fn sync_with_config(
+        &mut self,
+        stored_paths: Option<Vec<PathBuf>>,
+        config_paths: &[PathBuf],
+        progress: bool,
+    ) -> IndexResult<(usize, usize, usize, usize)> {
+        let (new_paths, removed_paths) =
+            Self::config_path_changes(&stored_paths.unwrap_or_default(), config_paths);
+
         if new_paths.is_empty() && removed_paths.is_empty() {
             return Ok((0, 0, 0, 0));
         }
@@ -2358,6 +3257,9 @@ impl SimpleIndexer {
         // No centralized resolver to clear anymore
         self.trait_symbols_by_file.clear();
         self.variable_types.clear();
+        self.method_calls_by_file.clear();
+        self.type_methods.clear();
+        self.unresolved.clear();
 
         // Clear semantic search if enabled
         if let Some(ref semantic) = self.semantic_search {
@@ -2387,6 +3289,31 @@ impl SimpleIndexer {
             .map_err(|e| IndexError::General(format!("Search failed: {e}")))
     }
 
+    /// Search symbols carrying an attribute, decorator or annotation
+    ///
+    /// `annotation` matches a full name (`app.route`) or one segment of it
+    /// (`route`). The query may be empty to list all such symbols.
+    pub fn search_with_annotation(
+        &self,
+        query: &str,
+        limit: usize,
+        kind_filter: Option<crate::types::SymbolKind>,
+        module_filter: Option<&str>,
+        language_filter: Option<&str>,
+        annotation: &str,
+    ) -> IndexResult<Vec<SearchResult>> {
+        self.document_index
+            .search_with_annotation(
+                query,
+                limit,
+                kind_filter,
+                module_filter,
+                language_filter,
+                Some(annotation),
+            )
+            .map_err(|e| IndexError::General(format!("Search failed: {e}")))
+    }
+
     /// Get total number of indexed documents
     pub fn document_count(&self) -> IndexResult<u64> {
         self.document_index
@@ -2453,6 +3380,21 @@ impl SimpleIndexer {
             return Ok(stats);
         }
 
+        self.index_files(files, progress, force)
+    }
+
+    /// Index an explicit list of files, then resolve cross-file relationships
+    ///
+    /// Used by directory indexing after the walk and by the dependency layer,
+    /// whose files come from several unrelated source roots.
+    #[must_use = "The indexing result should be checked for errors"]
+    pub fn index_files(
+        &mut self,
+        files: Vec<PathBuf>,
+        progress: bool,
+        force: bool,
+    ) -> IndexResult<IndexStats> {
+        let total_files = files.len();
         let mut stats = IndexStats::new();
 
         // Process files one at a time with batched commits
@@ -2473,10 +3415,22 @@ impl SimpleIndexer {
             None
         };
 
+        // Journal the run before anything is written so an interruption can resume
+        if total_files > 0 {
+            self.journal
+                .begin(&files)
+                .map_err(|e| self.journal_write_error(e))?;
+        }
+
         // Start batch once before the loop
         self.start_tantivy_batch()?;
         const COMMIT_BATCH_SIZE: usize = 100; // Commit every 100 files to reduce I/O
-        let mut files_in_batch = 0;
+        let mut transaction = self.begin_transaction();
+
+        // Soft limit: pending resolution state above this goes to disk, but
+        // only checked between batches
+        let memory_limit = self.settings.indexing.memory_limit_mb * 1_000_000;
+        let mut spill: Option<SpillStore> = None;
 
         for file_path in files {
             let mut file_success = false;
@@ -2484,13 +3438,28 @@ impl SimpleIndexer {
             {
                 match self.index_file_internal(&file_path, force) {
                     Ok(result) => {
-                        files_in_batch += 1;
+                        transaction.add_file(file_path.clone());
 
                         // Commit batch periodically to avoid excessive memory usage
-                        if files_in_batch >= COMMIT_BATCH_SIZE {
-                            self.commit_tantivy_batch()?;
+                        if transaction.len() >= COMMIT_BATCH_SIZE {
+                            self.commit_transaction(std::mem::take(&mut transaction))?;
                             self.start_tantivy_batch()?;
-                            files_in_batch = 0;
+                            stats.batches += 1;
+
+                            if memory_limit > 0 && self.pending_resolution_bytes() > memory_limit {
+                                let store = match &mut spill {
+                                    Some(store) => store,
+                                    None => spill.insert(
+                                        SpillStore::create(&self.index_dir()).map_err(
+                                            |source| IndexError::FileWrite {
+                                                path: self.index_dir(),
+                                                source,
+                                            },
+                                        )?,
+                                    ),
+                                };
+                                self.spill_pending_resolution(store, &mut stats)?;
+                            }
                         }
 
                         let file_id = result.file_id();
@@ -2531,13 +3500,17 @@ impl SimpleIndexer {
         }
 
         // Commit any remaining files in the batch
-        if files_in_batch > 0 {
-            self.commit_tantivy_batch()?;
+        if !transaction.is_empty() {
+            self.commit_transaction(transaction)?;
+            stats.batches += 1;
         }
 
         // Resolve cross-file relationships after all files are indexed
-        if !dry_run {
-            self.resolve_cross_file_relationships()?;
+        self.resolve_pending_relationships(spill.as_ref())?;
+        if total_files > 0 {
+            self.journal
+                .resolved()
+                .map_err(|e| self.journal_write_error(e))?;
         }
 
         // Stop timing and update final stats before returning
@@ -2547,6 +3520,135 @@ impl SimpleIndexer {
         Ok(stats)
     }
 
+    /// Rough heap size of the state cross-file resolution holds on to
+    ///
+    /// Counts the unresolved relationships and the method calls and variable
+    /// types read while resolving them. Language behaviors and `type_methods`,
+    /// which stay in memory anyway, are not counted.
+    fn pending_resolution_bytes(&self) -> usize {
+        let relationships: usize = self
+            .unresolved_relationships
+            .iter()
+            .map(|rel| {
+                std::mem::size_of::<UnresolvedRelationship>()
+                    + rel.from_name.len()
+                    + rel.to_name.len()
+                    + rel
+                        .metadata
+                        .as_ref()
+                        .and_then(|metadata| metadata.context.as_ref())
+                        .map_or(0, |context| context.len())
+            })
+            .sum();
+        let method_calls: usize = self
+            .method_calls_by_file
+            .values()
+            .flatten()
+            .map(|call| {
+                std::mem::size_of::<MethodCall>()
+                    + call.caller.len()
+                    + call.method_name.len()
+                    + call.receiver.as_ref().map_or(0, String::len)
+            })
+            .sum();
+        let variable_types: usize = self
+            .variable_types
+            .iter()
+            .map(|((_, expr), type_name)| {
+                std::mem::size_of::<((FileId, String), String)>() + expr.len() + type_name.len()
+            })
+            .sum();
+        relationships + method_calls + variable_types
+    }
+
+    /// Move the pending resolution state to disk as one spill chunk
+    ///
+    /// Method calls and variable types only matter for the relationships of
+    /// their own file, so those of files without pending relationships are
+    /// dropped. Trait kinds are only read while a file is being indexed.
+    fn spill_pending_resolution(
+        &mut self,
+        spill: &mut SpillStore,
+        stats: &mut IndexStats,
+    ) -> IndexResult<()> {
+        let mut files: std::collections::HashMap<FileId, SpilledFile> =
+            std::collections::HashMap::new();
+        for rel in std::mem::take(&mut self.unresolved_relationships) {
+            files
+                .entry(rel.file_id)
+                .or_insert_with(|| SpilledFile {
+                    file_id: rel.file_id,
+                    relationships: Vec::new(),
+                    method_calls: Vec::new(),
+                    variable_types: Vec::new(),
+                })
+                .relationships
+                .push(SpilledRelationship {
+                    from_id: rel.from_id,
+                    from_name: rel.from_name.to_string(),
+                    to_name: rel.to_name.to_string(),
+                    kind: rel.kind,
+                    metadata: rel.metadata,
+                });
+        }
+        for (file_id, method_calls) in std::mem::take(&mut self.method_calls_by_file) {
+            if let Some(file) = files.get_mut(&file_id) {
+                file.method_calls = method_calls;
+            }
+        }
+        for ((file_id, expr), type_name) in std::mem::take(&mut self.variable_types) {
+            if let Some(file) = files.get_mut(&file_id) {
+                file.variable_types.push((expr, type_name));
+            }
+        }
+        self.trait_symbols_by_file.clear();
+
+        let mut files: Vec<SpilledFile> = files.into_values().collect();
+        files.sort_by_key(|file| file.file_id.value());
+        for file in &mut files {
+            file.variable_types.sort();
+        }
+        spill
+            .write(&files)
+            .map_err(|source| IndexError::FileWrite {
+                path: spill.path().to_path_buf(),
+                source,
+            })?;
+
+        stats.relationships_spilled += files
+            .iter()
+            .map(|file| file.relationships.len())
+            .sum::<usize>();
+        stats.spill_chunks = spill.chunk_count();
+        stats.spill_bytes = spill.bytes();
+        Ok(())
+    }
+
+    /// Put a spilled chunk back in place, returning its relationships
+    fn restore_spilled(&mut self, files: Vec<SpilledFile>) -> Vec<UnresolvedRelationship> {
+        let mut relationships = Vec::new();
+        for file in files {
+            let file_id = file.file_id;
+            relationships.extend(file.relationships.into_iter().map(|rel| {
+                UnresolvedRelationship {
+                    from_id: rel.from_id,
+                    from_name: rel.from_name.into(),
+                    to_name: rel.to_name.into(),
+                    file_id,
+                    kind: rel.kind,
+                    metadata: rel.metadata,
+                }
+            }));
+            if !file.method_calls.is_empty() {
+                self.method_calls_by_file.insert(file_id, file.method_calls);
+            }
+            for (expr, type_name) in file.variable_types {
+                self.variable_types.insert((file_id, expr), type_name);
+            }
+        }
+        relationships
+    }
+
     // RESOLUTION SYSTEM: State reconstruction removed
     // Resolution state is now maintained by language behaviors
     // The new behavior system builds state incrementally during indexing
@@ -2731,6 +3833,13 @@ impl SimpleIndexer {
                 return None; // External library - don't resolve
             }
 
+            // The receiver is the type itself, so its recorded methods decide first
+            if let Some(id) =
+                self.resolve_method_on_type(receiver, &method_call.method_name, file_id, context)
+            {
+                return Some(id);
+            }
+
             // For static calls, receiver is the type name, try Type::method format
             let static_method = format!("{}::{}", receiver, method_call.method_name);
 
@@ -2756,12 +3865,27 @@ impl SimpleIndexer {
             return result;
         }
 
-        // For instance methods, look up receiver's type
+        // For instance methods, look up receiver's type: the enclosing type for
+        // self/this, otherwise the type inferred for the receiver expression
         let receiver_key = Self::normalize_expression_key(receiver);
-        let type_name = self
-            .variable_types
-            .get(&(file_id, receiver_key.clone()))
-            .cloned()
+        let enclosing_type = if type_methods::is_self_receiver(receiver) {
+            let caller = method_call
+                .caller
+                .rsplit(['.', ':'])
+                .next()
+                .unwrap_or(&method_call.caller);
+            self.type_methods
+                .enclosing_type(file_id, caller, method_call.range.start_line)
+                .map(str::to_string)
+        } else {
+            None
+        };
+        let type_name = enclosing_type
+            .or_else(|| {
+                self.variable_types
+                    .get(&(file_id, receiver_key.clone()))
+                    .cloned()
+            })
             .or_else(|| {
                 if crate::config::is_global_debug_enabled() {
                     eprintln!(
@@ -2783,6 +3907,13 @@ impl SimpleIndexer {
             eprintln!("[EXT-RESOLVE] Found type for receiver '{receiver}': {type_name}");
         }
 
+        // The method the receiver's type defines or inherits, if we saw its definition
+        if let Some(id) =
+            self.resolve_method_on_type(&type_name, &method_call.method_name, file_id, context)
+        {
+            return Some(id);
+        }
+
         // Qualify method name with receiver type for extension function resolution
         // E.g., receiver="42" type="Int" method="double" → "Int.double"
         let qualified_method = format!("{}.{}", type_name, method_call.method_name);
@@ -2793,37 +3924,230 @@ impl SimpleIndexer {
         context.resolve(&qualified_method)
     }
 
-    /// Build resolution context for a file with all available symbols
-    fn build_resolution_context(&self, file_id: FileId) -> IndexResult<Box<dyn ResolutionScope>> {
-        // Use behavior's build_resolution_context which handles imports with our new matching logic
-        let behavior = self.get_behavior_for_file(file_id)?;
+    /// Resolve `method` on a receiver of type `type_name`
+    ///
+    /// The language's inheritance resolver finds the type that provides the
+    /// method (the type itself, a base class, an implemented trait), then the
+    /// recorded definition site identifies the symbol. When several types
+    /// share the owner's name, the one defined in this file or visible from it
+    /// wins; otherwise the call is left to name-based resolution.
+    fn resolve_method_on_type(
+        &self,
+        type_name: &str,
+        method: &str,
+        file_id: FileId,
+        context: &dyn ResolutionScope,
+    ) -> Option<SymbolId> {
+        let language_id = *self.file_languages.get(&file_id)?;
+        let owner = self.type_methods.owner(language_id, type_name, method)?;
+        let sites = self.type_methods.sites(language_id, &owner, method);
+
+        let site = match sites.as_slice() {
+            [] => return None,
+            [site] => *site,
+            _ => sites
+                .iter()
+                .find(|site| site.file_id == file_id)
+                .or_else(|| {
+                    let type_id = context.resolve(&owner)?;
+                    let type_file = self.get_symbol(type_id)?.file_id;
+                    sites.iter().find(|site| site.file_id == type_file)
+                })
+                .copied()?,
+        };
 
-        // NEW: Check if we can use cache-based resolution
-        if let Some(cache) = self.symbol_cache() {
-            // Build context with cache (fast path)
-            behavior.build_resolution_context_with_cache(file_id, cache, &self.document_index)
-        } else {
-            // Fall back to existing path (compatibility)
-            behavior.build_resolution_context(file_id, &self.document_index)
-        }
-    }
+        debug_print!(
+            self,
+            "Receiver type {} resolves {} to {} in {:?}",
+            type_name,
+            method,
+            owner,
+            site.file_id
+        );
+
+        // Some languages name methods `Type.method`, so compare the last segment
+        self.document_index
+            .find_symbols_by_file(site.file_id)
+            .ok()?
+            .into_iter()
+            .filter(|symbol| {
+                let name: &str = &symbol.name;
+                name.rsplit(['.', ':']).next() == Some(method)
+            })
+            .filter(|symbol| {
+                symbol.range.start_line <= site.range.end_line
+                    && symbol.range.end_line >= site.range.start_line
+            })
+            .min_by_key(|symbol| symbol.range.start_line.abs_diff(site.range.start_line))
+            .map(|symbol| symbol.id)
+    }
+
+    /// Rebuild the inheritance resolvers used for receiver-typed calls
+    ///
+    /// Each language's resolver starts from the behavior of its first file,
+    /// by file ID, so every run builds the same one.
+    fn prepare_type_methods(&mut self) {
+        let behaviors = &self.file_behaviors;
+        let languages = &self.file_languages;
+        self.type_methods.prepare(|language_id| {
+            languages
+                .iter()
+                .filter(|(file_id, language)| {
+                    **language == language_id && behaviors.contains_key(file_id)
+                })
+                .map(|(file_id, _)| file_id)
+                .min_by_key(|file_id| file_id.value())
+                .and_then(|file_id| behaviors.get(file_id))
+                .map(|behavior| behavior.create_inheritance_resolver())
+        });
+    }
+
+    /// Build resolution context for a file with all available symbols
+    fn build_resolution_context(&self, file_id: FileId) -> IndexResult<Box<dyn ResolutionScope>> {
+        // Use behavior's build_resolution_context which handles imports with our new matching logic
+        let behavior = self.get_behavior_for_file(file_id)?;
+
+        // NEW: Check if we can use cache-based resolution
+        if let Some(cache) = self.symbol_cache() {
+            // Build context with cache (fast path)
+            behavior.build_resolution_context_with_cache(file_id, cache, &self.document_index)
+        } else {
+            // Fall back to existing path (compatibility)
+            behavior.build_resolution_context(file_id, &self.document_index)
+        }
+    }
+
+    /// Find the handler a route names, across all indexed files
+    ///
+    /// Matches on the last segment of the handler path and uses the
+    /// qualifier (controller, module) to pick between candidates. Only a
+    /// unique match is linked.
+    fn resolve_route_handler(&self, handler: &str) -> Option<SymbolId> {
+        use crate::parsing::route::handler_short_name;
+
+        let (qualifier, short) = match handler.strip_suffix("@__invoke") {
+            // Invokable controllers are routed to the class itself
+            Some(class) => ("", handler_short_name(class)),
+            None => {
+                let short = handler_short_name(handler);
+                let qualifier =
+                    handler[..handler.len() - short.len()].trim_end_matches(['.', ':', '\\', '@']);
+                (handler_short_name(qualifier), short)
+            }
+        };
+
+        let candidates: Vec<Symbol> = self
+            .document_index
+            .find_symbols_by_name(short, None)
+            .ok()?
+            .into_iter()
+            .filter(|s| {
+                matches!(
+                    s.kind,
+                    SymbolKind::Function | SymbolKind::Method | SymbolKind::Class
+                ) && handler_short_name(&s.name) == short
+            })
+            .collect();
+
+        let candidates: Vec<&Symbol> = if candidates.len() > 1 && !qualifier.is_empty() {
+            candidates
+                .iter()
+                .filter(|s| {
+                    s.module_path
+                        .as_deref()
+                        .is_some_and(|m| m.contains(qualifier))
+                        || s.file_path.contains(qualifier)
+                        || s.name.contains(qualifier)
+                })
+                .collect()
+        } else {
+            candidates.iter().collect()
+        };
+
+        match candidates.as_slice() {
+            [symbol] => Some(symbol.id),
+            _ => None,
+        }
+    }
+
+    /// Why a relationship target stayed unresolved
+    ///
+    /// External when the language mapped the call to an external module or
+    /// the target (or its receiver or qualifier) is an external import;
+    /// ambiguous when several indexed symbols carry the name.
+    fn unresolved_reason(
+        &self,
+        rel: &UnresolvedRelationship,
+        context: &dyn ResolutionScope,
+        mapped_external: bool,
+        symbol_lookup_cache: &DashMap<String, Vec<Symbol>>,
+    ) -> UnresolvedReason {
+        let to_name: &str = &rel.to_name;
+        let (receiver, _, _, _) = self.extract_metadata_hints(rel.metadata.as_ref());
+        let qualifier = to_name
+            .split_once("::")
+            .or_else(|| to_name.split_once('.'))
+            .map(|(qualifier, _)| qualifier);
+        let is_external = mapped_external
+            || context.is_external_import(to_name)
+            || receiver
+                .as_deref()
+                .is_some_and(|receiver| context.is_external_import(receiver))
+            || qualifier.is_some_and(|qualifier| context.is_external_import(qualifier));
+        if is_external {
+            return UnresolvedReason::External;
+        }
+
+        let short_name = to_name.rsplit(['.', ':']).next().unwrap_or(to_name);
+        let candidates = match symbol_lookup_cache.get(short_name) {
+            Some(cached) => cached.len(),
+            None => {
+                let symbols = self
+                    .document_index
+                    .find_symbols_by_name(short_name, None)
+                    .unwrap_or_default();
+                let count = symbols.len();
+                symbol_lookup_cache.insert(short_name.to_string(), symbols);
+                count
+            }
+        };
+        if candidates > 1 {
+            UnresolvedReason::Ambiguous
+        } else {
+            UnresolvedReason::NotFound
+        }
+    }
 
     /// Resolve cross-file relationships using imports
-    fn resolve_cross_file_relationships(&mut self) -> IndexResult<()> {
+    ///
+    /// Completes files indexed with [`Self::index_file_no_resolve`].
+    pub fn resolve_cross_file_relationships(&mut self) -> IndexResult<()> {
+        self.resolve_pending_relationships(None)
+    }
+
+    /// Resolve the relationships held in memory, then those spilled to disk
+    ///
+    /// Spilled chunks are read back one at a time. Their method calls and
+    /// variable types are dropped again once the chunk is resolved, so memory
+    /// stays near the size of one chunk.
+    fn resolve_pending_relationships(&mut self, spill: Option<&SpillStore>) -> IndexResult<()> {
         // Process all unresolved relationships
-        let unresolved = std::mem::take(&mut self.unresolved_relationships);
+        let mut in_memory = std::mem::take(&mut self.unresolved_relationships);
+        let spilled = spill.map_or(0, SpillStore::relationship_count);
+        let spilled_chunks = spill.map_or(0, SpillStore::chunk_count);
 
         eprintln!(
             "Resolving cross-file relationships: {} unresolved entries",
-            unresolved.len()
+            in_memory.len() + spilled
         );
         debug_print!(
             self,
-            "resolve_cross_file_relationships: {} unresolved relationships",
-            unresolved.len()
+            "resolve_cross_file_relationships: {} unresolved relationships ({} spilled)",
+            in_memory.len() + spilled,
+            spilled
         );
 
-        if unresolved.is_empty() {
+        if in_memory.is_empty() && spilled == 0 {
             eprintln!("DEBUG: No unresolved relationships to process");
             return Ok(());
         }
@@ -2831,9 +4155,20 @@ impl SimpleIndexer {
         // Start a batch for relationship updates
         self.start_tantivy_batch()?;
 
+        // Inheritance resolvers for receiver-typed method calls
+        self.prepare_type_methods();
+
+        // Rust exports that other languages call through FFI bindings
+        let ffi_exports = self.collect_ffi_exports()?;
+        let mut foreign_calls = Vec::new();
+
+        // RPCs of `.proto` schemas, implemented and called through generated code
+        let rpc_table = self.collect_rpcs()?;
+        let mut rpc_calls = Vec::new();
+
         let mut resolved_count = 0;
         let mut skipped_count = 0;
-        let total_unresolved = unresolved.len();
+        let total_unresolved = in_memory.len() + spilled;
 
         let progress = if total_unresolved > 0 {
             let options = ProgressBarOptions::default()
@@ -2852,161 +4187,297 @@ impl SimpleIndexer {
             None
         };
 
-        // Group relationships by file for efficient context building
-        let mut relationships_by_file: std::collections::HashMap<
-            FileId,
-            Vec<UnresolvedRelationship>,
-        > = std::collections::HashMap::new();
-        for rel in unresolved {
-            relationships_by_file
-                .entry(rel.file_id)
-                .or_default()
-                .push(rel);
+        // Symbol lookup cache to avoid millions of duplicate Tantivy queries,
+        // shared by the threads resolving files. Maps symbol_name -> Vec<Symbol>
+        let symbol_lookup_cache: DashMap<String, Vec<Symbol>> = DashMap::new();
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(self.settings.indexing.parallel_threads)
+            .build()
+            .map_err(|e| IndexError::General(format!("Failed to start resolution threads: {e}")))?;
+
+        for chunk in 0..=spilled_chunks {
+            // Relationships held in memory first, then each spilled chunk
+            let (unresolved, restored) = match spill {
+                Some(spill) if chunk > 0 => {
+                    let files = spill
+                        .read(chunk - 1)
+                        .map_err(|source| IndexError::FileRead {
+                            path: spill.path().to_path_buf(),
+                            source,
+                        })?;
+                    let restored: std::collections::HashSet<FileId> =
+                        files.iter().map(|file| file.file_id).collect();
+                    (self.restore_spilled(files), restored)
+                }
+                _ => (
+                    std::mem::take(&mut in_memory),
+                    std::collections::HashSet::new(),
+                ),
+            };
+
+            // Group relationships by file for efficient context building
+            let mut relationships_by_file: std::collections::HashMap<
+                FileId,
+                Vec<UnresolvedRelationship>,
+            > = std::collections::HashMap::new();
+            for rel in unresolved {
+                relationships_by_file
+                    .entry(rel.file_id)
+                    .or_default()
+                    .push(rel);
+            }
+
+            // Resolve files in parallel, in file ID order so relationships are
+            // stored in the same order on every run
+            let mut relationships_by_file: Vec<_> = relationships_by_file.into_iter().collect();
+            relationships_by_file.sort_by_key(|(file_id, _)| file_id.value());
+            let bar = progress.as_ref().map(|(bar, _)| bar.as_ref());
+            let resolutions = pool.install(|| {
+                relationships_by_file
+                    .into_par_iter()
+                    .map(|(file_id, file_relationships)| {
+                        self.resolve_file_relationships(
+                            file_id,
+                            file_relationships,
+                            &ffi_exports,
+                            &rpc_table,
+                            &symbol_lookup_cache,
+                            bar,
+                        )
+                    })
+                    .collect::<IndexResult<Vec<_>>>()
+            })?;
+
+            for resolution in resolutions {
+                for (from, to, relationship) in resolution.relationships {
+                    self.add_relationship_internal(from, to, relationship)?;
+                }
+                for reference in &resolution.unresolved {
+                    self.unresolved.record(reference);
+                }
+                foreign_calls.extend(resolution.foreign_calls);
+                rpc_calls.extend(resolution.rpc_calls);
+                resolved_count += resolution.resolved;
+                skipped_count += resolution.skipped;
+            }
+
+            if !restored.is_empty() {
+                for file_id in &restored {
+                    self.method_calls_by_file.remove(file_id);
+                }
+                self.variable_types
+                    .retain(|(file_id, _), _| !restored.contains(file_id));
+                symbol_lookup_cache.clear();
+            }
         }
 
-        // Symbol lookup cache to avoid millions of duplicate Tantivy queries
-        // Maps symbol_name -> Vec<Symbol>
-        let mut symbol_lookup_cache: std::collections::HashMap<String, Vec<Symbol>> =
-            std::collections::HashMap::new();
+        resolved_count += self.link_ffi_calls(&ffi_exports, foreign_calls)?;
+        resolved_count += self.link_grpc(&rpc_table, rpc_calls)?;
+
+        // Commit the batch with all the relationships
+        self.commit_tantivy_batch()?;
 
-        // Process each file's relationships with its resolution context
-        for (file_id, file_relationships) in relationships_by_file {
-            // Build resolution context for this file
-            let context = self.build_resolution_context(file_id)?;
+        if let Some((bar, status)) = progress {
+            drop(status);
+            eprintln!("{bar}");
+        }
+
+        debug_print!(
+            self,
+            "Relationship resolution complete - resolved: {}, skipped: {}, total: {}",
+            resolved_count,
+            skipped_count,
+            total_unresolved
+        );
+
+        Ok(())
+    }
+
+    /// Resolve one file's relationships with the file's resolution context
+    ///
+    /// Only reads the index, so files are resolved in parallel; the caller
+    /// stores the outcome.
+    fn resolve_file_relationships(
+        &self,
+        file_id: FileId,
+        file_relationships: Vec<UnresolvedRelationship>,
+        ffi_exports: &ExportTable,
+        rpc_table: &RpcTable,
+        symbol_lookup_cache: &DashMap<String, Vec<Symbol>>,
+        progress: Option<&ProgressBar>,
+    ) -> IndexResult<FileResolution> {
+        let mut resolution = FileResolution::default();
+
+        // Build resolution context for this file
+        let context = self.build_resolution_context(file_id)?;
+        let file_path = self.get_file_path(file_id).unwrap_or_default();
+        let language = self
+            .file_languages
+            .get(&file_id)
+            .map(|language| language.as_str().to_string())
+            .unwrap_or_default();
+
+        for rel in file_relationships {
+            if let Some(bar) = progress {
+                bar.inc();
+            }
+
+            debug_print!(
+                self,
+                "Processing relationship: {} -> {} (kind: {:?}, file: {:?})",
+                rel.from_name,
+                rel.to_name,
+                rel.kind,
+                rel.file_id
+            );
 
-            for rel in file_relationships {
-                if let Some((bar, _)) = &progress {
-                    bar.inc();
+            // Find 'from' symbols - use from_id when available to skip lookup
+            let from_symbols: Vec<_> = if let Some(from_id) = rel.from_id {
+                // We already have the from_id, just fetch the symbol
+                debug_print!(
+                    self,
+                    "Using cached from_id: {:?} for '{}'",
+                    from_id,
+                    rel.from_name
+                );
+                match self.document_index.find_symbol_by_id(from_id) {
+                    Ok(Some(symbol)) => vec![symbol],
+                    Ok(None) => {
+                        debug_print!(
+                            self,
+                            "WARNING: from_id {:?} not found for '{}'",
+                            from_id,
+                            rel.from_name
+                        );
+                        Vec::new()
+                    }
+                    Err(e) => {
+                        debug_print!(self, "ERROR: Failed to fetch symbol {:?}: {}", from_id, e);
+                        Vec::new()
+                    }
                 }
+            } else {
+                // Fallback to name-based lookup
+                debug_print!(
+                    self,
+                    "No from_id available, falling back to name lookup for '{}'",
+                    rel.from_name
+                );
+                let behavior_for_file = self.get_behavior_for_file(file_id)?;
+                let from_query_name =
+                    behavior_for_file.normalize_caller_name(&rel.from_name, file_id);
+
+                // Check cache first to avoid duplicate Tantivy queries
+                let all_from_symbols =
+                    if let Some(cached) = symbol_lookup_cache.get(&from_query_name) {
+                        cached.value().clone()
+                    } else {
+                        // Cache miss - query Tantivy and cache the result
+                        let symbols = self
+                            .document_index
+                            .find_symbols_by_name(&from_query_name, None)
+                            .map_err(|e| IndexError::TantivyError {
+                                operation: "find_symbols_by_name".to_string(),
+                                cause: e.to_string(),
+                            })?;
+                        symbol_lookup_cache.insert(from_query_name.clone(), symbols.clone());
+                        symbols
+                    };
 
                 debug_print!(
                     self,
-                    "Processing relationship: {} -> {} (kind: {:?}, file: {:?})",
-                    rel.from_name,
-                    rel.to_name,
-                    rel.kind,
-                    rel.file_id
+                    "Looking for '{}' symbols, found {} total",
+                    from_query_name,
+                    all_from_symbols.len()
                 );
 
-                // Find 'from' symbols - use from_id when available to skip lookup
-                let from_symbols: Vec<_> = if let Some(from_id) = rel.from_id {
-                    // We already have the from_id, just fetch the symbol
-                    debug_print!(
-                        self,
-                        "Using cached from_id: {:?} for '{}'",
-                        from_id,
-                        rel.from_name
-                    );
-                    match self.document_index.find_symbol_by_id(from_id) {
-                        Ok(Some(symbol)) => vec![symbol],
-                        Ok(None) => {
-                            debug_print!(
-                                self,
-                                "WARNING: from_id {:?} not found for '{}'",
-                                from_id,
-                                rel.from_name
-                            );
-                            Vec::new()
-                        }
-                        Err(e) => {
-                            debug_print!(
-                                self,
-                                "ERROR: Failed to fetch symbol {:?}: {}",
-                                from_id,
-                                e
-                            );
-                            Vec::new()
-                        }
+                // Filter to only symbols from the current file
+                let mut from_symbols: Vec<_> = all_from_symbols
+                    .into_iter()
+                    .filter(|s| s.file_id == file_id)
+                    .collect();
+
+                // Languages that name methods `Type.method` report the caller
+                // by its bare name; the type whose method spans the call names it
+                if from_symbols.is_empty() {
+                    let owner = rel
+                        .metadata
+                        .as_ref()
+                        .and_then(|metadata| metadata.line)
+                        .and_then(|line| {
+                            self.type_methods
+                                .enclosing_type(file_id, &from_query_name, line)
+                        });
+                    if let Some(owner) = owner {
+                        let qualified = format!("{owner}.{from_query_name}");
+                        from_symbols = self
+                            .document_index
+                            .find_symbols_by_name(&qualified, None)
+                            .unwrap_or_default()
+                            .into_iter()
+                            .filter(|s| s.file_id == file_id && *s.name == *qualified)
+                            .collect();
                     }
-                } else {
-                    // Fallback to name-based lookup
-                    debug_print!(
-                        self,
-                        "No from_id available, falling back to name lookup for '{}'",
-                        rel.from_name
-                    );
-                    let behavior_for_file = self.get_behavior_for_file(file_id)?;
-                    let from_query_name =
-                        behavior_for_file.normalize_caller_name(&rel.from_name, file_id);
-
-                    // Check cache first to avoid duplicate Tantivy queries
-                    let all_from_symbols =
-                        if let Some(cached) = symbol_lookup_cache.get(&from_query_name) {
-                            cached.clone()
-                        } else {
-                            // Cache miss - query Tantivy and cache the result
-                            let symbols = self
-                                .document_index
-                                .find_symbols_by_name(&from_query_name, None)
-                                .map_err(|e| IndexError::TantivyError {
-                                    operation: "find_symbols_by_name".to_string(),
-                                    cause: e.to_string(),
-                                })?;
-                            symbol_lookup_cache.insert(from_query_name.clone(), symbols.clone());
-                            symbols
-                        };
+                }
 
+                debug_print!(
+                    self,
+                    "Found {} from_symbols in current file",
+                    from_symbols.len()
+                );
+
+                if from_symbols.is_empty() && rel.kind == RelationKind::Calls {
                     debug_print!(
                         self,
-                        "Looking for '{}' symbols, found {} total",
+                        "WARNING: No '{}' symbol found in file {:?} for Calls relationship to '{}'",
                         from_query_name,
-                        all_from_symbols.len()
+                        file_id,
+                        rel.to_name
                     );
+                }
 
-                    // Filter to only symbols from the current file
-                    let from_symbols: Vec<_> = all_from_symbols
-                        .into_iter()
-                        .filter(|s| s.file_id == file_id)
-                        .collect();
-
-                    debug_print!(
-                        self,
-                        "Found {} from_symbols in current file",
-                        from_symbols.len()
-                    );
+                from_symbols
+            };
 
-                    if from_symbols.is_empty() && rel.kind == RelationKind::Calls {
-                        debug_print!(
-                            self,
-                            "WARNING: No '{}' symbol found in file {:?} for Calls relationship to '{}'",
-                            from_query_name,
-                            file_id,
-                            rel.to_name
-                        );
-                    }
+            // Calls of a generated gRPC client method, resolved or not
+            if rel.kind == RelationKind::Calls {
+                if let Some(call) = self.rpc_call(rpc_table, &rel, &from_symbols) {
+                    resolution.rpc_calls.push(call);
+                }
+            }
 
-                    from_symbols
-                };
+            // Set when the language maps an unresolved call to an external module
+            let mut external_target = false;
 
-                // Use the clean resolution API that delegates to language-specific logic
-                let to_symbol_id = if rel.kind == RelationKind::Calls && from_symbols.len() == 1 {
-                    // Special handling for method calls with enhanced resolution
-                    debug_print!(self, "Resolving as method call: '{}'", rel.to_name);
-                    let res = self.resolve_method_call_enhanced(
-                        &rel.to_name,
-                        &rel.from_name,
-                        file_id,
-                        context.as_ref(),
-                        rel.metadata.as_ref(),
-                    );
+            // Use the clean resolution API that delegates to language-specific logic
+            let to_symbol_id = if rel.kind == RelationKind::Calls && from_symbols.len() == 1 {
+                // Special handling for method calls with enhanced resolution
+                debug_print!(self, "Resolving as method call: '{}'", rel.to_name);
+                let res = self.resolve_method_call_enhanced(
+                    &rel.to_name,
+                    &rel.from_name,
+                    file_id,
+                    context.as_ref(),
+                    rel.metadata.as_ref(),
+                );
+                debug_print!(
+                    self,
+                    "resolve_method_call_enhanced returned: {:?} for {}",
+                    res,
+                    rel.to_name
+                );
+                if res.is_none() {
                     debug_print!(
                         self,
-                        "resolve_method_call_enhanced returned: {:?} for {}",
-                        res,
+                        "Resolution failed, trying external mapping for {}",
                         rel.to_name
                     );
-                    if res.is_none() {
-                        debug_print!(
-                            self,
-                            "Resolution failed, trying external mapping for {}",
-                            rel.to_name
-                        );
-                        // Try external mapping as a fallback
-                        if let Some(behavior) = self.file_behaviors.get(&file_id) {
-                            // Build a better external key using MethodCall receiver if available
-                            let to_key = if let Some(method_calls) =
-                                self.method_calls_by_file.get(&file_id)
-                            {
+                    // Try external mapping as a fallback
+                    if let Some(behavior) = self.file_behaviors.get(&file_id) {
+                        // Build a better external key using MethodCall receiver if available
+                        let to_key =
+                            if let Some(method_calls) = self.method_calls_by_file.get(&file_id) {
                                 let caller_name =
                                     from_symbols.first().map(|s| s.name.as_ref()).unwrap_or("");
                                 let target_name = rel.to_name.as_ref();
@@ -3025,230 +4496,539 @@ impl SimpleIndexer {
                                 rel.to_name.to_string()
                             };
 
+                        debug_print!(
+                            self,
+                            "Trying to resolve external call target: '{}' for file {:?}",
+                            to_key,
+                            file_id
+                        );
+                        if let Some((module_path, symbol_name)) =
+                            behavior.resolve_external_call_target(&to_key, file_id)
+                        {
+                            // Skip external symbol creation for resolved external calls
+                            external_target = true;
                             debug_print!(
                                 self,
-                                "Trying to resolve external call target: '{}' for file {:?}",
+                                "Skipping external symbol for resolved call: {} -> {}::{}",
                                 to_key,
-                                file_id
+                                module_path,
+                                symbol_name
                             );
-                            if let Some((module_path, symbol_name)) =
-                                behavior.resolve_external_call_target(&to_key, file_id)
-                            {
-                                // Skip external symbol creation for resolved external calls
-                                debug_print!(
-                                    self,
-                                    "Skipping external symbol for resolved call: {} -> {}::{}",
-                                    to_key,
-                                    module_path,
-                                    symbol_name
-                                );
-                                None
-                            } else {
-                                None
-                            }
+                            None
+                        } else {
+                            None
+                        }
+                    } else {
+                        None
+                    }
+                } else {
+                    res
+                }
+            } else {
+                // Delegate all relationship resolution to the language-specific context
+                // This includes Defines, Implements, Extends, and other relationships
+                debug_print!(
+                    self,
+                    "Resolving relationship: {} -> {} (kind: {:?})",
+                    rel.from_name,
+                    rel.to_name,
+                    rel.kind
+                );
+                let result =
+                    context.resolve_relationship(&rel.from_name, &rel.to_name, rel.kind, file_id);
+                debug_print!(self, "Resolution result: {:?}", result);
+                // If unresolved call, try language behavior external mapping
+                if result.is_none() && rel.kind == RelationKind::Calls {
+                    if let Some(behavior) = self.file_behaviors.get(&file_id) {
+                        if let Some((module_path, symbol_name)) =
+                            behavior.resolve_external_call_target(&rel.to_name, file_id)
+                        {
+                            // Skip external symbol creation for mapped external calls
+                            external_target = true;
+                            debug_print!(
+                                self,
+                                "Skipping external symbol for mapped call: {} -> {}::{}",
+                                rel.to_name,
+                                module_path,
+                                symbol_name
+                            );
+                            None
                         } else {
                             None
                         }
                     } else {
-                        res
+                        None
                     }
+                } else if result.is_none() && rel.kind == RelationKind::Routes {
+                    // Route handlers are often named by qualified path
+                    // (`handlers::create`, `OrderController@store`)
+                    self.resolve_route_handler(&rel.to_name)
                 } else {
-                    // Delegate all relationship resolution to the language-specific context
-                    // This includes Defines, Implements, Extends, and other relationships
+                    result
+                }
+            };
+
+            let to_symbol_id = match to_symbol_id {
+                Some(id) => {
                     debug_print!(
                         self,
-                        "Resolving relationship: {} -> {} (kind: {:?})",
-                        rel.from_name,
+                        "Resolved target symbol '{}' to ID: {:?}",
                         rel.to_name,
-                        rel.kind
+                        id
                     );
-                    let result = context.resolve_relationship(
-                        &rel.from_name,
-                        &rel.to_name,
-                        rel.kind,
-                        file_id,
+                    id
+                }
+                None => {
+                    debug_print!(
+                        self,
+                        "[SKIP-RESOLUTION] Failed to resolve '{}' from '{}' in file {:?} (kind: {:?})",
+                        rel.to_name,
+                        rel.from_name,
+                        rel.file_id,
+                        rel.kind
                     );
-                    debug_print!(self, "Resolution result: {:?}", result);
-                    // If unresolved call, try language behavior external mapping
-                    if result.is_none() && rel.kind == RelationKind::Calls {
-                        if let Some(behavior) = self.file_behaviors.get(&file_id) {
-                            if let Some((module_path, symbol_name)) =
-                                behavior.resolve_external_call_target(&rel.to_name, file_id)
-                            {
-                                // Skip external symbol creation for mapped external calls
-                                debug_print!(
-                                    self,
-                                    "Skipping external symbol for mapped call: {} -> {}::{}",
-                                    rel.to_name,
-                                    module_path,
-                                    symbol_name
-                                );
-                                None
-                            } else {
-                                None
-                            }
-                        } else {
-                            None
-                        }
+                    // Unresolved calls may still reach a Rust FFI export
+                    let foreign = if rel.kind == RelationKind::Calls {
+                        self.foreign_call(ffi_exports, &rel, &from_symbols)
                     } else {
-                        result
+                        None
+                    };
+                    if let Some(call) = foreign {
+                        resolution.foreign_calls.push(call);
+                    } else if rel.kind != RelationKind::Defines {
+                        let reason = self.unresolved_reason(
+                            &rel,
+                            context.as_ref(),
+                            external_target,
+                            symbol_lookup_cache,
+                        );
+                        let reference = UnresolvedReference {
+                            name: rel.to_name.to_string(),
+                            file_path: file_path.clone(),
+                            line: rel
+                                .metadata
+                                .as_ref()
+                                .and_then(|metadata| metadata.line)
+                                .map(|line| line + 1),
+                            language: language.clone(),
+                            reason,
+                        };
+                        debug_print!(self, "[UNRESOLVED] {:?}", reference);
+                        resolution.unresolved.push(reference);
                     }
-                };
+                    // Symbol not in scope - skip this relationship
+                    resolution.skipped += 1;
+                    if let Some(bar) = progress {
+                        bar.add_extra2(1);
+                    }
+                    continue;
+                }
+            };
 
-                let to_symbol_id = match to_symbol_id {
-                    Some(id) => {
-                        debug_print!(
-                            self,
-                            "Resolved target symbol '{}' to ID: {:?}",
-                            rel.to_name,
-                            id
-                        );
-                        id
+            // Get the full symbol data
+            debug_print!(self, "Looking up symbol by ID: {:?}", to_symbol_id);
+            let to_symbol = match self
+                .document_index
+                .find_symbol_by_id(to_symbol_id)
+                .map_err(|e| IndexError::TantivyError {
+                    operation: "find_symbol_by_id".to_string(),
+                    cause: e.to_string(),
+                })? {
+                Some(symbol) => {
+                    debug_print!(self, "Found target symbol: {}", symbol.name);
+                    symbol
+                }
+                None => {
+                    debug_print!(
+                        self,
+                        "[SKIP-NOT-FOUND] Symbol ID {:?} not found in index for '{}'",
+                        to_symbol_id,
+                        rel.to_name
+                    );
+                    resolution.skipped += 1;
+                    if let Some(bar) = progress {
+                        bar.add_extra2(1);
                     }
-                    None => {
+                    continue;
+                }
+            };
+
+            // Process with our filtering logic
+            debug_print!(self, "Processing {} from symbols", from_symbols.len());
+            for from_symbol in &from_symbols {
+                debug_print!(
+                    self,
+                    "Checking relationship from {} to {}",
+                    from_symbol.name,
+                    to_symbol.name
+                );
+
+                // Check symbol kind compatibility
+                if !Self::is_compatible_relationship(from_symbol.kind, to_symbol.kind, rel.kind) {
+                    debug_print!(
+                        self,
+                        "[SKIP-INCOMPATIBLE] {} ({:?}) -> {} ({:?}) for {:?}",
+                        from_symbol.name,
+                        from_symbol.kind,
+                        to_symbol.name,
+                        to_symbol.kind,
+                        rel.kind
+                    );
+                    resolution.skipped += 1;
+                    if let Some(bar) = progress {
+                        bar.add_extra2(1);
+                    }
+                    continue;
+                }
+
+                // Check visibility (skip for Defines - a type can always see its own
+                // methods - and Routes, which frameworks dispatch to by reflection)
+                if !matches!(rel.kind, RelationKind::Defines | RelationKind::Routes) {
+                    debug_print!(
+                        self,
+                        "Checking visibility: {} (vis: {:?}, module: {:?}) from {} (module: {:?})",
+                        to_symbol.name,
+                        to_symbol.visibility,
+                        to_symbol.module_path,
+                        from_symbol.name,
+                        from_symbol.module_path
+                    );
+                    if !Self::is_symbol_visible_from(&to_symbol, from_symbol) {
                         debug_print!(
                             self,
-                            "[SKIP-RESOLUTION] Failed to resolve '{}' from '{}' in file {:?} (kind: {:?})",
-                            rel.to_name,
-                            rel.from_name,
-                            rel.file_id,
-                            rel.kind
+                            "[SKIP-VISIBILITY] {} not visible from {} (to_vis: {:?}, to_module: {:?}, from_module: {:?})",
+                            to_symbol.name,
+                            from_symbol.name,
+                            to_symbol.visibility,
+                            to_symbol.module_path,
+                            from_symbol.module_path
                         );
-                        // Symbol not in scope - skip this relationship
-                        skipped_count += 1;
-                        if let Some((bar, _)) = &progress {
+                        resolution.skipped += 1;
+                        if let Some(bar) = progress {
                             bar.add_extra2(1);
                         }
                         continue;
                     }
-                };
+                }
+
+                // Add the relationship with preserved metadata
+                debug_print!(
+                    self,
+                    "[SUCCESS] Adding relationship: {} ({:?}) -> {} ({:?}) kind: {:?}",
+                    from_symbol.name,
+                    from_symbol.id,
+                    to_symbol.name,
+                    to_symbol.id,
+                    rel.kind
+                );
+                let mut relationship = Relationship::new(rel.kind);
+                if let Some(ref metadata) = rel.metadata {
+                    relationship = relationship.with_metadata(metadata.clone());
+                }
+                // Calls that already resolved into a Rust FFI export record the binding
+                if rel.kind == RelationKind::Calls
+                    && from_symbol.language_id != to_symbol.language_id
+                {
+                    if let Some(mechanism) = from_symbol
+                        .language_id
+                        .and_then(|id| ffi_exports.mechanism(to_symbol.id, id.as_str()))
+                    {
+                        let mut metadata = relationship.metadata.take().unwrap_or_default();
+                        metadata.context = Some(
+                            ffi::with_mechanism(metadata.context.as_deref(), mechanism).into(),
+                        );
+                        relationship = relationship.with_metadata(metadata);
+                    }
+                }
+                resolution
+                    .relationships
+                    .push((from_symbol.id, to_symbol.id, relationship));
+                resolution.resolved += 1;
+                if let Some(bar) = progress {
+                    bar.add_extra1(1);
+                }
+            }
+        }
+
+        Ok(resolution)
+    }
+
+    /// Rust symbols exported to other languages, by foreign-visible name
+    fn collect_ffi_exports(&self) -> IndexResult<ExportTable> {
+        let mut symbols = Vec::new();
+        for annotation in ffi::EXPORT_ANNOTATIONS {
+            let found = self
+                .document_index
+                .find_symbols_by_annotation(annotation, 100_000)
+                .map_err(|e| IndexError::TantivyError {
+                    operation: "find_symbols_by_annotation".to_string(),
+                    cause: e.to_string(),
+                })?;
+            symbols.extend(found);
+        }
+        Ok(ExportTable::new(&symbols))
+    }
+
+    /// Keep an unresolved call from a caller language if it may reach an export
+    fn foreign_call(
+        &self,
+        exports: &ExportTable,
+        rel: &UnresolvedRelationship,
+        from_symbols: &[Symbol],
+    ) -> Option<ForeignCall> {
+        if exports.is_empty() {
+            return None;
+        }
+        let language = from_symbols.first()?.language_id?.as_str();
+        if !ffi::is_caller_language(language) {
+            return None;
+        }
+        let (receiver, _, _, _) = self.extract_metadata_hints(rel.metadata.as_ref());
+        let call = ForeignCall {
+            from_ids: from_symbols.iter().map(|s| s.id).collect(),
+            language,
+            file_id: rel.file_id,
+            name: rel.to_name.to_string(),
+            receiver,
+            metadata: rel.metadata.clone(),
+        };
+        exports.has_name(call.short_name()).then_some(call)
+    }
+
+    /// Link Rust FFI exports to their declarations and callers in other languages
+    ///
+    /// Creates `Calls` relationships across languages with `ffi:<mechanism>`
+    /// in the metadata context. Returns the number of relationships added.
+    fn link_ffi_calls(
+        &mut self,
+        exports: &ExportTable,
+        calls: Vec<ForeignCall>,
+    ) -> IndexResult<usize> {
+        if exports.is_empty() {
+            return Ok(0);
+        }
+        let mut linked = std::collections::HashSet::new();
+        let mut count = 0;
+
+        // Binding declarations: `external fun`, `[DllImport]`, `.pyi`/`.d.ts` stubs
+        for export in exports.exports() {
+            let candidates = self
+                .document_index
+                .find_symbols_by_name(&export.name, None)
+                .map_err(|e| IndexError::TantivyError {
+                    operation: "find_symbols_by_name".to_string(),
+                    cause: e.to_string(),
+                })?;
+            for declaration in exports.declarations(export, &candidates) {
+                let metadata = RelationshipMetadata::new()
+                    .at_position(declaration.range.start_line, declaration.range.start_column)
+                    .with_context(ffi::with_mechanism(None, export.mechanism));
+                if self.add_ffi_call(declaration.id, export, metadata, &mut linked)? {
+                    count += 1;
+                }
+            }
+        }
+
+        // Call sites that ordinary resolution left unresolved
+        let mut imports_by_file = std::collections::HashMap::new();
+        for call in calls {
+            let imports = imports_by_file.entry(call.file_id).or_insert_with(|| {
+                self.document_index
+                    .get_imports_for_file(call.file_id)
+                    .unwrap_or_default()
+            });
+            let Some(export) = exports.match_call(&call, imports) else {
+                continue;
+            };
+            let mut metadata = call.metadata.clone().unwrap_or_default();
+            metadata.context =
+                Some(ffi::with_mechanism(metadata.context.as_deref(), export.mechanism).into());
+            for from_id in &call.from_ids {
+                if self.add_ffi_call(*from_id, export, metadata.clone(), &mut linked)? {
+                    count += 1;
+                }
+            }
+        }
+
+        debug_print!(self, "FFI linking added {} cross-language calls", count);
+        Ok(count)
+    }
+
+    /// Add one FFI call unless it is already recorded
+    fn add_ffi_call(
+        &mut self,
+        from_id: SymbolId,
+        export: &FfiExport,
+        metadata: RelationshipMetadata,
+        linked: &mut std::collections::HashSet<(SymbolId, SymbolId)>,
+    ) -> IndexResult<bool> {
+        if !linked.insert((from_id, export.symbol_id)) {
+            return Ok(false);
+        }
+        let existing = self
+            .document_index
+            .get_relationships_from(from_id, RelationKind::Calls)
+            .map_err(|e| IndexError::TantivyError {
+                operation: "get_relationships_from".to_string(),
+                cause: e.to_string(),
+            })?;
+        if existing.iter().any(|(_, to, _)| *to == export.symbol_id) {
+            return Ok(false);
+        }
+        let relationship = Relationship::new(RelationKind::Calls).with_metadata(metadata);
+        self.add_relationship_internal(from_id, export.symbol_id, relationship)?;
+        Ok(true)
+    }
+
+    /// RPCs declared in the indexed `.proto` schemas
+    fn collect_rpcs(&self) -> IndexResult<RpcTable> {
+        let symbols = self
+            .document_index
+            .find_symbols_by_kind(SymbolKind::Method, Some("protobuf"), 100_000)
+            .map_err(|e| IndexError::TantivyError {
+                operation: "find_symbols_by_kind".to_string(),
+                cause: e.to_string(),
+            })?;
+        Ok(RpcTable::new(&symbols))
+    }
 
-                // Get the full symbol data
-                debug_print!(self, "Looking up symbol by ID: {:?}", to_symbol_id);
-                let to_symbol = match self
-                    .document_index
-                    .find_symbol_by_id(to_symbol_id)
-                    .map_err(|e| IndexError::TantivyError {
-                        operation: "find_symbol_by_id".to_string(),
-                        cause: e.to_string(),
-                    })? {
-                    Some(symbol) => {
-                        debug_print!(self, "Found target symbol: {}", symbol.name);
-                        symbol
-                    }
-                    None => {
-                        debug_print!(
-                            self,
-                            "[SKIP-NOT-FOUND] Symbol ID {:?} not found in index for '{}'",
-                            to_symbol_id,
-                            rel.to_name
-                        );
-                        skipped_count += 1;
-                        if let Some((bar, _)) = &progress {
-                            bar.add_extra2(1);
-                        }
-                        continue;
-                    }
-                };
+    /// Keep a call if it may go through a generated gRPC client
+    fn rpc_call(
+        &self,
+        rpcs: &RpcTable,
+        rel: &UnresolvedRelationship,
+        from_symbols: &[Symbol],
+    ) -> Option<ForeignCall> {
+        if rpcs.is_empty() {
+            return None;
+        }
+        let language = from_symbols.first()?.language_id?.as_str();
+        if !grpc::LINKED_LANGUAGES.contains(&language) {
+            return None;
+        }
+        let (receiver, _, _, _) = self.extract_metadata_hints(rel.metadata.as_ref());
+        let call = ForeignCall {
+            from_ids: from_symbols.iter().map(|s| s.id).collect(),
+            language,
+            file_id: rel.file_id,
+            name: rel.to_name.to_string(),
+            receiver,
+            metadata: rel.metadata.clone(),
+        };
+        rpcs.has_method(call.short_name()).then_some(call)
+    }
 
-                // Process with our filtering logic
-                debug_print!(self, "Processing {} from symbols", from_symbols.len());
-                for from_symbol in &from_symbols {
-                    debug_print!(
-                        self,
-                        "Checking relationship from {} to {}",
-                        from_symbol.name,
-                        to_symbol.name
-                    );
+    /// Source text of an indexed file, for checks generated code leaves to the text
+    fn read_indexed_source(&self, file_id: FileId) -> Option<(String, String)> {
+        let path = self.get_file_path(file_id)?;
+        let full_path = match &self.settings.workspace_root {
+            Some(root) if Path::new(&path).is_relative() => root.join(&path),
+            _ => PathBuf::from(&path),
+        };
+        let text = std::fs::read_to_string(full_path).ok()?;
+        Some((path, text))
+    }
 
-                    // Check symbol kind compatibility
-                    if !Self::is_compatible_relationship(from_symbol.kind, to_symbol.kind, rel.kind)
-                    {
-                        debug_print!(
-                            self,
-                            "[SKIP-INCOMPATIBLE] {} ({:?}) -> {} ({:?}) for {:?}",
-                            from_symbol.name,
-                            from_symbol.kind,
-                            to_symbol.name,
-                            to_symbol.kind,
-                            rel.kind
-                        );
-                        skipped_count += 1;
-                        if let Some((bar, _)) = &progress {
-                            bar.add_extra2(1);
-                        }
+    /// Link schema RPCs to their server implementations and client call sites
+    ///
+    /// Creates `Implements` relationships from implementations and `Calls`
+    /// relationships from callers, with `grpc:server`/`grpc:client` in the
+    /// metadata context. Returns the number of relationships added.
+    fn link_grpc(&mut self, rpcs: &RpcTable, calls: Vec<ForeignCall>) -> IndexResult<usize> {
+        if rpcs.is_empty() {
+            return Ok(0);
+        }
+        let mut sources: std::collections::HashMap<FileId, Option<(String, String)>> =
+            std::collections::HashMap::new();
+        let mut links = Vec::new();
+
+        // Server side: methods named after an RPC that take its request
+        let mut seen = std::collections::HashSet::new();
+        for language in grpc::LINKED_LANGUAGES {
+            for rpc in rpcs.rpcs() {
+                for name in rpc.method_names(language) {
+                    if !seen.insert((language, name.clone())) {
                         continue;
                     }
-
-                    // Check visibility (skip for Defines - a type can always see its own methods)
-                    if rel.kind != RelationKind::Defines {
-                        debug_print!(
-                            self,
-                            "Checking visibility: {} (vis: {:?}, module: {:?}) from {} (module: {:?})",
-                            to_symbol.name,
-                            to_symbol.visibility,
-                            to_symbol.module_path,
-                            from_symbol.name,
-                            from_symbol.module_path
-                        );
-                        if !Self::is_symbol_visible_from(&to_symbol, from_symbol) {
-                            debug_print!(
-                                self,
-                                "[SKIP-VISIBILITY] {} not visible from {} (to_vis: {:?}, to_module: {:?}, from_module: {:?})",
-                                to_symbol.name,
-                                from_symbol.name,
-                                to_symbol.visibility,
-                                to_symbol.module_path,
-                                from_symbol.module_path
-                            );
-                            skipped_count += 1;
-                            if let Some((bar, _)) = &progress {
-                                bar.add_extra2(1);
-                            }
+                    let candidates = self
+                        .document_index
+                        .find_symbols_by_name(&name, Some(language))
+                        .map_err(|e| IndexError::TantivyError {
+                            operation: "find_symbols_by_name".to_string(),
+                            cause: e.to_string(),
+                        })?;
+                    for symbol in candidates {
+                        let source = sources
+                            .entry(symbol.file_id)
+                            .or_insert_with(|| self.read_indexed_source(symbol.file_id));
+                        let Some((path, text)) = source else {
                             continue;
+                        };
+                        if let Some(rpc) = rpcs.implemented_by(&symbol, path, text) {
+                            let metadata = RelationshipMetadata::new()
+                                .at_position(symbol.range.start_line, symbol.range.start_column)
+                                .with_context(grpc::with_role(None, "server"));
+                            links.push((
+                                symbol.id,
+                                rpc.symbol_id,
+                                RelationKind::Implements,
+                                metadata,
+                            ));
                         }
                     }
-
-                    // Add the relationship with preserved metadata
-                    debug_print!(
-                        self,
-                        "[SUCCESS] Adding relationship: {} ({:?}) -> {} ({:?}) kind: {:?}",
-                        from_symbol.name,
-                        from_symbol.id,
-                        to_symbol.name,
-                        to_symbol.id,
-                        rel.kind
-                    );
-                    let mut relationship = Relationship::new(rel.kind);
-                    if let Some(ref metadata) = rel.metadata {
-                        relationship = relationship.with_metadata(metadata.clone());
-                    }
-                    self.add_relationship_internal(from_symbol.id, to_symbol.id, relationship)?;
-                    resolved_count += 1;
-                    if let Some((bar, _)) = &progress {
-                        bar.add_extra1(1);
-                    }
                 }
             }
         }
 
-        // Commit the batch with all the relationships
-        self.commit_tantivy_batch()?;
-
-        if let Some((bar, status)) = progress {
-            drop(status);
-            eprintln!("{bar}");
+        // Client side: calls in files that hold the service client
+        for call in calls {
+            let source = sources
+                .entry(call.file_id)
+                .or_insert_with(|| self.read_indexed_source(call.file_id));
+            let Some((path, text)) = source else {
+                continue;
+            };
+            if grpc::is_generated_file(path) {
+                continue;
+            }
+            let Some(rpc) = rpcs.match_call(&call, text) else {
+                continue;
+            };
+            let mut metadata = call.metadata.clone().unwrap_or_default();
+            metadata.context = Some(grpc::with_role(metadata.context.as_deref(), "client").into());
+            for from_id in &call.from_ids {
+                links.push((
+                    *from_id,
+                    rpc.symbol_id,
+                    RelationKind::Calls,
+                    metadata.clone(),
+                ));
+            }
         }
 
-        debug_print!(
-            self,
-            "Relationship resolution complete - resolved: {}, skipped: {}, total: {}",
-            resolved_count,
-            skipped_count,
-            total_unresolved
-        );
+        let mut count = 0;
+        let mut linked = std::collections::HashSet::new();
+        for (from_id, to_id, kind, metadata) in links {
+            if !linked.insert((from_id, to_id, kind)) {
+                continue;
+            }
+            let existing = self
+                .document_index
+                .get_relationships_from(from_id, kind)
+                .map_err(|e| IndexError::TantivyError {
+                    operation: "get_relationships_from".to_string(),
+                    cause: e.to_string(),
+                })?;
+            if existing.iter().any(|(_, to, _)| *to == to_id) {
+                continue;
+            }
+            let relationship = Relationship::new(kind).with_metadata(metadata);
+            self.add_relationship_internal(from_id, to_id, relationship)?;
+            count += 1;
+        }
 
-        Ok(())
+        debug_print!(self, "gRPC linking added {} relationships", count);
+        Ok(count)
     }
 
     // Note: external symbol creation moved to language behavior implementations
@@ -3403,13 +5183,52 @@ impl SimpleIndexer {
 
     /// Get the path for the symbol cache file
     fn get_cache_path(&self) -> PathBuf {
-        let index_base = if let Some(ref workspace_root) = self.settings.workspace_root {
+        self.index_dir().join("symbol_cache.bin")
+    }
+
+    /// Directory holding the Tantivy index, symbol cache and semantic data
+    pub fn index_dir(&self) -> PathBuf {
+        if let Some(ref workspace_root) = self.settings.workspace_root {
             workspace_root.join(&self.settings.index_path)
         } else {
             self.settings.index_path.clone()
-        };
+        }
+    }
+
+    /// Tantivy store, for integrity checks that need raw documents
+    pub(crate) fn document_index(&self) -> &DocumentIndex {
+        &self.document_index
+    }
+}
 
-        index_base.join("symbol_cache.bin")
+/// Give the symbols of one file their stable IDs, visiting them in source order
+///
+/// Symbols without a module path (or an empty one) are told apart by their
+/// file path instead.
+fn assign_stable_ids(
+    symbols: &mut [crate::Symbol],
+    path_str: &str,
+    stable_ids: &mut StableIdAssigner,
+) {
+    let mut order: Vec<usize> = (0..symbols.len()).collect();
+    order.sort_by_key(|&i| {
+        let symbol = &symbols[i];
+        (
+            symbol.range.start_line,
+            symbol.range.start_column,
+            symbol.id.value(),
+        )
+    });
+    for i in order {
+        let symbol = &mut symbols[i];
+        let language = symbol.language_id.map(|id| id.as_str()).unwrap_or("");
+        let module_path = symbol
+            .module_path
+            .as_deref()
+            .filter(|path| !path.is_empty())
+            .unwrap_or(path_str);
+        let stable_id = stable_ids.assign(language, module_path, &symbol.name, symbol.kind);
+        symbol.stable_id = Some(stable_id);
     }
 }
 
@@ -3423,6 +5242,25 @@ mod tests {
     use crate::types::SymbolCounter;
     use crate::{FileId, RelationKind, Symbol, SymbolKind, Visibility};
 
+    /// Write `files` (workspace-relative path and source) into a fresh
+    /// workspace and index them in order
+    fn index_workspace(files: &[(&str, &str)]) -> (TempDir, SimpleIndexer) {
+        let temp_dir = TempDir::new().unwrap();
+        let settings = Arc::new(Settings {
+            index_path: temp_dir.path().join("index"),
+            workspace_root: Some(temp_dir.path().to_path_buf()),
+            ..Settings::default()
+        });
+        let mut indexer = SimpleIndexer::with_settings(settings);
+        for (path, source) in files {
+            let file = temp_dir.path().join(path);
+            fs::create_dir_all(file.parent().unwrap()).unwrap();
+            fs::write(&file, source).unwrap();
+            indexer.index_file(&file).unwrap();
+        }
+        (temp_dir, indexer)
+    }
+
     #[test]
     fn test_indexer_skips_child_path_when_parent_tracked() {
         let temp_dir = TempDir::new().unwrap();
@@ -3471,6 +5309,460 @@ mod tests {
         assert_eq!(stored_paths[0], parent.canonicalize().unwrap());
     }
 
+    #[test]
+    fn test_unresolved_references_are_counted_per_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("app.py");
+        fs::write(
+            &file,
+            "import requests\n\ndef helper():\n    return 1\n\ndef run():\n    helper()\n    missing_function()\n    requests.get('x')\n",
+        )
+        .unwrap();
+
+        let settings = Arc::new(Settings {
+            index_path: temp_dir.path().join("index"),
+            workspace_root: Some(temp_dir.path().to_path_buf()),
+            ..Settings::default()
+        });
+        let mut indexer = SimpleIndexer::with_settings(settings);
+        indexer.index_file(&file).unwrap();
+
+        let totals = indexer.unresolved_summary().totals();
+        assert!(totals.not_found >= 1, "missing_function: {totals:?}");
+        assert!(totals.external >= 1, "requests.get: {totals:?}");
+        let languages = indexer.unresolved_summary().by_language();
+        assert_eq!(languages["python"], totals);
+
+        // Re-indexing replaces the file's counts
+        fs::write(
+            &file,
+            "def helper():\n    return 1\n\ndef run():\n    helper()\n",
+        )
+        .unwrap();
+        indexer.index_file(&file).unwrap();
+        assert_eq!(indexer.unresolved_summary().totals().total(), 0);
+    }
+
+    #[test]
+    fn test_spilled_relationships_resolve_like_in_memory_ones() {
+        fn calls_of_run(indexer: &SimpleIndexer) -> Vec<String> {
+            let run = indexer.find_symbol("run").unwrap();
+            let mut calls: Vec<String> = indexer
+                .get_called_functions(run)
+                .into_iter()
+                .map(|symbol| symbol.name.to_string())
+                .collect();
+            calls.sort();
+            calls
+        }
+
+        let temp_dir = TempDir::new().unwrap();
+        let src = temp_dir.path().join("src");
+        fs::create_dir(&src).unwrap();
+        fs::write(src.join("lib.rs"), "pub mod config;\npub mod app;\n").unwrap();
+        fs::write(
+            src.join("config.rs"),
+            "pub struct Config;\n\nimpl Config {\n    pub fn new() -> Self { Config }\n}\n\npub fn load() -> Config { Config::new() }\n",
+        )
+        .unwrap();
+        fs::write(
+            src.join("app.rs"),
+            "use crate::config::{load, Config};\n\npub fn run() {\n    let config = Config::new();\n    load();\n}\n",
+        )
+        .unwrap();
+        let files = ["lib.rs", "config.rs", "app.rs"].map(|name| src.join(name));
+
+        let indexer_in = |index: &str| {
+            SimpleIndexer::with_settings(Arc::new(Settings {
+                index_path: temp_dir.path().join(index),
+                workspace_root: Some(temp_dir.path().to_path_buf()),
+                ..Settings::default()
+            }))
+        };
+
+        let mut in_memory = indexer_in("in_memory");
+        in_memory.index_files(files.to_vec(), false, false).unwrap();
+        let expected = calls_of_run(&in_memory);
+        assert!(expected.contains(&"load".to_string()), "{expected:?}");
+
+        let mut spilling = indexer_in("spilling");
+        let mut spill = SpillStore::create(&spilling.index_dir()).unwrap();
+        let mut stats = IndexStats::new();
+        for (n, file) in files.iter().enumerate() {
+            spilling.index_file_no_resolve(file).unwrap();
+            if n == 1 {
+                assert!(spilling.pending_resolution_bytes() > 0);
+                spilling
+                    .spill_pending_resolution(&mut spill, &mut stats)
+                    .unwrap();
+                assert!(spilling.unresolved_relationships.is_empty());
+                assert!(spilling.method_calls_by_file.is_empty());
+            }
+        }
+        assert_eq!(stats.spill_chunks, 1);
+        assert_eq!(stats.relationships_spilled, spill.relationship_count());
+        assert!(stats.relationships_spilled > 0);
+
+        spilling
+            .resolve_pending_relationships(Some(&spill))
+            .unwrap();
+        assert_eq!(calls_of_run(&spilling), expected);
+        assert_eq!(
+            spilling.relationship_count(),
+            in_memory.relationship_count()
+        );
+    }
+
+    #[test]
+    fn test_parallel_resolution_matches_a_single_thread() {
+        let temp_dir = TempDir::new().unwrap();
+        let src = temp_dir.path().join("src");
+        fs::create_dir(&src).unwrap();
+        let mut files = vec![src.join("lib.rs")];
+        let mut lib = String::new();
+        for n in 0..8 {
+            let next = (n + 1) % 8;
+            lib.push_str(&format!("pub mod m{n};\n"));
+            let path = src.join(format!("m{n}.rs"));
+            fs::write(
+                &path,
+                format!(
+                    "use crate::m{next}::{{helper{next}, Item{next}}};\n\npub struct Item{n};\n\nimpl Item{n} {{\n    pub fn new() -> Self {{ Item{n} }}\n}}\n\npub fn helper{n}() {{}}\n\npub fn run{n}() {{\n    let item = Item{next}::new();\n    helper{next}();\n    missing{n}();\n}}\n"
+                ),
+            )
+            .unwrap();
+            files.push(path);
+        }
+        fs::write(src.join("lib.rs"), lib).unwrap();
+
+        let resolved_with = |threads: usize| {
+            let mut settings = Settings {
+                index_path: temp_dir.path().join(format!("index_{threads}")),
+                workspace_root: Some(temp_dir.path().to_path_buf()),
+                ..Settings::default()
+            };
+            settings.indexing.parallel_threads = threads;
+            let mut indexer = SimpleIndexer::with_settings(Arc::new(settings));
+            for file in &files {
+                indexer.index_file_no_resolve(file).unwrap();
+            }
+            indexer.resolve_cross_file_relationships().unwrap();
+
+            let mut calls: Vec<(String, String)> = indexer
+                .get_all_symbols()
+                .into_iter()
+                .flat_map(|symbol| {
+                    indexer
+                        .get_called_functions(symbol.id)
+                        .into_iter()
+                        .map(move |called| (symbol.name.to_string(), called.name.to_string()))
+                })
+                .collect();
+            calls.sort();
+            (
+                calls,
+                indexer.relationship_count(),
+                indexer.unresolved_summary().totals(),
+            )
+        };
+
+        let single = resolved_with(1);
+        assert!(
+            single
+                .0
+                .contains(&("run0".to_string(), "helper1".to_string()))
+        );
+        assert!(single.2.not_found >= 8, "{:?}", single.2);
+        assert_eq!(resolved_with(4), single);
+    }
+
+    #[test]
+    fn test_stable_ids_survive_a_full_reindex() {
+        let temp_dir = TempDir::new().unwrap();
+        let shapes = temp_dir.path().join("shapes.rs");
+        let other = temp_dir.path().join("other.rs");
+        fs::write(
+            &shapes,
+            "pub struct Circle;\n\nimpl Circle {\n    pub fn new() -> Self { Circle }\n}\n\npub struct Square;\n\nimpl Square {\n    pub fn new() -> Self { Square }\n}\n",
+        )
+        .unwrap();
+        fs::write(&other, "pub fn unrelated() {}\n").unwrap();
+
+        let index = |name: &str, files: &[&PathBuf]| {
+            let settings = Arc::new(Settings {
+                index_path: temp_dir.path().join(name),
+                workspace_root: Some(temp_dir.path().to_path_buf()),
+                ..Settings::default()
+            });
+            let mut indexer = SimpleIndexer::with_settings(settings);
+            for file in files {
+                indexer.index_file(file).unwrap();
+            }
+            indexer
+        };
+        let stable_ids = |indexer: &SimpleIndexer| {
+            let mut ids: Vec<_> = indexer
+                .find_symbols_by_name("new", None)
+                .into_iter()
+                .map(|symbol| {
+                    (
+                        symbol.range.start_line,
+                        symbol.id,
+                        symbol.stable_id.unwrap(),
+                    )
+                })
+                .collect();
+            ids.sort_by_key(|(line, ..)| *line);
+            ids
+        };
+
+        let first = index("first", &[&shapes]);
+        // Indexing another file first hands out different numeric IDs
+        let second = index("second", &[&other, &shapes]);
+        let before = stable_ids(&first);
+        let after = stable_ids(&second);
+        assert_eq!(before.len(), 2);
+        assert_ne!(before[0].2, before[1].2, "same name, module and kind");
+        assert_ne!(before[0].1, after[0].1);
+        assert_eq!(before[0].2, after[0].2);
+        assert_eq!(before[1].2, after[1].2);
+
+        let found = second
+            .get_symbol_by_ref(&SymbolRef::Stable(before[1].2.clone()))
+            .unwrap();
+        assert_eq!(found.id, after[1].1);
+        assert_eq!(
+            second
+                .get_symbol_by_ref(&SymbolRef::Id(after[0].1))
+                .unwrap()
+                .stable_id,
+            Some(before[0].2.clone())
+        );
+    }
+
+    #[test]
+    fn test_interrupted_run_is_rolled_back_and_resumed() {
+        let temp_dir = TempDir::new().unwrap();
+        let util = temp_dir.path().join("util.py");
+        let app = temp_dir.path().join("app.py");
+        let jobs = temp_dir.path().join("jobs.py");
+        fs::write(&util, "def helper():\n    return 1\n").unwrap();
+        fs::write(
+            &app,
+            "from util import helper\n\ndef run():\n    helper()\n",
+        )
+        .unwrap();
+        fs::write(&jobs, "def schedule():\n    return 2\n").unwrap();
+
+        let settings = Arc::new(Settings {
+            index_path: temp_dir.path().join("index"),
+            workspace_root: Some(temp_dir.path().to_path_buf()),
+            ..Settings::default()
+        });
+
+        // The first batch reached Tantivy, then the process died before
+        // relationships were resolved and before jobs.py was indexed
+        {
+            let mut indexer = SimpleIndexer::with_settings(settings.clone());
+            indexer
+                .index_files(vec![util.clone(), app.clone()], false, false)
+                .unwrap();
+            let journal = IndexJournal::new(&temp_dir.path().join("index"));
+            journal
+                .begin(&[util.clone(), app.clone(), jobs.clone()])
+                .unwrap();
+            let mut batch = IndexTransaction::default();
+            batch.add_file(util.clone());
+            batch.add_file(app.clone());
+            journal.commit(&mut batch).unwrap();
+        }
+
+        let mut indexer = SimpleIndexer::with_settings(settings);
+        let recovery = indexer.recover_interrupted_run().unwrap().unwrap();
+        assert_eq!(recovery.rolled_back, 2);
+        assert_eq!(recovery.remaining, vec![util, app, jobs]);
+        assert!(indexer.find_symbols_by_name("run", None).is_empty());
+
+        indexer
+            .index_files(recovery.remaining, false, false)
+            .unwrap();
+        let run = indexer.find_symbols_by_name("run", None);
+        assert_eq!(run.len(), 1);
+        let called: Vec<String> = indexer
+            .get_called_functions(run[0].id)
+            .iter()
+            .map(|s| s.name.to_string())
+            .collect();
+        assert_eq!(called, vec!["helper"]);
+        assert_eq!(indexer.find_symbols_by_name("schedule", None).len(), 1);
+
+        indexer.finish_index_journal().unwrap();
+        assert!(indexer.recover_interrupted_run().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_file_outline_nests_indexed_symbols() {
+        let (_temp_dir, indexer) = index_workspace(&[(
+            "shapes.rs",
+            "pub struct Circle {\n    radius: f64,\n}\n\nimpl Circle {\n    pub fn area(&self) -> f64 {\n        fn square(x: f64) -> f64 {\n            x * x\n        }\n        square(self.radius)\n    }\n}\n\npub mod util {\n    pub fn helper() {}\n}\n",
+        )]);
+
+        let outline = indexer.get_file_outline("./shapes.rs").unwrap();
+        assert_eq!(outline.file_path, "shapes.rs");
+        assert_eq!(outline.symbol_count, 6);
+        let names = |nodes: &[crate::symbol::outline::OutlineNode]| {
+            nodes.iter().map(|n| n.name.clone()).collect::<Vec<_>>()
+        };
+        assert_eq!(names(&outline.symbols), ["Circle", "util"]);
+
+        // The impl method sits outside the struct's range; `Defines` places it
+        let circle = &outline.symbols[0];
+        assert_eq!(names(&circle.children), ["radius", "area"]);
+        // The nested function names `area` as its parent scope
+        let area = &circle.children[1];
+        assert_eq!(names(&area.children), ["square"]);
+        // Module members have neither and fall back to the enclosing range
+        assert_eq!(names(&outline.symbols[1].children), ["helper"]);
+
+        assert!(indexer.get_file_outline("missing.rs").is_none());
+    }
+
+    #[test]
+    fn test_find_symbol_at_boundaries() {
+        use crate::symbol::position::SourcePosition;
+
+        let (_temp_dir, indexer) = index_workspace(&[(
+            "shapes.rs",
+            "pub struct Circle {\n    radius: f64,\n}\n\nimpl Circle {\n    pub fn area(&self) -> f64 {\n        fn square(x: f64) -> f64 {\n            x * x\n        }\n        square(self.radius)\n    }\n}\n",
+        )]);
+
+        // Positions are one-based, as printed by editors and compilers
+        let at = |line: u32, column: Option<u16>| {
+            let position = SourcePosition {
+                file_path: "shapes.rs".to_string(),
+                line,
+                column,
+            };
+            indexer
+                .find_symbol_at(&position, false)
+                .map(|found| found.symbol.name.to_string())
+        };
+
+        // `fn square` starts at column 9; column 8 is still inside `area`
+        assert_eq!(at(7, Some(9)).as_deref(), Some("square"));
+        assert_eq!(at(7, Some(8)).as_deref(), Some("area"));
+        // Last column of the closing braces
+        assert_eq!(at(9, Some(9)).as_deref(), Some("square"));
+        assert_eq!(at(11, Some(5)).as_deref(), Some("area"));
+        assert_eq!(at(11, Some(7)), None);
+        // A line alone lands on the innermost definition spanning it
+        assert_eq!(at(10, None).as_deref(), Some("area"));
+        assert_eq!(at(8, None).as_deref(), Some("square"));
+        // Blank line between the struct and the impl block
+        assert_eq!(at(4, None), None);
+    }
+
+    #[test]
+    fn test_resolve_stack_trace_normalizes_frame_paths() {
+        use crate::symbol::trace::{FrameMatch, parse_stack_trace};
+
+        let (temp_dir, indexer) = index_workspace(&[(
+            "src/app.rs",
+            "pub fn parse(input: &str) -> usize {\n    input.len()\n}\n\npub fn run() -> usize {\n    parse(\"x\")\n}\n",
+        )]);
+        let file = temp_dir.path().join("src").join("app.rs");
+
+        let trace = format!(
+            "stack backtrace:\n   0: app::parse\n             at {}:2:5\n   1: app::run\n             at ./src/app.rs:6:5\n   2: std::rt::lang_start\n             at /rustc/abc/library/std/src/rt.rs:206:5",
+            file.display()
+        );
+        let resolved = indexer.resolve_stack_trace(parse_stack_trace(&trace), 5);
+        assert_eq!(resolved.len(), 3);
+
+        // An absolute path inside the workspace root resolves like the stored one
+        let parse = &resolved[0];
+        assert!(parse.in_index);
+        assert_eq!(parse.matched_by, Some(FrameMatch::Position));
+        let symbol = parse.symbol.as_ref().unwrap();
+        assert_eq!(symbol.name.as_ref(), "parse");
+        assert_eq!(symbol.file_path.as_ref(), "src/app.rs");
+        assert!(parse.callers.iter().any(|caller| caller.name == "run"));
+
+        let run = &resolved[1];
+        assert_eq!(run.matched_by, Some(FrameMatch::Position));
+        assert_eq!(run.symbol.as_ref().unwrap().name.as_ref(), "run");
+
+        assert!(!resolved[2].in_index);
+        assert!(resolved[2].symbol.is_none());
+    }
+
+    #[test]
+    fn test_diff_impact_reports_callers_of_changed_symbols() {
+        use crate::symbol::impact::{ImpactKind, parse_unified_diff};
+
+        let (_temp_dir, indexer) = index_workspace(&[(
+            "src/lib.rs",
+            "pub fn callee() -> u32 {\n    42\n}\n\npub fn caller() -> u32 {\n    callee() + 1\n}\n\npub fn unrelated() {}\n",
+        )]);
+
+        let diff = "diff --git a/src/lib.rs b/src/lib.rs\n--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1,3 +1,3 @@\n pub fn callee() -> u32 {\n-    41\n+    42\n }\n";
+        let impact = indexer.diff_impact(&parse_unified_diff(diff), 3);
+        assert!(impact.unindexed_files.is_empty());
+        assert_eq!(impact.files.len(), 1);
+        assert_eq!(impact.files[0].file_path, "src/lib.rs");
+
+        let entries: Vec<_> = impact.files[0]
+            .symbols
+            .iter()
+            .map(|entry| (entry.name.as_str(), entry.impact, entry.via.clone()))
+            .collect();
+        assert_eq!(
+            entries,
+            [
+                ("callee", ImpactKind::Changed, vec![]),
+                ("caller", ImpactKind::Impacted, vec!["callee".to_string()]),
+            ]
+        );
+        assert_eq!((impact.changed_count, impact.impacted_count), (1, 1));
+    }
+
+    #[test]
+    fn test_find_tests_for_indexed_tests() {
+        let (_temp_dir, indexer) = index_workspace(&[
+            (
+                "math.rs",
+                "pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n\n#[cfg(test)]\nmod tests {\n    use super::*;\n\n    #[test]\n    fn adds() {\n        let sum = add(1, 2);\n        assert_eq!(sum, 3);\n    }\n}\n",
+            ),
+            (
+                "geometry.py",
+                "def area(width, height):\n    return width * height\n",
+            ),
+            // pytest collects `test_*` functions from `test_*.py` files
+            (
+                "test_geometry.py",
+                "from geometry import area\n\n\ndef test_area():\n    assert area(2, 3) == 6\n",
+            ),
+        ]);
+
+        let tests_for = |name: &str| {
+            let symbol = indexer.find_symbol(name).unwrap();
+            indexer
+                .find_tests_for(symbol, 3)
+                .unwrap()
+                .tests
+                .into_iter()
+                .map(|test| (test.name, test.depth))
+                .collect::<Vec<_>>()
+        };
+
+        // Rust `#[test]` attribute
+        assert_eq!(tests_for("add"), [("adds".to_string(), 1)]);
+        // Python `test_*` naming convention
+        assert_eq!(tests_for("area"), [("test_area".to_string(), 1)]);
+        // A test reports itself
+        assert_eq!(tests_for("adds"), [("adds".to_string(), 0)]);
+    }
+
     #[test]
     fn test_trait_implementations_resolution() {
         // Test the relationship resolution bug directly by creating symbols manually
@@ -3522,6 +5814,9 @@ mod tests {
             module_path: Some("test".into()),
             scope_context: None,
             language_id: None,
+            is_test: false,
+            annotations: Vec::new(),
+            stable_id: None,
         };
 
         let struct_symbol = Symbol {
@@ -3537,6 +5832,9 @@ mod tests {
             module_path: Some("test".into()),
             scope_context: None,
             language_id: None,
+            is_test: false,
+            annotations: Vec::new(),
+            stable_id: None,
         };
 
         // Store symbols
@@ -4231,6 +6529,9 @@ pub struct Another {
             doc_comment: None,
             scope_context: None,
             language_id: None,
+            is_test: false,
+            annotations: Vec::new(),
+            stable_id: None,
         };
 
         let module_path = Some("crate::module".to_string());
@@ -4275,6 +6576,9 @@ pub struct Another {
             doc_comment: None,
             scope_context: None,
             language_id: None,
+            is_test: false,
+            annotations: Vec::new(),
+            stable_id: None,
         };
 
         let module_path = Some("test_module".to_string());
@@ -4316,6 +6620,9 @@ pub struct Another {
             doc_comment: None,
             scope_context: None,
             language_id: None,
+            is_test: false,
+            annotations: Vec::new(),
+            stable_id: None,
         };
 
         let module_path = Some("App\\Utils".to_string());
@@ -4364,6 +6671,9 @@ pub struct Another {
             doc_comment: None,
             scope_context: None,
             language_id: None,
+            is_test: false,
+            annotations: Vec::new(),
+            stable_id: None,
         };
 
         let mut python_symbol = Symbol {
@@ -4379,6 +6689,9 @@ pub struct Another {
             doc_comment: None,
             scope_context: None,
             language_id: None,
+            is_test: false,
+            annotations: Vec::new(),
+            stable_id: None,
         };
 
         let mut php_symbol = Symbol {
@@ -4394,6 +6707,9 @@ pub struct Another {
             doc_comment: None,
             scope_context: None,
             language_id: None,
+            is_test: false,
+            annotations: Vec::new(),
+            stable_id: None,
         };
 
         // Configure each symbol with its behavior
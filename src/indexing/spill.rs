@@ -0,0 +1,173 @@
+//! Spill-to-disk for relationships waiting on cross-file resolution
+//!
+//! A relationship can only be resolved once every file's symbols are indexed,
+//! so a run keeps it until the end, together with the method calls and
+//! variable types its resolution reads. On a large repository that state
+//! dominates memory. With `indexing.memory_limit_mb` set, the indexer writes it
+//! out as a chunk whenever a batch of files pushes it past the ceiling, and the
+//! resolution pass reads the chunks back one at a time.
+//!
+//! Chunks are lz4-compressed JSON in a `spill-*` directory of the index, which
+//! is removed when the run ends. Directories left behind by an interrupted run
+//! are removed when the next one spills.
+
+use crate::parsing::MethodCall;
+use crate::relationship::{RelationKind, RelationshipMetadata};
+use crate::{FileId, SymbolId};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+const SPILL_DIR_PREFIX: &str = "spill-";
+
+/// A relationship waiting for resolution, as written to disk
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpilledRelationship {
+    pub from_id: Option<SymbolId>,
+    pub from_name: String,
+    pub to_name: String,
+    pub kind: RelationKind,
+    pub metadata: Option<RelationshipMetadata>,
+}
+
+/// What resolution needs from one file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpilledFile {
+    pub file_id: FileId,
+    pub relationships: Vec<SpilledRelationship>,
+    pub method_calls: Vec<MethodCall>,
+    /// Normalized receiver expression and its type
+    pub variable_types: Vec<(String, String)>,
+}
+
+/// Chunks of spilled files for the run in progress
+#[derive(Debug)]
+pub struct SpillStore {
+    dir: TempDir,
+    chunks: Vec<PathBuf>,
+    relationships: usize,
+    bytes: u64,
+}
+
+impl SpillStore {
+    /// Create an empty store under `index_dir`
+    pub fn create(index_dir: &Path) -> io::Result<Self> {
+        fs::create_dir_all(index_dir)?;
+        remove_stale(index_dir);
+        let dir = tempfile::Builder::new()
+            .prefix(SPILL_DIR_PREFIX)
+            .tempdir_in(index_dir)?;
+        Ok(Self {
+            dir,
+            chunks: Vec::new(),
+            relationships: 0,
+            bytes: 0,
+        })
+    }
+
+    /// Write `files` as the next chunk
+    pub fn write(&mut self, files: &[SpilledFile]) -> io::Result<()> {
+        let path = self
+            .dir
+            .path()
+            .join(format!("chunk_{}.json.lz4", self.chunks.len()));
+        let mut encoder = lz4_flex::frame::FrameEncoder::new(BufWriter::new(File::create(&path)?));
+        serde_json::to_writer(&mut encoder, files)?;
+        encoder.finish().map_err(io::Error::other)?.flush()?;
+
+        self.relationships += files.iter().map(|f| f.relationships.len()).sum::<usize>();
+        self.bytes += fs::metadata(&path)?.len();
+        self.chunks.push(path);
+        Ok(())
+    }
+
+    /// Read a chunk back, in the order it was written
+    pub fn read(&self, chunk: usize) -> io::Result<Vec<SpilledFile>> {
+        let file = File::open(&self.chunks[chunk])?;
+        let decoder = lz4_flex::frame::FrameDecoder::new(BufReader::new(file));
+        Ok(serde_json::from_reader(decoder)?)
+    }
+
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Relationships across all chunks
+    pub fn relationship_count(&self) -> usize {
+        self.relationships
+    }
+
+    /// Compressed size of all chunks
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+}
+
+/// Remove spill directories an interrupted run left behind
+fn remove_stale(index_dir: &Path) {
+    let Ok(entries) = fs::read_dir(index_dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let is_spill = entry
+            .file_name()
+            .to_str()
+            .is_some_and(|name| name.starts_with(SPILL_DIR_PREFIX));
+        if is_spill && entry.path().is_dir() {
+            let _ = fs::remove_dir_all(entry.path());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Range;
+
+    #[test]
+    fn test_chunks_round_trip_and_are_removed_on_drop() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let stale = temp.path().join("spill-old");
+        fs::create_dir(&stale).unwrap();
+
+        let mut store = SpillStore::create(temp.path()).unwrap();
+        assert!(!stale.exists());
+
+        let file = SpilledFile {
+            file_id: FileId::new(3).unwrap(),
+            relationships: vec![SpilledRelationship {
+                from_id: SymbolId::new(7),
+                from_name: "main".to_string(),
+                to_name: "Config::new".to_string(),
+                kind: RelationKind::Calls,
+                metadata: Some(RelationshipMetadata {
+                    line: Some(4),
+                    column: Some(8),
+                    context: None,
+                }),
+            }],
+            method_calls: vec![
+                MethodCall::new("main", "new", Range::new(4, 8, 4, 21)).with_receiver("Config"),
+            ],
+            variable_types: vec![("config".to_string(), "Config".to_string())],
+        };
+        store.write(std::slice::from_ref(&file)).unwrap();
+        store.write(&[]).unwrap();
+
+        assert_eq!(store.chunk_count(), 2);
+        assert_eq!(store.relationship_count(), 1);
+        assert!(store.bytes() > 0);
+        assert_eq!(store.read(0).unwrap(), vec![file]);
+        assert!(store.read(1).unwrap().is_empty());
+
+        let dir = store.path().to_path_buf();
+        drop(store);
+        assert!(!dir.exists());
+    }
+}
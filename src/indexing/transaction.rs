@@ -1,25 +1,194 @@
-//! Transaction support for atomic indexing operations
+//! Write-ahead journal for indexing runs
 //!
-//! This module provides transactional guarantees for index updates,
-//! ensuring that either all changes are committed or none are.
+//! An indexing run writes three stores that become durable at different
+//! times: Tantivy commits every batch of files, the symbol cache is rebuilt
+//! from Tantivy after each commit, and the semantic vectors are only saved
+//! with the index metadata once the run is over. Cross-file relationships are
+//! resolved after the last batch. A run that is killed part way (Ctrl-C, OOM)
+//! therefore leaves files in Tantivy whose relationships were never resolved
+//! and whose embeddings were never saved, and a re-run skips them because
+//! their content hash is already stored.
 //!
-//! With Tantivy-only architecture, transactions are handled by Tantivy's
-//! writer commit system, so this is now a lightweight compatibility layer.
+//! [`IndexJournal`] records the run before anything is written:
+//!
+//! 1. `begin` with every file the run will index
+//! 2. `commit` with the files of each batch once Tantivy committed it
+//! 3. `resolved` once cross-file relationships are resolved and committed
+//!
+//! The journal is removed when the index is saved. If it is still there on
+//! startup the previous run was interrupted, and
+//! `SimpleIndexer::recover_interrupted_run` uses the [`InterruptedRun`] read
+//! back from it to roll back the partially committed batches and resume.
 
-/// A transaction that can be committed or rolled back
-///
-/// In the Tantivy-only architecture, this is a lightweight wrapper
-/// since Tantivy handles transactions internally through its writer.
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+/// File name of the journal inside the index directory
+pub const JOURNAL_FILE: &str = "index.journal";
+
+/// One line of the journal
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum JournalRecord {
+    Begin { files: Vec<PathBuf> },
+    Commit { files: Vec<PathBuf> },
+    Resolved,
+}
+
+/// Journal of the indexing run in progress
 #[derive(Debug)]
+pub struct IndexJournal {
+    path: PathBuf,
+}
+
+impl IndexJournal {
+    /// Journal stored in the index directory `index_base`
+    pub fn new(index_base: &Path) -> Self {
+        Self {
+            path: index_base.join(JOURNAL_FILE),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn exists(&self) -> bool {
+        self.path.exists()
+    }
+
+    /// Start a run over `files`, replacing any previous journal
+    pub fn begin(&self, files: &[PathBuf]) -> io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = File::create(&self.path)?;
+        write_record(
+            file,
+            &JournalRecord::Begin {
+                files: files.to_vec(),
+            },
+        )
+    }
+
+    /// Record that Tantivy committed the files of `transaction`
+    pub fn commit(&self, transaction: &mut IndexTransaction) -> io::Result<()> {
+        self.append(&JournalRecord::Commit {
+            files: transaction.files.clone(),
+        })?;
+        transaction.complete();
+        Ok(())
+    }
+
+    /// Record that relationships of all committed files are resolved
+    pub fn resolved(&self) -> io::Result<()> {
+        self.append(&JournalRecord::Resolved)
+    }
+
+    /// Append to the run in progress; without a `begin` there is nothing to record
+    fn append(&self, record: &JournalRecord) -> io::Result<()> {
+        match OpenOptions::new().append(true).open(&self.path) {
+            Ok(file) => write_record(file, record),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Remove the journal once every store holds the run's results
+    ///
+    /// A run that never recorded [`Self::resolved`] keeps its journal, so the
+    /// next start still rolls it back and resumes it.
+    pub fn finish(&self) -> io::Result<()> {
+        if self.read()?.is_some_and(|run| !run.resolved) {
+            return Ok(());
+        }
+        match std::fs::remove_file(&self.path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    /// Read back the run the journal describes
+    ///
+    /// Returns `None` when there is no journal. A truncated last line, left
+    /// by a crash while it was written, is ignored.
+    pub fn read(&self) -> io::Result<Option<InterruptedRun>> {
+        let file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        let mut run = InterruptedRun::default();
+        let mut planned = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let Ok(record) = serde_json::from_str::<JournalRecord>(&line?) else {
+                break;
+            };
+            match record {
+                JournalRecord::Begin { files } => planned = files,
+                JournalRecord::Commit { files } => run.committed.extend(files),
+                JournalRecord::Resolved => run.resolved = true,
+            }
+        }
+        let committed: HashSet<&PathBuf> = run.committed.iter().collect();
+        let pending = planned
+            .into_iter()
+            .filter(|path| !committed.contains(path))
+            .collect();
+        run.pending = pending;
+        Ok(Some(run))
+    }
+}
+
+fn write_record(mut file: File, record: &JournalRecord) -> io::Result<()> {
+    let mut line = serde_json::to_string(record).map_err(io::Error::other)?;
+    line.push('\n');
+    file.write_all(line.as_bytes())?;
+    file.sync_data()
+}
+
+/// State of a run whose journal was found on startup
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InterruptedRun {
+    /// Files planned for the run that no committed batch contains
+    pub pending: Vec<PathBuf>,
+    /// Files of batches Tantivy committed
+    pub committed: Vec<PathBuf>,
+    /// Whether relationship resolution finished before the interruption
+    pub resolved: bool,
+}
+
+/// Outcome of repairing the index after an interrupted run
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunRecovery {
+    /// Committed files removed again from Tantivy, the cache and the vectors
+    pub rolled_back: usize,
+    /// Embeddings dropped because their symbol is no longer indexed
+    pub dropped_embeddings: usize,
+    /// Files the run still has to index, in their original order
+    pub remaining: Vec<PathBuf>,
+}
+
+/// A batch of files written to Tantivy but not yet committed
+///
+/// The files only become part of the journal through
+/// [`IndexJournal::commit`], so an interruption before that leaves them
+/// pending and they are indexed again when the run resumes.
+#[derive(Debug, Default)]
 pub struct IndexTransaction {
-    /// Whether this transaction has been committed or rolled back
+    files: Vec<PathBuf>,
     completed: bool,
 }
 
 impl IndexTransaction {
     /// Create a new transaction
+    #[deprecated(note = "Use IndexTransaction::default() or SimpleIndexer::begin_transaction()")]
     pub fn new(_data: &()) -> Self {
-        Self { completed: false }
+        Self::default()
     }
 
     /// Get the snapshot data for rollback (no longer applicable)
@@ -28,6 +197,24 @@ impl IndexTransaction {
         &()
     }
 
+    /// Add a file written in this batch
+    pub fn add_file(&mut self, path: impl Into<PathBuf>) {
+        self.files.push(path.into());
+    }
+
+    /// Files written in this batch
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
     /// Mark transaction as completed
     pub fn complete(&mut self) {
         self.completed = true;
@@ -41,26 +228,32 @@ impl IndexTransaction {
 
 impl Drop for IndexTransaction {
     fn drop(&mut self) {
-        if !self.completed {
-            eprintln!("Warning: IndexTransaction dropped without explicit commit or rollback");
+        if !self.completed && !self.files.is_empty() {
+            eprintln!(
+                "Warning: batch of {} files dropped without commit; it is indexed again on the next run",
+                self.files.len()
+            );
         }
     }
 }
 
 /// Transaction context for atomic file operations
 ///
-/// With Tantivy, file operations are atomic within a batch
+/// Kept for API compatibility; it never recorded anything.
+#[deprecated(note = "Use IndexTransaction, which carries the files of a batch into the journal")]
 pub struct FileTransaction {
     file_id: Option<crate::FileId>,
     completed: bool,
 }
 
+#[allow(deprecated)]
 impl Default for FileTransaction {
     fn default() -> Self {
         Self::new()
     }
 }
 
+#[allow(deprecated)]
 impl FileTransaction {
     /// Create a new file transaction
     pub fn new() -> Self {
@@ -85,3 +278,63 @@ impl FileTransaction {
         self.completed = true;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn paths(names: &[&str]) -> Vec<PathBuf> {
+        names.iter().map(PathBuf::from).collect()
+    }
+
+    #[test]
+    fn test_journal_replays_committed_batches() {
+        let dir = TempDir::new().unwrap();
+        let journal = IndexJournal::new(dir.path());
+        assert_eq!(journal.read().unwrap(), None);
+
+        journal.begin(&paths(&["a.rs", "b.rs", "c.rs"])).unwrap();
+        let mut batch = IndexTransaction::default();
+        batch.add_file("a.rs");
+        journal.commit(&mut batch).unwrap();
+        assert!(!batch.is_active());
+
+        assert_eq!(
+            journal.read().unwrap(),
+            Some(InterruptedRun {
+                pending: paths(&["b.rs", "c.rs"]),
+                committed: paths(&["a.rs"]),
+                resolved: false,
+            })
+        );
+
+        // Saving an unfinished run keeps its journal
+        journal.finish().unwrap();
+        assert!(journal.exists());
+
+        journal.resolved().unwrap();
+        assert!(journal.read().unwrap().unwrap().resolved);
+
+        journal.finish().unwrap();
+        assert!(!journal.exists());
+        journal.finish().unwrap();
+    }
+
+    #[test]
+    fn test_journal_ignores_torn_last_line() {
+        let dir = TempDir::new().unwrap();
+        let journal = IndexJournal::new(dir.path());
+        journal.begin(&paths(&["a.rs", "b.rs"])).unwrap();
+
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(journal.path())
+            .unwrap();
+        file.write_all(br#"{"op":"commit","files":["a.r"#).unwrap();
+
+        let run = journal.read().unwrap().unwrap();
+        assert_eq!(run.pending, paths(&["a.rs", "b.rs"]));
+        assert!(run.committed.is_empty());
+    }
+}
@@ -0,0 +1,319 @@
+//! Receiver-type method resolution
+//!
+//! A call such as `doc.save()` only names the method. Resolving it by name
+//! binds it to whichever `save` the resolution context sees first. While
+//! files are parsed, the indexer records here which type defines which
+//! method (from `find_defines`) and the extends/implements edges between
+//! types (from `find_extends`/`find_implementations`). When the receiver's
+//! type is known from `find_variable_types`, or is the enclosing type for
+//! `self`/`this`, the language's [`InheritanceResolver`] picks the type that
+//! provides the method and the recorded definition site names the symbol.
+
+use crate::parsing::{InheritanceResolver, LanguageId};
+use crate::{FileId, Range};
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+
+/// Receivers that refer to the instance of the enclosing type
+const SELF_RECEIVERS: [&str; 3] = ["self", "this", "$this"];
+
+/// Where a type defines a method
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MethodSite {
+    pub file_id: FileId,
+    pub range: Range,
+}
+
+/// One method definition recorded for a file
+#[derive(Debug, Clone)]
+struct Definition {
+    language: LanguageId,
+    type_name: String,
+    method: String,
+    range: Range,
+}
+
+/// One extends/implements edge recorded for a file
+#[derive(Debug, Clone)]
+struct Edge {
+    language: LanguageId,
+    child: String,
+    parent: String,
+    kind: &'static str,
+}
+
+/// Method ownership and type hierarchy collected during indexing
+#[derive(Default)]
+pub struct TypeMethods {
+    definitions: HashMap<FileId, Vec<Definition>>,
+    edges: HashMap<FileId, Vec<Edge>>,
+    /// Built on demand from the recorded data, one per language
+    resolvers: HashMap<LanguageId, Box<dyn InheritanceResolver>>,
+    dirty: bool,
+}
+
+impl TypeMethods {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record that `type_name` defines `method` at `range`
+    pub fn add_method(
+        &mut self,
+        language: LanguageId,
+        file_id: FileId,
+        type_name: &str,
+        method: &str,
+        range: Range,
+    ) {
+        self.definitions
+            .entry(file_id)
+            .or_default()
+            .push(Definition {
+                language,
+                type_name: base_type_name(type_name).to_string(),
+                method: method.to_string(),
+                range,
+            });
+        self.dirty = true;
+    }
+
+    /// Record an inheritance edge; `kind` is "extends" or "implements"
+    pub fn add_parent(
+        &mut self,
+        language: LanguageId,
+        file_id: FileId,
+        child: &str,
+        parent: &str,
+        kind: &'static str,
+    ) {
+        self.edges.entry(file_id).or_default().push(Edge {
+            language,
+            child: base_type_name(child).to_string(),
+            parent: base_type_name(parent).to_string(),
+            kind,
+        });
+        self.dirty = true;
+    }
+
+    /// Forget everything recorded for a file before it is re-indexed or removed
+    pub fn remove_file(&mut self, file_id: FileId) {
+        let removed_definitions = self.definitions.remove(&file_id).is_some();
+        let removed_edges = self.edges.remove(&file_id).is_some();
+        if removed_definitions || removed_edges {
+            self.dirty = true;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.definitions.clear();
+        self.edges.clear();
+        self.resolvers.clear();
+        self.dirty = false;
+    }
+
+    /// Rebuild the per-language inheritance resolvers if anything changed
+    ///
+    /// `create` returns a fresh resolver for a language, normally from
+    /// `LanguageBehavior::create_inheritance_resolver`.
+    pub fn prepare(&mut self, create: impl Fn(LanguageId) -> Option<Box<dyn InheritanceResolver>>) {
+        if !self.dirty {
+            return;
+        }
+
+        let mut methods: HashMap<(LanguageId, &str), Vec<String>> = HashMap::new();
+        for definition in in_file_order(&self.definitions) {
+            let entry = methods
+                .entry((definition.language, definition.type_name.as_str()))
+                .or_default();
+            if !entry.contains(&definition.method) {
+                entry.push(definition.method.clone());
+            }
+        }
+
+        let mut resolvers: HashMap<LanguageId, Box<dyn InheritanceResolver>> = HashMap::new();
+        for ((language, type_name), type_methods) in methods {
+            if let Some(resolver) = resolver_for(&mut resolvers, &create, language) {
+                resolver.add_type_methods(type_name.to_string(), type_methods);
+            }
+        }
+        for edge in in_file_order(&self.edges) {
+            if let Some(resolver) = resolver_for(&mut resolvers, &create, edge.language) {
+                resolver.add_inheritance(edge.child.clone(), edge.parent.clone(), edge.kind);
+            }
+        }
+
+        self.resolvers = resolvers;
+        self.dirty = false;
+    }
+
+    /// Type that provides `method` for a receiver of type `type_name`
+    ///
+    /// Uses the language's inheritance resolver, so a method inherited from a
+    /// base class or provided by an implemented trait is found on the parent.
+    pub fn owner(&self, language: LanguageId, type_name: &str, method: &str) -> Option<String> {
+        let type_name = base_type_name(type_name);
+        if let Some(resolver) = self.resolvers.get(&language) {
+            if let Some(owner) = resolver.resolve_method(type_name, method) {
+                return Some(owner);
+            }
+        }
+        // Resolvers that only model part of the hierarchy still know direct methods
+        self.definitions
+            .values()
+            .flatten()
+            .any(|d| d.language == language && d.type_name == type_name && d.method == method)
+            .then(|| type_name.to_string())
+    }
+
+    /// Definition sites of `method` on `type_name`
+    pub fn sites(&self, language: LanguageId, type_name: &str, method: &str) -> Vec<MethodSite> {
+        let mut sites = Vec::new();
+        for (file_id, definitions) in &self.definitions {
+            for d in definitions {
+                if d.language == language && d.type_name == type_name && d.method == method {
+                    sites.push(MethodSite {
+                        file_id: *file_id,
+                        range: d.range,
+                    });
+                }
+            }
+        }
+        sites.sort_by_key(|site| (site.file_id.value(), site.range.start_line));
+        sites
+    }
+
+    /// Type whose method body contains `line`, for resolving `self`/`this`
+    ///
+    /// `caller` is the name of the calling method; the innermost matching
+    /// definition wins when types are nested.
+    pub fn enclosing_type(&self, file_id: FileId, caller: &str, line: u32) -> Option<&str> {
+        self.definitions
+            .get(&file_id)?
+            .iter()
+            .filter(|d| d.method == caller)
+            .filter(|d| d.range.start_line <= line && line <= d.range.end_line)
+            .min_by_key(|d| d.range.end_line - d.range.start_line)
+            .map(|d| d.type_name.as_str())
+    }
+}
+
+/// Records of all files, by ascending file ID
+fn in_file_order<T>(records: &HashMap<FileId, Vec<T>>) -> impl Iterator<Item = &T> {
+    let mut file_ids: Vec<&FileId> = records.keys().collect();
+    file_ids.sort_by_key(|file_id| file_id.value());
+    file_ids.into_iter().flat_map(|file_id| &records[file_id])
+}
+
+fn resolver_for<'a>(
+    resolvers: &'a mut HashMap<LanguageId, Box<dyn InheritanceResolver>>,
+    create: &impl Fn(LanguageId) -> Option<Box<dyn InheritanceResolver>>,
+    language: LanguageId,
+) -> Option<&'a mut Box<dyn InheritanceResolver>> {
+    if let Entry::Vacant(entry) = resolvers.entry(language) {
+        entry.insert(create(language)?);
+    }
+    resolvers.get_mut(&language)
+}
+
+/// Whether a receiver expression refers to the enclosing instance
+pub fn is_self_receiver(receiver: &str) -> bool {
+    SELF_RECEIVERS.contains(&receiver)
+}
+
+/// Bare type name from an inferred or declared type
+///
+/// Strips references, pointers, generic arguments and package qualifiers:
+/// `&mut Vec<T>` → `Vec`, `*pkg.Server` → `Server`, `models.Document` →
+/// `Document`.
+pub fn base_type_name(type_name: &str) -> &str {
+    let trimmed = type_name
+        .trim()
+        .trim_start_matches(['&', '*'])
+        .trim_start_matches("mut ")
+        .trim_start_matches("dyn ")
+        .trim_start_matches("impl ");
+    let without_args = trimmed.split(['<', '[', '(']).next().unwrap_or(trimmed);
+    without_args
+        .rsplit(['.', ':', '\\'])
+        .next()
+        .unwrap_or(without_args)
+        .trim()
+        .trim_end_matches('?')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::resolution::GenericInheritanceResolver;
+
+    fn python() -> LanguageId {
+        LanguageId::new("python")
+    }
+
+    fn file(id: u32) -> FileId {
+        FileId::new(id).unwrap()
+    }
+
+    fn generic(_: LanguageId) -> Option<Box<dyn InheritanceResolver>> {
+        Some(Box::new(GenericInheritanceResolver::new()))
+    }
+
+    #[test]
+    fn test_base_type_name() {
+        assert_eq!(base_type_name("&mut Vec<String>"), "Vec");
+        assert_eq!(base_type_name("*pkg.Server"), "Server");
+        assert_eq!(
+            base_type_name("crate::storage::DocumentIndex"),
+            "DocumentIndex"
+        );
+        assert_eq!(base_type_name("models.Document"), "Document");
+        assert_eq!(base_type_name("User?"), "User");
+    }
+
+    #[test]
+    fn test_owner_follows_inheritance() {
+        let mut table = TypeMethods::new();
+        table.add_method(python(), file(1), "Invoice", "save", Range::new(2, 4, 3, 0));
+        table.add_method(
+            python(),
+            file(1),
+            "Document",
+            "save",
+            Range::new(6, 4, 7, 0),
+        );
+        table.add_parent(python(), file(1), "Receipt", "Invoice", "extends");
+        table.prepare(generic);
+
+        assert_eq!(
+            table.owner(python(), "Receipt", "save"),
+            Some("Invoice".into())
+        );
+        assert_eq!(
+            table.owner(python(), "Document", "save"),
+            Some("Document".into())
+        );
+        assert_eq!(table.owner(python(), "Receipt", "missing"), None);
+        assert_eq!(
+            table.sites(python(), "Invoice", "save"),
+            vec![MethodSite {
+                file_id: file(1),
+                range: Range::new(2, 4, 3, 0)
+            }]
+        );
+    }
+
+    #[test]
+    fn test_enclosing_type_and_removal() {
+        let mut table = TypeMethods::new();
+        table.add_method(python(), file(1), "A", "run", Range::new(1, 4, 4, 0));
+        table.add_method(python(), file(1), "B", "run", Range::new(6, 4, 9, 0));
+
+        assert_eq!(table.enclosing_type(file(1), "run", 7), Some("B"));
+        assert_eq!(table.enclosing_type(file(1), "run", 5), None);
+
+        table.remove_file(file(1));
+        table.prepare(generic);
+        assert_eq!(table.owner(python(), "A", "run"), None);
+    }
+}
@@ -0,0 +1,208 @@
+//! Unresolved-reference accounting
+//!
+//! Cross-file resolution drops relationships whose target it cannot bind to
+//! a symbol. Each drop is recorded here as an [`UnresolvedReference`] and
+//! counted per file, so `codanna stats --unresolved` can show where
+//! resolution fails and a grammar or resolver change that loses references
+//! shows up as a jump in the counts. Counts are kept per file because
+//! incremental indexing only re-resolves the files that changed.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Why a relationship target could not be resolved
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnresolvedReason {
+    /// No symbol with the target's name is visible from the file
+    NotFound,
+    /// Several indexed symbols carry the name and none is in scope
+    Ambiguous,
+    /// The target comes from an import outside the indexed code
+    External,
+}
+
+impl UnresolvedReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::NotFound => "not found",
+            Self::Ambiguous => "ambiguous",
+            Self::External => "external",
+        }
+    }
+}
+
+/// One relationship target that resolution dropped
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnresolvedReference {
+    /// Target name as written at the use site
+    pub name: String,
+    pub file_path: String,
+    /// 1-based line of the use, when the parser recorded it
+    pub line: Option<u32>,
+    pub language: String,
+    pub reason: UnresolvedReason,
+}
+
+/// Unresolved references by reason
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnresolvedCounts {
+    pub not_found: u32,
+    pub ambiguous: u32,
+    pub external: u32,
+}
+
+impl UnresolvedCounts {
+    pub fn add(&mut self, reason: UnresolvedReason) {
+        match reason {
+            UnresolvedReason::NotFound => self.not_found += 1,
+            UnresolvedReason::Ambiguous => self.ambiguous += 1,
+            UnresolvedReason::External => self.external += 1,
+        }
+    }
+
+    pub fn merge(&mut self, other: &UnresolvedCounts) {
+        self.not_found += other.not_found;
+        self.ambiguous += other.ambiguous;
+        self.external += other.external;
+    }
+
+    pub fn total(&self) -> u32 {
+        self.not_found + self.ambiguous + self.external
+    }
+}
+
+/// Unresolved references of one file
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnresolvedFile {
+    pub language: String,
+    #[serde(flatten)]
+    pub counts: UnresolvedCounts,
+}
+
+/// Unresolved-reference counts for the whole index, keyed by file path
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnresolvedSummary {
+    pub files: BTreeMap<String, UnresolvedFile>,
+}
+
+impl UnresolvedSummary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    pub fn record(&mut self, reference: &UnresolvedReference) {
+        let file = self
+            .files
+            .entry(reference.file_path.clone())
+            .or_insert_with(|| UnresolvedFile {
+                language: reference.language.clone(),
+                counts: UnresolvedCounts::default(),
+            });
+        file.counts.add(reference.reason);
+    }
+
+    /// Forget a file's counts before it is re-resolved or removed
+    pub fn remove_file(&mut self, file_path: &str) {
+        self.files.remove(file_path);
+    }
+
+    pub fn clear(&mut self) {
+        self.files.clear();
+    }
+
+    pub fn totals(&self) -> UnresolvedCounts {
+        let mut totals = UnresolvedCounts::default();
+        for file in self.files.values() {
+            totals.merge(&file.counts);
+        }
+        totals
+    }
+
+    pub fn by_language(&self) -> BTreeMap<&str, UnresolvedCounts> {
+        let mut languages: BTreeMap<&str, UnresolvedCounts> = BTreeMap::new();
+        for file in self.files.values() {
+            languages
+                .entry(file.language.as_str())
+                .or_default()
+                .merge(&file.counts);
+        }
+        languages
+    }
+
+    /// Files with the most unresolved references first
+    pub fn top_files(&self, limit: usize) -> Vec<(&str, &UnresolvedFile)> {
+        let mut files: Vec<_> = self
+            .files
+            .iter()
+            .filter(|(_, file)| file.counts.total() > 0)
+            .map(|(path, file)| (path.as_str(), file))
+            .collect();
+        files.sort_by(|a, b| {
+            b.1.counts
+                .total()
+                .cmp(&a.1.counts.total())
+                .then(a.0.cmp(b.0))
+        });
+        files.truncate(limit);
+        files
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reference(path: &str, language: &str, reason: UnresolvedReason) -> UnresolvedReference {
+        UnresolvedReference {
+            name: "save".to_string(),
+            file_path: path.to_string(),
+            line: Some(3),
+            language: language.to_string(),
+            reason,
+        }
+    }
+
+    #[test]
+    fn test_summary_breakdowns() {
+        let mut summary = UnresolvedSummary::new();
+        summary.record(&reference("a.py", "python", UnresolvedReason::NotFound));
+        summary.record(&reference("a.py", "python", UnresolvedReason::External));
+        summary.record(&reference("b.py", "python", UnresolvedReason::Ambiguous));
+        summary.record(&reference("c.rs", "rust", UnresolvedReason::NotFound));
+
+        assert_eq!(
+            summary.totals(),
+            UnresolvedCounts {
+                not_found: 2,
+                ambiguous: 1,
+                external: 1
+            }
+        );
+        let languages = summary.by_language();
+        assert_eq!(languages["python"].total(), 3);
+        assert_eq!(languages["rust"].total(), 1);
+
+        let top: Vec<&str> = summary.top_files(2).iter().map(|(path, _)| *path).collect();
+        assert_eq!(top, vec!["a.py", "b.py"]);
+
+        summary.remove_file("a.py");
+        assert_eq!(summary.totals().total(), 2);
+    }
+
+    #[test]
+    fn test_summary_serializes_flat_counts() {
+        let mut summary = UnresolvedSummary::new();
+        summary.record(&reference("a.py", "python", UnresolvedReason::External));
+
+        let json = serde_json::to_value(&summary).unwrap();
+        assert_eq!(json["files"]["a.py"]["language"], "python");
+        assert_eq!(json["files"]["a.py"]["external"], 1);
+
+        let back: UnresolvedSummary = serde_json::from_value(json).unwrap();
+        assert_eq!(back, summary);
+    }
+}
@@ -16,12 +16,25 @@ use std::sync::Arc;
 #[derive(Debug)]
 pub struct FileWalker {
     settings: Arc<Settings>,
+    respect_ignore_files: bool,
 }
 
 impl FileWalker {
     /// Create a new file walker with the given settings
     pub fn new(settings: Arc<Settings>) -> Self {
-        Self { settings }
+        Self {
+            settings,
+            respect_ignore_files: true,
+        }
+    }
+
+    /// Set whether .gitignore and .codannaignore rules are applied
+    ///
+    /// Dependency sources such as `node_modules` are usually ignored by the
+    /// project, so walking them requires turning the rules off.
+    pub fn with_ignore_files(mut self, enabled: bool) -> Self {
+        self.respect_ignore_files = enabled;
+        self
     }
 
     /// Walk a directory and return an iterator of files to index
@@ -31,15 +44,19 @@ impl FileWalker {
         // Configure the walker
         builder
             .hidden(false) // Don't traverse hidden directories by default
-            .git_ignore(true) // Respect .gitignore files
-            .git_global(true) // Respect global gitignore
-            .git_exclude(true) // Respect .git/info/exclude
+            .git_ignore(self.respect_ignore_files) // Respect .gitignore files
+            .git_global(self.respect_ignore_files) // Respect global gitignore
+            .git_exclude(self.respect_ignore_files) // Respect .git/info/exclude
+            .ignore(self.respect_ignore_files) // Respect .ignore files
+            .parents(self.respect_ignore_files) // Apply ignore files from parent directories
             .follow_links(false) // Don't follow symlinks by default
             .max_depth(None) // No depth limit
             .require_git(false); // Allow gitignore to work in non-git directories
 
         // Always support .codannaignore files for custom ignore patterns (follows .gitignore pattern)
-        builder.add_custom_ignore_filename(".codannaignore");
+        if self.respect_ignore_files {
+            builder.add_custom_ignore_filename(".codannaignore");
+        }
 
         // The ignore crate's override feature is for INCLUDING files, not excluding them.
         // To add custom ignore patterns, we need to use a different approach.
@@ -179,4 +196,20 @@ mod tests {
         assert_eq!(files.len(), 1);
         assert!(files[0].ends_with("included.rs"));
     }
+
+    #[test]
+    fn test_ignore_files_can_be_disabled() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        fs::write(root.join(".gitignore"), "ignored.rs\n").unwrap();
+        fs::write(root.join("ignored.rs"), "fn ignored() {}").unwrap();
+        fs::write(root.join("included.rs"), "fn included() {}").unwrap();
+
+        let walker = FileWalker::new(create_test_settings()).with_ignore_files(false);
+
+        let files: Vec<_> = walker.walk(root).collect();
+
+        assert_eq!(files.len(), 2);
+    }
 }
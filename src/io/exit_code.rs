@@ -77,6 +77,9 @@ impl ExitCode {
             // Index corruption is a blocking error
             IndexError::IndexCorrupted { .. } => ExitCode::BlockingError,
 
+            // An index this version can't migrate has to be rebuilt
+            IndexError::SchemaMismatch { .. } => ExitCode::IndexCorrupted,
+
             // Specific recoverable errors
             IndexError::ParseError { .. } => ExitCode::ParseError,
             IndexError::FileRead { .. } | IndexError::FileWrite { .. } => ExitCode::IoError,
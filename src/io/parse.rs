@@ -261,6 +261,12 @@ pub fn execute_parse(
         Language::CSharp => tree_sitter_c_sharp::LANGUAGE.into(),
         Language::Gdscript => tree_sitter_gdscript::LANGUAGE.into(),
         Language::Kotlin => tree_sitter_kotlin::language(),
+        // Schemas are scanned without a tree-sitter grammar
+        Language::Protobuf => {
+            return Err(ParseError::UnsupportedLanguage {
+                extension: extension.to_string(),
+            });
+        }
     };
 
     parser
@@ -63,8 +63,11 @@ pub enum EntityType {
     Interface,
     Module,
     Variable,
+    File,
+    StackFrame,
     SearchResult,
     Impact,
+    Route,
     IndexInfo,
     Mixed,
 }
@@ -54,6 +54,8 @@ macro_rules! debug_print {
 }
 
 pub mod config;
+#[cfg(unix)]
+pub mod daemon;
 pub mod display;
 pub mod error;
 pub mod indexing;
@@ -84,5 +86,6 @@ pub use relationship::{RelationKind, Relationship, RelationshipEdge};
 pub use storage::IndexPersistence;
 pub use symbol::{CompactSymbol, ScopeContext, StringTable, Symbol, Visibility};
 pub use types::{
-    CompactString, FileId, IndexingResult, Range, SymbolId, SymbolKind, compact_string,
+    CompactString, FileId, IndexingResult, Range, StableSymbolId, SymbolId, SymbolKind, SymbolRef,
+    compact_string,
 };
@@ -8,6 +8,7 @@ use clap::{
     builder::styling::{AnsiColor, Effects, Styles},
 };
 use codanna::FileId;
+use codanna::indexing::IndexWriteLock;
 use codanna::parsing::{
     CSharpParser, GoParser, LanguageParser, PhpParser, PythonParser, RustParser, TypeScriptParser,
 };
@@ -170,8 +171,15 @@ enum Commands {
     },
 
     /// Index source files or directories
-    #[command(about = "Build searchable index from codebase")]
+    #[command(
+        about = "Build searchable index from codebase",
+        args_conflicts_with_subcommands = true,
+        after_help = "Examples:\n  codanna index src\n  codanna index --force --progress\n  codanna index pack ci-index.tar.lz4\n  codanna index unpack ci-index.tar.lz4"
+    )]
     Index {
+        #[command(subcommand)]
+        action: Option<IndexAction>,
+
         /// Paths to files or directories to index (multiple paths allowed)
         #[arg(value_name = "PATH")]
         paths: Vec<PathBuf>,
@@ -215,6 +223,53 @@ enum Commands {
     #[command(about = "List all directories that are being indexed")]
     ListDirs,
 
+    /// Show index statistics
+    #[command(
+        about = "Show index statistics and unresolved references",
+        long_about = "Show symbol and file counts of the index.\n\nWith --unresolved, break down the relationship targets that resolution could not bind to a symbol by reason (not found, ambiguous, external), language and file.",
+        after_help = "Examples:\n  codanna stats\n  codanna stats --unresolved\n  codanna stats --unresolved --limit 50 --json"
+    )]
+    Stats {
+        /// Break down unresolved references by language and file
+        #[arg(long)]
+        unresolved: bool,
+
+        /// Maximum number of files listed
+        #[arg(long, default_value = "20")]
+        limit: usize,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Check index integrity and repair it
+    #[command(
+        about = "Check index integrity and repair problems",
+        long_about = "Cross-check the Tantivy documents against the symbol cache, the semantic vectors and their metadata, the index metadata counts, relationship endpoints, the indexing journal and the files on disk.\n\nWith --fix, apply the targeted repair for each problem found (resume an interrupted run, remove deleted files, drop dangling relationships or orphaned embeddings, embed missing doc comments, rebuild the symbol cache) instead of a full --force reindex.",
+        after_help = "Examples:\n  codanna doctor\n  codanna doctor --fix\n  codanna doctor --json"
+    )]
+    Doctor {
+        /// Apply the suggested repairs
+        #[arg(long)]
+        fix: bool,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Keep the index loaded in a background process for retrieve queries
+    #[command(
+        about = "Manage the background daemon that answers retrieve queries",
+        long_about = "Run a background process that keeps the index loaded and answers `codanna retrieve` queries over a Unix socket in .codanna/, so each query skips loading Tantivy, the symbol cache and the embeddings.\n\nRetrieve commands use a running daemon automatically and load the index themselves when none answers. With `[daemon] enabled = true` in settings.toml, such a query also starts the daemon for the next call. The daemon exits after `idle_timeout_secs` without queries.",
+        after_help = "Examples:\n  codanna daemon start\n  codanna daemon status\n  codanna daemon stop"
+    )]
+    Daemon {
+        #[command(subcommand)]
+        action: DaemonAction,
+    },
+
     /// Query code relationships and dependencies
     #[command(
         about = "Search symbols, find callers/callees, analyze impact",
@@ -298,7 +353,7 @@ enum Commands {
     #[command(
         about = "Execute MCP tools directly",
         long_about = "Execute MCP tools directly without spawning a server.\n\nSupports positional arguments, key=value pairs, and JSON arguments.",
-        after_help = "Examples:\n  codanna mcp find_symbol main\n  codanna mcp get_calls process_file\n  codanna mcp semantic_search_docs query:\"error handling\" limit:5\n  codanna mcp search_symbols query:parse kind:function\n  codanna mcp find_symbol Parser --json | jq '.data[].symbol.name'\n  codanna mcp search_symbols query:Parser --json | jq '.data[].name'\n\nTools:\n  find_symbol                  Find symbol by exact name\n  search_symbols               Full-text search with fuzzy matching\n  semantic_search_docs         Natural language search\n  semantic_search_with_context Natural language search with relationships\n  get_calls                    Functions called by a function\n  find_callers                 Functions that call a function\n  analyze_impact               Impact radius of symbol changes\n  get_index_info               Index statistics"
+        after_help = "Examples:\n  codanna mcp find_symbol main\n  codanna mcp get_calls process_file\n  codanna mcp semantic_search_docs query:\"error handling\" limit:5\n  codanna mcp search_symbols query:parse kind:function\n  codanna mcp find_symbol Parser --json | jq '.data[].symbol.name'\n  codanna mcp search_symbols query:Parser --json | jq '.data[].name'\n\nTools:\n  find_symbol                  Find symbol by exact name\n  search_symbols               Full-text search with fuzzy matching\n  semantic_search_docs         Natural language search\n  semantic_search_with_context Natural language search with relationships\n  get_calls                    Functions called by a function\n  find_callers                 Functions that call a function\n  analyze_impact               Impact radius of symbol changes\n  get_index_info               Index statistics\n  get_symbol_source            Exact source of symbols (batch via symbol_ids:1,2,3)\n  get_file_outline             Nested symbol outline of a file\n  find_symbol_at               Symbol at a file:line[:column] position\n  resolve_stack_trace          Map stack trace frames (stdin) to symbols\n  find_tests                   Tests that reach a symbol through callers\n  find_routes                  HTTP routes and their handlers (method:, path:)"
     )]
     Mcp {
         /// Tool to call
@@ -317,6 +372,49 @@ enum Commands {
         json: bool,
     },
 
+    /// Show the impact of a diff
+    #[command(
+        about = "Find symbols changed by a diff and everything they affect",
+        long_about = "Map the hunks of a unified diff to the indexed symbols they touch, then list their transitive callers, users and implementors grouped by file.\n\nLine numbers are read from the new side of the diff, so index the changed tree first.",
+        after_help = "Examples:\n  git diff main | codanna impact --diff\n  codanna impact --diff changes.patch --depth 3\n  codanna impact --git main --json\n\nJSON paths:\n  .item.summary\n  .item.files[].symbols[] | select(.impact == \"impacted\") | .name"
+    )]
+    Impact {
+        /// Read a unified diff from FILE, or stdin when no file or "-" is given
+        #[arg(long, value_name = "FILE", num_args = 0..=1, conflicts_with = "git")]
+        diff: Option<Option<PathBuf>>,
+
+        /// Run `git diff [REV]` in the workspace instead of reading a diff
+        #[arg(long, value_name = "REV", num_args = 0..=1)]
+        git: Option<Option<String>>,
+
+        /// Maximum depth of callers/users/implementors to follow
+        #[arg(short, long, default_value = "2")]
+        depth: usize,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Map a stack trace to indexed symbols
+    #[command(
+        about = "Resolve stack trace frames to indexed symbols",
+        long_about = "Read a Python, Rust, Go, Java/Kotlin or Node.js stack trace from stdin (or a file) and map each frame to the indexed symbol by file and line, or by qualified name when paths differ.",
+        after_help = "Examples:\n  pytest 2>&1 | codanna trace\n  codanna trace crash.log --json\n  RUST_BACKTRACE=1 cargo run 2>&1 | codanna trace --max-callers 10\n\nJSON paths:\n  .items[].symbol.name\n  .items[] | select(.in_index | not) | .function"
+    )]
+    Trace {
+        /// File containing the stack trace (reads stdin when omitted or "-")
+        file: Option<PathBuf>,
+
+        /// Maximum callers listed per resolved frame
+        #[arg(long, default_value = "5")]
+        max_callers: usize,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+
     /// Benchmark parser performance
     #[command(about = "Benchmark parser performance")]
     Benchmark {
@@ -359,6 +457,17 @@ enum Commands {
         action: PluginAction,
     },
 
+    /// Index dependency sources into a read-only fallback layer
+    #[command(
+        about = "Index library sources into a separate read-only layer",
+        long_about = "Index the sources of the project's dependencies into a separate, read-only index layer.\n\nSupported: Cargo registry sources for Cargo.lock, node_modules, the site-packages of .venv/venv or $VIRTUAL_ENV, and the Go module cache for go.mod.\n\nSymbol lookups fall back to this layer only when the project index has no match, so project search results are unaffected.",
+        after_help = "Examples:\n  codanna deps index\n  codanna deps index --ecosystem cargo --ecosystem npm\n  codanna deps list"
+    )]
+    Deps {
+        #[command(subcommand)]
+        action: DepsAction,
+    },
+
     /// Manage project profiles
     #[command(
         about = "Initialize and manage project profiles",
@@ -371,6 +480,97 @@ enum Commands {
     },
 }
 
+/// Index archive actions
+#[derive(Subcommand)]
+enum IndexAction {
+    /// Bundle the index into one compressed archive
+    #[command(
+        about = "Bundle the index into one compressed archive",
+        long_about = "Write Tantivy segments, symbol cache, semantic vectors and metadata into one LZ4-compressed archive, with a manifest recording the git commit, codanna version, schema version and workspace root.\n\nUse it to build the index once in CI and unpack it in every checkout.",
+        after_help = "Examples:\n  codanna index pack\n  codanna index pack /artifacts/index.tar.lz4"
+    )]
+    Pack {
+        /// Archive to write
+        #[arg(value_name = "FILE", default_value = "codanna-index.tar.lz4")]
+        output: PathBuf,
+    },
+
+    /// Replace the index with one from an archive
+    #[command(
+        about = "Replace the index with one packed by `codanna index pack`",
+        long_about = "Unpack an archive written by `codanna index pack` into this workspace's index directory.\n\nAbsolute paths under the workspace root the index was built in are rewritten to this workspace's root, so an index built in /ci/workspace works in ~/src/repo.",
+        after_help = "Examples:\n  codanna index unpack codanna-index.tar.lz4\n  codanna index unpack /artifacts/index.tar.lz4 --force"
+    )]
+    Unpack {
+        /// Archive written by `codanna index pack`
+        #[arg(value_name = "FILE")]
+        archive: PathBuf,
+
+        /// Replace an existing index
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Compact the index and report the space reclaimed
+    #[command(
+        about = "Merge Tantivy segments and drop data nothing refers to",
+        long_about = "Merge Tantivy segments so deleted documents are dropped, remove import documents of files that are no longer indexed, rewrite the vector storage without removed symbols and superseded copies, and rebuild the symbol cache.\n\nReports the bytes reclaimed per store. Symbols, files and relationships are left as they are.",
+        after_help = "Examples:\n  codanna index gc\n  codanna index gc --json"
+    )]
+    Gc {
+        /// Output the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// Query daemon actions
+#[derive(Subcommand)]
+enum DaemonAction {
+    /// Start the daemon in the background
+    #[command(about = "Start the daemon in the background and wait until it answers")]
+    Start,
+
+    /// Stop the running daemon
+    #[command(about = "Stop the running daemon")]
+    Stop,
+
+    /// Show whether a daemon is running
+    #[command(about = "Show the running daemon and the index it serves")]
+    Status,
+
+    /// Serve queries in the foreground
+    #[command(about = "Serve queries in the foreground until stopped or idle")]
+    Run,
+}
+
+/// Dependency layer actions
+#[derive(Subcommand)]
+enum DepsAction {
+    /// Discover dependency sources and rebuild the layer
+    #[command(
+        about = "Discover dependency sources and rebuild the dependency layer",
+        after_help = "Examples:\n  codanna deps index\n  codanna deps index --ecosystem python --dry-run"
+    )]
+    Index {
+        /// Ecosystems to index: cargo, npm, python, go (defaults to [dependencies] in settings.toml)
+        #[arg(long = "ecosystem", value_name = "NAME")]
+        ecosystems: Vec<String>,
+
+        /// Show progress during indexing
+        #[arg(short, long)]
+        progress: bool,
+
+        /// Only list the dependencies that would be indexed
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// List dependencies in the current layer
+    #[command(about = "List the dependencies indexed into the dependency layer")]
+    List,
+}
+
 /// Plugin management actions
 #[derive(Subcommand)]
 enum PluginAction {
@@ -519,7 +719,7 @@ enum RetrieveQuery {
 
     /// Show what types implement a given trait
     #[command(
-        after_help = "Examples:\n  codanna retrieve implementations Parser\n  codanna retrieve implementations trait:Parser --json"
+        after_help = "Examples:\n  codanna retrieve implementations Parser\n  codanna retrieve implementations trait:Parser --json\n  codanna retrieve implementations OrderService.Create"
     )]
     Implementations {
         /// Positional arguments (trait name and/or key:value pairs)
@@ -563,7 +763,7 @@ enum RetrieveQuery {
     // },
     /// Search for symbols using full-text search
     #[command(
-        after_help = "Examples:\n  # Traditional flag format\n  codanna retrieve search \"parse\" --limit 5 --kind function\n  \n  # Key:value format (Unix-style)\n  codanna retrieve search query:parse limit:5 kind:function\n  \n  # Mixed format\n  codanna retrieve search \"parse\" limit:5 --json"
+        after_help = "Examples:\n  # Traditional flag format\n  codanna retrieve search \"parse\" --limit 5 --kind function\n  \n  # Key:value format (Unix-style)\n  codanna retrieve search query:parse limit:5 kind:function\n  \n  # Mixed format\n  codanna retrieve search \"parse\" limit:5 --json\n  \n  # Symbols with an attribute, decorator or annotation\n  codanna retrieve search annotation:route\n  codanna retrieve search \"order\" annotation:deprecated"
     )]
     Search {
         /// Positional arguments (query and/or key:value pairs)
@@ -611,6 +811,105 @@ enum RetrieveQuery {
         #[arg(long)]
         json: bool,
     },
+
+    /// Show the nested symbol outline of a file
+    #[command(
+        after_help = "Examples:\n  codanna retrieve outline src/main.rs\n  codanna retrieve outline file:src/lib.rs --json"
+    )]
+    Outline {
+        /// Positional arguments (file path and/or key:value pairs)
+        #[arg(num_args = 0..)]
+        args: Vec<String>,
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Find the innermost symbol at a file position
+    #[command(
+        after_help = "Examples:\n  codanna retrieve at src/main.rs:120\n  codanna retrieve at src/main.rs:120:8\n  codanna retrieve at src/main.rs:120 edges:false --json"
+    )]
+    At {
+        /// Position as path:line[:column] (one-based), plus optional key:value pairs
+        #[arg(num_args = 0..)]
+        args: Vec<String>,
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Show the tests that reach a symbol through calls
+    #[command(
+        after_help = "Examples:\n  codanna retrieve tests parse_file\n  codanna retrieve tests symbol_id:1771 depth:3\n  codanna retrieve tests Parser lang:rust --json\n\nJSON paths:\n  .items[].test_files[]\n  .items[].tests[].name"
+    )]
+    Tests {
+        /// Positional arguments (symbol name and/or key:value pairs)
+        #[arg(num_args = 0..)]
+        args: Vec<String>,
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// List HTTP routes and the symbols that handle them
+    #[command(
+        after_help = "Examples:\n  codanna retrieve routes\n  codanna retrieve routes POST /orders\n  codanna retrieve routes /orders/42 lang:typescript\n  codanna retrieve routes orders --json\n\nA path starting with / matches route patterns (/orders/42 finds /orders/{id});\nany other word matches part of the pattern.\n\nJSON paths:\n  .item.routes[].handler_symbol.name\n  .item.routes[].path"
+    )]
+    Routes {
+        /// HTTP method, path and/or key:value pairs (method:, path:, lang:)
+        #[arg(num_args = 0..)]
+        args: Vec<String>,
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Show the exact source code of one or more symbols
+    #[command(
+        after_help = "Examples:\n  codanna retrieve source parse_file\n  codanna retrieve source symbol_id:1771 context:3\n  codanna retrieve source symbol_id:1771,1772 doc:false --json"
+    )]
+    Source {
+        /// Positional arguments (symbol name and/or key:value pairs)
+        #[arg(num_args = 0..)]
+        args: Vec<String>,
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// Read symbol IDs for `codanna mcp` from `symbol_ids` or `symbol_id`.
+///
+/// Accepts a JSON array, a single number or stable ID, or a comma-separated
+/// string (`symbol_ids:12,34` on the command line).
+fn symbol_ids_argument(
+    arguments: Option<&serde_json::Map<String, serde_json::Value>>,
+) -> Vec<codanna::SymbolRef> {
+    let value = arguments.and_then(|m| m.get("symbol_ids").or_else(|| m.get("symbol_id")));
+    match value {
+        Some(serde_json::Value::Array(items)) => items
+            .iter()
+            .filter_map(|v| serde_json::from_value(v.clone()).ok())
+            .collect(),
+        Some(serde_json::Value::Number(n)) => n
+            .as_u64()
+            .map(|id| codanna::SymbolRef::Id(codanna::SymbolId(id as u32)))
+            .into_iter()
+            .collect(),
+        Some(serde_json::Value::String(s)) => {
+            s.split(',').filter_map(|id| id.parse().ok()).collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Read the `symbol_id` argument of `codanna mcp`: a number or a stable ID
+fn symbol_id_argument(
+    arguments: Option<&serde_json::Map<String, serde_json::Value>>,
+) -> Option<codanna::SymbolRef> {
+    arguments
+        .and_then(|m| m.get("symbol_id"))
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
 }
 
 /// Create and populate the provider registry with all language providers.
@@ -995,6 +1294,12 @@ async fn main() {
 
     let persistence = IndexPersistence::new(index_path.clone());
 
+    // A running daemon answers retrieve queries without loading the index here
+    #[cfg(unix)]
+    if matches!(cli.command, Commands::Retrieve { .. }) {
+        forward_to_daemon(&config, cli.config.as_deref());
+    }
+
     // Skip loading index for commands that don't need it
     let skip_index_load = matches!(
         cli.command,
@@ -1004,6 +1309,14 @@ async fn main() {
             | Commands::Config
             | Commands::Benchmark { .. }
             | Commands::Plugin { .. }
+            | Commands::Deps { .. }
+            | Commands::Stats { .. }
+            | Commands::Doctor { .. }
+            | Commands::Daemon { .. }
+            | Commands::Index {
+                action: Some(_),
+                ..
+            }
     );
 
     // Determine if we need full trait resolver initialization
@@ -1017,6 +1330,18 @@ async fn main() {
             | Commands::Serve { .. }
     );
 
+    // Commands that write the index hold the write lock from before loading
+    // until they exit, so they start from the previous writer's saved state
+    let locked_for_command = matches!(
+        cli.command,
+        Commands::Index { dry_run: false, .. } | Commands::Doctor { fix: true, .. }
+    );
+    let mut write_lock = if locked_for_command {
+        Some(lock_index_for_writing(&config))
+    } else {
+        None
+    };
+
     // Load existing index or create new one (unless we're in thin client mode)
     let settings = Arc::new(config.clone());
     let mut indexer = if skip_index_load {
@@ -1054,6 +1379,14 @@ async fn main() {
                     }
                     loaded
                 }
+                Err(e @ codanna::IndexError::SchemaMismatch { .. }) => {
+                    eprintln!("Error: {e}");
+                    eprintln!("\nRecovery steps:");
+                    for suggestion in e.recovery_suggestions() {
+                        eprintln!("  • {suggestion}");
+                    }
+                    std::process::exit(codanna::io::ExitCode::from_error(&e) as i32);
+                }
                 Err(e) => {
                     eprintln!("Warning: Could not load index: {e}. Creating new index.");
                     SimpleIndexer::with_settings(settings.clone())
@@ -1103,7 +1436,19 @@ async fn main() {
         None
     };
 
-    if config.semantic_search.enabled && !indexer.has_semantic_search() {
+    // The daemon loads its own index and archives copy the stored vectors, so
+    // neither needs the embedding model
+    if config.semantic_search.enabled
+        && !indexer.has_semantic_search()
+        && !matches!(
+            cli.command,
+            Commands::Daemon { .. }
+                | Commands::Index {
+                    action: Some(_),
+                    ..
+                }
+        )
+    {
         if let Err(e) = indexer.enable_semantic_search() {
             eprintln!("Warning: Failed to enable semantic search: {e}");
         } else {
@@ -1169,12 +1514,74 @@ async fn main() {
             }
         }
     }
+    // Repair the index after an interrupted indexing run and finish its files
+    // Force re-indexing starts over, so there is nothing to resume
+    // A journal left by a writer that still holds the lock belongs to a run in
+    // progress, not to an interrupted one
+    if !skip_index_load
+        && !is_force_index
+        && indexer.has_index_journal()
+        && try_lock_index(&mut write_lock, &config)
+    {
+        match indexer.recover_interrupted_run() {
+            Ok(Some(recovery)) => {
+                eprintln!("Recovering from an interrupted indexing run");
+                if recovery.rolled_back > 0 {
+                    eprintln!(
+                        "  ✓ Rolled back {} partially indexed files",
+                        recovery.rolled_back
+                    );
+                }
+                if recovery.dropped_embeddings > 0 {
+                    eprintln!(
+                        "  ✓ Dropped {} stale embeddings",
+                        recovery.dropped_embeddings
+                    );
+                }
+                let mut resumed = true;
+                if !recovery.remaining.is_empty() {
+                    eprintln!(
+                        "  Resuming: {} files left to index",
+                        recovery.remaining.len()
+                    );
+                    match indexer.index_files(recovery.remaining, show_progress, false) {
+                        Ok(stats) => eprintln!(
+                            "  ✓ Indexed {} files, {} symbols",
+                            stats.files_indexed, stats.symbols_found
+                        ),
+                        Err(e) => {
+                            // Saving would remove the journal and lose the rolled-back files
+                            eprintln!("  ✗ Failed to resume indexing: {e}");
+                            eprintln!("  The run is resumed again on the next start");
+                            resumed = false;
+                        }
+                    }
+                }
+                if resumed {
+                    if let Err(e) = persistence.save(&indexer) {
+                        eprintln!("Warning: Failed to save recovered index: {e}");
+                    }
+                }
+            }
+            Ok(None) => {}
+            Err(e) => eprintln!("Warning: Could not check for an interrupted indexing run: {e}"),
+        }
+    }
+
     // Track whether sync made changes (for later check); None means sync did not run
     let mut sync_made_changes: Option<bool> = None;
 
     if !skip_index_load && persistence.exists() && !is_force_index {
         // Load stored indexed_paths from metadata
         match IndexMetadata::load(&config.index_path) {
+            Ok(metadata)
+                if !SimpleIndexer::needs_sync(
+                    metadata.indexed_paths.as_deref(),
+                    &config.indexing.indexed_paths,
+                ) || !try_lock_index(&mut write_lock, &config) =>
+            {
+                sync_made_changes = Some(false);
+            }
             Ok(metadata) => {
                 let stored_paths = metadata.indexed_paths.clone();
 
@@ -1244,6 +1651,15 @@ async fn main() {
         }
     }
 
+    // Startup repairs hold the lock only while they write, so a long-running
+    // command such as serve does not keep other writers out
+    if !locked_for_command && write_lock.is_some() {
+        if let Err(e) = indexer.release_writer() {
+            eprintln!("Warning: {e}");
+        }
+        drop(write_lock.take());
+    }
+
     match cli.command {
         Commands::Init { .. } | Commands::Config => {
             // Already handled above
@@ -1384,6 +1800,18 @@ async fn main() {
                     let broadcaster =
                         Arc::new(NotificationBroadcaster::new(100).with_debug(config.mcp.debug));
 
+                    // Forward broadcast events to the client's resource subscriptions
+                    {
+                        let listener_server = server.clone();
+                        let receiver = broadcaster.subscribe();
+                        let mcp_debug = config.mcp.debug;
+                        tokio::spawn(async move {
+                            listener_server
+                                .start_notification_listener(receiver, mcp_debug)
+                                .await;
+                        });
+                    }
+
                     // If file watching is enabled in config, start the file system watcher
                     if config.file_watch.enabled {
                         use codanna::indexing::FileSystemWatcher;
@@ -1481,6 +1909,96 @@ async fn main() {
             } // End of match
         }
 
+        Commands::Index {
+            action: Some(action),
+            ..
+        } => {
+            use codanna::indexing::gc;
+            use codanna::storage::archive;
+
+            let root = config
+                .workspace_root
+                .clone()
+                .or_else(|| std::env::current_dir().ok())
+                .unwrap_or_default();
+            let result = match action {
+                IndexAction::Pack { output } => {
+                    archive::pack(&config.index_path, &root, &output).map(|manifest| {
+                        println!(
+                            "Packed {} symbols from {} files into {}",
+                            manifest.symbol_count,
+                            manifest.file_count,
+                            output.display()
+                        );
+                        if let Some(commit) = &manifest.commit {
+                            println!("  Commit: {commit}");
+                        }
+                        println!("  Root:   {}", manifest.root.display());
+                    })
+                }
+                IndexAction::Unpack { archive, force } => {
+                    archive::unpack(&archive, &config.index_path, &root, force).map(|report| {
+                        let manifest = &report.manifest;
+                        println!(
+                            "Unpacked {} symbols from {} files into {}",
+                            manifest.symbol_count,
+                            manifest.file_count,
+                            config.index_path.display()
+                        );
+                        if report.rewritten_paths > 0 {
+                            println!(
+                                "  Rewrote {} paths from {} to {}",
+                                report.rewritten_paths,
+                                manifest.root.display(),
+                                root.display()
+                            );
+                        }
+                        if manifest.codanna_version != env!("CARGO_PKG_VERSION") {
+                            eprintln!(
+                                "Warning: packed by codanna {}, this is {}",
+                                manifest.codanna_version,
+                                env!("CARGO_PKG_VERSION")
+                            );
+                        }
+                        let local_commit = archive::head_commit(&root);
+                        if manifest.commit.is_some() && manifest.commit != local_commit {
+                            eprintln!(
+                                "Note: indexed at commit {}; run 'codanna index <PATH>' to pick up later changes",
+                                manifest.commit.as_deref().unwrap_or_default()
+                            );
+                        }
+                    })
+                }
+                IndexAction::Gc { json } => {
+                    if !persistence.exists() {
+                        eprintln!("No index found at {}", config.index_path.display());
+                        eprintln!("\nTo create one: codanna index <path>");
+                        std::process::exit(codanna::io::ExitCode::NotFound as i32);
+                    }
+                    persistence
+                        .load_with_settings_lazy(settings.clone(), false, true)
+                        .and_then(|mut indexer| {
+                            let report = gc::collect(&mut indexer)?;
+                            // Refresh the document count recorded in index.meta
+                            persistence.save(&indexer)?;
+                            Ok(report)
+                        })
+                        .map(|report| print_gc_report(&report, json))
+                }
+            };
+            if let Err(e) = result {
+                eprintln!("Error: {e}");
+                let suggestions = e.recovery_suggestions();
+                if !suggestions.is_empty() {
+                    eprintln!("\nSuggestions:");
+                    for suggestion in suggestions {
+                        eprintln!("  • {suggestion}");
+                    }
+                }
+                std::process::exit(codanna::io::ExitCode::from_error(&e) as i32);
+            }
+        }
+
         Commands::Index {
             paths,
             force,
@@ -1830,222 +2348,100 @@ async fn main() {
             }
         }
 
-        Commands::Retrieve { query } => {
-            use codanna::io::OutputFormat;
-            use codanna::retrieve;
-
-            let exit_code = match query {
-                RetrieveQuery::Symbol { args, json } => {
-                    use codanna::io::args::parse_positional_args;
-
-                    // Parse positional arguments for symbol name and key:value pairs
-                    let (positional_name, params) = parse_positional_args(&args);
+        Commands::Stats {
+            unresolved,
+            limit,
+            json,
+        } => {
+            if !persistence.exists() {
+                eprintln!("No index found at {}", config.index_path.display());
+                eprintln!("\nTo create one: codanna index <path>");
+                std::process::exit(codanna::io::ExitCode::NotFound as i32);
+            }
+            let metadata = IndexMetadata::load(&config.index_path).unwrap_or_else(|e| {
+                eprintln!("Error: Failed to read index metadata: {e}");
+                std::process::exit(codanna::io::ExitCode::IndexCorrupted as i32);
+            });
+            print_stats(&metadata, unresolved, limit, json);
+        }
 
-                    // Determine symbol name or symbol_id (priority: positional > key:value)
-                    let final_name = positional_name
-                        .or_else(|| params.get("name").cloned())
-                        .or_else(|| params.get("symbol_id").map(|id| format!("symbol_id:{id}")))
-                        .unwrap_or_else(|| {
-                            eprintln!("Error: symbol requires a name or symbol_id");
-                            eprintln!("Usage: codanna retrieve symbol main");
-                            eprintln!("   or: codanna retrieve symbol name:main");
-                            eprintln!("   or: codanna retrieve symbol symbol_id:1771");
-                            std::process::exit(1);
-                        });
+        Commands::Doctor { fix, json } => {
+            use codanna::indexing::doctor;
 
-                    // Extract language filter
-                    let language = params.get("lang").map(|s| s.as_str());
+            if !persistence.exists() {
+                eprintln!("No index found at {}", config.index_path.display());
+                eprintln!("\nTo create one: codanna index <path>");
+                std::process::exit(codanna::io::ExitCode::NotFound as i32);
+            }
+            // Load without the startup recovery and sync so the diagnosis sees
+            // the index exactly as it is on disk
+            let mut indexer = persistence
+                .load_with_settings_lazy(settings.clone(), false, !fix)
+                .unwrap_or_else(|e| {
+                    eprintln!("Error: Failed to load index: {e}");
+                    eprintln!(
+                        "\nThe index cannot be repaired; rebuild it with: codanna index --force"
+                    );
+                    std::process::exit(codanna::io::ExitCode::IndexCorrupted as i32);
+                });
 
-                    let format = OutputFormat::from_json_flag(json);
-                    retrieve::retrieve_symbol(&indexer, &final_name, language, format)
+            let check = |indexer: &SimpleIndexer| {
+                doctor::check(indexer).unwrap_or_else(|e| {
+                    eprintln!("Error: {e}");
+                    std::process::exit(codanna::io::ExitCode::IndexCorrupted as i32);
+                })
+            };
+            let mut report = check(&indexer);
+
+            // A repair can expose the next problem (removing a deleted file
+            // leaves relationships to its symbols), so check again after each pass
+            let mut passes = 0;
+            while fix && !report.is_healthy() && passes < doctor::MAX_REPAIR_PASSES {
+                passes += 1;
+                match doctor::repair(&mut indexer, &report) {
+                    Ok(done) => {
+                        if !json {
+                            for line in &done {
+                                println!("Repair: {line}");
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("Error: Repair failed: {e}");
+                        std::process::exit(codanna::io::ExitCode::IndexCorrupted as i32);
+                    }
+                }
+                if let Err(e) = persistence.save(&indexer) {
+                    eprintln!("Error: Failed to save index: {e}");
+                    std::process::exit(codanna::io::ExitCode::IndexCorrupted as i32);
                 }
-                RetrieveQuery::Callers { args, json } => {
-                    use codanna::io::args::parse_positional_args;
+                report = check(&indexer);
+            }
+            if passes > 0 && !json {
+                println!();
+            }
 
-                    // Parse positional arguments for function name and key:value pairs
-                    let (positional_function, params) = parse_positional_args(&args);
+            print_doctor_report(&report, fix, json);
+            if !report.is_healthy() {
+                std::process::exit(codanna::io::ExitCode::IndexCorrupted as i32);
+            }
+        }
 
-                    // Determine function name or symbol_id (priority: positional > key:value)
-                    let final_function = positional_function
-                        .or_else(|| params.get("function").cloned())
-                        .or_else(|| params.get("symbol_id").map(|id| format!("symbol_id:{id}")))
-                        .unwrap_or_else(|| {
-                            eprintln!("Error: callers requires a function name or symbol_id");
-                            eprintln!("Usage: codanna retrieve callers main");
-                            eprintln!("   or: codanna retrieve callers function:main");
-                            eprintln!("   or: codanna retrieve callers symbol_id:1771");
-                            std::process::exit(1);
-                        });
-
-                    // Extract language filter
-                    let language = params.get("lang").map(|s| s.as_str());
-
-                    let format = OutputFormat::from_json_flag(json);
-                    retrieve::retrieve_callers(&indexer, &final_function, language, format)
-                }
-                RetrieveQuery::Calls { args, json } => {
-                    use codanna::io::args::parse_positional_args;
-
-                    // Parse positional arguments for function name and key:value pairs
-                    let (positional_function, params) = parse_positional_args(&args);
-
-                    // Determine function name or symbol_id (priority: positional > key:value)
-                    let final_function = positional_function
-                        .or_else(|| params.get("function").cloned())
-                        .or_else(|| params.get("symbol_id").map(|id| format!("symbol_id:{id}")))
-                        .unwrap_or_else(|| {
-                            eprintln!("Error: calls requires a function name or symbol_id");
-                            eprintln!("Usage: codanna retrieve calls process_file");
-                            eprintln!("   or: codanna retrieve calls function:process_file");
-                            eprintln!("   or: codanna retrieve calls symbol_id:1771");
-                            std::process::exit(1);
-                        });
-
-                    // Extract language filter
-                    let language = params.get("lang").map(|s| s.as_str());
-
-                    let format = OutputFormat::from_json_flag(json);
-                    retrieve::retrieve_calls(&indexer, &final_function, language, format)
-                }
-                RetrieveQuery::Implementations { args, json } => {
-                    use codanna::io::args::parse_positional_args;
-
-                    // Parse positional arguments for trait name and key:value pairs
-                    let (positional_trait, params) = parse_positional_args(&args);
-
-                    // Determine trait name (priority: positional > key:value)
-                    let final_trait = positional_trait
-                        .or_else(|| params.get("trait").cloned())
-                        .unwrap_or_else(|| {
-                            eprintln!("Error: implementations requires a trait name");
-                            eprintln!("Usage: codanna retrieve implementations Parser");
-                            eprintln!("   or: codanna retrieve implementations trait:Parser");
-                            std::process::exit(1);
-                        });
-
-                    // Extract language filter
-                    let language = params.get("lang").map(|s| s.as_str());
-
-                    let format = OutputFormat::from_json_flag(json);
-                    retrieve::retrieve_implementations(&indexer, &final_trait, language, format)
-                }
-                RetrieveQuery::Search {
-                    args,
-                    limit,
-                    json,
-                    kind,
-                    module,
-                } => {
-                    use codanna::io::args::parse_positional_args;
-
-                    // Parse positional arguments for query and key:value pairs
-                    let (positional_query, params) = parse_positional_args(&args);
-
-                    // Determine query source (priority: positional > key:value)
-                    let final_query = positional_query
-                        .or_else(|| params.get("query").cloned())
-                        .unwrap_or_else(|| {
-                            eprintln!("Error: search requires a query");
-                            eprintln!("Usage: codanna retrieve search \"query\" [options]");
-                            eprintln!(
-                                "   or: codanna retrieve search query:\"search text\" [options]"
-                            );
-                            std::process::exit(1);
-                        });
-
-                    // Merge parameters (flags take precedence over key:value)
-                    let final_limit = limit.unwrap_or_else(|| {
-                        params
-                            .get("limit")
-                            .and_then(|s| s.parse::<usize>().ok())
-                            .unwrap_or(10)
-                    });
-
-                    let final_kind = kind.or_else(|| params.get("kind").cloned());
-                    let final_module = module.or_else(|| params.get("module").cloned());
-
-                    // Extract language filter
-                    let language = params.get("lang").map(|s| s.as_str());
-
-                    // Call retrieve function with merged parameters
-                    let format = OutputFormat::from_json_flag(json);
-                    retrieve::retrieve_search(
-                        &indexer,
-                        &final_query,
-                        final_limit,
-                        final_kind.as_deref(),
-                        final_module.as_deref(),
-                        language,
-                        format,
-                    )
-                }
-                // DISABLED: Impact command handler commented out
-                // See the RetrieveQuery enum for deprecation details
-                // RetrieveQuery::Impact { args, depth, json } => {
-                //     use codanna::io::args::parse_positional_args;
-                //
-                //     // Parse positional arguments for symbol name and key:value pairs
-                //     let (positional_symbol, params) = parse_positional_args(&args);
-                //
-                //     // Determine symbol name (priority: positional > key:value)
-                //     let final_symbol = positional_symbol
-                //         .or_else(|| params.get("symbol").cloned())
-                //         .unwrap_or_else(|| {
-                //             eprintln!("Error: impact requires a symbol name");
-                //             eprintln!("Usage: codanna retrieve impact MyStruct");
-                //             eprintln!("   or: codanna retrieve impact symbol:MyStruct depth:3");
-                //             std::process::exit(1);
-                //         });
-                //
-                //     // Merge depth parameter (flags take precedence over key:value)
-                //     let final_depth = depth.unwrap_or_else(|| {
-                //         params
-                //             .get("depth")
-                //             .and_then(|s| s.parse::<usize>().ok())
-                //             .unwrap_or(5)
-                //     });
-                //
-                //     let format = OutputFormat::from_json_flag(json);
-                //     retrieve::retrieve_impact(&indexer, &final_symbol, final_depth, format)
-                // }
-                RetrieveQuery::Describe { args, json } => {
-                    use codanna::io::args::parse_positional_args;
-
-                    // Parse positional arguments for symbol name and key:value pairs
-                    let (positional_symbol, params) = parse_positional_args(&args);
-
-                    // Determine symbol name or symbol_id (priority: positional > key:value)
-                    let final_symbol = positional_symbol
-                        .or_else(|| params.get("symbol").cloned())
-                        .or_else(|| params.get("symbol_id").map(|id| format!("symbol_id:{id}")))
-                        .unwrap_or_else(|| {
-                            eprintln!("Error: describe requires a symbol name or symbol_id");
-                            eprintln!("Usage: codanna retrieve describe SimpleIndexer");
-                            eprintln!("   or: codanna retrieve describe symbol:SimpleIndexer");
-                            eprintln!("   or: codanna retrieve describe symbol_id:1771");
-                            std::process::exit(1);
-                        });
-
-                    // Extract language filter
-                    let language = params.get("lang").map(|s| s.as_str());
-
-                    let format = OutputFormat::from_json_flag(json);
-                    retrieve::retrieve_describe(&indexer, &final_symbol, language, format)
-                }
-                RetrieveQuery::Uses { symbol } => {
-                    eprintln!("'retrieve uses' command not yet implemented for: {symbol}");
-                    codanna::io::ExitCode::GeneralError
-                }
-                RetrieveQuery::Defines { symbol } => {
-                    eprintln!("'retrieve defines' command not yet implemented for: {symbol}");
-                    codanna::io::ExitCode::GeneralError
-                }
-                RetrieveQuery::Dependencies { symbol } => {
-                    eprintln!("'retrieve dependencies' command not yet implemented for: {symbol}");
-                    codanna::io::ExitCode::GeneralError
-                }
-            };
+        Commands::Daemon { action } => {
+            #[cfg(unix)]
+            run_daemon_command(action, settings.clone(), cli.config.as_deref());
+            #[cfg(not(unix))]
+            {
+                let _ = action;
+                eprintln!(
+                    "Error: the query daemon needs Unix domain sockets, which this platform lacks"
+                );
+                std::process::exit(1);
+            }
+        }
 
+        Commands::Retrieve { query } => {
+            let exit_code = run_retrieve(query, &indexer);
             std::process::exit(exit_code as i32);
         }
 
@@ -2104,6 +2500,39 @@ async fn main() {
                 Some(serde_json::Map::new())
             };
 
+            // Positions contain ':' and would otherwise be parsed as key:value pairs
+            let mut positional = positional;
+            if tool == "find_symbol_at" {
+                if let (Some(index), Some(ref mut args_map)) = (
+                    positional.iter().position(|arg| {
+                        codanna::symbol::position::SourcePosition::parse(arg).is_some()
+                    }),
+                    arguments.as_mut(),
+                ) {
+                    let position = positional.remove(index);
+                    args_map.insert("position".to_string(), serde_json::Value::String(position));
+                }
+            }
+            if tool == "find_routes" {
+                if let Some(ref mut args_map) = arguments {
+                    positional.retain(|arg| {
+                        if arg.starts_with('/') {
+                            args_map
+                                .insert("path".to_string(), serde_json::Value::String(arg.clone()));
+                            false
+                        } else if codanna::parsing::route::http_method(arg).is_some() {
+                            args_map.insert(
+                                "method".to_string(),
+                                serde_json::Value::String(arg.clone()),
+                            );
+                            false
+                        } else {
+                            true
+                        }
+                    });
+                }
+            }
+
             // Process positional arguments using unified parser
             if !positional.is_empty() {
                 if let Some(ref mut args_map) = arguments {
@@ -2143,6 +2572,30 @@ async fn main() {
                                     serde_json::Value::String(pos_arg.clone()),
                                 );
                             }
+                            "get_symbol_source" => {
+                                args_map.insert(
+                                    "symbol_name".to_string(),
+                                    serde_json::Value::String(pos_arg.clone()),
+                                );
+                            }
+                            "get_file_outline" => {
+                                args_map.insert(
+                                    "file_path".to_string(),
+                                    serde_json::Value::String(pos_arg.clone()),
+                                );
+                            }
+                            "find_tests" => {
+                                args_map.insert(
+                                    "symbol_name".to_string(),
+                                    serde_json::Value::String(pos_arg.clone()),
+                                );
+                            }
+                            "find_routes" => {
+                                args_map.insert(
+                                    "path".to_string(),
+                                    serde_json::Value::String(pos_arg.clone()),
+                                );
+                            }
                             _ => {
                                 eprintln!(
                                     "Warning: Unknown tool '{tool}', ignoring positional argument"
@@ -2171,6 +2624,21 @@ async fn main() {
                 }
             }
 
+            // Stack traces are multi-line, so they are read from stdin unless given in --args
+            if tool == "resolve_stack_trace" {
+                if let Some(ref mut args_map) = arguments {
+                    if !args_map.contains_key("trace") {
+                        use std::io::Read;
+                        let mut trace = String::new();
+                        if let Err(e) = std::io::stdin().read_to_string(&mut trace) {
+                            eprintln!("Error: Failed to read stack trace from stdin: {e}");
+                            std::process::exit(1);
+                        }
+                        args_map.insert("trace".to_string(), serde_json::Value::String(trace));
+                    }
+                }
+            }
+
             // Convert to Option<Map> only if we have arguments
             let arguments = arguments.filter(|map| !map.is_empty());
 
@@ -2229,11 +2697,7 @@ async fn main() {
 
             // Collect data for get_calls if JSON output is requested
             let get_calls_data = if json && tool == "get_calls" {
-                let symbol_id = arguments
-                    .as_ref()
-                    .and_then(|m| m.get("symbol_id"))
-                    .and_then(|v| v.as_u64())
-                    .map(|id| id as u32);
+                let symbol_id = symbol_id_argument(arguments.as_ref());
                 let function_name = arguments
                     .as_ref()
                     .and_then(|m| m.get("function_name"))
@@ -2247,7 +2711,7 @@ async fn main() {
                     use codanna::symbol::context::ContextIncludes;
 
                     // Direct lookup by symbol ID
-                    if let Some(symbol) = indexer.get_symbol(codanna::SymbolId(id)) {
+                    if let Some(symbol) = indexer.get_symbol_by_ref(&id) {
                         let mut all_calls = Vec::new();
 
                         let context = indexer.get_symbol_context(symbol.id, ContextIncludes::CALLS);
@@ -2312,11 +2776,7 @@ async fn main() {
 
             // Collect data for find_callers if JSON output is requested
             let find_callers_data = if json && tool == "find_callers" {
-                let symbol_id = arguments
-                    .as_ref()
-                    .and_then(|m| m.get("symbol_id"))
-                    .and_then(|v| v.as_u64())
-                    .map(|id| id as u32);
+                let symbol_id = symbol_id_argument(arguments.as_ref());
                 let function_name = arguments
                     .as_ref()
                     .and_then(|m| m.get("function_name"))
@@ -2328,7 +2788,7 @@ async fn main() {
 
                 if let Some(id) = symbol_id {
                     // Direct lookup by symbol ID
-                    if let Some(symbol) = indexer.get_symbol(codanna::SymbolId(id)) {
+                    if let Some(symbol) = indexer.get_symbol_by_ref(&id) {
                         let callers = indexer.get_calling_functions_with_metadata(symbol.id);
                         let all_callers: Vec<_> = callers.into_iter().collect();
                         Some(all_callers)
@@ -2368,11 +2828,7 @@ async fn main() {
 
             // Collect data for analyze_impact if JSON output is requested
             let analyze_impact_data = if json && tool == "analyze_impact" {
-                let symbol_id = arguments
-                    .as_ref()
-                    .and_then(|m| m.get("symbol_id"))
-                    .and_then(|v| v.as_u64())
-                    .map(|id| id as u32);
+                let symbol_id = symbol_id_argument(arguments.as_ref());
                 let symbol_name = arguments
                     .as_ref()
                     .and_then(|m| m.get("symbol_name"))
@@ -2384,7 +2840,7 @@ async fn main() {
 
                 if let Some(id) = symbol_id {
                     // Direct lookup by symbol ID
-                    if let Some(symbol) = indexer.get_symbol(codanna::SymbolId(id)) {
+                    if let Some(symbol) = indexer.get_symbol_by_ref(&id) {
                         let max_depth = arguments
                             .as_ref()
                             .and_then(|m| m.get("max_depth"))
@@ -2447,10 +2903,15 @@ async fn main() {
 
             // Collect data for search_symbols if JSON output is requested
             let search_symbols_data = if json && tool == "search_symbols" {
+                let annotation = arguments
+                    .as_ref()
+                    .and_then(|m| m.get("annotation"))
+                    .and_then(|v| v.as_str());
                 let query = arguments
                     .as_ref()
                     .and_then(|m| m.get("query"))
-                    .and_then(|v| v.as_str());
+                    .and_then(|v| v.as_str())
+                    .or(annotation.map(|_| ""));
 
                 if let Some(q) = query {
                     let limit = arguments
@@ -2481,10 +2942,22 @@ async fn main() {
                         "field" => Some(crate::SymbolKind::Field),
                         "module" => Some(crate::SymbolKind::Module),
                         "constant" => Some(crate::SymbolKind::Constant),
+                        "route" => Some(crate::SymbolKind::Route),
                         _ => None,
                     });
 
-                    match indexer.search(q, limit as usize, kind_filter, module, language) {
+                    let results = match annotation {
+                        Some(annotation) => indexer.search_with_annotation(
+                            q,
+                            limit as usize,
+                            kind_filter,
+                            module,
+                            language,
+                            annotation,
+                        ),
+                        None => indexer.search(q, limit as usize, kind_filter, module, language),
+                    };
+                    match results {
                         Ok(results) => Some(results),
                         Err(_) => Some(Vec::new()),
                     }
@@ -2641,6 +3114,127 @@ async fn main() {
             // Check semantic search status before moving indexer
             let has_semantic_search = indexer.has_semantic_search();
 
+            // If we need JSON output for resolve_stack_trace, collect data before moving indexer
+            let stack_trace_data = if json && tool == "resolve_stack_trace" {
+                let map = arguments.as_ref();
+                let max_callers = map
+                    .and_then(|m| m.get("max_callers"))
+                    .and_then(|v| v.as_u64().or_else(|| v.as_str()?.parse().ok()))
+                    .unwrap_or(5) as usize;
+                let trace = map
+                    .and_then(|m| m.get("trace"))
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default();
+                Some(indexer.resolve_stack_trace(
+                    codanna::symbol::trace::parse_stack_trace(trace),
+                    max_callers,
+                ))
+            } else {
+                None
+            };
+
+            // If we need JSON output for find_symbol_at, collect data before moving indexer
+            let symbol_at_data = if json && tool == "find_symbol_at" {
+                let map = arguments.as_ref();
+                let include_edges = map
+                    .and_then(|m| m.get("include_edges"))
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(true);
+                map.and_then(|m| m.get("position"))
+                    .and_then(|v| v.as_str())
+                    .and_then(codanna::symbol::position::SourcePosition::parse)
+                    .and_then(|position| indexer.find_symbol_at(&position, include_edges))
+            } else {
+                None
+            };
+
+            // If we need JSON output for find_tests, collect data before moving indexer
+            let tests_data = if json && tool == "find_tests" {
+                let map = arguments.as_ref();
+                let max_depth = map
+                    .and_then(|m| m.get("max_depth"))
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(5) as usize;
+                let symbols = if let Some(id) = symbol_id_argument(map) {
+                    indexer.get_symbol_by_ref(&id).into_iter().collect()
+                } else {
+                    map.and_then(|m| m.get("symbol_name"))
+                        .and_then(|v| v.as_str())
+                        .map(|name| indexer.find_symbols_by_name(name, None))
+                        .unwrap_or_default()
+                };
+                Some(
+                    symbols
+                        .iter()
+                        .filter_map(|symbol| indexer.find_tests_for(symbol.id, max_depth))
+                        .collect::<Vec<_>>(),
+                )
+            } else {
+                None
+            };
+
+            // If we need JSON output for find_routes, collect data before moving indexer
+            let routes_data = if json && tool == "find_routes" {
+                let field = |key: &str| {
+                    arguments
+                        .as_ref()
+                        .and_then(|m| m.get(key))
+                        .and_then(|v| v.as_str())
+                };
+                Some(indexer.find_routes(field("method"), field("path"), field("lang")))
+            } else {
+                None
+            };
+
+            // If we need JSON output for get_file_outline, collect data before moving indexer
+            let file_outline_data = if json && tool == "get_file_outline" {
+                arguments
+                    .as_ref()
+                    .and_then(|m| m.get("file_path"))
+                    .and_then(|v| v.as_str())
+                    .and_then(|path| indexer.get_file_outline(path))
+            } else {
+                None
+            };
+
+            // If we need JSON output for get_symbol_source, collect data before moving indexer
+            let symbol_source_data = if json && tool == "get_symbol_source" {
+                use codanna::symbol::source::{SourceOptions, read_source};
+
+                let map = arguments.as_ref();
+                let lang = map.and_then(|m| m.get("lang")).and_then(|v| v.as_str());
+                let mut symbols: Vec<_> = symbol_ids_argument(map)
+                    .into_iter()
+                    .filter_map(|id| indexer.get_symbol_by_ref(&id))
+                    .collect();
+                if let Some(name) = map
+                    .and_then(|m| m.get("symbol_name"))
+                    .and_then(|v| v.as_str())
+                {
+                    symbols.extend(indexer.find_symbols_by_name(name, lang));
+                }
+
+                let options = SourceOptions {
+                    context_lines: map
+                        .and_then(|m| m.get("context_lines"))
+                        .and_then(|v| v.as_u64())
+                        .unwrap_or(0) as usize,
+                    include_doc: map
+                        .and_then(|m| m.get("include_doc"))
+                        .and_then(|v| v.as_bool())
+                        .unwrap_or(true),
+                };
+                let workspace_root = indexer.settings().workspace_root.clone();
+                Some(
+                    symbols
+                        .iter()
+                        .filter_map(|sym| read_source(sym, workspace_root.as_deref(), options).ok())
+                        .collect::<Vec<_>>(),
+                )
+            } else {
+                None
+            };
+
             // If we need JSON output for get_index_info, collect data before moving indexer
             let index_info_data = if json && tool == "get_index_info" {
                 let symbol_count = indexer.symbol_count();
@@ -2731,11 +3325,7 @@ async fn main() {
                         .and_then(|v| v.as_str())
                         .map(|s| s.to_string());
 
-                    let symbol_id = arguments
-                        .as_ref()
-                        .and_then(|m| m.get("symbol_id"))
-                        .and_then(|v| v.as_u64())
-                        .map(|id| id as u32);
+                    let symbol_id = symbol_id_argument(arguments.as_ref());
 
                     // Require either function_name or symbol_id
                     if function_name.is_none() && symbol_id.is_none() {
@@ -2759,11 +3349,7 @@ async fn main() {
                         .and_then(|v| v.as_str())
                         .map(|s| s.to_string());
 
-                    let symbol_id = arguments
-                        .as_ref()
-                        .and_then(|m| m.get("symbol_id"))
-                        .and_then(|v| v.as_u64())
-                        .map(|id| id as u32);
+                    let symbol_id = symbol_id_argument(arguments.as_ref());
 
                     // Require either function_name or symbol_id
                     if function_name.is_none() && symbol_id.is_none() {
@@ -2787,11 +3373,7 @@ async fn main() {
                         .and_then(|v| v.as_str())
                         .map(|s| s.to_string());
 
-                    let symbol_id = arguments
-                        .as_ref()
-                        .and_then(|m| m.get("symbol_id"))
-                        .and_then(|v| v.as_u64())
-                        .map(|id| id as u32);
+                    let symbol_id = symbol_id_argument(arguments.as_ref());
 
                     // Require either symbol_name or symbol_id
                     if symbol_name.is_none() && symbol_id.is_none() {
@@ -2822,12 +3404,20 @@ async fn main() {
                         .await
                 }
                 "search_symbols" => {
+                    let annotation = arguments
+                        .as_ref()
+                        .and_then(|m| m.get("annotation"))
+                        .and_then(|v| v.as_str())
+                        .map(|s| s.to_string());
                     let query = arguments
                         .as_ref()
                         .and_then(|m| m.get("query"))
                         .and_then(|v| v.as_str())
+                        .or(annotation.as_ref().map(|_| ""))
                         .unwrap_or_else(|| {
-                            eprintln!("Error: search_symbols requires 'query' parameter");
+                            eprintln!(
+                                "Error: search_symbols requires 'query' or 'annotation' parameter"
+                            );
                             std::process::exit(1);
                         });
                     let limit = arguments
@@ -2857,6 +3447,7 @@ async fn main() {
                             kind,
                             module,
                             lang,
+                            annotation,
                         }))
                         .await
                 }
@@ -2930,6 +3521,149 @@ async fn main() {
                         ))
                         .await
                 }
+                "find_tests" => {
+                    let symbol_name = arguments
+                        .as_ref()
+                        .and_then(|m| m.get("symbol_name"))
+                        .and_then(|v| v.as_str())
+                        .map(|s| s.to_string());
+
+                    let symbol_id = symbol_id_argument(arguments.as_ref());
+
+                    if symbol_name.is_none() && symbol_id.is_none() {
+                        eprintln!(
+                            "Error: find_tests requires either 'symbol_name' or 'symbol_id' parameter"
+                        );
+                        std::process::exit(1);
+                    }
+
+                    let max_depth = arguments
+                        .as_ref()
+                        .and_then(|m| m.get("max_depth"))
+                        .and_then(|v| v.as_u64())
+                        .unwrap_or(5) as u32;
+                    server
+                        .find_tests(Parameters(FindTestsRequest {
+                            symbol_name,
+                            symbol_id,
+                            max_depth,
+                        }))
+                        .await
+                }
+                "find_routes" => {
+                    let field = |key: &str| {
+                        arguments
+                            .as_ref()
+                            .and_then(|m| m.get(key))
+                            .and_then(|v| v.as_str())
+                            .map(|s| s.to_string())
+                    };
+                    server
+                        .find_routes(Parameters(FindRoutesRequest {
+                            method: field("method"),
+                            path: field("path"),
+                            lang: field("lang"),
+                        }))
+                        .await
+                }
+                "resolve_stack_trace" => {
+                    let trace = arguments
+                        .as_ref()
+                        .and_then(|m| m.get("trace"))
+                        .and_then(|v| v.as_str())
+                        .unwrap_or_default();
+                    let max_callers = arguments
+                        .as_ref()
+                        .and_then(|m| m.get("max_callers"))
+                        .and_then(|v| v.as_u64().or_else(|| v.as_str()?.parse().ok()))
+                        .unwrap_or(5) as u32;
+                    server
+                        .resolve_stack_trace(Parameters(ResolveStackTraceRequest {
+                            trace: trace.to_string(),
+                            max_callers,
+                        }))
+                        .await
+                }
+                "find_symbol_at" => {
+                    let position = arguments
+                        .as_ref()
+                        .and_then(|m| m.get("position"))
+                        .and_then(|v| v.as_str())
+                        .unwrap_or_else(|| {
+                            eprintln!(
+                                "Error: find_symbol_at requires 'position' parameter (path:line[:column])"
+                            );
+                            std::process::exit(1);
+                        });
+                    let include_edges = arguments
+                        .as_ref()
+                        .and_then(|m| m.get("include_edges"))
+                        .and_then(|v| v.as_bool())
+                        .unwrap_or(true);
+                    server
+                        .find_symbol_at(Parameters(FindSymbolAtRequest {
+                            position: position.to_string(),
+                            include_edges,
+                        }))
+                        .await
+                }
+                "get_file_outline" => {
+                    let file_path = arguments
+                        .as_ref()
+                        .and_then(|m| m.get("file_path"))
+                        .and_then(|v| v.as_str())
+                        .unwrap_or_else(|| {
+                            eprintln!("Error: get_file_outline requires 'file_path' parameter");
+                            std::process::exit(1);
+                        });
+                    server
+                        .get_file_outline(Parameters(GetFileOutlineRequest {
+                            file_path: file_path.to_string(),
+                        }))
+                        .await
+                }
+                "get_symbol_source" => {
+                    let symbol_name = arguments
+                        .as_ref()
+                        .and_then(|m| m.get("symbol_name"))
+                        .and_then(|v| v.as_str())
+                        .map(|s| s.to_string());
+
+                    let symbol_ids = symbol_ids_argument(arguments.as_ref());
+
+                    // Require either symbol_name or symbol_ids
+                    if symbol_name.is_none() && symbol_ids.is_empty() {
+                        eprintln!(
+                            "Error: get_symbol_source requires either 'symbol_name' or 'symbol_ids' parameter"
+                        );
+                        std::process::exit(1);
+                    }
+
+                    let context_lines = arguments
+                        .as_ref()
+                        .and_then(|m| m.get("context_lines"))
+                        .and_then(|v| v.as_u64())
+                        .unwrap_or(0) as u32;
+                    let include_doc = arguments
+                        .as_ref()
+                        .and_then(|m| m.get("include_doc"))
+                        .and_then(|v| v.as_bool())
+                        .unwrap_or(true);
+                    let lang = arguments
+                        .as_ref()
+                        .and_then(|m| m.get("lang"))
+                        .and_then(|v| v.as_str())
+                        .map(|s| s.to_string());
+                    server
+                        .get_symbol_source(Parameters(GetSymbolSourceRequest {
+                            symbol_name,
+                            symbol_ids,
+                            context_lines,
+                            include_doc,
+                            lang,
+                        }))
+                        .await
+                }
                 _ => {
                     if json {
                         use codanna::io::exit_code::ExitCode;
@@ -2938,14 +3672,14 @@ async fn main() {
                             ExitCode::GeneralError,
                             &format!("Unknown tool: {tool}"),
                             vec![
-                                "Available tools: find_symbol, get_calls, find_callers, analyze_impact, get_index_info, search_symbols, semantic_search_docs, semantic_search_with_context",
+                                "Available tools: find_symbol, get_calls, find_callers, analyze_impact, get_index_info, get_symbol_source, get_file_outline, find_symbol_at, resolve_stack_trace, find_tests, find_routes, search_symbols, semantic_search_docs, semantic_search_with_context",
                             ],
                         );
                         println!("{}", serde_json::to_string_pretty(&response).unwrap());
                     } else {
                         eprintln!("Unknown tool: {tool}");
                         eprintln!(
-                            "Available tools: find_symbol, get_calls, find_callers, analyze_impact, get_index_info, search_symbols, semantic_search_docs, semantic_search_with_context"
+                            "Available tools: find_symbol, get_calls, find_callers, analyze_impact, get_index_info, get_symbol_source, get_file_outline, find_symbol_at, resolve_stack_trace, find_tests, find_routes, search_symbols, semantic_search_docs, semantic_search_with_context"
                         );
                     }
                     std::process::exit(1);
@@ -3035,10 +3769,7 @@ async fn main() {
                         } else {
                             // Function not found
                             use codanna::io::format::JsonResponse;
-                            let response = if let Some(id) = arguments
-                                .as_ref()
-                                .and_then(|m| m.get("symbol_id"))
-                                .and_then(|v| v.as_u64())
+                            let response = if let Some(id) = symbol_id_argument(arguments.as_ref())
                             {
                                 JsonResponse::not_found("Symbol", &format!("symbol_id:{id}"))
                             } else {
@@ -3077,10 +3808,7 @@ async fn main() {
                         } else {
                             // Function not found
                             use codanna::io::format::JsonResponse;
-                            let response = if let Some(id) = arguments
-                                .as_ref()
-                                .and_then(|m| m.get("symbol_id"))
-                                .and_then(|v| v.as_u64())
+                            let response = if let Some(id) = symbol_id_argument(arguments.as_ref())
                             {
                                 JsonResponse::not_found("Symbol", &format!("symbol_id:{id}"))
                             } else {
@@ -3100,20 +3828,17 @@ async fn main() {
                             use codanna::io::format::JsonResponse;
                             if impacted.is_empty() {
                                 // No symbols would be impacted
-                                let identifier = if let Some(id) = arguments
-                                    .as_ref()
-                                    .and_then(|m| m.get("symbol_id"))
-                                    .and_then(|v| v.as_u64())
-                                {
-                                    format!("symbol_id:{id}")
-                                } else {
-                                    arguments
-                                        .as_ref()
-                                        .and_then(|m| m.get("symbol_name"))
-                                        .and_then(|v| v.as_str())
-                                        .unwrap_or("unknown")
-                                        .to_string()
-                                };
+                                let identifier =
+                                    if let Some(id) = symbol_id_argument(arguments.as_ref()) {
+                                        format!("symbol_id:{id}")
+                                    } else {
+                                        arguments
+                                            .as_ref()
+                                            .and_then(|m| m.get("symbol_name"))
+                                            .and_then(|v| v.as_str())
+                                            .unwrap_or("unknown")
+                                            .to_string()
+                                    };
                                 use codanna::io::guidance_engine::generate_guidance_from_config;
 
                                 // Create a proper struct for the empty case
@@ -3171,10 +3896,7 @@ async fn main() {
                         } else {
                             // Symbol not found
                             use codanna::io::format::JsonResponse;
-                            let response = if let Some(id) = arguments
-                                .as_ref()
-                                .and_then(|m| m.get("symbol_id"))
-                                .and_then(|v| v.as_u64())
+                            let response = if let Some(id) = symbol_id_argument(arguments.as_ref())
                             {
                                 JsonResponse::not_found("Symbol", &format!("symbol_id:{id}"))
                             } else {
@@ -3432,26 +4154,135 @@ async fn main() {
                             println!("{}", serde_json::to_string_pretty(&response).unwrap());
                             std::process::exit(1);
                         }
-                    } else {
-                        // Default text output
-                        for content in &call_result.content {
-                            match &**content {
-                                rmcp::model::RawContent::Text(text_content) => {
-                                    println!("{}", text_content.text);
-                                }
-                                _ => {
-                                    eprintln!("Warning: Non-text content returned");
-                                }
-                            }
-                        }
-                    }
-                }
-                Err(e) => {
-                    if json {
+                    } else if json && tool == "resolve_stack_trace" {
+                        // Use pre-collected data for JSON output
                         use codanna::io::exit_code::ExitCode;
                         use codanna::io::format::JsonResponse;
-                        let response = JsonResponse::error(
-                            ExitCode::GeneralError,
+                        let frames = stack_trace_data.unwrap_or_default();
+                        if frames.iter().any(|f| f.in_index) {
+                            let response = JsonResponse::success(frames);
+                            println!("{}", serde_json::to_string_pretty(&response).unwrap());
+                        } else {
+                            let response = JsonResponse::error(
+                                ExitCode::NotFound,
+                                "No stack frame maps to an indexed symbol",
+                                vec![
+                                    "Check that the trace comes from this workspace and the index is up to date",
+                                ],
+                            );
+                            println!("{}", serde_json::to_string_pretty(&response).unwrap());
+                            std::process::exit(ExitCode::NotFound as i32);
+                        }
+                    } else if json && tool == "find_tests" {
+                        // Use pre-collected data for JSON output
+                        use codanna::io::exit_code::ExitCode;
+                        use codanna::io::format::JsonResponse;
+                        let reports = tests_data.unwrap_or_default();
+                        if reports.iter().any(|r| !r.tests.is_empty()) {
+                            let response = JsonResponse::success(reports);
+                            println!("{}", serde_json::to_string_pretty(&response).unwrap());
+                        } else {
+                            let response = JsonResponse::error(
+                                ExitCode::NotFound,
+                                "No tests reach the symbol",
+                                vec!["Increase max_depth or check the symbol with find_callers"],
+                            );
+                            println!("{}", serde_json::to_string_pretty(&response).unwrap());
+                            std::process::exit(ExitCode::NotFound as i32);
+                        }
+                    } else if json && tool == "find_routes" {
+                        // Use pre-collected data for JSON output
+                        use codanna::io::exit_code::ExitCode;
+                        use codanna::io::format::JsonResponse;
+                        match routes_data {
+                            Some(table) if !table.routes.is_empty() => {
+                                let response = JsonResponse::success(table);
+                                println!("{}", serde_json::to_string_pretty(&response).unwrap());
+                            }
+                            _ => {
+                                let response = JsonResponse::error(
+                                    ExitCode::NotFound,
+                                    "No routes match",
+                                    vec!["Drop the method or path filter to list every route"],
+                                );
+                                println!("{}", serde_json::to_string_pretty(&response).unwrap());
+                                std::process::exit(ExitCode::NotFound as i32);
+                            }
+                        }
+                    } else if json && tool == "find_symbol_at" {
+                        // Use pre-collected data for JSON output
+                        use codanna::io::exit_code::ExitCode;
+                        use codanna::io::format::JsonResponse;
+                        if let Some(found) = symbol_at_data {
+                            let response = JsonResponse::success(found);
+                            println!("{}", serde_json::to_string_pretty(&response).unwrap());
+                        } else {
+                            let response = JsonResponse::error(
+                                ExitCode::NotFound,
+                                "No indexed symbol at position",
+                                vec![
+                                    "Use path:line[:column] with a path relative to the workspace root",
+                                ],
+                            );
+                            println!("{}", serde_json::to_string_pretty(&response).unwrap());
+                            std::process::exit(ExitCode::NotFound as i32);
+                        }
+                    } else if json && tool == "get_file_outline" {
+                        // Use pre-collected data for JSON output
+                        use codanna::io::exit_code::ExitCode;
+                        use codanna::io::format::JsonResponse;
+                        if let Some(outline) = file_outline_data {
+                            let response = JsonResponse::success(outline);
+                            println!("{}", serde_json::to_string_pretty(&response).unwrap());
+                        } else {
+                            let response = JsonResponse::error(
+                                ExitCode::NotFound,
+                                "File not indexed",
+                                vec!["Use a path relative to the workspace root"],
+                            );
+                            println!("{}", serde_json::to_string_pretty(&response).unwrap());
+                            std::process::exit(ExitCode::NotFound as i32);
+                        }
+                    } else if json && tool == "get_symbol_source" {
+                        // Use pre-collected data for JSON output
+                        if let Some(sources) = symbol_source_data {
+                            use codanna::io::format::JsonResponse;
+                            use codanna::io::guidance_engine::generate_guidance_from_config;
+                            let result_count = sources.len();
+                            let mut response = JsonResponse::success(sources);
+
+                            // Add system guidance
+                            if let Some(guidance) = generate_guidance_from_config(
+                                &guidance_config,
+                                "get_symbol_source",
+                                None,
+                                result_count,
+                            ) {
+                                response = response.with_system_message(&guidance);
+                            }
+
+                            println!("{}", serde_json::to_string_pretty(&response).unwrap());
+                        }
+                    } else {
+                        // Default text output
+                        for content in &call_result.content {
+                            match &**content {
+                                rmcp::model::RawContent::Text(text_content) => {
+                                    println!("{}", text_content.text);
+                                }
+                                _ => {
+                                    eprintln!("Warning: Non-text content returned");
+                                }
+                            }
+                        }
+                    }
+                }
+                Err(e) => {
+                    if json {
+                        use codanna::io::exit_code::ExitCode;
+                        use codanna::io::format::JsonResponse;
+                        let response = JsonResponse::error(
+                            ExitCode::GeneralError,
                             &e.message,
                             vec!["Check the tool name and arguments"],
                         );
@@ -3465,6 +4296,84 @@ async fn main() {
             }
         }
 
+        Commands::Impact {
+            diff,
+            git,
+            depth,
+            json,
+        } => {
+            use codanna::io::{ExitCode, OutputFormat};
+            use std::io::Read;
+
+            let diff_text = match (diff, git) {
+                (Some(Some(path)), _) if path != Path::new("-") => std::fs::read_to_string(&path),
+                (Some(_), _) => {
+                    let mut diff_text = String::new();
+                    std::io::stdin()
+                        .read_to_string(&mut diff_text)
+                        .map(|_| diff_text)
+                }
+                (None, Some(rev)) => {
+                    // --relative keeps paths relative to the workspace, matching the index
+                    let mut command = std::process::Command::new("git");
+                    command.args(["diff", "--relative"]).args(rev);
+                    if let Some(root) = &config.workspace_root {
+                        command.current_dir(root);
+                    }
+                    command.output().and_then(|out| {
+                        if out.status.success() {
+                            Ok(String::from_utf8_lossy(&out.stdout).into_owned())
+                        } else {
+                            Err(std::io::Error::other(
+                                String::from_utf8_lossy(&out.stderr).trim().to_string(),
+                            ))
+                        }
+                    })
+                }
+                (None, None) => {
+                    eprintln!("Error: impact requires --diff or --git");
+                    eprintln!("Usage: git diff main | codanna impact --diff");
+                    eprintln!("   or: codanna impact --git main");
+                    std::process::exit(ExitCode::GeneralError as i32);
+                }
+            };
+            let diff_text = diff_text.unwrap_or_else(|e| {
+                eprintln!("Error: Failed to read diff: {e}");
+                std::process::exit(ExitCode::IoError as i32);
+            });
+
+            let format = OutputFormat::from_json_flag(json);
+            let exit_code =
+                codanna::retrieve::retrieve_diff_impact(&indexer, &diff_text, depth, format);
+            std::process::exit(exit_code as i32);
+        }
+
+        Commands::Trace {
+            file,
+            max_callers,
+            json,
+        } => {
+            use codanna::io::{ExitCode, OutputFormat};
+            use std::io::Read;
+
+            let trace = match file.as_deref() {
+                Some(path) if path != Path::new("-") => std::fs::read_to_string(path),
+                _ => {
+                    let mut trace = String::new();
+                    std::io::stdin().read_to_string(&mut trace).map(|_| trace)
+                }
+            };
+            let trace = trace.unwrap_or_else(|e| {
+                eprintln!("Error: Failed to read stack trace: {e}");
+                std::process::exit(ExitCode::IoError as i32);
+            });
+
+            let format = OutputFormat::from_json_flag(json);
+            let exit_code =
+                codanna::retrieve::retrieve_trace(&indexer, &trace, max_callers, format);
+            std::process::exit(exit_code as i32);
+        }
+
         Commands::Benchmark { language, file } => {
             run_benchmark_command(&language, file);
         }
@@ -3536,6 +4445,97 @@ async fn main() {
             }
         }
 
+        Commands::Deps { action } => {
+            use codanna::indexing::dependencies::{self, Ecosystem};
+
+            match action {
+                DepsAction::Index {
+                    ecosystems,
+                    progress,
+                    dry_run,
+                } => {
+                    let names = if ecosystems.is_empty() {
+                        config.dependencies.ecosystems.clone()
+                    } else {
+                        ecosystems
+                    };
+                    let mut selected = Vec::new();
+                    for name in &names {
+                        match Ecosystem::from_name(name) {
+                            Some(ecosystem) if !selected.contains(&ecosystem) => {
+                                selected.push(ecosystem)
+                            }
+                            Some(_) => {}
+                            None => {
+                                eprintln!(
+                                    "Error: unknown ecosystem '{name}' (expected cargo, npm, python or go)"
+                                );
+                                std::process::exit(1);
+                            }
+                        }
+                    }
+
+                    let root = config
+                        .workspace_root
+                        .clone()
+                        .unwrap_or_else(|| std::env::current_dir().unwrap_or_default());
+                    let sources = dependencies::discover(&root, &selected);
+
+                    if dry_run || sources.is_empty() {
+                        println!("Found {} dependencies with local sources:", sources.len());
+                        for source in &sources {
+                            println!(
+                                "  {:<7} {} {}  {}",
+                                source.ecosystem,
+                                source.name,
+                                source.version,
+                                source.path.display()
+                            );
+                        }
+                        return;
+                    }
+
+                    println!("Indexing {} dependencies...", sources.len());
+                    match dependencies::build_layer(&config, &sources, progress) {
+                        Ok(stats) => {
+                            println!(
+                                "Indexed {} files, {} symbols into {}",
+                                stats.files_indexed,
+                                stats.symbols_found,
+                                dependencies::layer_path(&config).display()
+                            );
+                            if stats.files_failed > 0 {
+                                println!("  {} files failed", stats.files_failed);
+                            }
+                        }
+                        Err(e) => {
+                            eprintln!("Error: failed to index dependencies: {e}");
+                            std::process::exit(1);
+                        }
+                    }
+                }
+                DepsAction::List => match dependencies::DependencyLayer::open(&config) {
+                    Some(layer) => {
+                        println!(
+                            "Dependency layer: {} dependencies, {} symbols",
+                            layer.sources().len(),
+                            layer.symbol_count()
+                        );
+                        for source in layer.sources() {
+                            println!(
+                                "  {:<7} {} {}",
+                                source.ecosystem, source.name, source.version
+                            );
+                        }
+                    }
+                    None => {
+                        println!("No dependency layer found.");
+                        println!("\nTo build one: codanna deps index");
+                    }
+                },
+            }
+        }
+
         Commands::Profile { action } => {
             // Execute profile management command
             use codanna::profiles;
@@ -3780,6 +4780,710 @@ mod add_paths_tests {
         drop(settings); // ensure no unused warnings
     }
 }
+/// Print `codanna stats` output from the index metadata
+/// Take the index write lock for a command that writes the index
+///
+/// Waits up to `indexing.lock_timeout_secs` for another writer to finish and
+/// exits with an error when it does not.
+fn lock_index_for_writing(config: &Settings) -> IndexWriteLock {
+    let timeout = std::time::Duration::from_secs(config.indexing.lock_timeout_secs);
+    match IndexWriteLock::try_acquire(&config.index_path) {
+        Ok(Some(lock)) => return lock,
+        Ok(None) if !timeout.is_zero() => {
+            let holder = IndexWriteLock::holder(&config.index_path)
+                .unwrap_or_else(|| "unknown process".to_string());
+            eprintln!(
+                "Waiting up to {}s for another codanna process to finish writing the index ({holder})",
+                timeout.as_secs()
+            );
+        }
+        _ => {}
+    }
+    IndexWriteLock::acquire(&config.index_path, timeout).unwrap_or_else(|e| {
+        eprintln!("Error: {e}");
+        eprintln!("\nRecovery steps:");
+        for suggestion in e.recovery_suggestions() {
+            eprintln!("  • {suggestion}");
+        }
+        std::process::exit(codanna::io::ExitCode::from_error(&e) as i32);
+    })
+}
+
+/// Take the index write lock for a startup repair that can wait for a later command
+///
+/// Returns false, leaving the index as the other writer last committed it,
+/// when another process is writing.
+fn try_lock_index(write_lock: &mut Option<IndexWriteLock>, config: &Settings) -> bool {
+    if write_lock.is_some() {
+        return true;
+    }
+    match IndexWriteLock::try_acquire(&config.index_path) {
+        Ok(Some(lock)) => {
+            *write_lock = Some(lock);
+            true
+        }
+        Ok(None) => {
+            let holder = IndexWriteLock::holder(&config.index_path)
+                .unwrap_or_else(|| "unknown process".to_string());
+            eprintln!(
+                "Note: another codanna process is writing the index ({holder}); using its last committed state"
+            );
+            false
+        }
+        Err(e) => {
+            eprintln!("Warning: Could not lock the index for writing: {e}");
+            false
+        }
+    }
+}
+
+/// Run a retrieve query through the daemon and exit, if one answers
+///
+/// Otherwise the caller loads the index itself. With `[daemon] enabled`, a
+/// daemon is started in the background for the queries that follow.
+#[cfg(unix)]
+fn forward_to_daemon(config: &Settings, config_file: Option<&std::path::Path>) {
+    use codanna::daemon;
+
+    let socket = daemon::socket_path(&config.index_path);
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(exit_code) = daemon::forward_query(&socket, &args) {
+        std::process::exit(exit_code);
+    }
+    if config.daemon.enabled && daemon::status(&socket).is_none() {
+        if let Err(e) = daemon::spawn(config_file) {
+            eprintln!("Warning: Could not start the query daemon: {e}");
+        }
+    }
+}
+
+/// Handle `codanna daemon` subcommands
+#[cfg(unix)]
+fn run_daemon_command(
+    action: DaemonAction,
+    settings: Arc<Settings>,
+    config_file: Option<&std::path::Path>,
+) {
+    use codanna::daemon::{self, Request, Response};
+    use std::time::{Duration, Instant};
+
+    let socket = daemon::socket_path(&settings.index_path);
+    match action {
+        DaemonAction::Start => {
+            if let Some(status) = daemon::status(&socket) {
+                println!("Daemon already running (pid {})", status.pid);
+                return;
+            }
+            if let Err(e) = daemon::spawn(config_file) {
+                eprintln!("Error: Could not start the query daemon: {e}");
+                std::process::exit(1);
+            }
+            // Loading a large index with embeddings takes a while
+            let started = Instant::now();
+            while started.elapsed() < Duration::from_secs(60) {
+                if let Some(status) = daemon::status(&socket) {
+                    match status.symbols {
+                        Some(symbols) => println!(
+                            "Daemon started (pid {}, {symbols} symbols loaded)",
+                            status.pid
+                        ),
+                        None => println!("Daemon started (pid {}, no index yet)", status.pid),
+                    }
+                    return;
+                }
+                std::thread::sleep(Duration::from_millis(100));
+            }
+            eprintln!(
+                "Error: Daemon did not answer on {}; run `codanna daemon run` to see why",
+                socket.display()
+            );
+            std::process::exit(1);
+        }
+        DaemonAction::Stop => {
+            let stop = daemon::request(&socket, &Request::Stop, Some(Duration::from_secs(5)));
+            if matches!(stop, Ok(Response::Stopping)) {
+                println!("Daemon stopped");
+            } else {
+                println!("No daemon running");
+            }
+        }
+        DaemonAction::Status => {
+            let Some(status) = daemon::status(&socket) else {
+                println!("No daemon running");
+                std::process::exit(1);
+            };
+            println!(
+                "Daemon running (pid {}, codanna {})",
+                status.pid, status.version
+            );
+            println!("  Socket:   {}", socket.display());
+            println!("  Index:    {}", status.index_path.display());
+            match status.symbols {
+                Some(symbols) => println!("  Symbols:  {symbols}"),
+                None => println!("  Symbols:  no index yet"),
+            }
+            println!("  Requests: {}", status.requests);
+            println!("  Uptime:   {}s", status.uptime_secs);
+            if status.idle_timeout_secs > 0 {
+                println!(
+                    "  Exits after {}s without queries",
+                    status.idle_timeout_secs
+                );
+            }
+        }
+        DaemonAction::Run => {
+            let settings_file = config_file
+                .map(std::path::Path::to_path_buf)
+                .or_else(Settings::find_workspace_config);
+            eprintln!("Serving queries on {}", socket.display());
+            let served = daemon::serve(settings, settings_file, |indexer, args| {
+                let argv = std::iter::once("codanna".to_string()).chain(args.iter().cloned());
+                match Cli::try_parse_from(argv) {
+                    Ok(Cli {
+                        command: Commands::Retrieve { query },
+                        ..
+                    }) => run_retrieve(query, indexer) as i32,
+                    Ok(_) => {
+                        eprintln!("Error: the daemon only answers retrieve queries");
+                        codanna::io::ExitCode::GeneralError as i32
+                    }
+                    Err(e) => {
+                        let _ = e.print();
+                        e.exit_code()
+                    }
+                }
+            });
+            match served {
+                Ok(true) => {}
+                Ok(false) => eprintln!("Another daemon already serves {}", socket.display()),
+                Err(e) => {
+                    eprintln!("Error: {}: {e}", socket.display());
+                    std::process::exit(1);
+                }
+            }
+        }
+    }
+}
+
+fn print_doctor_report(report: &codanna::indexing::doctor::DoctorReport, fixed: bool, json: bool) {
+    use codanna::indexing::doctor::CheckStatus;
+
+    if json {
+        let mut value = serde_json::to_value(report).unwrap();
+        value["healthy"] = report.is_healthy().into();
+        println!("{}", serde_json::to_string_pretty(&value).unwrap());
+        return;
+    }
+
+    for check in &report.checks {
+        let mark = match check.status {
+            CheckStatus::Ok => "✓",
+            CheckStatus::Problem => "✗",
+            CheckStatus::Skipped => "-",
+        };
+        println!("{mark} {:<14} {}", check.name, check.detail);
+    }
+
+    if report.is_healthy() {
+        println!("\nIndex is healthy");
+        return;
+    }
+
+    println!("\nSuggested repairs:");
+    for repair in report.repairs() {
+        println!("  - {}", repair.as_str());
+    }
+    if !fixed {
+        println!("\nTo apply them: codanna doctor --fix");
+    }
+}
+
+fn print_gc_report(report: &codanna::indexing::gc::GcReport, json: bool) {
+    if json {
+        let mut value = serde_json::to_value(report).unwrap();
+        value["reclaimed"] = report.reclaimed().into();
+        println!("{}", serde_json::to_string_pretty(&value).unwrap());
+        return;
+    }
+
+    for store in &report.stores {
+        println!(
+            "{:<13} {:>10} -> {:>10}  reclaimed {:>10}  {}",
+            store.store,
+            format_bytes(store.bytes_before),
+            format_bytes(store.bytes_after),
+            format_bytes(store.reclaimed()),
+            store.detail
+        );
+    }
+    println!("\nReclaimed {} in total", format_bytes(report.reclaimed()));
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+fn print_stats(metadata: &IndexMetadata, unresolved: bool, limit: usize, json: bool) {
+    use codanna::indexing::unresolved::UnresolvedCounts;
+
+    let summary = &metadata.unresolved;
+    let totals = summary.totals();
+
+    if json {
+        let counts = |counts: &UnresolvedCounts| {
+            serde_json::json!({
+                "total": counts.total(),
+                "not_found": counts.not_found,
+                "ambiguous": counts.ambiguous,
+                "external": counts.external,
+            })
+        };
+        let mut value = serde_json::json!({
+            "symbols": metadata.symbol_count,
+            "files": metadata.file_count,
+            "unresolved": counts(&totals),
+        });
+        if unresolved {
+            let languages: serde_json::Map<String, serde_json::Value> = summary
+                .by_language()
+                .iter()
+                .map(|(language, language_counts)| (language.to_string(), counts(language_counts)))
+                .collect();
+            let files: Vec<serde_json::Value> = summary
+                .top_files(limit)
+                .into_iter()
+                .map(|(path, file)| {
+                    let mut entry = counts(&file.counts);
+                    entry["path"] = path.into();
+                    entry["language"] = file.language.clone().into();
+                    entry
+                })
+                .collect();
+            value["languages"] = languages.into();
+            value["top_files"] = files.into();
+        }
+        println!("{}", serde_json::to_string_pretty(&value).unwrap());
+        return;
+    }
+
+    println!("Symbols: {}", metadata.symbol_count);
+    println!("Files:   {}", metadata.file_count);
+    println!(
+        "Unresolved references: {} ({} not found, {} ambiguous, {} external)",
+        totals.total(),
+        totals.not_found,
+        totals.ambiguous,
+        totals.external
+    );
+    if !unresolved {
+        if totals.total() > 0 {
+            println!("\nFor a breakdown: codanna stats --unresolved");
+        }
+        return;
+    }
+    if summary.is_empty() {
+        println!("\nNo unresolved references recorded. Re-index to collect them.");
+        return;
+    }
+
+    println!("\nBy language:");
+    println!(
+        "  {:<12} {:>8} {:>10} {:>10} {:>9}",
+        "LANGUAGE", "TOTAL", "NOT FOUND", "AMBIGUOUS", "EXTERNAL"
+    );
+    for (language, counts) in summary.by_language() {
+        println!(
+            "  {:<12} {:>8} {:>10} {:>10} {:>9}",
+            language,
+            counts.total(),
+            counts.not_found,
+            counts.ambiguous,
+            counts.external
+        );
+    }
+
+    let files = summary.top_files(limit);
+    println!("\nBy file (top {}):", files.len());
+    println!(
+        "  {:>8} {:>10} {:>10} {:>9}  PATH",
+        "TOTAL", "NOT FOUND", "AMBIGUOUS", "EXTERNAL"
+    );
+    for (path, file) in files {
+        println!(
+            "  {:>8} {:>10} {:>10} {:>9}  {}",
+            file.counts.total(),
+            file.counts.not_found,
+            file.counts.ambiguous,
+            file.counts.external,
+            path
+        );
+    }
+}
+
+/// Run a `retrieve` query against a loaded index
+fn run_retrieve(query: RetrieveQuery, indexer: &SimpleIndexer) -> codanna::io::ExitCode {
+    use codanna::io::{ExitCode, OutputFormat};
+    use codanna::retrieve;
+
+    match query {
+        RetrieveQuery::Symbol { args, json } => {
+            use codanna::io::args::parse_positional_args;
+
+            // Parse positional arguments for symbol name and key:value pairs
+            let (positional_name, params) = parse_positional_args(&args);
+
+            // Determine symbol name or symbol_id (priority: positional > key:value)
+            let Some(final_name) = positional_name
+                .or_else(|| params.get("name").cloned())
+                .or_else(|| params.get("symbol_id").map(|id| format!("symbol_id:{id}")))
+            else {
+                eprintln!("Error: symbol requires a name or symbol_id");
+                eprintln!("Usage: codanna retrieve symbol main");
+                eprintln!("   or: codanna retrieve symbol name:main");
+                eprintln!("   or: codanna retrieve symbol symbol_id:1771");
+                return ExitCode::GeneralError;
+            };
+
+            // Extract language filter
+            let language = params.get("lang").map(|s| s.as_str());
+
+            let format = OutputFormat::from_json_flag(json);
+            retrieve::retrieve_symbol(indexer, &final_name, language, format)
+        }
+        RetrieveQuery::Callers { args, json } => {
+            use codanna::io::args::parse_positional_args;
+
+            // Parse positional arguments for function name and key:value pairs
+            let (positional_function, params) = parse_positional_args(&args);
+
+            // Determine function name or symbol_id (priority: positional > key:value)
+            let Some(final_function) = positional_function
+                .or_else(|| params.get("function").cloned())
+                .or_else(|| params.get("symbol_id").map(|id| format!("symbol_id:{id}")))
+            else {
+                eprintln!("Error: callers requires a function name or symbol_id");
+                eprintln!("Usage: codanna retrieve callers main");
+                eprintln!("   or: codanna retrieve callers function:main");
+                eprintln!("   or: codanna retrieve callers symbol_id:1771");
+                return ExitCode::GeneralError;
+            };
+
+            // Extract language filter
+            let language = params.get("lang").map(|s| s.as_str());
+
+            let format = OutputFormat::from_json_flag(json);
+            retrieve::retrieve_callers(indexer, &final_function, language, format)
+        }
+        RetrieveQuery::Calls { args, json } => {
+            use codanna::io::args::parse_positional_args;
+
+            // Parse positional arguments for function name and key:value pairs
+            let (positional_function, params) = parse_positional_args(&args);
+
+            // Determine function name or symbol_id (priority: positional > key:value)
+            let Some(final_function) = positional_function
+                .or_else(|| params.get("function").cloned())
+                .or_else(|| params.get("symbol_id").map(|id| format!("symbol_id:{id}")))
+            else {
+                eprintln!("Error: calls requires a function name or symbol_id");
+                eprintln!("Usage: codanna retrieve calls process_file");
+                eprintln!("   or: codanna retrieve calls function:process_file");
+                eprintln!("   or: codanna retrieve calls symbol_id:1771");
+                return ExitCode::GeneralError;
+            };
+
+            // Extract language filter
+            let language = params.get("lang").map(|s| s.as_str());
+
+            let format = OutputFormat::from_json_flag(json);
+            retrieve::retrieve_calls(indexer, &final_function, language, format)
+        }
+        RetrieveQuery::Implementations { args, json } => {
+            use codanna::io::args::parse_positional_args;
+
+            // Parse positional arguments for trait name and key:value pairs
+            let (positional_trait, params) = parse_positional_args(&args);
+
+            // Determine trait name (priority: positional > key:value)
+            let Some(final_trait) = positional_trait.or_else(|| params.get("trait").cloned())
+            else {
+                eprintln!("Error: implementations requires a trait name");
+                eprintln!("Usage: codanna retrieve implementations Parser");
+                eprintln!("   or: codanna retrieve implementations trait:Parser");
+                return ExitCode::GeneralError;
+            };
+
+            // Extract language filter
+            let language = params.get("lang").map(|s| s.as_str());
+
+            let format = OutputFormat::from_json_flag(json);
+            retrieve::retrieve_implementations(indexer, &final_trait, language, format)
+        }
+        RetrieveQuery::Search {
+            args,
+            limit,
+            json,
+            kind,
+            module,
+        } => {
+            use codanna::io::args::parse_positional_args;
+
+            // Parse positional arguments for query and key:value pairs
+            let (positional_query, params) = parse_positional_args(&args);
+
+            // An annotation filter can stand in for the query
+            let annotation = params.get("annotation").map(|s| s.as_str());
+
+            // Determine query source (priority: positional > key:value)
+            let Some(final_query) = positional_query
+                .or_else(|| params.get("query").cloned())
+                .or_else(|| annotation.map(|_| String::new()))
+            else {
+                eprintln!("Error: search requires a query");
+                eprintln!("Usage: codanna retrieve search \"query\" [options]");
+                eprintln!("   or: codanna retrieve search query:\"search text\" [options]");
+                eprintln!("   or: codanna retrieve search annotation:route [options]");
+                return ExitCode::GeneralError;
+            };
+
+            // Merge parameters (flags take precedence over key:value)
+            let final_limit = limit.unwrap_or_else(|| {
+                params
+                    .get("limit")
+                    .and_then(|s| s.parse::<usize>().ok())
+                    .unwrap_or(10)
+            });
+
+            let final_kind = kind.or_else(|| params.get("kind").cloned());
+            let final_module = module.or_else(|| params.get("module").cloned());
+
+            // Extract language filter
+            let language = params.get("lang").map(|s| s.as_str());
+
+            // Call retrieve function with merged parameters
+            let format = OutputFormat::from_json_flag(json);
+            retrieve::retrieve_search(
+                indexer,
+                &final_query,
+                final_limit,
+                final_kind.as_deref(),
+                final_module.as_deref(),
+                language,
+                annotation,
+                format,
+            )
+        }
+        // DISABLED: Impact command handler commented out
+        // See the RetrieveQuery enum for deprecation details
+        // RetrieveQuery::Impact { args, depth, json } => {
+        //     use codanna::io::args::parse_positional_args;
+        //
+        //     // Parse positional arguments for symbol name and key:value pairs
+        //     let (positional_symbol, params) = parse_positional_args(&args);
+        //
+        //     // Determine symbol name (priority: positional > key:value)
+        //     let final_symbol = positional_symbol
+        //         .or_else(|| params.get("symbol").cloned())
+        //         .unwrap_or_else(|| {
+        //             eprintln!("Error: impact requires a symbol name");
+        //             eprintln!("Usage: codanna retrieve impact MyStruct");
+        //             eprintln!("   or: codanna retrieve impact symbol:MyStruct depth:3");
+        //             return ExitCode::GeneralError;
+        //         });
+        //
+        //     // Merge depth parameter (flags take precedence over key:value)
+        //     let final_depth = depth.unwrap_or_else(|| {
+        //         params
+        //             .get("depth")
+        //             .and_then(|s| s.parse::<usize>().ok())
+        //             .unwrap_or(5)
+        //     });
+        //
+        //     let format = OutputFormat::from_json_flag(json);
+        //     retrieve::retrieve_impact(indexer, &final_symbol, final_depth, format)
+        // }
+        RetrieveQuery::Describe { args, json } => {
+            use codanna::io::args::parse_positional_args;
+
+            // Parse positional arguments for symbol name and key:value pairs
+            let (positional_symbol, params) = parse_positional_args(&args);
+
+            // Determine symbol name or symbol_id (priority: positional > key:value)
+            let Some(final_symbol) = positional_symbol
+                .or_else(|| params.get("symbol").cloned())
+                .or_else(|| params.get("symbol_id").map(|id| format!("symbol_id:{id}")))
+            else {
+                eprintln!("Error: describe requires a symbol name or symbol_id");
+                eprintln!("Usage: codanna retrieve describe SimpleIndexer");
+                eprintln!("   or: codanna retrieve describe symbol:SimpleIndexer");
+                eprintln!("   or: codanna retrieve describe symbol_id:1771");
+                return ExitCode::GeneralError;
+            };
+
+            // Extract language filter
+            let language = params.get("lang").map(|s| s.as_str());
+
+            let format = OutputFormat::from_json_flag(json);
+            retrieve::retrieve_describe(indexer, &final_symbol, language, format)
+        }
+        RetrieveQuery::Source { args, json } => {
+            use codanna::io::args::parse_positional_args;
+            use codanna::symbol::source::SourceOptions;
+
+            // Parse positional arguments for symbol name and key:value pairs
+            let (positional_symbol, params) = parse_positional_args(&args);
+
+            // Collect symbol names and comma-separated symbol_ids
+            let mut queries: Vec<String> = positional_symbol
+                .or_else(|| params.get("symbol").cloned())
+                .into_iter()
+                .collect();
+            if let Some(ids) = params.get("symbol_id") {
+                queries.extend(
+                    ids.split(',')
+                        .map(str::trim)
+                        .filter(|id| !id.is_empty())
+                        .map(|id| format!("symbol_id:{id}")),
+                );
+            }
+            if queries.is_empty() {
+                eprintln!("Error: source requires a symbol name or symbol_id");
+                eprintln!("Usage: codanna retrieve source parse_file");
+                eprintln!("   or: codanna retrieve source symbol_id:1771");
+                eprintln!("   or: codanna retrieve source symbol_id:1771,1772 context:3");
+                return ExitCode::GeneralError;
+            }
+
+            let options = SourceOptions {
+                context_lines: params
+                    .get("context")
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(0),
+                include_doc: params.get("doc").is_none_or(|s| s != "false"),
+            };
+
+            // Extract language filter
+            let language = params.get("lang").map(|s| s.as_str());
+
+            let format = OutputFormat::from_json_flag(json);
+            retrieve::retrieve_source(indexer, &queries, language, options, format)
+        }
+        RetrieveQuery::Outline { args, json } => {
+            use codanna::io::args::parse_positional_args;
+
+            // Parse positional arguments for file path and key:value pairs
+            let (positional_file, params) = parse_positional_args(&args);
+
+            let Some(file_path) = positional_file.or_else(|| params.get("file").cloned()) else {
+                eprintln!("Error: outline requires a file path");
+                eprintln!("Usage: codanna retrieve outline src/main.rs");
+                eprintln!("   or: codanna retrieve outline file:src/main.rs");
+                return ExitCode::GeneralError;
+            };
+
+            let format = OutputFormat::from_json_flag(json);
+            retrieve::retrieve_outline(indexer, &file_path, format)
+        }
+        RetrieveQuery::At { args, json } => {
+            use codanna::io::args::parse_positional_args;
+            use codanna::symbol::position::SourcePosition;
+
+            // Positions contain ':' so they are split off before key:value parsing
+            let (positions, rest): (Vec<String>, Vec<String>) = args
+                .into_iter()
+                .partition(|arg| SourcePosition::parse(arg).is_some());
+            let (_, params) = parse_positional_args(&rest);
+
+            let Some(position) = positions.first().and_then(|p| SourcePosition::parse(p)) else {
+                eprintln!("Error: at requires a position as path:line[:column]");
+                eprintln!("Usage: codanna retrieve at src/main.rs:120");
+                eprintln!("   or: codanna retrieve at src/main.rs:120:8");
+                return ExitCode::GeneralError;
+            };
+            let include_edges = params.get("edges").is_none_or(|s| s != "false");
+
+            let format = OutputFormat::from_json_flag(json);
+            retrieve::retrieve_at(indexer, &position, include_edges, format)
+        }
+        RetrieveQuery::Tests { args, json } => {
+            use codanna::io::args::parse_positional_args;
+
+            let (positional_symbol, params) = parse_positional_args(&args);
+
+            let Some(query) = positional_symbol
+                .or_else(|| params.get("symbol").cloned())
+                .or_else(|| params.get("symbol_id").map(|id| format!("symbol_id:{id}")))
+            else {
+                eprintln!("Error: tests requires a symbol name or symbol_id");
+                eprintln!("Usage: codanna retrieve tests parse_file");
+                eprintln!("   or: codanna retrieve tests symbol_id:1771 depth:3");
+                return ExitCode::GeneralError;
+            };
+            let language = params.get("lang").map(|s| s.as_str());
+            let depth = params
+                .get("depth")
+                .and_then(|d| d.parse().ok())
+                .unwrap_or(5);
+
+            let format = OutputFormat::from_json_flag(json);
+            retrieve::retrieve_tests(indexer, &query, language, depth, format)
+        }
+        RetrieveQuery::Routes { args, json } => {
+            use codanna::parsing::route::http_method;
+
+            // Paths may contain ':' (`/orders/:id`), so only known keys are split off
+            let mut method = None;
+            let mut path = None;
+            let mut language = None;
+            for arg in &args {
+                if let Some(value) = arg.strip_prefix("method:") {
+                    method = Some(value.to_string());
+                } else if let Some(value) = arg.strip_prefix("path:") {
+                    path = Some(value.to_string());
+                } else if let Some(value) = arg.strip_prefix("lang:") {
+                    language = Some(value.to_string());
+                } else if method.is_none() && http_method(arg).is_some() {
+                    method = Some(arg.to_uppercase());
+                } else {
+                    path = Some(arg.clone());
+                }
+            }
+
+            let format = OutputFormat::from_json_flag(json);
+            retrieve::retrieve_routes(
+                indexer,
+                method.as_deref(),
+                path.as_deref(),
+                language.as_deref(),
+                format,
+            )
+        }
+        RetrieveQuery::Uses { symbol } => {
+            eprintln!("'retrieve uses' command not yet implemented for: {symbol}");
+            ExitCode::GeneralError
+        }
+        RetrieveQuery::Defines { symbol } => {
+            eprintln!("'retrieve defines' command not yet implemented for: {symbol}");
+            ExitCode::GeneralError
+        }
+        RetrieveQuery::Dependencies { symbol } => {
+            eprintln!("'retrieve dependencies' command not yet implemented for: {symbol}");
+            ExitCode::GeneralError
+        }
+    }
+}
+
 /// Run parser performance benchmarks
 fn run_benchmark_command(language: &str, custom_file: Option<PathBuf>) {
     use codanna::display::theme::Theme;
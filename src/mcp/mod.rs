@@ -28,6 +28,8 @@ pub mod client;
 pub mod http_server;
 pub mod https_server;
 pub mod notifications;
+pub mod prompts;
+pub mod resources;
 pub mod watcher;
 
 use rmcp::{
@@ -43,7 +45,7 @@ use serde_json;
 use std::sync::Arc;
 use tokio::sync::{Mutex, RwLock};
 
-use crate::{Settings, SimpleIndexer, Symbol};
+use crate::{Settings, SimpleIndexer, Symbol, SymbolRef};
 
 /// Generate guidance for MCP tool responses
 fn generate_mcp_guidance(settings: &Settings, tool: &str, result_count: usize) -> Option<String> {
@@ -77,6 +79,24 @@ pub fn format_relative_time(timestamp: u64) -> String {
     }
 }
 
+/// `symbol_id` arguments take the numeric ID or the stable ID of a symbol
+impl schemars::JsonSchema for SymbolRef {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "SymbolRef".into()
+    }
+
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn json_schema(_generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "type": ["integer", "string"],
+            "description": "Numeric symbol_id, or a stable ID such as rust.4f1c9e0a7b3d2c58 that survives reindexing"
+        })
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
 pub struct FindSymbolRequest {
     /// Name of the symbol to find
@@ -91,9 +111,9 @@ pub struct GetCallsRequest {
     /// Name of the function to analyze (use symbol_id for unambiguous lookup)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub function_name: Option<String>,
-    /// Symbol ID for direct lookup (recommended to avoid ambiguity)
+    /// Symbol ID or stable ID for direct lookup (recommended to avoid ambiguity)
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub symbol_id: Option<u32>,
+    pub symbol_id: Option<SymbolRef>,
 }
 
 #[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
@@ -101,9 +121,9 @@ pub struct FindCallersRequest {
     /// Name of the function to find callers for (use symbol_id for unambiguous lookup)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub function_name: Option<String>,
-    /// Symbol ID for direct lookup (recommended to avoid ambiguity)
+    /// Symbol ID or stable ID for direct lookup (recommended to avoid ambiguity)
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub symbol_id: Option<u32>,
+    pub symbol_id: Option<SymbolRef>,
 }
 
 #[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
@@ -111,9 +131,9 @@ pub struct AnalyzeImpactRequest {
     /// Name of the symbol to analyze impact for (use symbol_id for unambiguous lookup)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub symbol_name: Option<String>,
-    /// Symbol ID for direct lookup (recommended to avoid ambiguity)
+    /// Symbol ID or stable ID for direct lookup (recommended to avoid ambiguity)
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub symbol_id: Option<u32>,
+    pub symbol_id: Option<SymbolRef>,
     /// Maximum depth to search (default: 3)
     #[serde(default = "default_depth")]
     pub max_depth: u32,
@@ -121,7 +141,8 @@ pub struct AnalyzeImpactRequest {
 
 #[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
 pub struct SearchSymbolsRequest {
-    /// Search query (supports fuzzy matching)
+    /// Search query (supports fuzzy matching); may be empty when `annotation` is set
+    #[serde(default)]
     pub query: String,
     /// Maximum number of results (default: 10)
     #[serde(default = "default_limit")]
@@ -135,6 +156,10 @@ pub struct SearchSymbolsRequest {
     /// Filter by programming language (e.g., "rust", "python", "typescript", "php")
     #[serde(skip_serializing_if = "Option::is_none")]
     pub lang: Option<String>,
+    /// Only symbols with this attribute, decorator or annotation, by full name or
+    /// last segment (e.g., "route", "app.route", "Deprecated", "tokio::main")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotation: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
@@ -170,6 +195,75 @@ pub struct SemanticSearchWithContextRequest {
 #[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
 pub struct GetIndexInfoRequest {}
 
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct GetFileOutlineRequest {
+    /// Path of the file (relative to the workspace root, as shown in other results)
+    pub file_path: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct ResolveStackTraceRequest {
+    /// Stack trace text (Python, Rust, Go, Java/Kotlin or Node.js)
+    pub trace: String,
+    /// Maximum callers listed per resolved frame (default: 5)
+    #[serde(default = "default_context_limit")]
+    pub max_callers: u32,
+}
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct FindTestsRequest {
+    /// Name of the symbol to find tests for (every symbol with that name is checked)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol_name: Option<String>,
+    /// Symbol ID or stable ID for direct lookup (recommended to avoid ambiguity)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol_id: Option<SymbolRef>,
+    /// Maximum number of calls between a test and the symbol (default: 5)
+    #[serde(default = "default_test_depth")]
+    pub max_depth: u32,
+}
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct FindRoutesRequest {
+    /// HTTP method to match, e.g. "POST" (routes accepting any method are included)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    /// Request path such as "/orders/42" (matched against route patterns) or part of a pattern
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Filter by language of the registering file (e.g., "rust", "python", "typescript")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct FindSymbolAtRequest {
+    /// Position as path:line or path:line:column (one-based, e.g. "src/main.rs:120:8")
+    pub position: String,
+    /// Include relationships recorded on that line, such as calls (default: true)
+    #[serde(default = "default_true")]
+    pub include_edges: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct GetSymbolSourceRequest {
+    /// Name of the symbol (use symbol_ids for unambiguous lookup)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol_name: Option<String>,
+    /// Symbol IDs or stable IDs to fetch in a single call
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub symbol_ids: Vec<SymbolRef>,
+    /// Lines of surrounding context before and after the symbol (default: 0)
+    #[serde(default)]
+    pub context_lines: u32,
+    /// Include the doc comment (default: true)
+    #[serde(default = "default_true")]
+    pub include_doc: bool,
+    /// Filter by programming language (e.g., "rust", "python", "typescript", "php")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+}
+
 fn default_depth() -> u32 {
     3
 }
@@ -182,11 +276,20 @@ fn default_context_limit() -> u32 {
     5
 }
 
+fn default_test_depth() -> u32 {
+    5
+}
+
+fn default_true() -> bool {
+    true
+}
+
 #[derive(Clone)]
 pub struct CodeIntelligenceServer {
     pub indexer: Arc<RwLock<SimpleIndexer>>,
     tool_router: ToolRouter<Self>,
     peer: Arc<Mutex<Option<Peer<RoleServer>>>>,
+    subscriptions: resources::ResourceSubscriptions,
 }
 
 #[tool_router]
@@ -196,6 +299,7 @@ impl CodeIntelligenceServer {
             indexer: Arc::new(RwLock::new(indexer)),
             tool_router: Self::tool_router(),
             peer: Arc::new(Mutex::new(None)),
+            subscriptions: resources::ResourceSubscriptions::new(),
         }
     }
 
@@ -205,6 +309,7 @@ impl CodeIntelligenceServer {
             indexer,
             tool_router: Self::tool_router(),
             peer: Arc::new(Mutex::new(None)),
+            subscriptions: resources::ResourceSubscriptions::new(),
         }
     }
 
@@ -215,6 +320,7 @@ impl CodeIntelligenceServer {
             indexer,
             tool_router: Self::tool_router(),
             peer: Arc::new(Mutex::new(None)),
+            subscriptions: resources::ResourceSubscriptions::new(),
         }
     }
 
@@ -223,16 +329,35 @@ impl CodeIntelligenceServer {
         self.indexer.clone()
     }
 
+    /// Directory holding user prompt templates for this workspace
+    async fn prompts_dir(&self) -> std::path::PathBuf {
+        let indexer = self.indexer.read().await;
+        prompts::prompts_dir(indexer.settings().workspace_root.as_deref())
+    }
+
+    /// Subscribed resource URIs that depend on the given source file
+    pub(crate) async fn subscribed_uris_for_file(
+        &self,
+        file_path: &std::path::Path,
+    ) -> Vec<String> {
+        let normalized = self.indexer.read().await.normalize_indexed_path(file_path);
+        self.subscriptions.affected_by_file(&normalized).await
+    }
+
     /// Send a notification when a file is re-indexed
     pub async fn notify_file_reindexed(&self, file_path: &str) {
+        let uris = self
+            .subscribed_uris_for_file(std::path::Path::new(file_path))
+            .await;
+
         let peer_guard = self.peer.lock().await;
         if let Some(peer) = peer_guard.as_ref() {
-            // Send a resource updated notification
-            let _ = peer
-                .notify_resource_updated(ResourceUpdatedNotificationParam {
-                    uri: format!("file://{file_path}"),
-                })
-                .await;
+            // Notify only the resources the client subscribed to
+            for uri in uris {
+                let _ = peer
+                    .notify_resource_updated(ResourceUpdatedNotificationParam { uri })
+                    .await;
+            }
 
             // Also send a logging message for visibility
             let _ = peer
@@ -259,6 +384,43 @@ impl CodeIntelligenceServer {
         let symbols = indexer.find_symbols_by_name(&name, lang.as_deref());
 
         if symbols.is_empty() {
+            // Fall back to library symbols from the dependency layer
+            if let Some(layer) = indexer.dependency_layer() {
+                let found = layer.find_symbols_by_name(&name, lang.as_deref());
+                if !found.is_empty() {
+                    let mut result = format!(
+                        "No project symbol named '{name}'. Found {} symbol(s) in dependencies:\n\n",
+                        found.len()
+                    );
+                    for ctx in &found {
+                        let symbol = &ctx.symbol;
+                        result.push_str(&format!(
+                            "{:?} {} at {}:{}",
+                            symbol.kind,
+                            symbol.name,
+                            ctx.file_path,
+                            symbol.range.start_line + 1
+                        ));
+                        if let Some(source) = layer.source_for_path(&ctx.file_path) {
+                            result.push_str(&format!(
+                                " [{} {} {}]",
+                                source.ecosystem, source.name, source.version
+                            ));
+                        }
+                        result.push('\n');
+                        if let Some(sig) = symbol.as_signature() {
+                            result.push_str(&format!("Signature: {sig}\n"));
+                        }
+                        if let Some(doc) = symbol.as_doc_comment() {
+                            let preview: Vec<&str> = doc.lines().take(3).collect();
+                            result.push_str(&format!("Documentation: {}\n", preview.join(" ")));
+                        }
+                        result.push('\n');
+                    }
+                    return Ok(CallToolResult::success(vec![Content::text(result)]));
+                }
+            }
+
             let mut output = format!("No symbols found with name: {name}");
             // Add guidance for no results
             if let Some(guidance) = generate_mcp_guidance(indexer.settings(), "find_symbol", 0) {
@@ -393,7 +555,7 @@ impl CodeIntelligenceServer {
         // Get the symbol either by ID or by name
         let (symbol, identifier) = if let Some(id) = symbol_id {
             // Direct lookup by symbol ID
-            match indexer.get_symbol(crate::SymbolId(id)) {
+            match indexer.get_symbol_by_ref(&id) {
                 Some(sym) => (sym, format!("symbol_id:{id}")),
                 None => {
                     return Ok(CallToolResult::success(vec![Content::text(format!(
@@ -538,7 +700,7 @@ impl CodeIntelligenceServer {
         // Get the symbol either by ID or by name
         let (symbol, identifier) = if let Some(id) = symbol_id {
             // Direct lookup by symbol ID - UNAMBIGUOUS
-            match indexer.get_symbol(crate::SymbolId(id)) {
+            match indexer.get_symbol_by_ref(&id) {
                 Some(sym) => (sym, format!("symbol_id:{id}")),
                 None => {
                     return Ok(CallToolResult::success(vec![Content::text(format!(
@@ -639,6 +801,16 @@ impl CodeIntelligenceServer {
                 } else {
                     String::new()
                 };
+                // Foreign callers reach Rust through an FFI binding, RPC
+                // callers reach the schema through a generated client
+                let context = meta.context.as_deref().unwrap_or_default();
+                let info = match crate::indexing::ffi::mechanism_in(context) {
+                    Some(mechanism) => format!("{info} (via {mechanism})"),
+                    None if crate::indexing::grpc::role_in(context).is_some() => {
+                        format!("{info} (via grpc client)")
+                    }
+                    None => info,
+                };
 
                 // Use call site line if available, otherwise definition line
                 let line = meta
@@ -690,7 +862,7 @@ impl CodeIntelligenceServer {
         // Get the symbol either by ID or by name
         let (symbol, identifier) = if let Some(id) = symbol_id {
             // Direct lookup by symbol ID - UNAMBIGUOUS
-            match indexer.get_symbol(crate::SymbolId(id)) {
+            match indexer.get_symbol_by_ref(&id) {
                 Some(sym) => (sym, format!("symbol_id:{id}")),
                 None => {
                     return Ok(CallToolResult::success(vec![Content::text(format!(
@@ -810,6 +982,300 @@ impl CodeIntelligenceServer {
         Ok(CallToolResult::success(vec![Content::text(result)]))
     }
 
+    #[tool(
+        description = "Get the exact source code of symbols by symbol_id (batch) or name, optionally with surrounding context lines and doc comments.\n\nUse instead of reading whole files after find_symbol or search_symbols."
+    )]
+    pub async fn get_symbol_source(
+        &self,
+        Parameters(GetSymbolSourceRequest {
+            symbol_name,
+            symbol_ids,
+            context_lines,
+            include_doc,
+            lang,
+        }): Parameters<GetSymbolSourceRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        use crate::symbol::source::{SourceOptions, read_source};
+
+        let indexer = self.indexer.read().await;
+
+        let mut symbols = Vec::new();
+        let mut missing = Vec::new();
+        for id in &symbol_ids {
+            match indexer.get_symbol_by_ref(id) {
+                Some(sym) => symbols.push(sym),
+                None => missing.push(format!("symbol_id:{id}")),
+            }
+        }
+
+        if let Some(name) = symbol_name {
+            let matches = indexer.find_symbols_by_name(&name, lang.as_deref());
+            match matches.len() {
+                0 => missing.push(name),
+                1 => symbols.extend(matches),
+                _ => {
+                    let mut msg = format!(
+                        "Ambiguous: found {} symbol(s) named '{}':\n",
+                        matches.len(),
+                        name
+                    );
+                    for (i, sym) in matches.iter().take(10).enumerate() {
+                        msg.push_str(&format!(
+                            "  {}. symbol_id:{} - {:?} at {}:{}\n",
+                            i + 1,
+                            sym.id.value(),
+                            sym.kind,
+                            sym.file_path,
+                            sym.range.start_line + 1
+                        ));
+                    }
+                    if matches.len() > 10 {
+                        msg.push_str(&format!("  ... and {} more\n", matches.len() - 10));
+                    }
+                    msg.push_str(
+                        "\nUse: get_symbol_source symbol_ids:[<id>, ...] for specific symbols",
+                    );
+                    return Ok(CallToolResult::success(vec![Content::text(msg)]));
+                }
+            }
+        } else if symbol_ids.is_empty() {
+            return Ok(CallToolResult::success(vec![Content::text(
+                "Error: Either symbol_name or symbol_ids must be provided".to_string(),
+            )]));
+        }
+
+        let options = SourceOptions {
+            context_lines: context_lines as usize,
+            include_doc,
+        };
+        let workspace_root = indexer.settings().workspace_root.as_deref();
+
+        let mut result = String::new();
+        let mut result_count = 0;
+        for symbol in &symbols {
+            match read_source(symbol, workspace_root, options) {
+                Ok(source) => {
+                    result_count += 1;
+                    result.push_str(&source.to_string());
+                    result.push_str("\n\n");
+                }
+                Err(e) => {
+                    result.push_str(&format!(
+                        "Could not read source of {} [symbol_id:{}] from {}: {e}\n\n",
+                        symbol.name,
+                        symbol.id.value(),
+                        symbol.file_path
+                    ));
+                }
+            }
+        }
+
+        if !missing.is_empty() {
+            result.push_str(&format!("Symbol(s) not found: {}\n", missing.join(", ")));
+        }
+
+        // Add system guidance
+        if let Some(guidance) =
+            generate_mcp_guidance(indexer.settings(), "get_symbol_source", result_count)
+        {
+            result.push_str("\n---\n💡 ");
+            result.push_str(&guidance);
+            result.push('\n');
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        description = "Get the nested symbol outline of a file (modules > classes > methods > fields) with line ranges, visibility and symbol_ids.\n\nUse to understand a file's structure without reading it."
+    )]
+    pub async fn get_file_outline(
+        &self,
+        Parameters(GetFileOutlineRequest { file_path }): Parameters<GetFileOutlineRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let indexer = self.indexer.read().await;
+
+        let Some(outline) = indexer.get_file_outline(&file_path) else {
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "File not indexed: {file_path}"
+            ))]));
+        };
+
+        let mut result = outline.format_tree();
+
+        // Add system guidance
+        if let Some(guidance) =
+            generate_mcp_guidance(indexer.settings(), "get_file_outline", outline.symbol_count)
+        {
+            result.push_str("\n---\n💡 ");
+            result.push_str(&guidance);
+            result.push('\n');
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        description = "Find the innermost symbol at a file position (path:line[:column]) from a stack trace, compiler error or review comment, plus the relationships (calls, type uses) recorded on that line."
+    )]
+    pub async fn find_symbol_at(
+        &self,
+        Parameters(FindSymbolAtRequest {
+            position,
+            include_edges,
+        }): Parameters<FindSymbolAtRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        use crate::symbol::position::SourcePosition;
+
+        let Some(parsed) = SourcePosition::parse(&position) else {
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "Error: Invalid position '{position}', expected path:line or path:line:column"
+            ))]));
+        };
+
+        let indexer = self.indexer.read().await;
+        let Some(found) = indexer.find_symbol_at(&parsed, include_edges) else {
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "No indexed symbol at {parsed}"
+            ))]));
+        };
+
+        let mut result = found.to_string();
+        result.push('\n');
+
+        // Add system guidance
+        if let Some(guidance) = generate_mcp_guidance(indexer.settings(), "find_symbol_at", 1) {
+            result.push_str("\n---\n💡 ");
+            result.push_str(&guidance);
+            result.push('\n');
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        description = "Resolve a pasted stack trace (Python traceback, Rust panic/backtrace, Go panic, Java/Kotlin exception, Node.js stack) to indexed symbols. Each frame is mapped by file and line, or by qualified name when paths differ, and enriched with signature, doc comment and callers. Frames outside the index are marked."
+    )]
+    pub async fn resolve_stack_trace(
+        &self,
+        Parameters(ResolveStackTraceRequest { trace, max_callers }): Parameters<
+            ResolveStackTraceRequest,
+        >,
+    ) -> Result<CallToolResult, McpError> {
+        use crate::symbol::trace::parse_stack_trace;
+
+        let frames = parse_stack_trace(&trace);
+        if frames.is_empty() {
+            return Ok(CallToolResult::success(vec![Content::text(
+                "No stack frames recognised. Supported: Python, Rust, Go, Java/Kotlin and Node.js traces.",
+            )]));
+        }
+
+        let indexer = self.indexer.read().await;
+        let frames = indexer.resolve_stack_trace(frames, max_callers as usize);
+        let resolved = frames.iter().filter(|f| f.in_index).count();
+
+        let mut result = format!("Resolved {resolved} of {} frames:\n\n", frames.len());
+        for frame in &frames {
+            result.push_str(&format!("{frame}\n"));
+        }
+
+        // Add system guidance
+        if let Some(guidance) =
+            generate_mcp_guidance(indexer.settings(), "resolve_stack_trace", resolved)
+        {
+            result.push_str("\n---\n💡 ");
+            result.push_str(&guidance);
+            result.push('\n');
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        description = "Find the tests that exercise a symbol by walking its callers backwards until test functions are reached (Rust #[test], pytest test_*, Go TestXxx, JUnit/Kotlin @Test, Jest it/test blocks). Lists each test with the call chain and the test files to run after changing the symbol."
+    )]
+    pub async fn find_tests(
+        &self,
+        Parameters(FindTestsRequest {
+            symbol_name,
+            symbol_id,
+            max_depth,
+        }): Parameters<FindTestsRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let indexer = self.indexer.read().await;
+
+        let symbols = if let Some(id) = &symbol_id {
+            indexer
+                .get_symbol_by_ref(id)
+                .into_iter()
+                .collect::<Vec<_>>()
+        } else if let Some(name) = &symbol_name {
+            indexer.find_symbols_by_name(name, None)
+        } else {
+            return Ok(CallToolResult::success(vec![Content::text(
+                "Error: Either symbol_name or symbol_id must be provided".to_string(),
+            )]));
+        };
+        if symbols.is_empty() {
+            let identifier = symbol_id
+                .map(|id| format!("symbol_id:{id}"))
+                .or(symbol_name)
+                .unwrap_or_default();
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "Symbol not found: {identifier}"
+            ))]));
+        }
+
+        let reports: Vec<_> = symbols
+            .iter()
+            .filter_map(|symbol| indexer.find_tests_for(symbol.id, max_depth as usize))
+            .collect();
+        let test_count: usize = reports.iter().map(|r| r.tests.len()).sum();
+
+        let mut result = reports
+            .iter()
+            .map(|report| report.to_string())
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        result.push('\n');
+
+        // Add system guidance
+        if let Some(guidance) = generate_mcp_guidance(indexer.settings(), "find_tests", test_count)
+        {
+            result.push_str("\n---\n💡 ");
+            result.push_str(&guidance);
+            result.push('\n');
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        description = "Find the HTTP routes of the codebase and the functions that handle them (axum, actix, Rocket, Express, Fastify, NestJS, Next.js, Flask, FastAPI, Django, Spring, Ktor, ASP.NET, Laravel, Symfony). Answers questions like 'what handles POST /orders?': filter by method and by a concrete path, which matches patterns such as /orders/{id}."
+    )]
+    pub async fn find_routes(
+        &self,
+        Parameters(FindRoutesRequest { method, path, lang }): Parameters<FindRoutesRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let indexer = self.indexer.read().await;
+
+        let table = indexer.find_routes(method.as_deref(), path.as_deref(), lang.as_deref());
+        let mut result = table.to_string();
+        result.push('\n');
+
+        // Add system guidance
+        if let Some(guidance) =
+            generate_mcp_guidance(indexer.settings(), "find_routes", table.routes.len())
+        {
+            result.push_str("\n---\n💡 ");
+            result.push_str(&guidance);
+            result.push('\n');
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
     #[tool(description = "Get information about the indexed codebase")]
     pub async fn get_index_info(
         &self,
@@ -1346,7 +1812,9 @@ impl CodeIntelligenceServer {
         }
     }
 
-    #[tool(description = "Search for symbols using full-text search with fuzzy matching")]
+    #[tool(
+        description = "Search for symbols using full-text search with fuzzy matching. Use 'annotation' to keep only symbols with an attribute, decorator or annotation (e.g., 'route', 'Deprecated', 'tokio::main'); the query may then be empty."
+    )]
     pub async fn search_symbols(
         &self,
         Parameters(SearchSymbolsRequest {
@@ -1355,10 +1823,22 @@ impl CodeIntelligenceServer {
             kind,
             module,
             lang,
+            annotation,
         }): Parameters<SearchSymbolsRequest>,
     ) -> Result<CallToolResult, McpError> {
         let indexer = self.indexer.read().await;
 
+        if query.trim().is_empty() && annotation.is_none() {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "search_symbols requires a query or an annotation",
+            )]));
+        }
+        let described = match &annotation {
+            Some(annotation) if query.trim().is_empty() => format!("annotation '{annotation}'"),
+            Some(annotation) => format!("query '{query}' with annotation '{annotation}'"),
+            None => format!("query '{query}'"),
+        };
+
         // Parse the kind filter if provided
         let kind_filter = kind.as_ref().and_then(|k| match k.to_lowercase().as_str() {
             "function" => Some(crate::SymbolKind::Function),
@@ -1368,19 +1848,32 @@ impl CodeIntelligenceServer {
             "field" => Some(crate::SymbolKind::Field),
             "module" => Some(crate::SymbolKind::Module),
             "constant" => Some(crate::SymbolKind::Constant),
+            "route" => Some(crate::SymbolKind::Route),
             _ => None,
         });
 
-        match indexer.search(
-            &query,
-            limit as usize,
-            kind_filter,
-            module.as_deref(),
-            lang.as_deref(),
-        ) {
+        let search_results = match &annotation {
+            Some(annotation) => indexer.search_with_annotation(
+                &query,
+                limit as usize,
+                kind_filter,
+                module.as_deref(),
+                lang.as_deref(),
+                annotation,
+            ),
+            None => indexer.search(
+                &query,
+                limit as usize,
+                kind_filter,
+                module.as_deref(),
+                lang.as_deref(),
+            ),
+        };
+
+        match search_results {
             Ok(results) => {
                 if results.is_empty() {
-                    let mut output = format!("No results found for query: {query}");
+                    let mut output = format!("No results found for {described}");
                     // Add guidance for no results
                     if let Some(guidance) =
                         generate_mcp_guidance(indexer.settings(), "search_symbols", 0)
@@ -1392,11 +1885,7 @@ impl CodeIntelligenceServer {
                     return Ok(CallToolResult::success(vec![Content::text(output)]));
                 }
 
-                let mut result = format!(
-                    "Found {} result(s) for query '{}':\n\n",
-                    results.len(),
-                    query
-                );
+                let mut result = format!("Found {} result(s) for {described}:\n\n", results.len());
 
                 for (i, search_result) in results.iter().enumerate() {
                     result.push_str(&format!(
@@ -1414,6 +1903,15 @@ impl CodeIntelligenceServer {
                         result.push_str(&format!("   Module: {}\n", search_result.module_path));
                     }
 
+                    if !search_result.annotations.is_empty() {
+                        let annotations: Vec<String> = search_result
+                            .annotations
+                            .iter()
+                            .map(|a| a.to_string())
+                            .collect();
+                        result.push_str(&format!("   Annotations: {}\n", annotations.join(", ")));
+                    }
+
                     if let Some(ref doc) = search_result.doc_comment {
                         // Show first line of doc comment
                         let first_line = doc.lines().next().unwrap_or("");
@@ -1452,6 +1950,10 @@ impl ServerHandler for CodeIntelligenceServer {
         ServerInfo {
             protocol_version: ProtocolVersion::V_2024_11_05,
             capabilities: ServerCapabilities::builder()
+                .enable_prompts()
+                .enable_resources()
+                .enable_resources_list_changed()
+                .enable_resources_subscribe()
                 .enable_tools()
                 .build(),
             server_info: Implementation {
@@ -1466,7 +1968,10 @@ impl ServerHandler for CodeIntelligenceServer {
                 WORKFLOW: Start with 'semantic_search_with_context' or 'semantic_search_docs' to anchor on the right files and APIs - they provide the highest-quality context. \
                 Then use 'find_symbol' and 'search_symbols' to lock onto exact files and kinds. \
                 Treat 'get_calls', 'find_callers', and 'analyze_impact' as hints; confirm with code reading or tighter queries (unique names, kind filters). \
-                Use 'get_index_info' to understand what's indexed."
+                Use 'find_symbol_at' to map a file:line position to a symbol, 'resolve_stack_trace' for pasted stack traces, 'find_tests' to pick the tests to run after a change, 'find_routes' to find the handler of an HTTP endpoint, 'get_file_outline' to see a file's structure and 'get_symbol_source' to read the exact code of symbols instead of whole files. \
+                Use 'get_index_info' to understand what's indexed. \
+                RESOURCES: 'codanna://file/{path}' returns a file outline, 'codanna://symbol/{id}' returns symbol source with relationships, and 'codanna://index/status' returns index statistics; subscribe to receive updates when they change. \
+                PROMPTS: 'explain_symbol', 'trace_call_path' and 'review_impact' provide guided investigation workflows."
                 .to_string()
             ),
         }
@@ -1484,4 +1989,105 @@ impl ServerHandler for CodeIntelligenceServer {
         // Return the server info
         Ok(self.get_info())
     }
+
+    async fn list_prompts(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListPromptsResult, McpError> {
+        let dir = self.prompts_dir().await;
+        let prompts = prompts::load_prompts(&dir)
+            .iter()
+            .map(prompts::PromptTemplate::to_prompt)
+            .collect();
+        Ok(ListPromptsResult::with_all_items(prompts))
+    }
+
+    async fn get_prompt(
+        &self,
+        GetPromptRequestParam { name, arguments }: GetPromptRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<GetPromptResult, McpError> {
+        let dir = self.prompts_dir().await;
+        let template = prompts::find_prompt(&dir, &name)
+            .ok_or_else(|| McpError::invalid_params(format!("Unknown prompt: {name}"), None))?;
+
+        let arguments = prompts::arguments_from_json(arguments.as_ref());
+        let messages = template
+            .render_messages(&arguments)
+            .map_err(|e| McpError::invalid_params(e, None))?;
+
+        Ok(GetPromptResult {
+            description: template.description,
+            messages,
+        })
+    }
+
+    async fn list_resources(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, McpError> {
+        Ok(ListResourcesResult::with_all_items(
+            resources::list_resources(),
+        ))
+    }
+
+    async fn list_resource_templates(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourceTemplatesResult, McpError> {
+        Ok(ListResourceTemplatesResult::with_all_items(
+            resources::list_resource_templates(),
+        ))
+    }
+
+    async fn read_resource(
+        &self,
+        ReadResourceRequestParam { uri }: ReadResourceRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, McpError> {
+        let parsed = resources::ResourceUri::parse(&uri).ok_or_else(|| {
+            McpError::invalid_params(format!("Unsupported resource URI: {uri}"), None)
+        })?;
+
+        let indexer = self.indexer.read().await;
+        match resources::read_resource(&indexer, &parsed) {
+            Some(contents) => Ok(ReadResourceResult {
+                contents: vec![contents],
+            }),
+            None => Err(McpError::resource_not_found(
+                format!("Resource not found: {uri}"),
+                None,
+            )),
+        }
+    }
+
+    async fn subscribe(
+        &self,
+        SubscribeRequestParam { uri }: SubscribeRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<(), McpError> {
+        let parsed = resources::ResourceUri::parse(&uri).ok_or_else(|| {
+            McpError::invalid_params(format!("Unsupported resource URI: {uri}"), None)
+        })?;
+
+        let watched = {
+            let indexer = self.indexer.read().await;
+            resources::watched_files(&indexer, &parsed)
+        }
+        .ok_or_else(|| McpError::resource_not_found(format!("Resource not found: {uri}"), None))?;
+        self.subscriptions.subscribe(uri, watched).await;
+        Ok(())
+    }
+
+    async fn unsubscribe(
+        &self,
+        UnsubscribeRequestParam { uri }: UnsubscribeRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<(), McpError> {
+        self.subscriptions.unsubscribe(&uri).await;
+        Ok(())
+    }
 }
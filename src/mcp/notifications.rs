@@ -83,8 +83,30 @@ impl super::CodeIntelligenceServer {
                         eprintln!("DEBUG: Received broadcast event: {event:?}");
                     }
 
+                    // Resolve subscribed resources before taking the peer lock
+                    let subscribed_uris = match &event {
+                        FileChangeEvent::FileReindexed { path }
+                        | FileChangeEvent::FileCreated { path }
+                        | FileChangeEvent::FileDeleted { path } => {
+                            self.subscribed_uris_for_file(path).await
+                        }
+                        FileChangeEvent::IndexReloaded => self.subscriptions.all().await,
+                    };
+
                     let peer_guard = self.peer.lock().await;
                     if let Some(peer) = peer_guard.as_ref() {
+                        // Only resources the client subscribed to receive updates
+                        for uri in subscribed_uris {
+                            if mcp_debug {
+                                eprintln!(
+                                    "DEBUG: Sending resource updated notification for: {uri}"
+                                );
+                            }
+                            let _ = peer
+                                .notify_resource_updated(ResourceUpdatedNotificationParam { uri })
+                                .await;
+                        }
+
                         match event {
                             FileChangeEvent::FileReindexed { path } => {
                                 let path_str = path.display().to_string();
@@ -93,13 +115,6 @@ impl super::CodeIntelligenceServer {
                                     eprintln!("DEBUG: Sending MCP notifications for: {path_str}");
                                 }
 
-                                // Send resource updated notification
-                                let _ = peer
-                                    .notify_resource_updated(ResourceUpdatedNotificationParam {
-                                        uri: format!("file://{path_str}"),
-                                    })
-                                    .await;
-
                                 // Send logging message
                                 let _ = peer
                                     .notify_logging_message(LoggingMessageNotificationParam {
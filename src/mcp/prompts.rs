@@ -0,0 +1,408 @@
+//! MCP prompts for common investigation workflows
+//!
+//! Prompts are markdown templates rendered with the same `{{variable}}`
+//! substitution as profile templates. Codanna ships built-in workflows and
+//! loads additional (or overriding) templates from `.codanna/prompts/*.md`.
+//!
+//! A template may start with a TOML frontmatter block describing the prompt:
+//!
+//! ```text
+//! +++
+//! description = "Explain a symbol"
+//!
+//! [[arguments]]
+//! name = "symbol"
+//! description = "Symbol name or symbol_id:N"
+//! required = true
+//! +++
+//! Explain `{{symbol}}` ...
+//! ```
+//!
+//! Without frontmatter every `{{variable}}` in the body becomes a required
+//! argument.
+
+use crate::profiles::template::substitute_variables;
+use rmcp::model::{Prompt, PromptArgument, PromptMessage, PromptMessageRole};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Directory name (inside `.codanna/`) holding user prompt templates
+pub const PROMPTS_DIR_NAME: &str = "prompts";
+
+const FRONTMATTER_DELIMITER: &str = "+++";
+
+const EXPLAIN_SYMBOL: &str = r#"+++
+description = "Explain what a symbol does, how it is used and what it depends on"
+
+[[arguments]]
+name = "symbol"
+description = "Symbol name or symbol_id:N"
+required = true
+
+[[arguments]]
+name = "focus"
+description = "Optional aspect to focus the explanation on"
++++
+Explain the symbol `{{symbol}}` in this codebase. {{focus}}
+
+1. Use `find_symbol` with `{{symbol}}` to locate the definition. If several symbols match, pick the relevant one and continue with its `symbol_id:N`.
+2. Read the `codanna://symbol/{id}` resource (or the source file) to see the implementation and documentation.
+3. Use `get_calls` to list what it depends on and `find_callers` to see how it is used.
+4. Summarize its purpose, inputs and outputs, side effects and the most important callers. Cite file paths and line numbers.
+"#;
+
+const TRACE_CALL_PATH: &str = r#"+++
+description = "Trace how execution flows from one symbol to another"
+
+[[arguments]]
+name = "from"
+description = "Starting symbol name or symbol_id:N"
+required = true
+
+[[arguments]]
+name = "to"
+description = "Target symbol name or symbol_id:N"
+required = true
++++
+Trace the call path from `{{from}}` to `{{to}}`.
+
+1. Use `find_symbol` to resolve both `{{from}}` and `{{to}}` to a `symbol_id:N`.
+2. Starting at `{{from}}`, follow `get_calls` breadth-first, and from `{{to}}` follow `find_callers` backwards, until the two frontiers meet.
+3. Confirm each hop by reading the call site; relationships are hints, not proof.
+4. Report the path as an ordered list of `name (file:line)` hops. If no path exists, say so and show the closest symbols reached from each side.
+"#;
+
+const REVIEW_IMPACT: &str = r#"+++
+description = "Review the impact of changing a symbol before editing it"
+
+[[arguments]]
+name = "symbol"
+description = "Symbol name or symbol_id:N"
+required = true
+
+[[arguments]]
+name = "change"
+description = "Optional description of the planned change"
++++
+Review the impact of changing `{{symbol}}`. Planned change: {{change}}
+
+1. Use `find_symbol` to resolve `{{symbol}}` to a `symbol_id:N` and read its definition.
+2. Use `analyze_impact` on that `symbol_id` to collect every caller, implementor and type user.
+3. Group the affected symbols by file and flag public API, trait implementations and tests.
+4. List the changes each affected site would need and call out the riskiest ones first.
+"#;
+
+/// Built-in prompt templates as `(name, source)` pairs
+const BUILTIN_PROMPTS: &[(&str, &str)] = &[
+    ("explain_symbol", EXPLAIN_SYMBOL),
+    ("trace_call_path", TRACE_CALL_PATH),
+    ("review_impact", REVIEW_IMPACT),
+];
+
+/// Argument declared by a prompt template
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct PromptArgumentSpec {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PromptFrontmatter {
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    arguments: Vec<PromptArgumentSpec>,
+}
+
+/// A parsed prompt template
+#[derive(Debug, Clone)]
+pub struct PromptTemplate {
+    pub name: String,
+    pub description: Option<String>,
+    pub arguments: Vec<PromptArgumentSpec>,
+    pub body: String,
+}
+
+impl PromptTemplate {
+    /// Parse a template from its source text
+    pub fn parse(name: &str, source: &str) -> Result<Self, String> {
+        let (frontmatter, body) = split_frontmatter(source);
+
+        let frontmatter: PromptFrontmatter = match frontmatter {
+            Some(text) => toml::from_str(text).map_err(|e| format!("invalid frontmatter: {e}"))?,
+            None => PromptFrontmatter {
+                description: None,
+                arguments: placeholder_names(body)
+                    .into_iter()
+                    .map(|name| PromptArgumentSpec {
+                        name,
+                        description: None,
+                        required: true,
+                    })
+                    .collect(),
+            },
+        };
+
+        Ok(Self {
+            name: name.to_string(),
+            description: frontmatter.description,
+            arguments: frontmatter.arguments,
+            body: body.to_string(),
+        })
+    }
+
+    /// Convert to the MCP prompt descriptor returned by `prompts/list`
+    pub fn to_prompt(&self) -> Prompt {
+        let arguments = self
+            .arguments
+            .iter()
+            .map(|arg| PromptArgument {
+                name: arg.name.clone(),
+                title: None,
+                description: arg.description.clone(),
+                required: Some(arg.required),
+            })
+            .collect::<Vec<_>>();
+
+        Prompt::new(
+            &self.name,
+            self.description.as_deref(),
+            (!arguments.is_empty()).then_some(arguments),
+        )
+    }
+
+    /// Render the template with the given arguments
+    ///
+    /// Missing optional arguments render as empty strings. Missing required
+    /// arguments and placeholders that are not declared are reported as errors.
+    pub fn render(&self, arguments: &HashMap<String, String>) -> Result<String, String> {
+        let mut variables = HashMap::new();
+        for spec in &self.arguments {
+            match arguments.get(&spec.name) {
+                Some(value) => {
+                    variables.insert(spec.name.clone(), value.clone());
+                }
+                None if spec.required => {
+                    return Err(format!("Missing required argument '{}'", spec.name));
+                }
+                None => {
+                    variables.insert(spec.name.clone(), String::new());
+                }
+            }
+        }
+
+        substitute_variables(&self.body, &variables).map_err(|e| e.to_string())
+    }
+
+    /// Render the template into a single user message
+    pub fn render_messages(
+        &self,
+        arguments: &HashMap<String, String>,
+    ) -> Result<Vec<PromptMessage>, String> {
+        let text = self.render(arguments)?;
+        Ok(vec![PromptMessage::new_text(
+            PromptMessageRole::User,
+            text.trim(),
+        )])
+    }
+}
+
+/// Resolve the prompt template directory for a workspace
+pub fn prompts_dir(workspace_root: Option<&Path>) -> PathBuf {
+    let local_dir = PathBuf::from(crate::init::local_dir_name());
+    match workspace_root {
+        Some(root) => root.join(local_dir).join(PROMPTS_DIR_NAME),
+        None => local_dir.join(PROMPTS_DIR_NAME),
+    }
+}
+
+/// Load built-in prompts merged with templates from `dir`
+///
+/// A file named `<name>.md` overrides the built-in prompt of the same name.
+/// Templates that fail to parse are skipped with a warning.
+pub fn load_prompts(dir: &Path) -> Vec<PromptTemplate> {
+    let mut prompts: BTreeMap<String, PromptTemplate> = BUILTIN_PROMPTS
+        .iter()
+        .filter_map(|(name, source)| PromptTemplate::parse(name, source).ok())
+        .map(|template| (template.name.clone(), template))
+        .collect();
+
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return prompts.into_values().collect();
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("md") {
+            continue;
+        }
+        let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+
+        let parsed = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|source| PromptTemplate::parse(name, &source));
+        match parsed {
+            Ok(template) => {
+                prompts.insert(template.name.clone(), template);
+            }
+            Err(e) => {
+                tracing::warn!("Skipping prompt template {}: {}", path.display(), e);
+            }
+        }
+    }
+
+    prompts.into_values().collect()
+}
+
+/// Find a prompt by name
+pub fn find_prompt(dir: &Path, name: &str) -> Option<PromptTemplate> {
+    load_prompts(dir).into_iter().find(|p| p.name == name)
+}
+
+/// Convert MCP prompt arguments (a JSON object) into template variables
+///
+/// Non-string values are rendered with their JSON representation.
+pub fn arguments_from_json(
+    arguments: Option<&serde_json::Map<String, serde_json::Value>>,
+) -> HashMap<String, String> {
+    arguments
+        .into_iter()
+        .flatten()
+        .map(|(key, value)| {
+            let value = match value {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            (key.clone(), value)
+        })
+        .collect()
+}
+
+fn split_frontmatter(source: &str) -> (Option<&str>, &str) {
+    let trimmed = source.trim_start();
+    let Some(rest) = trimmed.strip_prefix(FRONTMATTER_DELIMITER) else {
+        return (None, source);
+    };
+    let Some(rest) = rest
+        .strip_prefix('\n')
+        .or_else(|| rest.strip_prefix("\r\n"))
+    else {
+        return (None, source);
+    };
+
+    let closing = format!("\n{FRONTMATTER_DELIMITER}");
+    match rest.find(&closing) {
+        Some(end) => {
+            let body = &rest[end + closing.len()..];
+            let body = body.split_once('\n').map_or("", |(_, body)| body);
+            (Some(&rest[..end]), body)
+        }
+        None => (None, source),
+    }
+}
+
+fn placeholder_names(body: &str) -> Vec<String> {
+    let pattern = regex::Regex::new(r"\{\{(\w+)\}\}").expect("Invalid regex");
+    let mut names = Vec::new();
+    for capture in pattern.captures_iter(body) {
+        let name = capture[1].to_string();
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn args(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_builtin_prompts_parse() {
+        for (name, source) in BUILTIN_PROMPTS {
+            let template = PromptTemplate::parse(name, source).unwrap();
+            assert!(template.description.is_some(), "{name} has no description");
+            assert!(
+                template.arguments.iter().any(|a| a.required),
+                "{name} has no required argument"
+            );
+            assert!(!template.body.starts_with(FRONTMATTER_DELIMITER));
+        }
+    }
+
+    #[test]
+    fn test_render_with_optional_argument() {
+        let template = PromptTemplate::parse("explain_symbol", EXPLAIN_SYMBOL).unwrap();
+
+        let text = template.render(&args(&[("symbol", "Parser")])).unwrap();
+        assert!(text.contains("Explain the symbol `Parser`"));
+        assert!(!text.contains("{{"));
+
+        let err = template.render(&args(&[("focus", "errors")])).unwrap_err();
+        assert!(err.contains("symbol"));
+    }
+
+    #[test]
+    fn test_placeholders_without_frontmatter() {
+        let template =
+            PromptTemplate::parse("custom", "Compare {{a}} with {{b}} and {{a}}").unwrap();
+        let names: Vec<_> = template.arguments.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["a", "b"]);
+        assert!(template.arguments.iter().all(|a| a.required));
+        assert_eq!(
+            template.render(&args(&[("a", "x"), ("b", "y")])).unwrap(),
+            "Compare x with y and x"
+        );
+    }
+
+    #[test]
+    fn test_load_prompts_from_directory() {
+        let temp = TempDir::new().unwrap();
+        std::fs::write(
+            temp.path().join("explain_symbol.md"),
+            "+++\ndescription = \"Custom explain\"\n+++\nJust explain it.\n",
+        )
+        .unwrap();
+        std::fs::write(temp.path().join("find_owner.md"), "Who owns {{path}}?").unwrap();
+        std::fs::write(temp.path().join("notes.txt"), "ignored").unwrap();
+
+        let prompts = load_prompts(temp.path());
+        let names: Vec<_> = prompts.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "explain_symbol",
+                "find_owner",
+                "review_impact",
+                "trace_call_path"
+            ]
+        );
+
+        let explain = find_prompt(temp.path(), "explain_symbol").unwrap();
+        assert_eq!(explain.description.as_deref(), Some("Custom explain"));
+        assert!(explain.arguments.is_empty());
+    }
+
+    #[test]
+    fn test_arguments_from_json() {
+        let json = serde_json::json!({"symbol": "main", "depth": 3});
+        let map = arguments_from_json(json.as_object());
+        assert_eq!(map.get("symbol").map(String::as_str), Some("main"));
+        assert_eq!(map.get("depth").map(String::as_str), Some("3"));
+        assert!(arguments_from_json(None).is_empty());
+    }
+}
@@ -0,0 +1,399 @@
+//! MCP resources for indexed files, symbols and index status
+//!
+//! Resources use the `codanna://` scheme:
+//!
+//! - `codanna://file/{path}` - nested outline of an indexed file (symbols with ranges)
+//! - `codanna://symbol/{id}` - symbol source text plus relationships (numeric or stable ID)
+//! - `codanna://index/status` - index statistics
+//!
+//! Clients can subscribe to any of these URIs. File change events from the
+//! [`NotificationBroadcaster`](super::notifications::NotificationBroadcaster)
+//! are only forwarded for URIs that a client is watching.
+
+use rmcp::model::{
+    AnnotateAble, RawResource, RawResourceTemplate, Resource, ResourceContents, ResourceTemplate,
+};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::SimpleIndexer;
+use crate::symbol::context::{ContextIncludes, SymbolContext};
+use crate::symbol::source::read_symbol_source;
+use crate::types::{SymbolId, SymbolRef};
+
+const FILE_PREFIX: &str = "codanna://file/";
+const SYMBOL_PREFIX: &str = "codanna://symbol/";
+
+/// URI of the index status resource
+pub const INDEX_STATUS_URI: &str = "codanna://index/status";
+/// URI template for file outline resources
+pub const FILE_RESOURCE_TEMPLATE: &str = "codanna://file/{path}";
+/// URI template for symbol resources
+pub const SYMBOL_RESOURCE_TEMPLATE: &str = "codanna://symbol/{id}";
+
+const JSON_MIME_TYPE: &str = "application/json";
+
+/// A parsed `codanna://` resource URI
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResourceUri {
+    /// Outline of an indexed file (workspace-relative path)
+    File(String),
+    /// Source and relationships of a single symbol
+    Symbol(SymbolRef),
+    /// Index statistics
+    IndexStatus,
+}
+
+impl ResourceUri {
+    /// Parse a resource URI, returning `None` for unknown schemes or paths
+    pub fn parse(uri: &str) -> Option<Self> {
+        if uri == INDEX_STATUS_URI {
+            return Some(Self::IndexStatus);
+        }
+
+        if let Some(path) = uri.strip_prefix(FILE_PREFIX) {
+            if path.is_empty() {
+                return None;
+            }
+            return Some(Self::File(path.to_string()));
+        }
+
+        if let Some(id) = uri.strip_prefix(SYMBOL_PREFIX) {
+            return id
+                .parse::<SymbolRef>()
+                .ok()
+                .filter(|id| *id != SymbolRef::Id(SymbolId(0)))
+                .map(Self::Symbol);
+        }
+
+        None
+    }
+
+    /// Build the URI for a file outline
+    pub fn file_uri(path: &str) -> String {
+        format!("{FILE_PREFIX}{path}")
+    }
+
+    /// Build the URI for a symbol
+    pub fn symbol_uri(id: SymbolId) -> String {
+        format!("{SYMBOL_PREFIX}{}", id.value())
+    }
+}
+
+impl fmt::Display for ResourceUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::File(path) => write!(f, "{}", Self::file_uri(path)),
+            Self::Symbol(id) => write!(f, "{SYMBOL_PREFIX}{id}"),
+            Self::IndexStatus => write!(f, "{INDEX_STATUS_URI}"),
+        }
+    }
+}
+
+/// Files whose changes affect a subscribed resource
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatchedFiles {
+    /// Every indexed file, as for the index status
+    All,
+    /// A single workspace-relative file
+    File(String),
+}
+
+impl WatchedFiles {
+    fn includes(&self, file: &str) -> bool {
+        match self {
+            Self::All => true,
+            Self::File(watched) => watched == file,
+        }
+    }
+}
+
+/// Resource URIs that the connected client subscribed to
+///
+/// Each URI is stored with the files it depends on, so change events can be
+/// matched without re-querying the index. Symbol IDs are reassigned on
+/// re-index, which is why the file is captured at subscription time.
+#[derive(Debug, Clone, Default)]
+pub struct ResourceSubscriptions {
+    uris: Arc<RwLock<HashMap<String, WatchedFiles>>>,
+}
+
+impl ResourceSubscriptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start watching a URI
+    pub async fn subscribe(&self, uri: String, watched: WatchedFiles) {
+        self.uris.write().await.insert(uri, watched);
+    }
+
+    /// Stop watching a URI
+    pub async fn unsubscribe(&self, uri: &str) {
+        self.uris.write().await.remove(uri);
+    }
+
+    /// All subscribed URIs, sorted for deterministic notification order
+    pub async fn all(&self) -> Vec<String> {
+        let mut uris: Vec<String> = self.uris.read().await.keys().cloned().collect();
+        uris.sort();
+        uris
+    }
+
+    /// Subscribed URIs affected by a change to `file` (workspace-relative)
+    pub async fn affected_by_file(&self, file: &str) -> Vec<String> {
+        let mut uris: Vec<String> = self
+            .uris
+            .read()
+            .await
+            .iter()
+            .filter(|(_, watched)| watched.includes(file))
+            .map(|(uri, _)| uri.clone())
+            .collect();
+        uris.sort();
+        uris
+    }
+}
+
+/// Content of a `codanna://symbol/{id}` resource
+#[derive(Debug, Clone, Serialize)]
+pub struct SymbolResource {
+    #[serde(flatten)]
+    pub context: SymbolContext,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+/// Semantic search section of the index status resource
+#[derive(Debug, Clone, Serialize)]
+pub struct SemanticStatus {
+    pub model: String,
+    pub embeddings: usize,
+    pub dimension: usize,
+    pub updated_at: u64,
+}
+
+/// Content of the `codanna://index/status` resource
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexStatusResource {
+    pub symbol_count: usize,
+    pub file_count: u32,
+    pub relationship_count: usize,
+    pub indexed_paths: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub semantic_search: Option<SemanticStatus>,
+}
+
+/// Static resources advertised by `resources/list`
+pub fn list_resources() -> Vec<Resource> {
+    vec![
+        RawResource {
+            uri: INDEX_STATUS_URI.to_string(),
+            name: "index-status".to_string(),
+            title: Some("Index status".to_string()),
+            description: Some(
+                "Symbol, file and relationship counts plus semantic search state".to_string(),
+            ),
+            mime_type: Some(JSON_MIME_TYPE.to_string()),
+            size: None,
+            icons: None,
+        }
+        .no_annotation(),
+    ]
+}
+
+/// Resource templates advertised by `resources/templates/list`
+pub fn list_resource_templates() -> Vec<ResourceTemplate> {
+    vec![
+        RawResourceTemplate {
+            uri_template: FILE_RESOURCE_TEMPLATE.to_string(),
+            name: "file-outline".to_string(),
+            title: Some("File outline".to_string()),
+            description: Some(
+                "Nested outline of an indexed file with ranges, visibility and signatures"
+                    .to_string(),
+            ),
+            mime_type: Some(JSON_MIME_TYPE.to_string()),
+        }
+        .no_annotation(),
+        RawResourceTemplate {
+            uri_template: SYMBOL_RESOURCE_TEMPLATE.to_string(),
+            name: "symbol".to_string(),
+            title: Some("Symbol".to_string()),
+            description: Some(
+                "Source text of a symbol with its callers, callees and implementations".to_string(),
+            ),
+            mime_type: Some(JSON_MIME_TYPE.to_string()),
+        }
+        .no_annotation(),
+    ]
+}
+
+/// Read a resource, returning `None` when the file or symbol is not indexed
+pub fn read_resource(indexer: &SimpleIndexer, uri: &ResourceUri) -> Option<ResourceContents> {
+    let value = match uri {
+        ResourceUri::File(path) => serde_json::to_string_pretty(&indexer.get_file_outline(path)?),
+        ResourceUri::Symbol(id) => serde_json::to_string_pretty(&symbol_resource(indexer, id)?),
+        ResourceUri::IndexStatus => serde_json::to_string_pretty(&index_status(indexer)),
+    }
+    .ok()?;
+
+    Some(ResourceContents::TextResourceContents {
+        uri: uri.to_string(),
+        mime_type: Some(JSON_MIME_TYPE.to_string()),
+        text: value,
+        meta: None,
+    })
+}
+
+/// Files a resource depends on, or `None` when the file or symbol is not indexed
+pub fn watched_files(indexer: &SimpleIndexer, uri: &ResourceUri) -> Option<WatchedFiles> {
+    match uri {
+        ResourceUri::File(path) => {
+            let path = path.strip_prefix("./").unwrap_or(path);
+            indexer.get_file_id_by_path(path)?;
+            Some(WatchedFiles::File(
+                indexer.normalize_indexed_path(Path::new(path)),
+            ))
+        }
+        ResourceUri::Symbol(id) => Some(WatchedFiles::File(
+            indexer.get_symbol_by_ref(id)?.file_path.to_string(),
+        )),
+        ResourceUri::IndexStatus => Some(WatchedFiles::All),
+    }
+}
+
+fn symbol_resource(indexer: &SimpleIndexer, id: &SymbolRef) -> Option<SymbolResource> {
+    let symbol = indexer.get_symbol_by_ref(id)?;
+    let context = indexer.get_symbol_context(symbol.id, ContextIncludes::ALL)?;
+    let workspace_root = indexer.settings().workspace_root.as_deref();
+    let source = read_symbol_source(&context.symbol, workspace_root).ok();
+
+    Some(SymbolResource { context, source })
+}
+
+fn index_status(indexer: &SimpleIndexer) -> IndexStatusResource {
+    let mut indexed_paths: Vec<String> = indexer
+        .get_indexed_paths()
+        .iter()
+        .map(|p| p.display().to_string())
+        .collect();
+    indexed_paths.sort();
+
+    IndexStatusResource {
+        symbol_count: indexer.symbol_count(),
+        file_count: indexer.file_count(),
+        relationship_count: indexer.relationship_count(),
+        indexed_paths,
+        semantic_search: indexer.get_semantic_metadata().map(|m| SemanticStatus {
+            model: m.model_name,
+            embeddings: m.embedding_count,
+            dimension: m.dimension,
+            updated_at: m.updated_at,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_resource_uris() {
+        assert_eq!(
+            ResourceUri::parse("codanna://index/status"),
+            Some(ResourceUri::IndexStatus)
+        );
+        assert_eq!(
+            ResourceUri::parse("codanna://file/src/lib.rs"),
+            Some(ResourceUri::File("src/lib.rs".to_string()))
+        );
+        assert_eq!(
+            ResourceUri::parse("codanna://symbol/42"),
+            Some(ResourceUri::Symbol(SymbolRef::Id(SymbolId(42))))
+        );
+        assert_eq!(
+            ResourceUri::parse("codanna://symbol/symbol_id:42"),
+            Some(ResourceUri::Symbol(SymbolRef::Id(SymbolId(42))))
+        );
+
+        assert_eq!(ResourceUri::parse("codanna://symbol/0"), None);
+        assert_eq!(ResourceUri::parse("codanna://symbol/abc"), None);
+        assert_eq!(ResourceUri::parse("codanna://file/"), None);
+        assert_eq!(ResourceUri::parse("file:///src/lib.rs"), None);
+    }
+
+    #[test]
+    fn test_resource_uri_roundtrip() {
+        for uri in [
+            "codanna://index/status",
+            "codanna://file/src/main.rs",
+            "codanna://symbol/7",
+            "codanna://symbol/rust.4f1c9e0a7b3d2c58",
+        ] {
+            assert_eq!(ResourceUri::parse(uri).unwrap().to_string(), uri);
+        }
+    }
+
+    #[test]
+    fn test_watched_file_requires_an_indexed_target() {
+        use crate::config::Settings;
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let settings = Settings {
+            index_path: temp.path().join(".codanna/index"),
+            workspace_root: Some(temp.path().to_path_buf()),
+            ..Settings::default()
+        };
+        let mut indexer = SimpleIndexer::with_settings(Arc::new(settings));
+        let path = temp.path().join("lib.rs");
+        std::fs::write(&path, "pub fn alpha() {}\n").unwrap();
+        indexer.index_file(&path).unwrap();
+        let alpha = indexer.find_symbols_by_name("alpha", None)[0].id;
+
+        let watched = |uri: &str| watched_files(&indexer, &ResourceUri::parse(uri).unwrap());
+        let lib = Some(WatchedFiles::File("lib.rs".to_string()));
+        assert_eq!(watched(&ResourceUri::symbol_uri(alpha)), lib);
+        assert_eq!(watched("codanna://file/lib.rs"), lib);
+        assert_eq!(watched(INDEX_STATUS_URI), Some(WatchedFiles::All));
+
+        // Unknown targets must not fall back to watching every file
+        assert_eq!(watched("codanna://symbol/9999"), None);
+        assert_eq!(watched("codanna://symbol/rust.4f1c9e0a7b3d2c58"), None);
+        assert_eq!(watched("codanna://file/missing.rs"), None);
+    }
+
+    #[tokio::test]
+    async fn test_subscriptions_filter_by_file() {
+        let subs = ResourceSubscriptions::new();
+        subs.subscribe(
+            "codanna://file/src/a.rs".to_string(),
+            WatchedFiles::File("src/a.rs".to_string()),
+        )
+        .await;
+        subs.subscribe(
+            "codanna://symbol/3".to_string(),
+            WatchedFiles::File("src/b.rs".to_string()),
+        )
+        .await;
+        subs.subscribe(INDEX_STATUS_URI.to_string(), WatchedFiles::All)
+            .await;
+
+        assert_eq!(
+            subs.affected_by_file("src/a.rs").await,
+            vec!["codanna://file/src/a.rs", INDEX_STATUS_URI]
+        );
+        assert_eq!(
+            subs.affected_by_file("src/b.rs").await,
+            vec![INDEX_STATUS_URI, "codanna://symbol/3"]
+        );
+
+        subs.unsubscribe(INDEX_STATUS_URI).await;
+        assert!(subs.affected_by_file("src/c.rs").await.is_empty());
+        assert_eq!(subs.all().await.len(), 2);
+    }
+}
@@ -14,6 +14,7 @@ use super::{
     CodeIntelligenceServer,
     notifications::{FileChangeEvent, NotificationBroadcaster},
 };
+use crate::indexing::IndexWriteLock;
 use crate::{IndexPersistence, Settings, SimpleIndexer};
 
 /// Watches the index file and reloads it when changes are detected
@@ -101,6 +102,13 @@ impl IndexWatcher {
             return Ok(());
         }
 
+        // A writer in another process commits Tantivy before it saves the rest
+        // of the index; keep serving the last generation until it is done
+        if IndexWriteLock::held_elsewhere(&self.index_path) {
+            debug!("Index is being written by another process, reload deferred");
+            return Ok(());
+        }
+
         // Get current modification time of the actual index metadata file
         let meta_file_path = self.index_path.join("tantivy").join("meta.json");
         let metadata = std::fs::metadata(&meta_file_path)?;
@@ -247,6 +255,12 @@ impl IndexWatcher {
                 files_to_reindex.len()
             );
 
+            // Retry on the next tick while another writer holds the index
+            let Some(_lock) = IndexWriteLock::try_acquire(&self.index_path)? else {
+                debug!("Index is being written by another process, re-indexing deferred");
+                return Ok(());
+            };
+
             let mut indexer = self.indexer.write().await;
             let mut reindexed_count = 0;
 
@@ -289,6 +303,7 @@ impl IndexWatcher {
                     error!("Failed to persist index after re-indexing: {}", e);
                 }
             }
+            indexer.release_writer()?;
         }
 
         Ok(())
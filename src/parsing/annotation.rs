@@ -0,0 +1,452 @@
+//! Attributes, decorators and annotations attached to symbols
+//!
+//! `#[derive(Debug)]`, `@app.route("/orders")`, `@Override`, `[HttpGet]` and
+//! `[[nodiscard]]` are kept out of symbol names by the parsers. Each parser
+//! reads them from its syntax tree with [`attach_annotations`], which finds the
+//! annotation nodes around a symbol's definition: the ones it starts with
+//! (Kotlin annotations, C# attributes, TypeScript class decorators) and the
+//! ones directly before it (Rust attributes, Python decorators). The text of
+//! each node is then split into [`Annotation`]s by [`parse_annotation_line`].
+
+use crate::{Range, Symbol};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use tree_sitter::{Node, Point};
+
+/// How a language writes annotations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnnotationSyntax {
+    /// `#[name(args)]` (Rust, PHP 8)
+    Hash,
+    /// `@name(args)` (Python, Kotlin, TypeScript, GDScript)
+    At,
+    /// `[Name(args), Other]` (C#)
+    Bracket,
+    /// `[[name(args)]]` (C++11, C23)
+    DoubleBracket,
+}
+
+impl AnnotationSyntax {
+    fn opener(self) -> &'static str {
+        match self {
+            Self::Hash => "#[",
+            Self::At => "@",
+            Self::Bracket => "[",
+            Self::DoubleBracket => "[[",
+        }
+    }
+}
+
+/// An attribute, decorator or annotation on a symbol
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Annotation {
+    /// Name as written, with its path (e.g. `tokio::main`, `app.route`)
+    pub name: Box<str>,
+    /// Text between the parentheses, or after `=` for `#[name = value]`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arguments: Option<Box<str>>,
+}
+
+impl Annotation {
+    /// Parse one annotation without its delimiters, e.g. `app.route("/x")`
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        let name_end = text
+            .find(|c: char| !(c.is_alphanumeric() || "_.:\\".contains(c)))
+            .unwrap_or(text.len());
+        let name = text[..name_end].trim_matches(|c| c == ':' || c == '.');
+        if name.is_empty() {
+            return None;
+        }
+
+        let rest = text[name_end..].trim_start();
+        let arguments = if rest.starts_with('(') {
+            let close = matching_close(rest)?;
+            Some(rest[1..close].trim())
+        } else {
+            rest.strip_prefix('=').map(str::trim)
+        };
+
+        Some(Self {
+            name: name.into(),
+            arguments: arguments.filter(|a| !a.is_empty()).map(Into::into),
+        })
+    }
+
+    /// Last segment of the name, e.g. `route` for `app.route`
+    pub fn short_name(&self) -> &str {
+        self.segments().last().unwrap_or(&self.name)
+    }
+
+    /// Lowercased terms a filter can match: the full name and each segment
+    pub fn search_terms(&self) -> Vec<String> {
+        let mut terms = vec![self.name.to_lowercase()];
+        for segment in self.segments() {
+            let segment = segment.to_lowercase();
+            if !terms.contains(&segment) {
+                terms.push(segment);
+            }
+        }
+        terms
+    }
+
+    /// Whether a filter like `route` or `tokio::main` names this annotation
+    pub fn matches(&self, filter: &str) -> bool {
+        let filter = filter.trim_start_matches(['@', '#', '[']).to_lowercase();
+        self.search_terms().contains(&filter)
+    }
+
+    fn segments(&self) -> impl Iterator<Item = &str> {
+        self.name
+            .split(['.', ':', '\\'])
+            .filter(|segment| !segment.is_empty())
+    }
+}
+
+impl fmt::Display for Annotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.arguments {
+            Some(arguments) => write!(f, "{}({arguments})", self.name),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+/// Byte offset of the bracket closing the one `text` starts with
+pub(crate) fn matching_close(text: &str) -> Option<usize> {
+    let mut depth = 0i32;
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    for (i, c) in text.char_indices() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' | '`' => quote = Some(c),
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Split on commas that are not nested in brackets or strings
+pub(crate) fn split_top_level(text: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut depth = 0i32;
+    let mut quote: Option<char> = None;
+    for (i, c) in text.char_indices() {
+        if let Some(q) = quote {
+            if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' => quote = Some(c),
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&text[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+/// Parse the annotations a line of source starts with
+pub fn parse_annotation_line(line: &str, syntax: AnnotationSyntax) -> Vec<Annotation> {
+    let mut annotations = Vec::new();
+    let mut rest = line.trim();
+
+    loop {
+        match syntax {
+            AnnotationSyntax::At => {
+                let Some(after) = rest.strip_prefix('@') else {
+                    break;
+                };
+                let name_end = after
+                    .find(|c: char| !(c.is_alphanumeric() || "_.:".contains(c)))
+                    .unwrap_or(after.len());
+                let mut end = name_end;
+                let tail = &after[name_end..];
+                if tail.starts_with('(') {
+                    match matching_close(tail) {
+                        Some(close) => end += close + 1,
+                        None => end = after.len(),
+                    }
+                }
+                annotations.extend(Annotation::parse(&after[..end]));
+                rest = after[end..].trim_start();
+            }
+            AnnotationSyntax::Hash
+            | AnnotationSyntax::Bracket
+            | AnnotationSyntax::DoubleBracket => {
+                let opener = syntax.opener();
+                if !rest.starts_with(opener) {
+                    break;
+                }
+                // `[[x]]` closes with the outer bracket of its `[[`
+                let start = opener.len() - 1;
+                let Some(close) = matching_close(&rest[start..]) else {
+                    break;
+                };
+                let mut inner = &rest[start + 1..start + close];
+                if syntax == AnnotationSyntax::DoubleBracket {
+                    inner = inner.strip_prefix('[').unwrap_or(inner);
+                    inner = inner.strip_suffix(']').unwrap_or(inner);
+                }
+                for part in split_top_level(inner) {
+                    let mut part = part.trim();
+                    // C# targets like `[return: NotNull]`
+                    if let Some((target, attribute)) = part.split_once(':') {
+                        if !attribute.starts_with(':')
+                            && target.chars().all(|c| c.is_ascii_lowercase())
+                        {
+                            part = attribute;
+                        }
+                    }
+                    annotations.extend(Annotation::parse(part));
+                }
+                rest = rest[start + close + 1..].trim_start();
+                if syntax == AnnotationSyntax::DoubleBracket {
+                    rest = rest.strip_prefix(']').unwrap_or(rest).trim_start();
+                }
+            }
+        }
+    }
+    annotations
+}
+
+/// Tree-sitter node kinds a language writes annotations with
+#[derive(Debug, Clone, Copy)]
+pub struct AnnotationNodes {
+    pub syntax: AnnotationSyntax,
+    /// Nodes holding one annotation or a bracketed list of them
+    pub annotations: &'static [&'static str],
+    /// Nodes grouping annotations with other modifiers, e.g. Kotlin `modifiers`
+    pub containers: &'static [&'static str],
+}
+
+impl AnnotationNodes {
+    /// Annotations of the definition `node`, in source order
+    fn of_definition(&self, node: Node, code: &str) -> Vec<Annotation> {
+        let mut annotations = Vec::new();
+
+        // Directly before the definition, e.g. Rust attributes and the
+        // decorators of a Python `decorated_definition`
+        let mut before = Vec::new();
+        let mut sibling = node.prev_named_sibling();
+        while let Some(previous) = sibling {
+            if self.annotations.contains(&previous.kind()) {
+                before.push(previous);
+            } else if !is_comment(previous) {
+                break;
+            }
+            sibling = previous.prev_named_sibling();
+        }
+        for annotation in before.into_iter().rev() {
+            annotations.extend(self.parse(annotation, code));
+        }
+
+        // Leading children of the definition and of inner nodes sharing its range
+        let mut current = Some(node);
+        while let Some(node) = current {
+            let mut cursor = node.walk();
+            for child in node.named_children(&mut cursor) {
+                if self.annotations.contains(&child.kind()) {
+                    annotations.extend(self.parse(child, code));
+                } else if self.containers.contains(&child.kind()) {
+                    let mut cursor = child.walk();
+                    for nested in child.named_children(&mut cursor) {
+                        if self.annotations.contains(&nested.kind()) {
+                            annotations.extend(self.parse(nested, code));
+                        }
+                    }
+                } else if !is_comment(child) {
+                    break;
+                }
+            }
+            current = node
+                .named_child(0)
+                .filter(|child| child.byte_range() == node.byte_range());
+        }
+
+        annotations
+    }
+
+    fn parse(&self, node: Node, code: &str) -> Vec<Annotation> {
+        // Arguments spread over several lines are read as one line
+        let text = code[node.byte_range()]
+            .lines()
+            .map(str::trim)
+            .collect::<Vec<_>>()
+            .join(" ");
+        parse_annotation_line(&text, self.syntax)
+    }
+}
+
+/// Fill `Symbol::annotations` from the syntax tree of their file.
+///
+/// A symbol's definition is the outermost node spanning exactly its range;
+/// symbols without one keep the annotations they have.
+pub fn attach_annotations(root: Node, code: &str, symbols: &mut [Symbol], nodes: &AnnotationNodes) {
+    for symbol in symbols {
+        if let Some(definition) = definition_node(root, &symbol.range) {
+            symbol.annotations = nodes.of_definition(definition, code);
+        }
+    }
+}
+
+fn definition_node<'tree>(root: Node<'tree>, range: &Range) -> Option<Node<'tree>> {
+    let start = Point::new(range.start_line as usize, range.start_column as usize);
+    let end = Point::new(range.end_line as usize, range.end_column as usize);
+    let spans_range = |node: &Node| node.start_position() == start && node.end_position() == end;
+
+    let mut node = root
+        .named_descendant_for_point_range(start, end)
+        .filter(spans_range)?;
+    while let Some(parent) = node.parent().filter(spans_range) {
+        node = parent;
+    }
+    Some(node)
+}
+
+fn is_comment(node: Node) -> bool {
+    node.kind().contains("comment")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FileId;
+    use crate::parsing::{KotlinParser, LanguageParser, PythonParser, RustParser};
+    use crate::types::SymbolCounter;
+
+    fn parse(parser: &mut dyn LanguageParser, source: &str) -> Vec<Symbol> {
+        parser.parse(source, FileId::new(1).unwrap(), &mut SymbolCounter::new())
+    }
+
+    fn names(annotations: &[Annotation]) -> Vec<String> {
+        annotations.iter().map(|a| a.to_string()).collect()
+    }
+
+    fn annotations_of(symbols: &[Symbol], name: &str) -> Vec<String> {
+        let symbol = symbols.iter().find(|s| &*s.name == name);
+        names(
+            &symbol
+                .unwrap_or_else(|| panic!("no symbol {name}"))
+                .annotations,
+        )
+    }
+
+    #[test]
+    fn test_rust_attributes_above_symbol() {
+        let source = "use super::*;\n\n/// Adds\n#[derive(Debug, Clone)]\n\n// note\n#[tokio::main]\nfn main() {\n    let s = \"#[test]\";\n}\n";
+        let symbols = parse(&mut RustParser::new().unwrap(), source);
+        assert_eq!(
+            annotations_of(&symbols, "main"),
+            vec!["derive(Debug, Clone)", "tokio::main"]
+        );
+    }
+
+    #[test]
+    fn test_multiline_python_decorator() {
+        let source = "x = 1\n@app.route(\n    \"/orders\",\n    methods=[\"POST\"],\n)\n\n@login_required\ndef create_order():\n    pass\n";
+        let symbols = parse(&mut PythonParser::new().unwrap(), source);
+        let symbol = symbols.iter().find(|s| &*s.name == "create_order").unwrap();
+        let annotations = &symbol.annotations;
+        assert_eq!(annotations.len(), 2);
+        assert_eq!(&*annotations[0].name, "app.route");
+        assert_eq!(
+            annotations[0].arguments.as_deref(),
+            Some("\"/orders\", methods=[\"POST\"],")
+        );
+        assert!(annotations[0].matches("route"));
+        assert!(annotations[1].matches("@login_required"));
+    }
+
+    #[test]
+    fn test_annotation_text_in_strings_is_ignored() {
+        let source = "@cache\ndef first(): pass\ndef second():\n    \"\"\"\n    @app.route(\"/x\")\n    \"\"\"\n    pass\n";
+        let symbols = parse(&mut PythonParser::new().unwrap(), source);
+        assert_eq!(annotations_of(&symbols, "first"), vec!["cache"]);
+        assert!(annotations_of(&symbols, "second").is_empty());
+
+        let source = "#[cfg(test)]\nmod tests {\n    #[test]\n    fn adds() {}\n}\n";
+        let symbols = parse(&mut RustParser::new().unwrap(), source);
+        assert_eq!(annotations_of(&symbols, "tests"), vec!["cfg(test)"]);
+        assert_eq!(annotations_of(&symbols, "adds"), vec!["test"]);
+    }
+
+    #[test]
+    fn test_annotations_inside_range() {
+        let source = "class OrderController {\n    @Test @DisplayName(\"a@b\")\n    fun adds() {\n    }\n}\n";
+        let symbols = parse(&mut KotlinParser::new().unwrap(), source);
+        assert_eq!(
+            annotations_of(&symbols, "adds"),
+            vec!["Test", "DisplayName(\"a@b\")"]
+        );
+        assert!(annotations_of(&symbols, "OrderController").is_empty());
+    }
+
+    #[test]
+    fn test_bracket_syntaxes() {
+        assert_eq!(
+            names(&parse_annotation_line(
+                "[HttpGet(\"orders/{id}\"), Authorize] [return: NotNull]",
+                AnnotationSyntax::Bracket
+            )),
+            vec!["HttpGet(\"orders/{id}\")", "Authorize", "NotNull"]
+        );
+        assert_eq!(
+            names(&parse_annotation_line(
+                "[[nodiscard, deprecated(\"use g\")]] int f();",
+                AnnotationSyntax::DoubleBracket
+            )),
+            vec!["nodiscard", "deprecated(\"use g\")"]
+        );
+        assert_eq!(
+            names(&parse_annotation_line(
+                "#[Route('/x'), Deprecated]",
+                AnnotationSyntax::Hash
+            )),
+            vec!["Route('/x')", "Deprecated"]
+        );
+        assert_eq!(
+            names(&parse_annotation_line(
+                "#[doc = \"x\"]",
+                AnnotationSyntax::Hash
+            )),
+            vec!["doc(\"x\")"]
+        );
+    }
+
+    #[test]
+    fn test_search_terms() {
+        let annotation = Annotation::parse("tokio::main(flavor = \"current_thread\")").unwrap();
+        assert_eq!(
+            annotation.search_terms(),
+            vec!["tokio::main", "tokio", "main"]
+        );
+        assert!(annotation.matches("Main"));
+        assert!(!annotation.matches("tok"));
+    }
+}
@@ -1,5 +1,6 @@
 //! C language parser implementation
 
+use crate::parsing::annotation::{AnnotationNodes, AnnotationSyntax, attach_annotations};
 use crate::parsing::method_call::MethodCall;
 use crate::parsing::parser::check_recursion_depth;
 use crate::parsing::{
@@ -11,6 +12,13 @@ use crate::{FileId, Symbol, SymbolKind};
 use std::any::Any;
 use tree_sitter::{Node, Parser};
 
+/// Standard attributes read into `Symbol::annotations`
+const ANNOTATION_NODES: AnnotationNodes = AnnotationNodes {
+    syntax: AnnotationSyntax::DoubleBracket,
+    annotations: &["attribute_declaration"],
+    containers: &[],
+};
+
 pub struct CParser {
     parser: Parser,
     context: ParserContext,
@@ -812,28 +820,37 @@ impl CParser {
     fn find_calls_in_node<'a>(
         node: Node,
         code: &'a str,
+        caller: &'a str,
         calls: &mut Vec<(&'a str, &'a str, Range)>,
     ) {
-        // Simple implementation that doesn't track containing functions
+        // Calls inside a function body are attributed to that function
+        let caller = if node.kind() == "function_definition" {
+            node.child_by_field_name("declarator")
+                .and_then(Self::find_function_name_node)
+                .map(|name_node| &code[name_node.byte_range()])
+                .unwrap_or(caller)
+        } else {
+            caller
+        };
+
         if node.kind() == "call_expression" {
             if let Some(function_node) = node.child_by_field_name("function") {
                 let target_name = &code[function_node.byte_range()];
-                // We don't have caller information in this simple implementation
                 let range = Range::new(
                     node.start_position().row as u32,
                     node.start_position().column as u16,
                     node.end_position().row as u32,
                     node.end_position().column as u16,
                 );
-                // Use empty string for caller as we don't track it in this simple implementation
-                calls.push(("", target_name, range));
+                // Calls outside any function keep an empty caller
+                calls.push((caller, target_name, range));
             }
         }
 
         // Process children
         for i in 0..node.child_count() {
             if let Some(child) = node.child(i) {
-                Self::find_calls_in_node(child, code, calls);
+                Self::find_calls_in_node(child, code, caller, calls);
             }
         }
     }
@@ -947,6 +964,7 @@ impl LanguageParser for CParser {
 
         // Start recursion at depth 0
         self.extract_symbols_from_node(root_node, code, file_id, &mut symbols, symbol_counter, 0);
+        attach_annotations(root_node, code, &mut symbols, &ANNOTATION_NODES);
 
         symbols
     }
@@ -969,7 +987,7 @@ impl LanguageParser for CParser {
         let root_node = tree.root_node();
         let mut calls = Vec::new();
 
-        Self::find_calls_in_node(root_node, code, &mut calls);
+        Self::find_calls_in_node(root_node, code, "", &mut calls);
         calls
     }
 
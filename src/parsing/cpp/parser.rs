@@ -1,5 +1,6 @@
 //! C++ language parser implementation
 
+use crate::parsing::annotation::{AnnotationNodes, AnnotationSyntax, attach_annotations};
 use crate::parsing::context::ParserContext;
 use crate::parsing::method_call::MethodCall;
 use crate::parsing::parser::check_recursion_depth;
@@ -9,6 +10,13 @@ use crate::{FileId, Symbol, SymbolKind, Visibility};
 use std::any::Any;
 use tree_sitter::{Node, Parser};
 
+/// Standard attributes read into `Symbol::annotations`
+const ANNOTATION_NODES: AnnotationNodes = AnnotationNodes {
+    syntax: AnnotationSyntax::DoubleBracket,
+    annotations: &["attribute_declaration"],
+    containers: &[],
+};
+
 pub struct CppParser {
     parser: Parser,
     context: ParserContext,
@@ -735,6 +743,7 @@ impl LanguageParser for CppParser {
         let mut symbols = Vec::new();
 
         self.extract_symbols_from_node(root_node, code, file_id, &mut symbols, symbol_counter, 0);
+        attach_annotations(root_node, code, &mut symbols, &ANNOTATION_NODES);
 
         symbols
     }
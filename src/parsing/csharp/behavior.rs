@@ -75,6 +75,15 @@ impl LanguageBehavior for CSharpBehavior {
         tree_sitter_c_sharp::LANGUAGE.into()
     }
 
+    fn extract_routes(
+        &self,
+        source: &str,
+        _file_path: &str,
+        symbols: &[crate::Symbol],
+    ) -> Vec<crate::parsing::RouteEntry> {
+        super::routes::extract_routes(source, symbols)
+    }
+
     fn module_separator(&self) -> &'static str {
         "." // C# uses dots for namespace separation
     }
@@ -292,6 +301,13 @@ impl LanguageBehavior for CSharpBehavior {
         symbol.module_path = Some(module_path.to_string().into());
         symbol.scope_context = Some(crate::symbol::ScopeContext::Global);
         symbol.language_id = Some(language_id);
+        symbol.stable_id = Some(crate::StableSymbolId::derive(
+            language_id.as_str(),
+            module_path,
+            &symbol.name,
+            symbol.kind,
+            0,
+        ));
 
         document_index
             .index_symbol(&symbol, &path_str)
@@ -45,6 +45,7 @@ pub mod behavior;
 pub mod definition;
 pub mod parser;
 pub mod resolution;
+pub mod routes;
 
 pub use behavior::CSharpBehavior;
 pub use definition::CSharpLanguage;
@@ -25,6 +25,7 @@
 //! - External framework references (e.g., System.Console) require special handling
 
 use crate::parsing::Import;
+use crate::parsing::annotation::{AnnotationNodes, AnnotationSyntax, attach_annotations};
 use crate::parsing::parser::check_recursion_depth;
 use crate::parsing::{
     HandledNode, LanguageParser, MethodCall, NodeTracker, NodeTrackingState, ParserContext,
@@ -36,6 +37,13 @@ use std::any::Any;
 use std::collections::HashSet;
 use tree_sitter::{Language, Node, Parser};
 
+/// Attribute lists read into `Symbol::annotations`
+const ANNOTATION_NODES: AnnotationNodes = AnnotationNodes {
+    syntax: AnnotationSyntax::Bracket,
+    annotations: &["attribute_list"],
+    containers: &[],
+};
+
 /// C# language parser using tree-sitter
 ///
 /// This parser traverses C# Abstract Syntax Trees (AST) to extract symbols,
@@ -119,6 +127,7 @@ impl CSharpParser {
                     "", // Module path will be determined by behavior
                     0,
                 );
+                attach_annotations(root_node, code, &mut symbols, &ANNOTATION_NODES);
             }
             None => {
                 eprintln!("Failed to parse C# file");
@@ -284,12 +284,17 @@ impl ResolutionScope for CSharpResolutionContext {
                 // Definition relationship
                 self.resolve(to_name)
             }
+            RelationKind::Routes => {
+                // Route to its handler method
+                self.resolve(to_name)
+            }
             RelationKind::CalledBy
             | RelationKind::ExtendedBy
             | RelationKind::ImplementedBy
             | RelationKind::UsedBy
             | RelationKind::DefinedIn
-            | RelationKind::ReferencedBy => {
+            | RelationKind::ReferencedBy
+            | RelationKind::RoutedBy => {
                 // Reverse relationships - typically used for finding references
                 self.resolve(to_name)
             }
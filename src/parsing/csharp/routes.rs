@@ -0,0 +1,198 @@
+//! HTTP route extraction for ASP.NET Core
+//!
+//! - Controllers: `[HttpGet("{id}")]` / `[Route("...")]` on actions, prefixed by
+//!   the controller's `[Route("api/[controller]")]`
+//! - Minimal APIs: `app.MapPost("/orders", CreateOrder)`, including groups from
+//!   `var orders = app.MapGroup("/orders")`
+
+use crate::parsing::route::{
+    self, ANY_METHOD, RouteEntry, arguments, find_calls, http_method, http_methods_in, join_path,
+    path_argument,
+};
+use crate::{Symbol, SymbolKind};
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+static MAP_CALL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b(\w+)\s*\.\s*Map(Get|Post|Put|Delete|Patch|Methods)\s*\(")
+        .expect("Invalid regex")
+});
+
+static MAP_GROUP: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b(\w+)\s*=\s*(\w+)\s*\.\s*MapGroup\s*\(").expect("Invalid regex")
+});
+
+const TEMPLATE_KEYS: [&str; 2] = ["template", "Template"];
+
+pub fn extract_routes(source: &str, symbols: &[Symbol]) -> Vec<RouteEntry> {
+    let mut routes = controller_routes(symbols);
+    routes.extend(minimal_api_routes(source));
+    route::resolve_local_handlers(&mut routes, symbols);
+    routes
+}
+
+/// Attribute name without the optional `Attribute` suffix
+fn attribute_name(name: &str) -> &str {
+    name.strip_suffix("Attribute").unwrap_or(name)
+}
+
+fn template(arguments: Option<&str>) -> Option<String> {
+    path_argument(arguments.unwrap_or_default(), &TEMPLATE_KEYS)
+}
+
+fn controller_routes(symbols: &[Symbol]) -> Vec<RouteEntry> {
+    let mut routes = Vec::new();
+    for symbol in symbols.iter().filter(|s| s.kind == SymbolKind::Method) {
+        let class = route::enclosing_class(symbols, symbol);
+        let controller = class
+            .map(|c| route::handler_short_name(&c.name))
+            .map(|name| name.strip_suffix("Controller").unwrap_or(name))
+            .unwrap_or_default();
+        let action = route::handler_short_name(&symbol.name);
+        let prefix = class
+            .and_then(|c| {
+                c.annotations
+                    .iter()
+                    .find(|a| attribute_name(a.short_name()) == "Route")
+            })
+            .and_then(|a| template(a.arguments.as_deref()));
+
+        let mut verbs: Vec<(&str, Option<String>)> = Vec::new();
+        let mut action_route = None;
+        for annotation in &symbol.annotations {
+            let args = annotation.arguments.as_deref();
+            match attribute_name(annotation.short_name()) {
+                "Route" => action_route = template(args),
+                "AcceptVerbs" => verbs.extend(
+                    http_methods_in(args.unwrap_or_default())
+                        .into_iter()
+                        .map(|m| (m, None)),
+                ),
+                name => {
+                    if let Some(method) = name.strip_prefix("Http").and_then(http_method) {
+                        verbs.push((method, template(args)));
+                    }
+                }
+            }
+        }
+        if verbs.is_empty() {
+            if action_route.is_none() {
+                continue;
+            }
+            verbs.push((ANY_METHOD, None));
+        }
+
+        for (method, verb_template) in verbs {
+            let Some(path) = verb_template.or_else(|| action_route.clone()).map_or_else(
+                || prefix.clone(),
+                |path| Some(join_path(prefix.as_deref().unwrap_or_default(), &path)),
+            ) else {
+                continue;
+            };
+            let path = path
+                .replace("[controller]", controller)
+                .replace("[action]", action);
+            routes.push(
+                RouteEntry::new(method, &path, "aspnet", symbol.range.start_line)
+                    .with_handler_symbol(symbol),
+            );
+        }
+    }
+    routes
+}
+
+fn minimal_api_routes(source: &str) -> Vec<RouteEntry> {
+    // Route group variables and their full prefixes
+    let mut groups: HashMap<&str, String> = HashMap::new();
+    for call in find_calls(source, &MAP_GROUP) {
+        let (Some(variable), Some(parent)) = (call.groups[0], call.groups[1]) else {
+            continue;
+        };
+        if let Some(prefix) = arguments(call.args)
+            .first()
+            .and_then(|a| route::string_literal(a))
+        {
+            let parent = groups.get(parent).cloned().unwrap_or_default();
+            groups.insert(variable, join_path(&parent, &prefix));
+        }
+    }
+
+    let mut routes = Vec::new();
+    for call in find_calls(source, &MAP_CALL) {
+        let (Some(receiver), Some(verb)) = (call.groups[0], call.groups[1]) else {
+            continue;
+        };
+        let args = arguments(call.args);
+        let Some(path) = args.first().and_then(|a| route::string_literal(a)) else {
+            continue;
+        };
+        let path = match groups.get(receiver) {
+            Some(prefix) => join_path(prefix, &path),
+            None => path,
+        };
+        let methods = if verb == "Methods" {
+            args.get(1).map(|a| http_methods_in(a)).unwrap_or_default()
+        } else {
+            http_method(verb).into_iter().collect()
+        };
+        let handler = args.last().copied().filter(|_| args.len() > 1);
+        for method in methods {
+            routes.push(
+                RouteEntry::new(method, &path, "aspnet", call.line)
+                    .with_handler(handler.unwrap_or_default()),
+            );
+        }
+    }
+    routes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::CSharpParser;
+
+    fn parse(source: &str) -> Vec<Symbol> {
+        let mut parser = CSharpParser::new().unwrap();
+        route::parse_symbols(&mut parser, source)
+    }
+
+    #[test]
+    fn test_controller_and_minimal_api_routes() {
+        let source = r#"
+[ApiController]
+[Route("api/[controller]")]
+public class OrdersController : ControllerBase
+{
+    [HttpGet("{id}")]
+    public IActionResult Get(int id) => Ok();
+
+    [HttpPost]
+    public IActionResult Create(Order order) => Ok();
+}
+
+public static class Endpoints
+{
+    public static void Map(WebApplication app)
+    {
+        var orders = app.MapGroup("/v2/orders");
+        orders.MapDelete("/{id}", DeleteOrder);
+        app.MapGet("/health", () => "ok");
+    }
+}
+"#;
+        let symbols = parse(source);
+        let routes = extract_routes(source, &symbols);
+        let names: Vec<String> = routes.iter().map(|r| r.signature()).collect();
+
+        assert_eq!(
+            names,
+            vec![
+                "GET /api/Orders/{id} -> Get (aspnet)",
+                "POST /api/Orders -> Create (aspnet)",
+                "DELETE /v2/orders/{id} -> DeleteOrder (aspnet)",
+                "GET /health -> <inline> (aspnet)",
+            ]
+        );
+    }
+}
@@ -6,8 +6,9 @@
 use super::{
     CBehavior, CParser, CSharpBehavior, CSharpParser, CppBehavior, CppParser, GdscriptBehavior,
     GdscriptParser, GoBehavior, GoParser, KotlinBehavior, KotlinParser, Language, LanguageBehavior,
-    LanguageId, LanguageParser, PhpBehavior, PhpParser, PythonBehavior, PythonParser, RustBehavior,
-    RustParser, TypeScriptBehavior, TypeScriptParser, get_registry,
+    LanguageId, LanguageParser, PhpBehavior, PhpParser, ProtobufBehavior, ProtobufParser,
+    PythonBehavior, PythonParser, RustBehavior, RustParser, TypeScriptBehavior, TypeScriptParser,
+    get_registry,
 };
 use crate::{IndexError, IndexResult, Settings};
 use std::sync::Arc;
@@ -172,6 +173,10 @@ impl ParserFactory {
                 let parser = KotlinParser::new().map_err(|e| IndexError::General(e.to_string()))?;
                 Ok(Box::new(parser))
             }
+            Language::Protobuf => {
+                let parser = ProtobufParser::new().map_err(IndexError::General)?;
+                Ok(Box::new(parser))
+            }
         }
     }
 
@@ -288,6 +293,13 @@ impl ParserFactory {
                     behavior: Box::new(KotlinBehavior::new()),
                 }
             }
+            Language::Protobuf => {
+                let parser = ProtobufParser::new().map_err(IndexError::General)?;
+                ParserWithBehavior {
+                    parser: Box::new(parser),
+                    behavior: Box::new(ProtobufBehavior::new()),
+                }
+            }
         };
 
         Ok(result)
@@ -324,6 +336,7 @@ impl ParserFactory {
             Language::C,
             Language::Cpp,
             Language::Gdscript,
+            Language::Protobuf,
         ]
         .into_iter()
         .filter(|&lang| self.is_language_enabled(lang))
@@ -3,6 +3,7 @@
 //! Provides basic symbol extraction for Godot's GDScript using tree-sitter.
 
 use crate::parsing::Import;
+use crate::parsing::annotation::{AnnotationNodes, AnnotationSyntax, attach_annotations};
 use crate::parsing::parser::check_recursion_depth;
 use crate::parsing::{
     HandledNode, Language, LanguageParser, NodeTracker, NodeTrackingState, ParserContext, ScopeType,
@@ -12,6 +13,13 @@ use crate::{FileId, Range, Symbol, SymbolKind};
 use std::any::Any;
 use tree_sitter::{Node, Parser};
 
+/// Annotations read into `Symbol::annotations`
+const ANNOTATION_NODES: AnnotationNodes = AnnotationNodes {
+    syntax: AnnotationSyntax::At,
+    annotations: &["annotation"],
+    containers: &["annotations"],
+};
+
 const SCRIPT_SCOPE: &str = "<script>";
 
 /// Parser for GDScript source files
@@ -1007,6 +1015,7 @@ impl LanguageParser for GdscriptParser {
             &mut context,
             0,
         );
+        attach_annotations(root, code, &mut symbols, &ANNOTATION_NODES);
 
         symbols
     }
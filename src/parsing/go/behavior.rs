@@ -48,6 +48,22 @@ impl LanguageBehavior for GoBehavior {
     fn get_language(&self) -> Language {
         tree_sitter_go::LANGUAGE.into()
     }
+    fn is_test_symbol(&self, symbol: &crate::Symbol, file_path: &str) -> bool {
+        // `go test` runs TestXxx and FuzzXxx functions from *_test.go files;
+        // TestMain is the harness entry point, not a test
+        if symbol.kind != crate::SymbolKind::Function || !file_path.ends_with("_test.go") {
+            return false;
+        }
+        let name: &str = &symbol.name;
+        if name == "TestMain" {
+            return false;
+        }
+        ["Test", "Fuzz"].iter().any(|prefix| {
+            name.strip_prefix(prefix)
+                .is_some_and(|rest| !rest.starts_with(|c: char| c.is_lowercase()))
+        })
+    }
+
     fn module_separator(&self) -> &'static str {
         "/"
     }
@@ -585,6 +601,29 @@ mod tests {
     use crate::parsing::registry::LanguageId;
     use std::path::Path;
 
+    #[test]
+    fn test_is_test_symbol() {
+        use crate::{Range, Symbol, SymbolKind};
+
+        let behavior = GoBehavior::new();
+        let function = |name: &str| {
+            Symbol::new(
+                SymbolId::new(1).unwrap(),
+                name,
+                SymbolKind::Function,
+                FileId::new(1).unwrap(),
+                Range::new(0, 0, 1, 0),
+            )
+        };
+
+        assert!(behavior.is_test_symbol(&function("TestAdd"), "calc/calc_test.go"));
+        assert!(behavior.is_test_symbol(&function("Test_add"), "calc/calc_test.go"));
+        assert!(behavior.is_test_symbol(&function("FuzzParse"), "calc/calc_test.go"));
+        assert!(!behavior.is_test_symbol(&function("Testify"), "calc/calc_test.go"));
+        assert!(!behavior.is_test_symbol(&function("TestMain"), "calc/calc_test.go"));
+        assert!(!behavior.is_test_symbol(&function("TestAdd"), "calc/calc.go"));
+    }
+
     #[test]
     fn test_module_separator() {
         let behavior = GoBehavior::new();
@@ -796,6 +835,9 @@ mod tests {
             visibility: Visibility::Private, // Will be updated by configure_symbol
             scope_context: None,
             language_id: Some(LanguageId::new("go")),
+            is_test: false,
+            annotations: Vec::new(),
+            stable_id: None,
         };
 
         behavior.configure_symbol(&mut symbol, Some("pkg/utils"));
@@ -825,6 +867,9 @@ mod tests {
             visibility: Visibility::Public, // Will be updated by configure_symbol
             scope_context: None,
             language_id: Some(LanguageId::new("go")),
+            is_test: false,
+            annotations: Vec::new(),
+            stable_id: None,
         };
 
         behavior.configure_symbol(&mut symbol, None);
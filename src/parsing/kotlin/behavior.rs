@@ -100,6 +100,32 @@ impl LanguageBehavior for KotlinBehavior {
         }
     }
 
+    fn extract_routes(
+        &self,
+        source: &str,
+        _file_path: &str,
+        symbols: &[crate::Symbol],
+    ) -> Vec<crate::parsing::RouteEntry> {
+        super::routes::extract_routes(source, symbols)
+    }
+
+    fn is_test_symbol(&self, symbol: &Symbol, _file_path: &str) -> bool {
+        use crate::parsing::test_detection::has_annotation;
+
+        // JUnit 4/5 and kotlin.test annotations
+        matches!(symbol.kind, SymbolKind::Function | SymbolKind::Method)
+            && has_annotation(
+                symbol,
+                &[
+                    "Test",
+                    "ParameterizedTest",
+                    "RepeatedTest",
+                    "TestFactory",
+                    "TestTemplate",
+                ],
+            )
+    }
+
     fn module_separator(&self) -> &'static str {
         "."
     }
@@ -277,6 +303,25 @@ impl LanguageBehavior for KotlinBehavior {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_is_test_symbol() {
+        use crate::parsing::{KotlinParser, LanguageParser};
+        use crate::types::SymbolCounter;
+
+        let behavior = KotlinBehavior::new();
+        let source = "class CalcTest {\n    @Test\n    fun multiplies() {}\n\n    @BeforeEach\n    fun setUp() {}\n}\n";
+        let symbols = KotlinParser::new().unwrap().parse(
+            source,
+            FileId::new(1).unwrap(),
+            &mut SymbolCounter::new(),
+        );
+        let function =
+            |name: &str| -> &Symbol { symbols.iter().find(|s| s.name.as_ref() == name).unwrap() };
+
+        assert!(behavior.is_test_symbol(function("multiplies"), "CalcTest.kt"));
+        assert!(!behavior.is_test_symbol(function("setUp"), "CalcTest.kt"));
+    }
+
     #[test]
     fn test_parse_visibility() {
         let behavior = KotlinBehavior::new();
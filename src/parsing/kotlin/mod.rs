@@ -5,6 +5,7 @@ pub mod behavior;
 pub mod definition;
 pub mod parser;
 pub mod resolution;
+pub mod routes;
 
 pub use audit::KotlinParserAudit;
 pub use behavior::KotlinBehavior;
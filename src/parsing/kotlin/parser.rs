@@ -4,6 +4,7 @@
 
 use crate::config;
 use crate::parsing::Import;
+use crate::parsing::annotation::{AnnotationNodes, AnnotationSyntax, attach_annotations};
 use crate::parsing::parser::check_recursion_depth;
 use crate::parsing::{
     HandledNode, Language, LanguageParser, NodeTracker, NodeTrackingState, ParserContext, ScopeType,
@@ -16,6 +17,13 @@ use std::collections::{HashMap, HashSet};
 use std::sync::OnceLock;
 use tree_sitter::{Node, Parser};
 
+/// Annotations read into `Symbol::annotations`
+const ANNOTATION_NODES: AnnotationNodes = AnnotationNodes {
+    syntax: AnnotationSyntax::At,
+    annotations: &["annotation"],
+    containers: &["modifiers"],
+};
+
 // Constants for commonly accessed node kinds
 const FILE_SCOPE: &str = "<file>";
 const NODE_CLASS_DECLARATION: &str = "class_declaration";
@@ -1933,6 +1941,7 @@ impl LanguageParser for KotlinParser {
             &mut context,
             0,
         );
+        attach_annotations(root, code, &mut symbols, &ANNOTATION_NODES);
 
         symbols
     }
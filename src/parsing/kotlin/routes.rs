@@ -0,0 +1,214 @@
+//! HTTP route extraction for Spring and Ktor
+//!
+//! - Spring: `@GetMapping("/{id}")` and `@RequestMapping(method = [...])` on
+//!   methods, prefixed by a class-level `@RequestMapping("/orders")`
+//! - Ktor routing DSL: `route("/orders") { get("/{id}") { ... } }`; handlers are
+//!   the functions that declare the routing block
+
+use crate::parsing::route::{
+    self, ANY_METHOD, RouteEntry, arguments, http_method, http_methods_in, join_path,
+    keyword_argument, path_argument,
+};
+use crate::{Symbol, SymbolKind};
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+static KTOR_BLOCK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"\b(get|post|put|delete|patch|head|options|route)\s*(?:\(\s*"([^"]*)"[^)]*\))?\s*\{"#,
+    )
+    .expect("Invalid regex")
+});
+
+const PATH_KEYS: [&str; 2] = ["value", "path"];
+
+pub fn extract_routes(source: &str, symbols: &[Symbol]) -> Vec<RouteEntry> {
+    let mut routes = spring_routes(symbols);
+    if source.contains("io.ktor") {
+        routes.extend(ktor_routes(source));
+    }
+    route::resolve_local_handlers(&mut routes, symbols);
+    routes
+}
+
+fn spring_routes(symbols: &[Symbol]) -> Vec<RouteEntry> {
+    let mut routes = Vec::new();
+    for symbol in symbols
+        .iter()
+        .filter(|s| matches!(s.kind, SymbolKind::Method | SymbolKind::Function))
+    {
+        let prefix = route::enclosing_class(symbols, symbol)
+            .and_then(|class| {
+                class
+                    .annotations
+                    .iter()
+                    .find(|a| a.short_name() == "RequestMapping")
+            })
+            .and_then(|a| path_argument(a.arguments.as_deref().unwrap_or_default(), &PATH_KEYS))
+            .unwrap_or_default();
+
+        for annotation in &symbol.annotations {
+            let args = annotation.arguments.as_deref().unwrap_or_default();
+            let methods = match annotation.short_name() {
+                "RequestMapping" => {
+                    let methods = keyword_argument(&arguments(args), "method")
+                        .map(http_methods_in)
+                        .unwrap_or_default();
+                    if methods.is_empty() {
+                        vec![ANY_METHOD]
+                    } else {
+                        methods
+                    }
+                }
+                name => match name.strip_suffix("Mapping").and_then(http_method) {
+                    Some(method) => vec![method],
+                    None => continue,
+                },
+            };
+            let path = join_path(
+                &prefix,
+                &path_argument(args, &PATH_KEYS).unwrap_or_default(),
+            );
+            for method in methods {
+                routes.push(
+                    RouteEntry::new(method, &path, "spring", symbol.range.start_line)
+                        .with_handler_symbol(symbol),
+                );
+            }
+        }
+    }
+    routes
+}
+
+/// Walk the routing DSL, tracking `route(...)` prefixes by brace depth
+fn ktor_routes(source: &str) -> Vec<RouteEntry> {
+    // Byte offset of each block's `{` -> (function, path)
+    let blocks: HashMap<usize, (&str, &str)> = KTOR_BLOCK
+        .captures_iter(source)
+        .filter_map(|c| {
+            let whole = c.get(0)?;
+            Some((
+                whole.end() - 1,
+                (c.get(1)?.as_str(), c.get(2).map_or("", |m| m.as_str())),
+            ))
+        })
+        .collect();
+
+    let mut routes = Vec::new();
+    let mut prefixes: Vec<(usize, &str)> = Vec::new();
+    let mut depth = 0usize;
+    let mut line = 0u32;
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+
+    for (offset, c) in source.char_indices() {
+        if c == '\n' {
+            line += 1;
+        }
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' => quote = Some(c),
+            '{' => {
+                depth += 1;
+                match blocks.get(&offset) {
+                    Some(("route", path)) => prefixes.push((depth, path)),
+                    Some((method, path)) => {
+                        let prefix = prefixes
+                            .iter()
+                            .fold(String::new(), |prefix, (_, p)| join_path(&prefix, p));
+                        if let Some(method) = http_method(method) {
+                            routes.push(RouteEntry::new(
+                                method,
+                                &join_path(&prefix, path),
+                                "ktor",
+                                line,
+                            ));
+                        }
+                    }
+                    None => {}
+                }
+            }
+            '}' => {
+                if prefixes.last().is_some_and(|(d, _)| *d == depth) {
+                    prefixes.pop();
+                }
+                depth = depth.saturating_sub(1);
+            }
+            _ => {}
+        }
+    }
+    routes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::KotlinParser;
+
+    fn parse(source: &str) -> Vec<Symbol> {
+        let mut parser = KotlinParser::new().unwrap();
+        route::parse_symbols(&mut parser, source)
+    }
+
+    #[test]
+    fn test_spring_controller() {
+        let source = r#"
+@RestController
+@RequestMapping("/orders")
+class OrderController {
+    @GetMapping("/{id}")
+    fun find(@PathVariable id: Long): Order = TODO()
+
+    @PostMapping
+    fun create(@RequestBody order: Order): Order = TODO()
+}
+"#;
+        let symbols = parse(source);
+        let routes = extract_routes(source, &symbols);
+        let names: Vec<String> = routes.iter().map(|r| r.signature()).collect();
+
+        assert_eq!(
+            names,
+            vec![
+                "GET /orders/{id} -> find (spring)",
+                "POST /orders -> create (spring)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ktor_routing() {
+        let source = r#"
+import io.ktor.server.routing.*
+
+fun Application.orderRoutes() {
+    routing {
+        route("/orders") {
+            get { call.respond(listOf<String>()) }
+            post("/{id}/cancel") { call.respondText("ok") }
+        }
+        get("/health") { call.respondText("{}") }
+    }
+}
+"#;
+        let symbols = parse(source);
+        let routes = extract_routes(source, &symbols);
+        let names: Vec<String> = routes.iter().map(|r| r.name()).collect();
+
+        assert_eq!(
+            names,
+            vec!["GET /orders", "POST /orders/{id}/cancel", "GET /health"]
+        );
+        assert!(routes.iter().all(|r| r.handler_id.is_some()));
+    }
+}
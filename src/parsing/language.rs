@@ -19,6 +19,7 @@ pub enum Language {
     CSharp,
     Gdscript,
     Kotlin,
+    Protobuf,
 }
 
 impl Language {
@@ -40,6 +41,7 @@ impl Language {
             Language::CSharp => super::LanguageId::new("csharp"),
             Language::Gdscript => super::LanguageId::new("gdscript"),
             Language::Kotlin => super::LanguageId::new("kotlin"),
+            Language::Protobuf => super::LanguageId::new("protobuf"),
         }
     }
 
@@ -60,6 +62,7 @@ impl Language {
             "csharp" => Some(Language::CSharp),
             "gdscript" => Some(Language::Gdscript),
             "kotlin" => Some(Language::Kotlin),
+            "protobuf" => Some(Language::Protobuf),
             _ => None,
         }
     }
@@ -95,6 +98,7 @@ impl Language {
             "cs" | "csx" => Some(Language::CSharp),
             "gd" => Some(Language::Gdscript),
             "kt" | "kts" => Some(Language::Kotlin),
+            "proto" => Some(Language::Protobuf),
             _ => None,
         }
     }
@@ -122,6 +126,7 @@ impl Language {
             Language::CSharp => &["cs", "csx"],
             Language::Gdscript => &["gd"],
             Language::Kotlin => &["kt", "kts"],
+            Language::Protobuf => &["proto"],
         }
     }
 
@@ -139,6 +144,7 @@ impl Language {
             Language::CSharp => "csharp",
             Language::Gdscript => "gdscript",
             Language::Kotlin => "kotlin",
+            Language::Protobuf => "protobuf",
         }
     }
 
@@ -156,6 +162,7 @@ impl Language {
             Language::CSharp => "C#",
             Language::Gdscript => "GDScript",
             Language::Kotlin => "Kotlin",
+            Language::Protobuf => "Protocol Buffers",
         }
     }
 }
@@ -190,6 +197,7 @@ mod tests {
         assert_eq!(Language::from_extension("go.sum"), Some(Language::Go));
         assert_eq!(Language::from_extension("txt"), None);
         assert_eq!(Language::from_extension("gd"), Some(Language::Gdscript));
+        assert_eq!(Language::from_extension("proto"), Some(Language::Protobuf));
     }
 
     #[test]
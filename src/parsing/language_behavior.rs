@@ -61,6 +61,7 @@ use crate::parsing::resolution::{
     GenericInheritanceResolver, GenericResolutionContext, ImportBinding, ImportOrigin,
     InheritanceResolver, ResolutionScope, ScopeLevel,
 };
+use crate::parsing::route::RouteEntry;
 use crate::relationship::RelationKind;
 use crate::storage::DocumentIndex;
 use crate::{FileId, IndexError, IndexResult, Symbol, SymbolId, Visibility};
@@ -170,6 +171,35 @@ pub trait LanguageBehavior: Send + Sync {
         }
     }
 
+    /// Check whether a symbol is a test recognised by the language's test framework
+    ///
+    /// Called for every symbol during indexing. Parsers have filled
+    /// `Symbol::annotations` by then, so behaviors can look at annotations and
+    /// naming conventions (see [`crate::parsing::test_detection`]).
+    ///
+    /// Default: not a test. Parsers may also set `Symbol::is_test` directly,
+    /// which is kept regardless of this check.
+    fn is_test_symbol(&self, _symbol: &Symbol, _file_path: &str) -> bool {
+        false
+    }
+
+    /// Extract HTTP routes registered in a file by the language's web frameworks
+    ///
+    /// `symbols` are the file's symbols after configuration, so extractors can
+    /// use their annotations and ranges. Handlers defined in the same file
+    /// should be set on [`RouteEntry::handler_id`]; the rest are resolved by
+    /// name across files (see [`crate::parsing::route`]).
+    ///
+    /// Default: the language has no route extractor.
+    fn extract_routes(
+        &self,
+        _source: &str,
+        _file_path: &str,
+        _symbols: &[Symbol],
+    ) -> Vec<RouteEntry> {
+        Vec::new()
+    }
+
     /// Calculate the module path from a file path according to language conventions
     ///
     /// This method converts a file system path to a language-specific module path.
@@ -683,6 +713,10 @@ pub trait LanguageBehavior: Send + Sync {
             imported_files.len()
         );
 
+        // Later files overwrite names of earlier ones, so load them in file ID order
+        let mut imported_files: Vec<FileId> = imported_files.into_iter().collect();
+        imported_files.sort_by_key(|file_id| file_id.value());
+
         // Only load public symbols from files we import from
         for imported_file_id in &imported_files {
             if *imported_file_id == file_id {
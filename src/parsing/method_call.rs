@@ -11,13 +11,17 @@
 //! - **Memory Efficiency**: Minimal overhead compared to tuple representation
 //! - **Future Extensibility**: Foundation for type-aware resolution
 //!
-//! # Current Integration Status
+//! # Integration
 //!
-//! This struct is not yet integrated into the indexing pipeline. The current
-//! system uses `(String, String, Range)` tuples with patterns like:
-//! - `"self.method"` for self calls
-//! - `"receiver@method"` for instance calls
-//! - `"method"` for plain function calls
+//! The indexer collects calls with `LanguageParser::find_method_calls` and
+//! keeps them per file until cross-file resolution. An instance call is
+//! resolved through its receiver's type: the enclosing type for `self`/`this`,
+//! otherwise the type `find_variable_types` inferred for the receiver. The
+//! language's `InheritanceResolver` then picks the type that provides the
+//! method, so `obj.save()` binds to the `save` of `obj`'s class or the base
+//! class it inherits from (see `indexing::type_methods`). Static calls look
+//! up the method on the receiver type directly. Calls whose receiver type is
+//! unknown fall back to name-based resolution.
 //!
 //! # Future Enhancements
 //!
@@ -26,6 +30,7 @@
 //! - Generic type parameter tracking
 
 use crate::Range;
+use serde::{Deserialize, Serialize};
 
 /// Represents a method call with rich receiver information
 ///
@@ -56,7 +61,7 @@ use crate::Range;
 /// let call = MethodCall::new("save", "validate", range)
 ///     .with_receiver("self");
 /// ```
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MethodCall {
     /// The function/method making the call
     ///
@@ -165,8 +170,8 @@ impl MethodCall {
     ///
     /// # Note
     ///
-    /// The current format loses receiver information for instance calls.
-    /// This is a limitation we aim to address with full MethodCall integration.
+    /// The tuple format loses receiver information for instance calls; the
+    /// indexer resolves calls from the `MethodCall` itself.
     #[must_use = "The converted tuple should be used"]
     pub fn to_simple_call(&self) -> (String, String, Range) {
         let target = if let Some(receiver) = &self.receiver {
@@ -13,6 +13,7 @@
 //! - C#
 //! - PHP
 //! - GDScript
+//! - Protocol Buffers (`.proto` スキーマ)
 //!
 //! # 主要なコンポーネント
 //!
@@ -31,6 +32,7 @@
 //! // let symbols = parser.parse(source, file_id);
 //! ```
 
+pub mod annotation;
 pub mod behavior_state;
 pub mod c;
 pub mod context;
@@ -46,12 +48,16 @@ pub mod language_behavior;
 pub mod method_call;
 pub mod parser;
 pub mod php;
+pub mod protobuf;
 pub mod python;
 pub mod registry;
 pub mod resolution;
+pub mod route;
 pub mod rust;
+pub mod test_detection;
 pub mod typescript;
 
+pub use annotation::{Annotation, AnnotationSyntax};
 pub use c::{CBehavior, CParser};
 pub use context::{ParserContext, ScopeType};
 pub use cpp::{CppBehavior, CppParser};
@@ -69,11 +75,13 @@ pub use parser::{
     safe_truncate_str, truncate_for_display,
 };
 pub use php::{PhpBehavior, PhpParser};
+pub use protobuf::{ProtobufBehavior, ProtobufParser};
 pub use python::{PythonBehavior, PythonParser};
 pub use registry::{LanguageDefinition, LanguageId, LanguageRegistry, RegistryError, get_registry};
 pub use resolution::{
     GenericInheritanceResolver, GenericResolutionContext, InheritanceResolver, ResolutionScope,
     ScopeLevel,
 };
+pub use route::RouteEntry;
 pub use rust::{RustBehavior, RustParser};
 pub use typescript::{TypeScriptBehavior, TypeScriptParser};
@@ -67,6 +67,15 @@ impl LanguageBehavior for PhpBehavior {
         }
     }
 
+    fn extract_routes(
+        &self,
+        source: &str,
+        _file_path: &str,
+        symbols: &[crate::Symbol],
+    ) -> Vec<crate::parsing::RouteEntry> {
+        super::routes::extract_routes(source, symbols)
+    }
+
     fn module_separator(&self) -> &'static str {
         "\\" // PHP namespace separator
     }
@@ -10,6 +10,7 @@
 //! version, verify compatibility with node type names used in this implementation.
 
 use crate::parsing::Import;
+use crate::parsing::annotation::{AnnotationNodes, AnnotationSyntax, attach_annotations};
 use crate::parsing::parser::check_recursion_depth;
 use crate::parsing::{
     Language, LanguageParser, MethodCall, NodeTracker, NodeTrackingState, ParserContext, ScopeType,
@@ -20,6 +21,13 @@ use std::any::Any;
 use thiserror::Error;
 use tree_sitter::{Node, Parser};
 
+/// Attribute groups read into `Symbol::annotations`
+const ANNOTATION_NODES: AnnotationNodes = AnnotationNodes {
+    syntax: AnnotationSyntax::Hash,
+    annotations: &["attribute_group"],
+    containers: &["attribute_list"],
+};
+
 /// PHP-specific parsing errors
 #[derive(Error, Debug)]
 pub enum PhpParseError {
@@ -84,6 +92,7 @@ impl PhpParser {
             symbol_counter,
             0,
         );
+        attach_annotations(tree.root_node(), code, &mut symbols, &ANNOTATION_NODES);
         symbols
     }
 
@@ -0,0 +1,195 @@
+//! HTTP route extraction for Laravel and Symfony
+//!
+//! - Laravel route files: `Route::post('/orders', [OrderController::class, 'store'])`,
+//!   `Route::match([...])`, `Route::resource('photos', PhotoController::class)`
+//! - Symfony attributes: `#[Route('/orders/{id}', methods: ['GET'])]`, prefixed by
+//!   the controller's class-level `#[Route]`
+//!
+//! Laravel handlers are recorded as `Controller@method`.
+
+use crate::parsing::route::{
+    self, ANY_METHOD, RouteEntry, arguments, find_calls, http_method, http_methods_in, join_path,
+    keyword_argument, path_argument,
+};
+use crate::{Symbol, SymbolKind};
+use regex::Regex;
+use std::sync::LazyLock;
+
+static ROUTE_CALL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"\bRoute\s*::\s*(get|post|put|patch|delete|options|any|match|resource|apiResource)\s*\(",
+    )
+    .expect("Invalid regex")
+});
+
+/// Actions registered by `Route::resource`: (action, method, path suffix, api)
+const RESOURCE_ACTIONS: [(&str, &str, &str, bool); 7] = [
+    ("index", "GET", "", true),
+    ("create", "GET", "/create", false),
+    ("store", "POST", "", true),
+    ("show", "GET", "/{param}", true),
+    ("edit", "GET", "/{param}/edit", false),
+    ("update", "PUT", "/{param}", true),
+    ("destroy", "DELETE", "/{param}", true),
+];
+
+pub fn extract_routes(source: &str, symbols: &[Symbol]) -> Vec<RouteEntry> {
+    let mut routes = symfony_routes(symbols);
+    routes.extend(laravel_routes(source));
+    route::resolve_local_handlers(&mut routes, symbols);
+    routes
+}
+
+/// Laravel action as `Controller@method`; `None` for closures
+fn laravel_action(action: &str) -> Option<String> {
+    let action = action.trim();
+    if let Some(pair) = action.strip_prefix('[').and_then(|a| a.strip_suffix(']')) {
+        let parts = arguments(pair);
+        let controller = parts.first()?.strip_suffix("::class")?.trim();
+        let method = parts.get(1).and_then(|m| route::string_literal(m))?;
+        return Some(format!("{controller}@{method}"));
+    }
+    if let Some(controller) = action.strip_suffix("::class") {
+        return Some(format!("{}@__invoke", controller.trim()));
+    }
+    route::string_literal(action)
+}
+
+/// Route parameter name for a resource (`photos` -> `photo`)
+fn singular(resource: &str) -> String {
+    let name = resource.rsplit(['.', '/']).next().unwrap_or(resource);
+    if let Some(stem) = name.strip_suffix("ies") {
+        format!("{stem}y")
+    } else {
+        name.strip_suffix('s').unwrap_or(name).replace('-', "_")
+    }
+}
+
+fn laravel_routes(source: &str) -> Vec<RouteEntry> {
+    let mut routes = Vec::new();
+    for call in find_calls(source, &ROUTE_CALL) {
+        let Some(verb) = call.groups[0] else {
+            continue;
+        };
+        let args = arguments(call.args);
+
+        if matches!(verb, "resource" | "apiResource") {
+            let (Some(name), Some(controller)) = (
+                args.first().and_then(|a| route::string_literal(a)),
+                args.get(1).and_then(|a| a.strip_suffix("::class")),
+            ) else {
+                continue;
+            };
+            let base = name.replace('.', "/");
+            let param = singular(&name);
+            for (action, method, suffix, api) in RESOURCE_ACTIONS {
+                if verb == "apiResource" && !api {
+                    continue;
+                }
+                let path = format!("{base}{}", suffix.replace("param", &param));
+                routes.push(
+                    RouteEntry::new(method, &path, "laravel", call.line)
+                        .with_handler(&format!("{}@{action}", controller.trim())),
+                );
+            }
+            continue;
+        }
+
+        let (methods, path, action) = if verb == "match" {
+            (
+                args.first().map(|a| http_methods_in(a)).unwrap_or_default(),
+                args.get(1),
+                args.get(2),
+            )
+        } else {
+            let method = http_method(verb).unwrap_or(ANY_METHOD);
+            (vec![method], args.first(), args.get(1))
+        };
+        let Some(path) = path.and_then(|p| route::string_literal(p)) else {
+            continue;
+        };
+        let handler = action.and_then(|a| laravel_action(a));
+        for method in methods {
+            routes.push(
+                RouteEntry::new(method, &path, "laravel", call.line)
+                    .with_handler(handler.as_deref().unwrap_or_default()),
+            );
+        }
+    }
+    routes
+}
+
+fn symfony_routes(symbols: &[Symbol]) -> Vec<RouteEntry> {
+    let mut routes = Vec::new();
+    for symbol in symbols.iter().filter(|s| s.kind == SymbolKind::Method) {
+        let prefix = route::enclosing_class(symbols, symbol)
+            .and_then(|class| class.annotations.iter().find(|a| a.short_name() == "Route"))
+            .and_then(|a| path_argument(a.arguments.as_deref().unwrap_or_default(), &["path"]))
+            .unwrap_or_default();
+
+        for annotation in symbol
+            .annotations
+            .iter()
+            .filter(|a| a.short_name() == "Route")
+        {
+            let args = annotation.arguments.as_deref().unwrap_or_default();
+            let Some(path) = path_argument(args, &["path"]) else {
+                continue;
+            };
+            let methods = keyword_argument(&arguments(args), "methods")
+                .map(http_methods_in)
+                .unwrap_or_default();
+            let methods = if methods.is_empty() {
+                vec![ANY_METHOD]
+            } else {
+                methods
+            };
+            for method in methods {
+                routes.push(
+                    RouteEntry::new(
+                        method,
+                        &join_path(&prefix, &path),
+                        "symfony",
+                        symbol.range.start_line,
+                    )
+                    .with_handler_symbol(symbol),
+                );
+            }
+        }
+    }
+    routes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_laravel_routes() {
+        let source = r#"<?php
+use App\Http\Controllers\OrderController;
+
+Route::post('/orders', [OrderController::class, 'store']);
+Route::get('/health', function () { return 'ok'; });
+Route::match(['get', 'post'], '/search', 'SearchController@run');
+Route::apiResource('photos', PhotoController::class);
+"#;
+        let routes = extract_routes(source, &[]);
+        let names: Vec<String> = routes.iter().map(|r| r.signature()).collect();
+
+        assert_eq!(
+            names,
+            vec![
+                "POST /orders -> OrderController@store (laravel)",
+                "GET /health -> <inline> (laravel)",
+                "GET /search -> SearchController@run (laravel)",
+                "POST /search -> SearchController@run (laravel)",
+                "GET /photos -> PhotoController@index (laravel)",
+                "POST /photos -> PhotoController@store (laravel)",
+                "GET /photos/{photo} -> PhotoController@show (laravel)",
+                "PUT /photos/{photo} -> PhotoController@update (laravel)",
+                "DELETE /photos/{photo} -> PhotoController@destroy (laravel)",
+            ]
+        );
+    }
+}
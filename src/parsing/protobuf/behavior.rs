@@ -0,0 +1,96 @@
+//! Protocol Buffers language behavior implementation
+
+use crate::parsing::LanguageBehavior;
+use crate::parsing::behavior_state::{BehaviorState, StatefulBehavior};
+use crate::parsing::{Import, LanguageId};
+use crate::{FileId, Symbol, Visibility};
+use std::path::PathBuf;
+use tree_sitter::Language;
+
+/// Language behavior for `.proto` schemas
+#[derive(Clone)]
+pub struct ProtobufBehavior {
+    state: BehaviorState,
+}
+
+impl ProtobufBehavior {
+    /// Create a new behavior instance
+    pub fn new() -> Self {
+        Self {
+            state: BehaviorState::new(),
+        }
+    }
+}
+
+impl StatefulBehavior for ProtobufBehavior {
+    fn state(&self) -> &BehaviorState {
+        &self.state
+    }
+}
+
+impl Default for ProtobufBehavior {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LanguageBehavior for ProtobufBehavior {
+    fn configure_symbol(&self, symbol: &mut Symbol, module_path: Option<&str>) {
+        // The parser already set the package-qualified path; keep it
+        if symbol.module_path.is_none() {
+            if let Some(path) = module_path {
+                symbol.module_path = Some(self.format_module_path(path, &symbol.name).into());
+            }
+        }
+        symbol.visibility = Visibility::Public;
+    }
+
+    fn format_module_path(&self, base_path: &str, _symbol_name: &str) -> String {
+        base_path.to_string()
+    }
+
+    fn parse_visibility(&self, _signature: &str) -> Visibility {
+        // Every schema declaration is visible to importers
+        Visibility::Public
+    }
+
+    fn module_separator(&self) -> &'static str {
+        "."
+    }
+
+    fn get_language(&self) -> Language {
+        // Schemas are scanned without a grammar; `validate_node_kind` and
+        // `get_abi_version` are overridden so this is never queried for nodes
+        tree_sitter_c::LANGUAGE.into()
+    }
+
+    fn validate_node_kind(&self, _node_kind: &str) -> bool {
+        false
+    }
+
+    fn get_abi_version(&self) -> usize {
+        0
+    }
+
+    fn is_symbol_visible_from_file(&self, symbol: &Symbol, from_file: FileId) -> bool {
+        // Schema types only refer to other schema types
+        symbol.file_id == from_file || symbol.language_id == Some(LanguageId::new("protobuf"))
+    }
+
+    // Override import tracking methods to use state
+    fn register_file(&self, path: PathBuf, file_id: FileId, module_path: String) {
+        self.register_file_with_state(path, file_id, module_path);
+    }
+
+    fn add_import(&self, import: Import) {
+        self.add_import_with_state(import);
+    }
+
+    fn get_imports_for_file(&self, file_id: FileId) -> Vec<Import> {
+        self.get_imports_from_state(file_id)
+    }
+
+    fn get_module_path_for_file(&self, file_id: FileId) -> Option<String> {
+        self.state.get_module_path(file_id)
+    }
+}
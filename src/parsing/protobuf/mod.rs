@@ -0,0 +1,12 @@
+//! Protocol Buffers schema parser implementation
+
+pub mod behavior;
+pub mod definition;
+pub mod parser;
+
+pub use behavior::ProtobufBehavior;
+pub use definition::ProtobufLanguage;
+pub use parser::{ProtobufParser, rpc_messages};
+
+// Re-export for registry registration
+pub(crate) use definition::register;
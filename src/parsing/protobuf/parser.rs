@@ -0,0 +1,950 @@
+//! Protocol Buffers schema parser
+//!
+//! There is no tree-sitter grammar for `.proto` files in the build, so this
+//! parser scans the schema with a small tokenizer and recursive descent over
+//! the declarations codanna indexes:
+//!
+//! - `message` (Struct) with its fields (Field) and nested types
+//! - `enum` (Enum) with its values (Constant)
+//! - `service` (Interface) with its `rpc`s (Method)
+//!
+//! Options, `reserved`, `extensions` and `extend` blocks are skipped. Symbols
+//! carry the package-qualified module path (`orders.v1.OrderService.Create`),
+//! fields and RPCs record the message types they use, and `import` statements
+//! become imports.
+
+use crate::parsing::{Import, Language, LanguageParser};
+use crate::symbol::ScopeContext;
+use crate::types::SymbolCounter;
+use crate::{FileId, Range, Symbol, SymbolKind, Visibility};
+use std::any::Any;
+use std::collections::HashMap;
+use tree_sitter::Node;
+
+/// Scalar types that never name a message or enum
+const SCALAR_TYPES: [&str; 15] = [
+    "double", "float", "int32", "int64", "uint32", "uint64", "sint32", "sint64", "fixed32",
+    "fixed64", "sfixed32", "sfixed64", "bool", "string", "bytes",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenKind {
+    Ident,
+    Number,
+    Str,
+    Punct,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Token<'a> {
+    kind: TokenKind,
+    text: &'a str,
+    line: u32,
+    column: u16,
+}
+
+#[derive(Debug)]
+struct Comment {
+    start_line: u32,
+    end_line: u32,
+    text: String,
+}
+
+/// Split a schema into tokens and comments
+fn tokenize(code: &str) -> (Vec<Token<'_>>, Vec<Comment>) {
+    let bytes = code.as_bytes();
+    let mut tokens = Vec::new();
+    let mut comments = Vec::new();
+    let mut i = 0;
+    let mut line = 0u32;
+    let mut line_start = 0usize;
+
+    while i < bytes.len() {
+        let c = bytes[i];
+        let column = (i - line_start) as u16;
+        match c {
+            b'\n' => {
+                line += 1;
+                i += 1;
+                line_start = i;
+            }
+            c if c.is_ascii_whitespace() => i += 1,
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                let end = code[i..].find('\n').map_or(code.len(), |n| i + n);
+                comments.push(Comment {
+                    start_line: line,
+                    end_line: line,
+                    text: code[i + 2..end].trim_start_matches('/').trim().to_string(),
+                });
+                i = end;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                let end = code[i + 2..]
+                    .find("*/")
+                    .map_or(code.len(), |n| i + 2 + n + 2);
+                let start_line = line;
+                let body = &code[i..end];
+                for (offset, _) in body.match_indices('\n') {
+                    line += 1;
+                    line_start = i + offset + 1;
+                }
+                let text = body
+                    .trim_start_matches("/*")
+                    .trim_end_matches("*/")
+                    .lines()
+                    .map(|l| l.trim().trim_start_matches('*').trim())
+                    .filter(|l| !l.is_empty())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                comments.push(Comment {
+                    start_line,
+                    end_line: line,
+                    text,
+                });
+                i = end;
+            }
+            b'"' | b'\'' => {
+                let mut end = i + 1;
+                while end < bytes.len() && bytes[end] != c && bytes[end] != b'\n' {
+                    end += if bytes[end] == b'\\' { 2 } else { 1 };
+                }
+                let end = (end + 1).min(bytes.len());
+                tokens.push(Token {
+                    kind: TokenKind::Str,
+                    text: &code[i..end],
+                    line,
+                    column,
+                });
+                i = end;
+            }
+            c if c.is_ascii_alphabetic()
+                || c == b'_'
+                || (c == b'.'
+                    && bytes
+                        .get(i + 1)
+                        .is_some_and(|n| n.is_ascii_alphabetic() || *n == b'_')) =>
+            {
+                let mut end = i + 1;
+                while end < bytes.len()
+                    && (bytes[end].is_ascii_alphanumeric()
+                        || bytes[end] == b'_'
+                        || bytes[end] == b'.')
+                {
+                    end += 1;
+                }
+                tokens.push(Token {
+                    kind: TokenKind::Ident,
+                    text: &code[i..end],
+                    line,
+                    column,
+                });
+                i = end;
+            }
+            c if c.is_ascii_digit() => {
+                let mut end = i + 1;
+                while end < bytes.len()
+                    && (bytes[end].is_ascii_alphanumeric() || bytes[end] == b'.')
+                {
+                    end += 1;
+                }
+                tokens.push(Token {
+                    kind: TokenKind::Number,
+                    text: &code[i..end],
+                    line,
+                    column,
+                });
+                i = end;
+            }
+            _ => {
+                let len = code[i..].chars().next().map_or(1, char::len_utf8);
+                tokens.push(Token {
+                    kind: TokenKind::Punct,
+                    text: &code[i..i + len],
+                    line,
+                    column,
+                });
+                i += len;
+            }
+        }
+    }
+    (tokens, comments)
+}
+
+/// One declaration of a schema
+#[derive(Debug, Clone)]
+pub(crate) struct ProtoDecl<'a> {
+    pub kind: SymbolKind,
+    pub name: &'a str,
+    /// Name of the enclosing message, enum or service
+    pub parent: Option<&'a str>,
+    /// Package-qualified path, e.g. `orders.v1.Order.Item`
+    pub qualified_name: String,
+    pub signature: String,
+    pub range: Range,
+    pub doc: Option<String>,
+    /// Message and enum types the declaration refers to
+    pub uses: Vec<&'a str>,
+}
+
+/// Declarations and imports of a schema
+#[derive(Debug, Default)]
+pub(crate) struct ProtoDocument<'a> {
+    pub package: Option<&'a str>,
+    pub imports: Vec<&'a str>,
+    pub decls: Vec<ProtoDecl<'a>>,
+}
+
+/// Content of a string token without its quotes
+fn unquote(text: &str) -> &str {
+    text.trim_matches(['"', '\''])
+}
+
+/// Referenced type name as written, without a leading `.`
+fn type_reference(text: &str) -> Option<&str> {
+    let name = text.trim_start_matches('.');
+    (!SCALAR_TYPES.contains(&name) && !name.is_empty()).then_some(name)
+}
+
+struct ProtoScanner<'a> {
+    tokens: Vec<Token<'a>>,
+    comments: Vec<Comment>,
+    pos: usize,
+    document: ProtoDocument<'a>,
+    /// Enclosing declarations: (name, qualified name)
+    scopes: Vec<(&'a str, String)>,
+}
+
+impl<'a> ProtoScanner<'a> {
+    fn new(code: &'a str) -> Self {
+        let (tokens, comments) = tokenize(code);
+        Self {
+            tokens,
+            comments,
+            pos: 0,
+            document: ProtoDocument::default(),
+            scopes: Vec::new(),
+        }
+    }
+
+    fn peek(&self) -> Option<Token<'a>> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        let token = self.peek()?;
+        self.pos += 1;
+        Some(token)
+    }
+
+    fn at(&self, text: &str) -> bool {
+        self.peek().is_some_and(|t| t.text == text)
+    }
+
+    fn eat(&mut self, text: &str) -> bool {
+        if self.at(text) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn ident(&mut self) -> Option<Token<'a>> {
+        let token = self.peek().filter(|t| t.kind == TokenKind::Ident)?;
+        self.pos += 1;
+        Some(token)
+    }
+
+    /// Line of the previously consumed token
+    fn last_line(&self) -> u32 {
+        self.pos
+            .checked_sub(1)
+            .and_then(|i| self.tokens.get(i))
+            .map_or(0, |t| t.line)
+    }
+
+    /// Skip a statement: up to its `;`, or over its balanced `{ ... }` block
+    fn skip_statement(&mut self) {
+        let mut depth = 0usize;
+        while let Some(token) = self.next() {
+            match token.text {
+                "{" | "[" | "(" | "<" => depth += 1,
+                "}" | "]" | ")" | ">" => {
+                    depth = depth.saturating_sub(1);
+                    if depth == 0 && token.text == "}" {
+                        return;
+                    }
+                }
+                ";" if depth == 0 => return,
+                _ => {}
+            }
+        }
+    }
+
+    /// Comment lines directly above `line`
+    fn doc_comment(&self, line: u32) -> Option<String> {
+        let mut lines = Vec::new();
+        let mut expected = line;
+        for comment in self.comments.iter().rev() {
+            if comment.end_line >= line {
+                continue;
+            }
+            if comment.end_line + 1 != expected {
+                break;
+            }
+            lines.push(comment.text.as_str());
+            expected = comment.start_line;
+        }
+        if lines.is_empty() {
+            return None;
+        }
+        lines.reverse();
+        Some(lines.join("\n"))
+    }
+
+    fn qualify(&self, name: &str) -> String {
+        let prefix = match self.scopes.last() {
+            Some((_, qualified)) => Some(qualified.as_str()),
+            None => self.document.package,
+        };
+        match prefix {
+            Some(prefix) => format!("{prefix}.{name}"),
+            None => name.to_string(),
+        }
+    }
+
+    fn push(
+        &mut self,
+        kind: SymbolKind,
+        name: Token<'a>,
+        start: Token<'a>,
+        end_line: u32,
+        signature: String,
+        uses: Vec<&'a str>,
+    ) {
+        let decl = ProtoDecl {
+            kind,
+            name: name.text,
+            parent: self.scopes.last().map(|(name, _)| *name),
+            qualified_name: self.qualify(name.text),
+            signature,
+            range: Range::new(start.line, start.column, end_line, 0),
+            doc: self.doc_comment(start.line),
+            uses,
+        };
+        self.document.decls.push(decl);
+    }
+
+    fn parse(mut self) -> ProtoDocument<'a> {
+        while let Some(token) = self.peek() {
+            match token.text {
+                "package" => {
+                    self.pos += 1;
+                    self.document.package = self.ident().map(|t| t.text);
+                    self.skip_statement();
+                }
+                "import" => {
+                    self.pos += 1;
+                    self.eat("public");
+                    self.eat("weak");
+                    if let Some(path) = self.peek().filter(|t| t.kind == TokenKind::Str) {
+                        self.document.imports.push(unquote(path.text));
+                    }
+                    self.skip_statement();
+                }
+                _ => self.declaration(),
+            }
+        }
+        self.document
+    }
+
+    /// A top-level or nested declaration; anything unknown is skipped
+    fn declaration(&mut self) {
+        let Some(token) = self.peek() else {
+            return;
+        };
+        match token.text {
+            "message" => self.message(),
+            "enum" => self.enumeration(),
+            "service" => self.service(),
+            ";" => self.pos += 1,
+            _ => self.skip_statement(),
+        }
+    }
+
+    /// Enter the `{` block of a declaration
+    fn open_block(&mut self, name: Token<'a>) -> bool {
+        if !self.eat("{") {
+            self.skip_statement();
+            return false;
+        }
+        self.scopes.push((name.text, self.qualify(name.text)));
+        true
+    }
+
+    fn close_block(&mut self) {
+        self.scopes.pop();
+    }
+
+    fn message(&mut self) {
+        let Some(start) = self.next() else {
+            return;
+        };
+        let Some(name) = self.ident() else {
+            self.skip_statement();
+            return;
+        };
+        let index = self.document.decls.len();
+        self.push(
+            SymbolKind::Struct,
+            name,
+            start,
+            start.line,
+            format!("message {}", name.text),
+            Vec::new(),
+        );
+        if !self.open_block(name) {
+            return;
+        }
+        self.message_body();
+        self.close_block();
+        self.document.decls[index].range.end_line = self.last_line();
+    }
+
+    /// Fields, nested types and `oneof` groups up to the closing `}`
+    fn message_body(&mut self) {
+        while let Some(token) = self.peek() {
+            match token.text {
+                "}" => {
+                    self.pos += 1;
+                    return;
+                }
+                "message" | "enum" => self.declaration(),
+                "oneof" => {
+                    self.pos += 1;
+                    let _ = self.ident();
+                    if self.eat("{") {
+                        // Oneof members are fields of the message
+                        self.message_body();
+                    } else {
+                        self.skip_statement();
+                    }
+                }
+                "option" | "reserved" | "extensions" | "extend" | "group" | ";" => {
+                    if token.text == ";" {
+                        self.pos += 1;
+                    } else {
+                        self.skip_statement();
+                    }
+                }
+                _ => self.field(),
+            }
+        }
+    }
+
+    /// `[label] type name = number [options];` or `map<K, V> name = number;`
+    fn field(&mut self) {
+        let start_pos = self.pos;
+        let Some(start) = self.peek() else {
+            return;
+        };
+        let label = match start.text {
+            "repeated" | "optional" | "required" => {
+                self.pos += 1;
+                Some(start.text)
+            }
+            _ => None,
+        };
+
+        let mut uses = Vec::new();
+        let type_text = if self.at("map") {
+            self.pos += 1;
+            if !self.eat("<") {
+                self.pos = start_pos;
+                self.skip_statement();
+                return;
+            }
+            let key = self.ident().map_or("", |t| t.text);
+            self.eat(",");
+            let value = self.ident().map_or("", |t| t.text);
+            self.eat(">");
+            uses.extend(type_reference(value));
+            format!("map<{key}, {value}>")
+        } else {
+            match self.ident() {
+                Some(field_type) => {
+                    uses.extend(type_reference(field_type.text));
+                    field_type.text.to_string()
+                }
+                None => {
+                    self.skip_statement();
+                    return;
+                }
+            }
+        };
+
+        let (Some(name), true) = (self.ident(), self.eat("=")) else {
+            self.pos = start_pos;
+            self.skip_statement();
+            return;
+        };
+        let number = self
+            .peek()
+            .filter(|t| t.kind == TokenKind::Number)
+            .map_or("", |t| t.text);
+        self.skip_statement();
+
+        let signature = match label {
+            Some(label) => format!("{label} {type_text} {} = {number}", name.text),
+            None => format!("{type_text} {} = {number}", name.text),
+        };
+        let end_line = self.last_line();
+        self.push(SymbolKind::Field, name, start, end_line, signature, uses);
+    }
+
+    fn enumeration(&mut self) {
+        let Some(start) = self.next() else {
+            return;
+        };
+        let Some(name) = self.ident() else {
+            self.skip_statement();
+            return;
+        };
+        let index = self.document.decls.len();
+        self.push(
+            SymbolKind::Enum,
+            name,
+            start,
+            start.line,
+            format!("enum {}", name.text),
+            Vec::new(),
+        );
+        if !self.open_block(name) {
+            return;
+        }
+        while let Some(token) = self.peek() {
+            match token.text {
+                "}" => {
+                    self.pos += 1;
+                    break;
+                }
+                "option" | "reserved" => self.skip_statement(),
+                ";" => self.pos += 1,
+                _ => {
+                    let Some(value) = self.ident() else {
+                        self.skip_statement();
+                        continue;
+                    };
+                    if !self.eat("=") {
+                        self.skip_statement();
+                        continue;
+                    }
+                    let negative = self.eat("-");
+                    let number = self
+                        .peek()
+                        .filter(|t| t.kind == TokenKind::Number)
+                        .map_or("", |t| t.text);
+                    self.skip_statement();
+                    let sign = if negative { "-" } else { "" };
+                    let signature = format!("{} = {sign}{number}", value.text);
+                    let end_line = self.last_line();
+                    self.push(
+                        SymbolKind::Constant,
+                        value,
+                        value,
+                        end_line,
+                        signature,
+                        Vec::new(),
+                    );
+                }
+            }
+        }
+        self.close_block();
+        self.document.decls[index].range.end_line = self.last_line();
+    }
+
+    fn service(&mut self) {
+        let Some(start) = self.next() else {
+            return;
+        };
+        let Some(name) = self.ident() else {
+            self.skip_statement();
+            return;
+        };
+        let index = self.document.decls.len();
+        self.push(
+            SymbolKind::Interface,
+            name,
+            start,
+            start.line,
+            format!("service {}", name.text),
+            Vec::new(),
+        );
+        if !self.open_block(name) {
+            return;
+        }
+        while let Some(token) = self.peek() {
+            match token.text {
+                "}" => {
+                    self.pos += 1;
+                    break;
+                }
+                "rpc" => self.rpc(),
+                ";" => self.pos += 1,
+                _ => self.skip_statement(),
+            }
+        }
+        self.close_block();
+        self.document.decls[index].range.end_line = self.last_line();
+    }
+
+    /// `(stream Type)` of an RPC
+    fn rpc_type(&mut self) -> Option<(bool, &'a str)> {
+        if !self.eat("(") {
+            return None;
+        }
+        let stream = self.at("stream")
+            && self
+                .tokens
+                .get(self.pos + 1)
+                .is_some_and(|t| t.kind == TokenKind::Ident);
+        if stream {
+            self.pos += 1;
+        }
+        let message = self.ident()?;
+        self.eat(")").then_some((stream, message.text))
+    }
+
+    /// `rpc Name(Request) returns (Response);` or with an options block
+    fn rpc(&mut self) {
+        let Some(start) = self.next() else {
+            return;
+        };
+        let parsed = self.ident().and_then(|name| {
+            let input = self.rpc_type()?;
+            self.eat("returns").then_some(())?;
+            let output = self.rpc_type()?;
+            Some((name, input, output))
+        });
+        self.skip_statement();
+        let Some((name, (input_stream, input), (output_stream, output))) = parsed else {
+            return;
+        };
+
+        let stream = |streaming: bool| if streaming { "stream " } else { "" };
+        let signature = format!(
+            "rpc {}({}{input}) returns ({}{output})",
+            name.text,
+            stream(input_stream),
+            stream(output_stream)
+        );
+        let uses = [input, output]
+            .into_iter()
+            .filter_map(type_reference)
+            .collect();
+        let end_line = self.last_line();
+        self.push(SymbolKind::Method, name, start, end_line, signature, uses);
+    }
+}
+
+/// Scan a schema into its declarations
+pub(crate) fn scan(code: &str) -> ProtoDocument<'_> {
+    ProtoScanner::new(code).parse()
+}
+
+/// Request and response message names of an RPC signature
+///
+/// `rpc Create(CreateOrderRequest) returns (stream Order)` gives
+/// `("CreateOrderRequest", "Order")`, without package qualifiers.
+pub fn rpc_messages(signature: &str) -> Option<(&str, &str)> {
+    fn short(text: &str) -> &str {
+        let text = text.trim();
+        let text = text.strip_prefix("stream ").unwrap_or(text);
+        text.rsplit('.').next().unwrap_or(text)
+    }
+
+    let rest = signature.strip_prefix("rpc ")?;
+    let (_, rest) = rest.split_once('(')?;
+    let (input, rest) = rest.split_once(')')?;
+    let (_, rest) = rest.split_once('(')?;
+    let (output, _) = rest.split_once(')')?;
+    Some((short(input), short(output)))
+}
+
+/// Parser for `.proto` schemas
+#[derive(Debug, Default)]
+pub struct ProtobufParser;
+
+impl ProtobufParser {
+    /// Create a new parser instance
+    pub fn new() -> Result<Self, String> {
+        Ok(Self)
+    }
+}
+
+impl LanguageParser for ProtobufParser {
+    fn parse(
+        &mut self,
+        code: &str,
+        file_id: FileId,
+        symbol_counter: &mut SymbolCounter,
+    ) -> Vec<Symbol> {
+        scan(code)
+            .decls
+            .into_iter()
+            .map(|decl| {
+                let scope = if decl.parent.is_some() {
+                    ScopeContext::ClassMember
+                } else {
+                    ScopeContext::Module
+                };
+                let mut symbol = Symbol::new(
+                    symbol_counter.next_id(),
+                    decl.name,
+                    decl.kind,
+                    file_id,
+                    decl.range,
+                )
+                .with_signature(decl.signature)
+                .with_module_path(decl.qualified_name)
+                .with_visibility(Visibility::Public)
+                .with_scope(scope);
+                if let Some(doc) = decl.doc {
+                    symbol = symbol.with_doc(doc);
+                }
+                symbol
+            })
+            .collect()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn extract_doc_comment(&self, _node: &Node, _code: &str) -> Option<String> {
+        // Doc comments are attached while scanning; there are no tree-sitter nodes
+        None
+    }
+
+    fn find_calls<'a>(&mut self, _code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        Vec::new()
+    }
+
+    fn find_implementations<'a>(&mut self, _code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        Vec::new()
+    }
+
+    fn find_uses<'a>(&mut self, code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        let mut uses = Vec::new();
+        for decl in scan(code).decls {
+            // A field is attributed to its message, an RPC to itself
+            let context = match decl.kind {
+                SymbolKind::Field => decl.parent.unwrap_or(decl.name),
+                _ => decl.name,
+            };
+            for used in decl.uses {
+                let short = used.rsplit('.').next().unwrap_or(used);
+                uses.push((context, short, decl.range));
+            }
+        }
+        uses
+    }
+
+    fn find_defines<'a>(&mut self, code: &'a str) -> Vec<(&'a str, &'a str, Range)> {
+        let decls = scan(code).decls;
+        // Definitions are resolved by name, and messages commonly repeat field
+        // names (`id`, `name`); only unambiguous pairs are reported
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for decl in &decls {
+            *counts.entry(decl.name).or_default() += 1;
+        }
+        decls
+            .iter()
+            .filter_map(|decl| Some((decl.parent?, decl.name, decl.range)))
+            .filter(|(parent, name, _)| counts[parent] == 1 && counts[name] == 1)
+            .collect()
+    }
+
+    fn find_imports(&mut self, code: &str, file_id: FileId) -> Vec<Import> {
+        scan(code)
+            .imports
+            .into_iter()
+            .map(|path| Import {
+                path: path.to_string(),
+                alias: None,
+                file_id,
+                is_glob: false,
+                is_type_only: false,
+            })
+            .collect()
+    }
+
+    fn language(&self) -> Language {
+        Language::Protobuf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: &str = r#"
+syntax = "proto3";
+
+package orders.v1;
+
+import "google/protobuf/timestamp.proto";
+
+// An order placed by a customer
+message Order {
+  string id = 1;
+  repeated LineItem items = 2 [packed = true];
+  map<string, string> labels = 3;
+  oneof payment {
+    Card card = 4;
+  }
+  google.protobuf.Timestamp created_at = 5;
+
+  message LineItem {
+    string sku = 1;
+  }
+}
+
+enum Status {
+  option allow_alias = true;
+  STATUS_UNSPECIFIED = 0;
+  STATUS_PAID = 1;
+}
+
+/* Order management */
+service OrderService {
+  rpc Create(CreateOrderRequest) returns (Order);
+  rpc Watch(WatchRequest) returns (stream Order) {
+    option (google.api.http) = { get: "/v1/orders:watch" };
+  }
+}
+"#;
+
+    #[test]
+    fn test_scan_declarations() {
+        let document = scan(SCHEMA);
+        assert_eq!(document.package, Some("orders.v1"));
+        assert_eq!(document.imports, vec!["google/protobuf/timestamp.proto"]);
+
+        let decls: Vec<(SymbolKind, &str, &str)> = document
+            .decls
+            .iter()
+            .map(|d| (d.kind, d.qualified_name.as_str(), d.signature.as_str()))
+            .collect();
+        assert_eq!(
+            decls,
+            vec![
+                (SymbolKind::Struct, "orders.v1.Order", "message Order"),
+                (SymbolKind::Field, "orders.v1.Order.id", "string id = 1"),
+                (
+                    SymbolKind::Field,
+                    "orders.v1.Order.items",
+                    "repeated LineItem items = 2"
+                ),
+                (
+                    SymbolKind::Field,
+                    "orders.v1.Order.labels",
+                    "map<string, string> labels = 3"
+                ),
+                (SymbolKind::Field, "orders.v1.Order.card", "Card card = 4"),
+                (
+                    SymbolKind::Field,
+                    "orders.v1.Order.created_at",
+                    "google.protobuf.Timestamp created_at = 5"
+                ),
+                (
+                    SymbolKind::Struct,
+                    "orders.v1.Order.LineItem",
+                    "message LineItem"
+                ),
+                (
+                    SymbolKind::Field,
+                    "orders.v1.Order.LineItem.sku",
+                    "string sku = 1"
+                ),
+                (SymbolKind::Enum, "orders.v1.Status", "enum Status"),
+                (
+                    SymbolKind::Constant,
+                    "orders.v1.Status.STATUS_UNSPECIFIED",
+                    "STATUS_UNSPECIFIED = 0"
+                ),
+                (
+                    SymbolKind::Constant,
+                    "orders.v1.Status.STATUS_PAID",
+                    "STATUS_PAID = 1"
+                ),
+                (
+                    SymbolKind::Interface,
+                    "orders.v1.OrderService",
+                    "service OrderService"
+                ),
+                (
+                    SymbolKind::Method,
+                    "orders.v1.OrderService.Create",
+                    "rpc Create(CreateOrderRequest) returns (Order)"
+                ),
+                (
+                    SymbolKind::Method,
+                    "orders.v1.OrderService.Watch",
+                    "rpc Watch(WatchRequest) returns (stream Order)"
+                ),
+            ]
+        );
+
+        let order = &document.decls[0];
+        assert_eq!(order.doc.as_deref(), Some("An order placed by a customer"));
+        assert_eq!((order.range.start_line, order.range.end_line), (8, 20));
+        let service = &document.decls[11];
+        assert_eq!(service.doc.as_deref(), Some("Order management"));
+    }
+
+    #[test]
+    fn test_relationships() {
+        let mut parser = ProtobufParser::new().unwrap();
+
+        let uses: Vec<(&str, &str)> = parser
+            .find_uses(SCHEMA)
+            .into_iter()
+            .map(|(from, to, _)| (from, to))
+            .collect();
+        assert!(uses.contains(&("Order", "LineItem")));
+        assert!(uses.contains(&("Order", "Timestamp")));
+        assert!(uses.contains(&("Create", "CreateOrderRequest")));
+        assert!(uses.contains(&("Watch", "Order")));
+        assert!(!uses.iter().any(|(_, to)| *to == "string"));
+
+        let defines: Vec<(&str, &str)> = parser
+            .find_defines(SCHEMA)
+            .into_iter()
+            .map(|(from, to, _)| (from, to))
+            .collect();
+        assert!(defines.contains(&("OrderService", "Create")));
+        assert!(defines.contains(&("Order", "LineItem")));
+        assert!(defines.contains(&("Status", "STATUS_PAID")));
+        // `Order` has an `id` field, but so could any other message
+        let ambiguous = format!("{SCHEMA}\nmessage Refund {{ string id = 1; }}");
+        assert!(
+            !parser
+                .find_defines(&ambiguous)
+                .iter()
+                .any(|(_, name, _)| *name == "id")
+        );
+    }
+
+    #[test]
+    fn test_rpc_messages() {
+        assert_eq!(
+            rpc_messages("rpc Create(CreateOrderRequest) returns (Order)"),
+            Some(("CreateOrderRequest", "Order"))
+        );
+        assert_eq!(
+            rpc_messages("rpc Sync(stream orders.v1.Item) returns (stream google.protobuf.Empty)"),
+            Some(("Item", "Empty"))
+        );
+    }
+}
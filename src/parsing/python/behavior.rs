@@ -140,6 +140,35 @@ impl LanguageBehavior for PythonBehavior {
         }
     }
 
+    fn extract_routes(
+        &self,
+        source: &str,
+        file_path: &str,
+        symbols: &[crate::Symbol],
+    ) -> Vec<crate::parsing::RouteEntry> {
+        super::routes::extract_routes(source, file_path, symbols)
+    }
+
+    fn is_test_symbol(&self, symbol: &crate::Symbol, file_path: &str) -> bool {
+        use crate::parsing::test_detection::{file_name, short_name};
+
+        if !matches!(
+            symbol.kind,
+            crate::SymbolKind::Function | crate::SymbolKind::Method
+        ) || !short_name(&symbol.name).starts_with("test")
+        {
+            return false;
+        }
+        // pytest collects test_*.py / *_test.py; unittest collects Test* classes
+        let file = file_name(file_path);
+        let test_file = file.starts_with("test_") || file.ends_with("_test.py");
+        let test_class = symbol
+            .name
+            .rsplit_once('.')
+            .is_some_and(|(class, _)| class.starts_with("Test") || class.ends_with("Test"));
+        test_file || test_class
+    }
+
     fn module_separator(&self) -> &'static str {
         "."
     }
@@ -278,6 +307,13 @@ impl LanguageBehavior for PythonBehavior {
         symbol.module_path = Some(module_path.to_string().into());
         symbol.scope_context = Some(crate::symbol::ScopeContext::Global);
         symbol.language_id = Some(language_id);
+        symbol.stable_id = Some(crate::StableSymbolId::derive(
+            language_id.as_str(),
+            module_path,
+            &symbol.name,
+            symbol.kind,
+            0,
+        ));
 
         document_index
             .index_symbol(&symbol, &path_str)
@@ -642,6 +678,31 @@ impl LanguageBehavior for PythonBehavior {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_is_test_symbol() {
+        use crate::{Range, Symbol, SymbolKind};
+
+        let behavior = PythonBehavior::new();
+        let symbol = |name: &str, kind: SymbolKind| {
+            Symbol::new(
+                SymbolId::new(1).unwrap(),
+                name,
+                kind,
+                FileId::new(1).unwrap(),
+                Range::new(0, 0, 1, 0),
+            )
+        };
+
+        let function = symbol("test_make", SymbolKind::Function);
+        assert!(behavior.is_test_symbol(&function, "tests/test_shapes.py"));
+        assert!(!behavior.is_test_symbol(&function, "src/shapes.py"));
+
+        let method = symbol("TestShape.test_area", SymbolKind::Method);
+        assert!(behavior.is_test_symbol(&method, "src/shapes.py"));
+        let method = symbol("Shape.test_area", SymbolKind::Method);
+        assert!(!behavior.is_test_symbol(&method, "src/shapes.py"));
+    }
+
     #[test]
     fn test_format_module_path() {
         let behavior = PythonBehavior::new();
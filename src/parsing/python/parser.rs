@@ -12,6 +12,7 @@
 //! verify compatibility with node type names used in this implementation.
 
 use crate::parsing::Import;
+use crate::parsing::annotation::{AnnotationNodes, AnnotationSyntax, attach_annotations};
 use crate::parsing::parser::check_recursion_depth;
 use crate::parsing::{
     HandledNode, Language, LanguageParser, MethodCall, NodeTracker, NodeTrackingState,
@@ -24,6 +25,13 @@ use std::collections::HashSet;
 use thiserror::Error;
 use tree_sitter::{Node, Parser};
 
+/// Decorators read into `Symbol::annotations`
+const ANNOTATION_NODES: AnnotationNodes = AnnotationNodes {
+    syntax: AnnotationSyntax::At,
+    annotations: &["decorator"],
+    containers: &[],
+};
+
 /// Python-specific parsing errors
 #[derive(Error, Debug)]
 pub enum PythonParseError {
@@ -105,6 +113,7 @@ impl PythonParser {
             &mut context,
             0,
         );
+        attach_annotations(root_node, code, &mut symbols, &ANNOTATION_NODES);
 
         symbols
     }
@@ -1206,25 +1215,52 @@ impl PythonParser {
     }
 
     /// Process assignment node with type annotation (x: int = 5 or x: int)
+    ///
+    /// Without an annotation, a constructor call (`x = Foo()`, `x = models.Foo()`)
+    /// gives the variable the class type. Classes are recognized by the PEP 8
+    /// capitalized name, so `x = make()` is not mistaken for a type.
     fn process_assignment_with_type<'a>(
         &self,
         node: Node,
         code: &'a str,
         variable_types: &mut Vec<(&'a str, &'a str, Range)>,
     ) {
-        // Only process assignments that have type annotations
+        let Some(target_node) = node.child_by_field_name("left") else {
+            return;
+        };
+        let Some(var_name) = self.extract_variable_name(target_node, code) else {
+            return;
+        };
+
         if let Some(type_node) = node.child_by_field_name("type") {
-            // Extract variable name from the left side
-            if let Some(target_node) = node.child_by_field_name("left") {
-                if let Some(var_name) = self.extract_variable_name(target_node, code) {
-                    let type_annotation = &code[type_node.byte_range()];
-                    let range = self.node_to_range(node);
-                    variable_types.push((var_name, type_annotation, range));
-                }
-            }
+            let type_annotation = &code[type_node.byte_range()];
+            let range = self.node_to_range(node);
+            variable_types.push((var_name, type_annotation, range));
+        } else if let Some(class_name) = node
+            .child_by_field_name("right")
+            .and_then(|right| Self::constructed_class(right, code))
+        {
+            let range = self.node_to_range(node);
+            variable_types.push((var_name, class_name, range));
         }
     }
 
+    /// Class instantiated by a call expression, e.g. `Foo` for `Foo(1)`
+    fn constructed_class<'a>(node: Node, code: &'a str) -> Option<&'a str> {
+        if node.kind() != "call" {
+            return None;
+        }
+        let function = node.child_by_field_name("function")?;
+        let name_node = match function.kind() {
+            "identifier" => function,
+            "attribute" => function.child_by_field_name("attribute")?,
+            _ => return None,
+        };
+        let name = &code[name_node.byte_range()];
+        name.starts_with(|c: char| c.is_ascii_uppercase())
+            .then_some(name)
+    }
+
     /// Extract variable name from assignment target
     fn extract_variable_name<'a>(&self, node: Node, code: &'a str) -> Option<&'a str> {
         match node.kind() {
@@ -2282,6 +2318,21 @@ def setup():
         }
     }
 
+    #[test]
+    fn test_variable_type_from_constructor_call() {
+        let mut parser = PythonParser::new().unwrap();
+        let code = r#"
+def archive():
+    doc = Document()
+    report = models.Report(doc)
+    total = compute()
+"#;
+        let var_types = parser.find_variable_types(code);
+        let pairs: Vec<(&str, &str)> = var_types.iter().map(|(n, t, _)| (*n, *t)).collect();
+
+        assert_eq!(pairs, vec![("doc", "Document"), ("report", "Report")]);
+    }
+
     // Test additional variable type annotation cases
     #[test]
     fn test_various_variable_type_annotations() {
@@ -379,7 +379,9 @@ impl PythonInheritanceResolver {
 
 impl InheritanceResolver for PythonInheritanceResolver {
     fn add_inheritance(&mut self, child: String, parent: String, kind: &str) {
-        if kind == "extends" || kind == "inherits" {
+        // The parser reports base classes through `find_implementations`,
+        // so "implements" is class inheritance as well
+        if kind == "extends" || kind == "inherits" || kind == "implements" {
             // In Python, this is class inheritance
             self.class_bases.entry(child).or_default().push(parent);
             // Clear MRO cache as hierarchy changed
@@ -0,0 +1,200 @@
+//! HTTP route extraction for Flask, FastAPI and Django
+//!
+//! - Decorators: `@app.route("/orders", methods=["POST"])`, `@router.get("/orders/{id}")`,
+//!   with `Blueprint(url_prefix=...)` and `APIRouter(prefix=...)` prefixes
+//! - Django URLconfs (`urls.py`): `path("orders/<int:pk>/", views.detail)`
+
+use crate::parsing::route::{
+    self, ANY_METHOD, RouteEntry, arguments, find_calls, first_string, http_method,
+    http_methods_in, join_path, keyword_argument, path_argument,
+};
+use crate::parsing::test_detection::file_name;
+use crate::{Symbol, SymbolKind};
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+static ROUTER_CALL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^\s*(\w+)\s*=\s*(?:[\w.]+\.)?(Blueprint|APIRouter)\s*\(")
+        .expect("Invalid regex")
+});
+
+static URLCONF_CALL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b(path|re_path|url)\s*\(").expect("Invalid regex"));
+
+static AS_VIEW: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\.as_view\s*\(.*$").expect("Invalid regex"));
+
+pub fn extract_routes(source: &str, file_path: &str, symbols: &[Symbol]) -> Vec<RouteEntry> {
+    let mut routes = decorator_routes(source, symbols);
+    if file_name(file_path) == "urls.py" {
+        routes.extend(urlconf_routes(source));
+    }
+    route::resolve_local_handlers(&mut routes, symbols);
+    routes
+}
+
+fn decorator_routes(source: &str, symbols: &[Symbol]) -> Vec<RouteEntry> {
+    let framework = if source.contains("fastapi") {
+        "fastapi"
+    } else {
+        "flask"
+    };
+
+    // Blueprint / APIRouter variables and their URL prefixes
+    let mut prefixes: HashMap<&str, String> = HashMap::new();
+    for call in find_calls(source, &ROUTER_CALL) {
+        let args = arguments(call.args);
+        let prefix = keyword_argument(&args, "url_prefix")
+            .or_else(|| keyword_argument(&args, "prefix"))
+            .and_then(first_string);
+        if let (Some(variable), Some(prefix)) = (call.groups[0], prefix) {
+            prefixes.insert(variable, prefix);
+        }
+    }
+
+    let mut routes = Vec::new();
+    for symbol in symbols
+        .iter()
+        .filter(|s| matches!(s.kind, SymbolKind::Function | SymbolKind::Method))
+    {
+        for annotation in &symbol.annotations {
+            let Some((receiver, decorator)) = annotation.name.rsplit_once('.') else {
+                continue;
+            };
+            let args = annotation.arguments.as_deref().unwrap_or_default();
+            let Some(path) = path_argument(args, &["rule", "path"]) else {
+                continue;
+            };
+            if !path.is_empty() && !path.starts_with('/') {
+                continue;
+            }
+
+            let methods = match decorator {
+                "route" | "api_route" | "api_view" => {
+                    let methods = keyword_argument(&arguments(args), "methods")
+                        .map(http_methods_in)
+                        .unwrap_or_default();
+                    if methods.is_empty() {
+                        vec!["GET"]
+                    } else {
+                        methods
+                    }
+                }
+                "websocket" => continue,
+                name => match http_method(name) {
+                    Some(method) => vec![method],
+                    None => continue,
+                },
+            };
+
+            let receiver = receiver.rsplit('.').next().unwrap_or(receiver);
+            let path = match prefixes.get(receiver) {
+                Some(prefix) => join_path(prefix, &path),
+                None => path,
+            };
+            for method in methods {
+                routes.push(
+                    RouteEntry::new(method, &path, framework, symbol.range.start_line)
+                        .with_handler_symbol(symbol),
+                );
+            }
+        }
+    }
+    routes
+}
+
+fn urlconf_routes(source: &str) -> Vec<RouteEntry> {
+    let mut routes = Vec::new();
+    for call in find_calls(source, &URLCONF_CALL) {
+        let args = arguments(call.args);
+        let (Some(pattern), Some(view)) = (
+            args.first().and_then(|a| route::string_literal(a)),
+            args.get(1),
+        ) else {
+            continue;
+        };
+        if view.starts_with("include") {
+            continue;
+        }
+        let view = AS_VIEW.replace(view, "");
+        let pattern = if call.groups[0] == Some("path") {
+            pattern
+        } else {
+            // Regex URL patterns keep their anchors out of the displayed path
+            pattern
+                .trim_start_matches('^')
+                .trim_end_matches('$')
+                .to_string()
+        };
+        routes.push(
+            RouteEntry::new(ANY_METHOD, &pattern, "django", call.line).with_handler(view.trim()),
+        );
+    }
+    routes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::PythonParser;
+
+    fn parse(source: &str) -> Vec<Symbol> {
+        let mut parser = PythonParser::new().unwrap();
+        route::parse_symbols(&mut parser, source)
+    }
+
+    #[test]
+    fn test_flask_and_fastapi_decorators() {
+        let source = r#"
+from flask import Blueprint
+
+orders = Blueprint("orders", __name__, url_prefix="/orders")
+
+@orders.route("/", methods=["GET", "POST"])
+def index():
+    pass
+
+@orders.delete("/<int:order_id>")
+def remove(order_id):
+    pass
+"#;
+        let symbols = parse(source);
+        let routes = extract_routes(source, "app/orders.py", &symbols);
+        let names: Vec<String> = routes.iter().map(|r| r.signature()).collect();
+
+        assert_eq!(
+            names,
+            vec![
+                "GET /orders -> index (flask)",
+                "POST /orders -> index (flask)",
+                "DELETE /orders/<int:order_id> -> remove (flask)",
+            ]
+        );
+        assert!(routes.iter().all(|r| r.handler_id.is_some()));
+    }
+
+    #[test]
+    fn test_django_urlconf() {
+        let source = r#"
+from django.urls import include, path, re_path
+from . import views
+
+urlpatterns = [
+    path("orders/<int:pk>/", views.OrderDetail.as_view(), name="detail"),
+    re_path(r"^legacy/$", views.legacy),
+    path("api/", include("api.urls")),
+]
+"#;
+        let routes = extract_routes(source, "shop/urls.py", &[]);
+        let names: Vec<String> = routes.iter().map(|r| r.signature()).collect();
+
+        assert_eq!(
+            names,
+            vec![
+                "ANY /orders/<int:pk>/ -> views.OrderDetail (django)",
+                "ANY /legacy/ -> views.legacy (django)",
+            ]
+        );
+    }
+}
@@ -380,6 +380,7 @@ fn initialize_registry(registry: &mut LanguageRegistry) {
     super::csharp::register(registry);
     super::gdscript::register(registry);
     super::kotlin::register(registry);
+    super::protobuf::register(registry);
 
     // Future languages will be added here:
     // super::javascript_definition::register(registry);
@@ -308,6 +308,14 @@ pub trait ResolutionScope: Send + Sync {
                 // Reverse of References - also permissive
                 true
             }
+            Routes => {
+                // Routes point at functions, methods or handler classes
+                from_kind == Route && matches!(to_kind, Function | Method | Class)
+            }
+            RoutedBy => {
+                // Reverse of Routes
+                matches!(from_kind, Function | Method | Class) && to_kind == Route
+            }
         }
     }
 }
@@ -0,0 +1,471 @@
+//! HTTP route extraction shared by the language route extractors
+//!
+//! Web frameworks register routes either with annotations on the handler
+//! (`#[get("/orders")]`, `@app.route(...)`, `@PostMapping`, `[HttpGet]`) or
+//! with registration calls (`.route("/orders", post(create))`,
+//! `app.post('/orders', create)`, `Route::post(...)`). Each language's
+//! `routes` module recognises its frameworks and produces [`RouteEntry`]
+//! values; the indexer stores them as `SymbolKind::Route` symbols named
+//! `METHOD /path` and links them to their handler with `RelationKind::Routes`.
+
+use crate::parsing::annotation::{matching_close, split_top_level};
+use crate::{Symbol, SymbolId, SymbolKind};
+use regex::Regex;
+
+/// Handler text recorded for closures and other inline handlers
+pub const INLINE_HANDLER: &str = "<inline>";
+
+/// Method recorded for routes that accept every HTTP method
+pub const ANY_METHOD: &str = "ANY";
+
+const HTTP_METHODS: [&str; 8] = [
+    "GET", "POST", "PUT", "DELETE", "PATCH", "HEAD", "OPTIONS", "TRACE",
+];
+
+/// An HTTP route found in a source file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteEntry {
+    /// Upper-case HTTP method, or [`ANY_METHOD`]
+    pub method: String,
+    /// Path pattern in the framework's own syntax, with a leading `/`
+    pub path: String,
+    /// Handler as written at the registration (`handlers::create_order`,
+    /// `OrderController@store`); `None` for inline handlers
+    pub handler: Option<String>,
+    /// Handler symbol when the file itself defines it
+    pub handler_id: Option<SymbolId>,
+    /// Zero-based line of the registration
+    pub line: u32,
+    /// Framework that recognised the route (e.g. `axum`, `flask`)
+    pub framework: String,
+}
+
+impl RouteEntry {
+    pub fn new(method: &str, path: &str, framework: &str, line: u32) -> Self {
+        Self {
+            method: method.to_uppercase(),
+            path: normalize_path(path),
+            handler: None,
+            handler_id: None,
+            line,
+            framework: framework.to_string(),
+        }
+    }
+
+    /// Handler given by name at the registration site
+    pub fn with_handler(mut self, handler: &str) -> Self {
+        if is_handler_path(handler) {
+            self.handler = Some(handler.to_string());
+        }
+        self
+    }
+
+    /// Handler already known as a symbol of the file
+    pub fn with_handler_symbol(mut self, symbol: &Symbol) -> Self {
+        self.handler = Some(symbol.name.to_string());
+        self.handler_id = Some(symbol.id);
+        self
+    }
+
+    /// Symbol name of the route, e.g. `POST /orders`
+    pub fn name(&self) -> String {
+        format!("{} {}", self.method, self.path)
+    }
+
+    /// Signature stored on the route symbol: `POST /orders -> create_order (flask)`
+    pub fn signature(&self) -> String {
+        format!(
+            "{} -> {} ({})",
+            self.name(),
+            self.handler.as_deref().unwrap_or(INLINE_HANDLER),
+            self.framework
+        )
+    }
+
+    /// Rebuild a route from a stored route symbol
+    pub fn from_symbol(symbol: &Symbol) -> Option<Self> {
+        if symbol.kind != SymbolKind::Route {
+            return None;
+        }
+        let (method, path) = symbol.name.split_once(' ')?;
+        let signature = symbol.signature.as_deref().unwrap_or_default();
+        let (handler, framework) = signature
+            .split_once(" -> ")
+            .and_then(|(_, rest)| rest.rsplit_once(" ("))
+            .map(|(handler, framework)| (handler, framework.trim_end_matches(')')))
+            .unwrap_or((INLINE_HANDLER, ""));
+
+        Some(Self {
+            method: method.to_string(),
+            path: path.to_string(),
+            handler: (handler != INLINE_HANDLER).then(|| handler.to_string()),
+            handler_id: None,
+            line: symbol.range.start_line,
+            framework: framework.to_string(),
+        })
+    }
+}
+
+/// Canonical upper-case form of an HTTP method name
+pub fn http_method(word: &str) -> Option<&'static str> {
+    HTTP_METHODS
+        .iter()
+        .find(|method| method.eq_ignore_ascii_case(word))
+        .copied()
+}
+
+/// HTTP methods named anywhere in `text`, e.g. `methods=["GET", "POST"]`
+pub fn http_methods_in(text: &str) -> Vec<&'static str> {
+    let mut methods = Vec::new();
+    for word in text.split(|c: char| !c.is_ascii_alphabetic()) {
+        if let Some(method) = http_method(word) {
+            if !methods.contains(&method) {
+                methods.push(method);
+            }
+        }
+    }
+    methods
+}
+
+/// Content of the string literal `text` starts with
+///
+/// Accepts `'..'`, `".."` and `` `..` `` with the prefixes Python (`r`, `f`),
+/// C# (`@`, `$`) and Rust (`r#`) put in front of them.
+pub fn string_literal(text: &str) -> Option<String> {
+    let text = text.trim_start();
+    let body = text.trim_start_matches(['r', 'f', 'b', 'u', 'R', 'F', 'B', 'U', '@', '$', '#']);
+    let quote = body
+        .chars()
+        .next()
+        .filter(|c| matches!(c, '"' | '\'' | '`'))?;
+    let rest = &body[1..];
+    let mut value = String::new();
+    let mut escaped = false;
+    for c in rest.chars() {
+        if escaped {
+            value.push(c);
+            escaped = false;
+        } else if c == '\\' {
+            value.push(c);
+            escaped = true;
+        } else if c == quote {
+            return Some(value);
+        } else {
+            value.push(c);
+        }
+    }
+    None
+}
+
+/// First string literal anywhere in `text`
+pub fn first_string(text: &str) -> Option<String> {
+    let start = text.find(['"', '\'', '`'])?;
+    string_literal(&text[start..])
+}
+
+/// Top-level arguments of an argument list, trimmed
+pub fn arguments(text: &str) -> Vec<&str> {
+    split_top_level(text)
+        .into_iter()
+        .map(str::trim)
+        .filter(|arg| !arg.is_empty())
+        .collect()
+}
+
+/// Split `key = value` or `key: value` (named arguments and object fields)
+fn split_keyword(arg: &str) -> Option<(&str, &str)> {
+    let end = arg.find(|c: char| !(c.is_alphanumeric() || c == '_'))?;
+    if end == 0 {
+        return None;
+    }
+    let rest = arg[end..].trim_start();
+    let value = if let Some(value) = rest.strip_prefix(':') {
+        if value.starts_with(':') {
+            return None;
+        }
+        value
+    } else {
+        let value = rest.strip_prefix('=')?;
+        if value.starts_with(['=', '>']) {
+            return None;
+        }
+        value
+    };
+    Some((&arg[..end], value.trim()))
+}
+
+/// Value of the named argument `key` in an argument list
+pub fn keyword_argument<'a>(args: &[&'a str], key: &str) -> Option<&'a str> {
+    args.iter()
+        .filter_map(|arg| split_keyword(arg))
+        .find(|(name, _)| name.eq_ignore_ascii_case(key))
+        .map(|(_, value)| value)
+}
+
+/// Path of a route annotation: the first positional argument, or one of `keys`
+pub fn path_argument(text: &str, keys: &[&str]) -> Option<String> {
+    let args = arguments(text);
+    if let Some(first) = args.first() {
+        if split_keyword(first).is_none() {
+            return first_string(first);
+        }
+    }
+    keys.iter()
+        .find_map(|key| keyword_argument(&args, key))
+        .and_then(first_string)
+}
+
+/// Path with a single leading `/`
+pub fn normalize_path(path: &str) -> String {
+    let path = path.trim();
+    let trimmed = path.trim_start_matches(['~', '/']);
+    format!("/{trimmed}")
+}
+
+/// Join a controller or group prefix and a route path
+///
+/// A path starting with `~/` (ASP.NET) ignores the prefix.
+pub fn join_path(prefix: &str, path: &str) -> String {
+    if path.starts_with("~/") || prefix.trim_matches('/').is_empty() {
+        return normalize_path(path);
+    }
+    let prefix = normalize_path(prefix);
+    let path = path.trim_start_matches('/');
+    if path.is_empty() {
+        prefix
+    } else {
+        format!("{}/{path}", prefix.trim_end_matches('/'))
+    }
+}
+
+/// Whether `text` is a plain handler reference such as `views.create_order`
+pub fn is_handler_path(text: &str) -> bool {
+    !text.is_empty()
+        && text.split(['.', ':', '\\', '@']).all(|segment| {
+            segment.is_empty()
+                || segment
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+        })
+        && text.chars().next().is_some_and(|c| !c.is_ascii_digit())
+        && text.chars().any(|c| c.is_alphabetic())
+}
+
+/// Last segment of a handler reference (`OrderController@store` -> `store`)
+pub fn handler_short_name(handler: &str) -> &str {
+    handler
+        .rsplit(['.', ':', '\\', '@'])
+        .next()
+        .unwrap_or(handler)
+}
+
+/// Innermost function or method whose range covers `line`
+pub fn enclosing_callable(symbols: &[Symbol], line: u32) -> Option<&Symbol> {
+    symbols
+        .iter()
+        .filter(|s| matches!(s.kind, SymbolKind::Function | SymbolKind::Method))
+        .filter(|s| s.range.start_line <= line && line <= s.range.end_line)
+        .min_by_key(|s| s.range.end_line - s.range.start_line)
+}
+
+/// Innermost class around `symbol`, for controller-level route prefixes
+pub fn enclosing_class<'a>(symbols: &'a [Symbol], symbol: &Symbol) -> Option<&'a Symbol> {
+    symbols
+        .iter()
+        .filter(|s| matches!(s.kind, SymbolKind::Class | SymbolKind::Struct))
+        .filter(|s| s.id != symbol.id)
+        .filter(|s| {
+            s.range.start_line <= symbol.range.start_line
+                && symbol.range.end_line <= s.range.end_line
+        })
+        .min_by_key(|s| s.range.end_line - s.range.start_line)
+}
+
+/// Link handlers named at the registration to symbols defined in the same file
+///
+/// Routes whose handler is still unknown afterwards are resolved across files
+/// by the indexer. Inline handlers are attributed to the enclosing function.
+pub fn resolve_local_handlers(routes: &mut [RouteEntry], symbols: &[Symbol]) {
+    for route in routes.iter_mut().filter(|r| r.handler_id.is_none()) {
+        match &route.handler {
+            Some(handler) => {
+                let short = handler_short_name(handler);
+                route.handler_id = symbols
+                    .iter()
+                    .filter(|s| {
+                        matches!(
+                            s.kind,
+                            SymbolKind::Function | SymbolKind::Method | SymbolKind::Class
+                        )
+                    })
+                    .find(|s| handler_short_name(&s.name) == short)
+                    .map(|s| s.id);
+            }
+            None => {
+                if let Some(symbol) = enclosing_callable(symbols, route.line) {
+                    route.handler_id = Some(symbol.id);
+                }
+            }
+        }
+    }
+}
+
+/// A call found by [`find_calls`]
+#[derive(Debug)]
+pub struct Call<'a> {
+    /// Capture groups of the pattern (group 0 excluded)
+    pub groups: Vec<Option<&'a str>>,
+    /// Text between the call's parentheses
+    pub args: &'a str,
+    /// Zero-based line of the call
+    pub line: u32,
+    /// Byte offset just after the closing parenthesis
+    pub end: usize,
+}
+
+/// Calls whose opening matches `pattern`; the pattern must end with `\(`
+pub fn find_calls<'a>(source: &'a str, pattern: &Regex) -> Vec<Call<'a>> {
+    let mut calls = Vec::new();
+    let mut line = 0u32;
+    let mut counted = 0usize;
+    for captures in pattern.captures_iter(source) {
+        let whole = captures.get(0).expect("group 0 always matches");
+        let open = whole.end() - 1;
+        let Some(close) = matching_close(&source[open..]) else {
+            continue;
+        };
+        line += source[counted..whole.start()].matches('\n').count() as u32;
+        counted = whole.start();
+        calls.push(Call {
+            groups: captures
+                .iter()
+                .skip(1)
+                .map(|group| group.map(|m| m.as_str()))
+                .collect(),
+            args: &source[open + 1..open + close],
+            line,
+            end: open + close + 1,
+        });
+    }
+    calls
+}
+
+/// Whether a concrete request path (`/orders/42`) matches a route pattern
+///
+/// Understands `{id}`, `:id`, `<int:id>`, `[id]` and `*` segments, plus
+/// catch-all forms like `{*rest}`, `[...slug]` and `**`.
+pub fn path_matches(pattern: &str, path: &str) -> bool {
+    let pattern_segments: Vec<&str> = pattern.trim_matches('/').split('/').collect();
+    let path_segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+    for (i, segment) in pattern_segments.iter().enumerate() {
+        let catch_all = segment.starts_with("{*")
+            || segment.starts_with("[...")
+            || segment.starts_with("[[...")
+            || *segment == "**"
+            || segment.starts_with("<path:");
+        if catch_all {
+            return path_segments.len() > i;
+        }
+        let Some(actual) = path_segments.get(i) else {
+            return false;
+        };
+        let parameter = (segment.starts_with('{') && segment.ends_with('}'))
+            || (segment.starts_with('<') && segment.ends_with('>'))
+            || (segment.starts_with('[') && segment.ends_with(']'))
+            || segment.starts_with(':')
+            || *segment == "*";
+        if !parameter && !segment.eq_ignore_ascii_case(actual) {
+            return false;
+        }
+    }
+    pattern_segments.len() == path_segments.len()
+}
+
+/// Parse `source` into symbols with their annotations, for extractor tests
+#[cfg(test)]
+pub(crate) fn parse_symbols(
+    parser: &mut dyn crate::parsing::LanguageParser,
+    source: &str,
+) -> Vec<Symbol> {
+    let mut counter = crate::types::SymbolCounter::new();
+    parser.parse(source, crate::FileId::new(1).unwrap(), &mut counter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arguments_and_paths() {
+        assert_eq!(
+            path_argument(r#""/orders", methods=["POST"]"#, &["rule"]),
+            Some("/orders".to_string())
+        );
+        assert_eq!(
+            path_argument(
+                r#"produces = ["application/json"], value = ["/x"]"#,
+                &["value", "path"]
+            ),
+            Some("/x".to_string())
+        );
+        assert_eq!(
+            keyword_argument(&arguments("'/x', methods: ['POST']"), "methods"),
+            Some("['POST']")
+        );
+        assert_eq!(
+            http_methods_in("[RequestMethod.POST, RequestMethod.PUT]"),
+            vec!["POST", "PUT"]
+        );
+        assert_eq!(
+            string_literal(r#"r"^orders/$""#),
+            Some("^orders/$".to_string())
+        );
+        assert_eq!(
+            join_path("api/[controller]", "{id}"),
+            "/api/[controller]/{id}"
+        );
+        assert_eq!(join_path("/api", ""), "/api");
+        assert_eq!(join_path("/api", "~/health"), "/health");
+        assert!(is_handler_path("OrderController@store"));
+        assert!(!is_handler_path("|| async { \"hi\" }"));
+        assert_eq!(handler_short_name("handlers::create_order"), "create_order");
+    }
+
+    #[test]
+    fn test_path_matches() {
+        assert!(path_matches("/orders/{id}", "/orders/42"));
+        assert!(path_matches("/orders/:id", "/orders/42/"));
+        assert!(path_matches("/orders/<int:id>", "/orders/42"));
+        assert!(path_matches("/docs/[...slug]", "/docs/a/b"));
+        assert!(!path_matches("/orders/{id}", "/orders"));
+        assert!(!path_matches("/orders", "/users"));
+    }
+
+    #[test]
+    fn test_signature_round_trip() {
+        let route = RouteEntry::new("post", "orders", "flask", 4).with_handler("views.create");
+        let mut symbol = Symbol::new(
+            SymbolId::new(7).unwrap(),
+            route.name(),
+            SymbolKind::Route,
+            crate::FileId::new(1).unwrap(),
+            crate::Range::new(4, 0, 4, 0),
+        );
+        symbol.signature = Some(route.signature().into());
+
+        assert_eq!(&*symbol.name, "POST /orders");
+        assert_eq!(RouteEntry::from_symbol(&symbol), Some(route));
+    }
+
+    #[test]
+    fn test_find_calls_reports_lines() {
+        let pattern = Regex::new(r"\.route\s*\(").unwrap();
+        let source = "let app = Router::new()\n    .route(\n        \"/a\",\n        get(a),\n    )\n    .route(\"/b\", post(b));";
+        let calls = find_calls(source, &pattern);
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].line, 1);
+        assert_eq!(calls[1].line, 5);
+        assert_eq!(calls[1].args, "\"/b\", post(b)");
+    }
+}
@@ -65,6 +65,25 @@ impl LanguageBehavior for RustBehavior {
         }
     }
 
+    fn is_test_symbol(&self, symbol: &crate::Symbol, _file_path: &str) -> bool {
+        use crate::parsing::test_detection::has_annotation;
+
+        // #[test], #[tokio::test], #[rstest], #[test_case(..)]
+        matches!(
+            symbol.kind,
+            crate::SymbolKind::Function | crate::SymbolKind::Method
+        ) && has_annotation(symbol, &["test", "rstest", "test_case"])
+    }
+
+    fn extract_routes(
+        &self,
+        source: &str,
+        _file_path: &str,
+        symbols: &[crate::Symbol],
+    ) -> Vec<crate::parsing::RouteEntry> {
+        super::routes::extract_routes(source, symbols)
+    }
+
     fn module_separator(&self) -> &'static str {
         "::"
     }
@@ -425,6 +444,26 @@ impl LanguageBehavior for RustBehavior {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_is_test_symbol() {
+        use crate::parsing::RustParser;
+        use crate::types::SymbolCounter;
+        use crate::{FileId, Symbol};
+
+        let behavior = RustBehavior::new();
+        let source = "#[cfg(test)]\nmod tests {\n    #[tokio::test]\n    async fn adds() {}\n\n    fn helper() {}\n}\n";
+        let symbols = RustParser::new().unwrap().parse(
+            source,
+            FileId::new(1).unwrap(),
+            &mut SymbolCounter::new(),
+        );
+        let function =
+            |name: &str| -> &Symbol { symbols.iter().find(|s| s.name.as_ref() == name).unwrap() };
+
+        assert!(behavior.is_test_symbol(function("adds"), "src/lib.rs"));
+        assert!(!behavior.is_test_symbol(function("helper"), "src/lib.rs"));
+    }
+
     #[test]
     fn test_format_module_path() {
         let behavior = RustBehavior::new();
@@ -5,6 +5,7 @@ pub mod behavior;
 pub mod definition;
 pub mod parser;
 pub mod resolution;
+pub mod routes;
 
 pub use behavior::RustBehavior;
 pub use definition::RustLanguage;
@@ -31,6 +31,7 @@
 //! - Eliminating all intermediate allocations
 
 use crate::parsing::Import;
+use crate::parsing::annotation::{AnnotationNodes, AnnotationSyntax, attach_annotations};
 use crate::parsing::method_call::MethodCall;
 use crate::parsing::parser::check_recursion_depth;
 use crate::parsing::{
@@ -40,6 +41,13 @@ use crate::types::SymbolCounter;
 use crate::{FileId, Range, Symbol, SymbolKind};
 use tree_sitter::{Node, Parser};
 
+/// Attributes read into `Symbol::annotations`
+const ANNOTATION_NODES: AnnotationNodes = AnnotationNodes {
+    syntax: AnnotationSyntax::Hash,
+    annotations: &["attribute_item"],
+    containers: &[],
+};
+
 /// Debug print macro that respects the debug setting
 macro_rules! debug_print {
     ($self:expr, $($arg:tt)*) => {
@@ -294,6 +302,7 @@ impl RustParser {
 
         // Walk the tree manually to find symbols
         self.extract_symbols_from_node(root_node, code, file_id, &mut symbols, symbol_counter, 0);
+        attach_annotations(root_node, code, &mut symbols, &ANNOTATION_NODES);
 
         symbols
     }
@@ -0,0 +1,166 @@
+//! HTTP route extraction for axum, actix-web and Rocket
+//!
+//! - Attribute routes: `#[get("/orders")]`, `#[route("/orders", method = "POST")]`
+//! - Router builders: `.route("/orders", get(list).post(create))` (axum) and
+//!   `.route("/orders", web::post().to(create))` (actix-web)
+
+use crate::parsing::route::{
+    self, ANY_METHOD, RouteEntry, arguments, find_calls, http_method, http_methods_in,
+    path_argument,
+};
+use crate::{Symbol, SymbolKind};
+use regex::Regex;
+use std::sync::LazyLock;
+
+static ROUTE_CALL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\.\s*route\s*\(").expect("Invalid regex"));
+
+static METHOD_CALL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b(get|post|put|delete|patch|head|options|trace|any)\s*\(").expect("Invalid regex")
+});
+
+static TO_CALL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\.\s*to\s*\(").expect("Invalid regex"));
+
+pub fn extract_routes(source: &str, symbols: &[Symbol]) -> Vec<RouteEntry> {
+    let attribute_framework = if source.contains("rocket") {
+        "rocket"
+    } else {
+        "actix"
+    };
+
+    let mut routes = Vec::new();
+    for symbol in symbols
+        .iter()
+        .filter(|s| matches!(s.kind, SymbolKind::Function | SymbolKind::Method))
+    {
+        for annotation in &symbol.annotations {
+            let args = annotation.arguments.as_deref().unwrap_or_default();
+            let Some(path) = path_argument(args, &["path", "uri"]) else {
+                continue;
+            };
+            let methods = match annotation.short_name() {
+                "route" => {
+                    let methods: Vec<&str> = arguments(args)
+                        .into_iter()
+                        .filter(|arg| arg.starts_with("method"))
+                        .flat_map(http_methods_in)
+                        .collect();
+                    if methods.is_empty() {
+                        vec![ANY_METHOD]
+                    } else {
+                        methods
+                    }
+                }
+                name => match http_method(name) {
+                    Some(method) => vec![method],
+                    None => continue,
+                },
+            };
+            for method in methods {
+                routes.push(
+                    RouteEntry::new(method, &path, attribute_framework, symbol.range.start_line)
+                        .with_handler_symbol(symbol),
+                );
+            }
+        }
+    }
+
+    for call in find_calls(source, &ROUTE_CALL) {
+        let args = arguments(call.args);
+        let (Some(path), Some(method_router)) = (
+            args.first().and_then(|a| route::string_literal(a)),
+            args.get(1),
+        ) else {
+            continue;
+        };
+
+        if let Some(to) = find_calls(method_router, &TO_CALL).first() {
+            // actix-web: web::post().to(handler)
+            let methods: Vec<&str> = find_calls(method_router, &METHOD_CALL)
+                .iter()
+                .filter(|c| c.args.trim().is_empty())
+                .filter_map(|c| c.groups[0])
+                .map(method_name)
+                .collect();
+            let handler = to.args.trim();
+            for method in if methods.is_empty() {
+                vec![ANY_METHOD]
+            } else {
+                methods
+            } {
+                routes
+                    .push(RouteEntry::new(method, &path, "actix", call.line).with_handler(handler));
+            }
+            continue;
+        }
+
+        // axum: get(list).post(create)
+        for method_call in find_calls(method_router, &METHOD_CALL) {
+            let Some(method) = method_call.groups[0] else {
+                continue;
+            };
+            routes.push(
+                RouteEntry::new(method_name(method), &path, "axum", call.line)
+                    .with_handler(method_call.args.trim()),
+            );
+        }
+    }
+
+    route::resolve_local_handlers(&mut routes, symbols);
+    routes
+}
+
+fn method_name(word: &str) -> &'static str {
+    http_method(word).unwrap_or(ANY_METHOD)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::RustParser;
+
+    fn parse(source: &str) -> Vec<Symbol> {
+        let mut parser = RustParser::new().unwrap();
+        route::parse_symbols(&mut parser, source)
+    }
+
+    #[test]
+    fn test_axum_and_actix_routes() {
+        let source = r#"
+use axum::{Router, routing::{get, post}};
+
+async fn list_orders() {}
+async fn create_order() {}
+
+fn app() -> Router {
+    Router::new()
+        .route("/orders", get(list_orders).post(handlers::create_order))
+        .route("/health", get(|| async { "ok" }))
+}
+
+#[actix_web::get("/users/{id}")]
+async fn get_user() {}
+
+fn config(cfg: &mut web::ServiceConfig) {
+    cfg.route("/users", web::post().to(create_user));
+}
+"#;
+        let symbols = parse(source);
+        let routes = extract_routes(source, &symbols);
+        let names: Vec<String> = routes.iter().map(|r| r.signature()).collect();
+
+        assert!(names.contains(&"GET /users/{id} -> get_user (actix)".to_string()));
+        assert!(names.contains(&"GET /orders -> list_orders (axum)".to_string()));
+        assert!(names.contains(&"POST /orders -> handlers::create_order (axum)".to_string()));
+        assert!(names.contains(&"POST /users -> create_user (actix)".to_string()));
+
+        let health = routes.iter().find(|r| r.path == "/health").unwrap();
+        let app = symbols.iter().find(|s| &*s.name == "app").unwrap();
+        assert_eq!(health.handler, None);
+        assert_eq!(health.handler_id, Some(app.id));
+
+        let list = routes.iter().find(|r| r.name() == "GET /orders").unwrap();
+        assert!(list.handler_id.is_some());
+    }
+}
@@ -0,0 +1,60 @@
+//! Shared helpers for recognising test symbols
+//!
+//! Test frameworks mark tests with attributes (`#[test]`), annotations
+//! (`@Test`) or naming conventions (`test_*`, `TestXxx`). Parsers fill
+//! `Symbol::annotations` from the syntax tree (see [`crate::parsing::annotation`]),
+//! so markers are checked with [`has_annotation`] and conventions by name.
+
+use crate::Symbol;
+
+/// Whether an annotation of `symbol` is one of `names`, compared by last segment
+///
+/// `#[tokio::test]` matches `test`, `@org.junit.jupiter.api.Test` matches `Test`.
+pub fn has_annotation(symbol: &Symbol, names: &[&str]) -> bool {
+    symbol
+        .annotations
+        .iter()
+        .any(|annotation| names.contains(&annotation.short_name()))
+}
+
+/// Last segment of a qualified name such as `TestShapes.test_area`
+pub fn short_name(name: &str) -> &str {
+    name.rsplit(['.', ':']).next().unwrap_or(name)
+}
+
+/// File name part of a path, for convention checks like `*_test.go`
+pub fn file_name(path: &str) -> &str {
+    path.rsplit(['/', '\\']).next().unwrap_or(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_has_annotation() {
+        use crate::parsing::Annotation;
+        use crate::{FileId, Range, SymbolId, SymbolKind};
+
+        let mut symbol = Symbol::new(
+            SymbolId::new(1).unwrap(),
+            "adds",
+            SymbolKind::Function,
+            FileId::new(1).unwrap(),
+            Range::new(0, 0, 0, 1),
+        );
+        symbol.annotations = ["tokio::test(flavor = \"multi_thread\")", "ignore"]
+            .into_iter()
+            .filter_map(Annotation::parse)
+            .collect();
+        assert!(has_annotation(&symbol, &["test"]));
+        assert!(!has_annotation(&symbol, &["tokio", "Test"]));
+    }
+
+    #[test]
+    fn test_short_and_file_names() {
+        assert_eq!(short_name("TestShapes.test_area"), "test_area");
+        assert_eq!(short_name("Calc::double"), "double");
+        assert_eq!(file_name("pkg/calc/calc_test.go"), "calc_test.go");
+    }
+}
@@ -108,6 +108,16 @@ impl LanguageBehavior for TypeScriptBehavior {
     fn get_language(&self) -> Language {
         tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into()
     }
+
+    fn extract_routes(
+        &self,
+        source: &str,
+        file_path: &str,
+        symbols: &[crate::Symbol],
+    ) -> Vec<crate::parsing::RouteEntry> {
+        super::routes::extract_routes(source, file_path, symbols)
+    }
+
     fn module_separator(&self) -> &'static str {
         "."
     }
@@ -468,6 +478,13 @@ impl LanguageBehavior for TypeScriptBehavior {
         symbol.module_path = Some(module_path.to_string().into());
         symbol.scope_context = Some(crate::symbol::ScopeContext::Global);
         symbol.language_id = Some(language_id);
+        symbol.stable_id = Some(crate::StableSymbolId::derive(
+            language_id.as_str(),
+            module_path,
+            &symbol.name,
+            symbol.kind,
+            0,
+        ));
 
         document_index
             .index_symbol(&symbol, &path_str)
@@ -6,6 +6,7 @@
 //! When migrating or updating the parser, ensure compatibility with ABI-14 features.
 
 use crate::parsing::Import;
+use crate::parsing::annotation::{AnnotationNodes, AnnotationSyntax, attach_annotations};
 use crate::parsing::parser::check_recursion_depth;
 use crate::parsing::{
     LanguageParser, MethodCall, NodeTracker, NodeTrackingState, ParserContext, ScopeType,
@@ -15,6 +16,13 @@ use crate::{FileId, Range, Symbol, SymbolKind, Visibility};
 use std::any::Any;
 use tree_sitter::{Language, Node, Parser};
 
+/// Decorators read into `Symbol::annotations`
+const ANNOTATION_NODES: AnnotationNodes = AnnotationNodes {
+    syntax: AnnotationSyntax::At,
+    annotations: &["decorator"],
+    containers: &[],
+};
+
 /// TypeScript language parser
 pub struct TypeScriptParser {
     parser: Parser,
@@ -87,6 +95,7 @@ impl TypeScriptParser {
                     "", // Module path will be determined by behavior
                     0,
                 );
+                attach_annotations(root_node, code, &mut symbols, &ANNOTATION_NODES);
             }
             None => {
                 eprintln!("Failed to parse TypeScript file");
@@ -278,6 +287,70 @@ impl TypeScriptParser {
                     depth + 1,
                 );
             }
+            "call_expression" => {
+                self.register_handled_node(node.kind(), node.kind_id());
+                let Some((is_test, name, callback)) = Self::jest_block(&node, code) else {
+                    let mut cursor = node.walk();
+                    for child in node.children(&mut cursor) {
+                        self.extract_symbols_from_node(
+                            child,
+                            code,
+                            file_id,
+                            counter,
+                            symbols,
+                            module_path,
+                            depth + 1,
+                        );
+                    }
+                    return;
+                };
+
+                // Jest/Vitest `it(...)` and `test(...)` blocks become test symbols
+                if is_test {
+                    let callee = node
+                        .child_by_field_name("function")
+                        .map(|f| &code[f.byte_range()])
+                        .unwrap_or("test");
+                    let mut symbol = self.create_symbol(
+                        counter.next_id(),
+                        name.to_string(),
+                        SymbolKind::Function,
+                        file_id,
+                        Range::new(
+                            node.start_position().row as u32,
+                            node.start_position().column as u16,
+                            node.end_position().row as u32,
+                            node.end_position().column as u16,
+                        ),
+                        Some(format!("{callee}(\"{name}\")")),
+                        None,
+                        module_path,
+                        Visibility::Private,
+                    );
+                    symbol.is_test = true;
+                    symbols.push(symbol);
+                }
+
+                // Nested blocks (`describe` -> `it`) live in the callback body
+                if let Some(body) = callback.child_by_field_name("body") {
+                    self.context.enter_scope(ScopeType::function());
+                    let saved_function = self.context.current_function().map(|s| s.to_string());
+                    if is_test {
+                        self.context.set_current_function(Some(name.to_string()));
+                    }
+                    self.extract_symbols_from_node(
+                        body,
+                        code,
+                        file_id,
+                        counter,
+                        symbols,
+                        module_path,
+                        depth + 1,
+                    );
+                    self.context.exit_scope();
+                    self.context.set_current_function(saved_function);
+                }
+            }
             "arrow_function" => {
                 self.register_handled_node(node.kind(), node.kind_id());
                 // Handle arrow functions assigned to variables
@@ -472,6 +545,80 @@ impl TypeScriptParser {
         }
     }
 
+    /// Jest/Vitest block such as `describe("math", ...)` or `it("adds", () => {})`
+    ///
+    /// Matches `describe`/`it`/`test` and their `.only`/`.skip`/`.concurrent`
+    /// variants whose first argument is a plain string and second argument a
+    /// callback. Returns whether it is a test (not `describe`), the description
+    /// and the callback node.
+    fn jest_block<'a, 'tree>(
+        node: &Node<'tree>,
+        code: &'a str,
+    ) -> Option<(bool, &'a str, Node<'tree>)> {
+        if node.kind() != "call_expression" {
+            return None;
+        }
+        let function = node.child_by_field_name("function")?;
+        let base = match function.kind() {
+            "identifier" => function,
+            "member_expression" => {
+                let property = function.child_by_field_name("property")?;
+                if !matches!(&code[property.byte_range()], "only" | "skip" | "concurrent") {
+                    return None;
+                }
+                function.child_by_field_name("object")?
+            }
+            _ => return None,
+        };
+        let is_test = match &code[base.byte_range()] {
+            "it" | "test" => true,
+            "describe" => false,
+            _ => return None,
+        };
+
+        let arguments = node.child_by_field_name("arguments")?;
+        let mut cursor = arguments.walk();
+        let mut args = arguments.named_children(&mut cursor);
+        let description = args.next()?;
+        let callback = args.next()?;
+        if !matches!(callback.kind(), "arrow_function" | "function_expression") {
+            return None;
+        }
+
+        let name = match description.kind() {
+            "string" => {
+                let mut cursor = description.walk();
+                let fragment = description
+                    .named_children(&mut cursor)
+                    .find(|n| n.kind() == "string_fragment")?;
+                &code[fragment.byte_range()]
+            }
+            "template_string" => {
+                let mut cursor = description.walk();
+                if description
+                    .named_children(&mut cursor)
+                    .any(|n| n.kind() == "template_substitution")
+                {
+                    return None;
+                }
+                let text = &code[description.byte_range()];
+                text.strip_prefix('`')?.strip_suffix('`')?
+            }
+            _ => return None,
+        };
+        if name.trim().is_empty() {
+            return None;
+        }
+        Some((is_test, name, callback))
+    }
+
+    /// Description of a Jest/Vitest `it`/`test` block
+    fn jest_test_name<'a>(node: &Node, code: &'a str) -> Option<&'a str> {
+        Self::jest_block(node, code)
+            .filter(|(is_test, _, _)| *is_test)
+            .map(|(_, name, _)| name)
+    }
+
     /// Process a function declaration
     fn process_function(
         &mut self,
@@ -1539,6 +1686,13 @@ impl TypeScriptParser {
                     );
                 }
                 Some(name)
+            } else if let Some(test_name) = node
+                .parent()
+                .and_then(|args| args.parent())
+                .and_then(|call| Self::jest_test_name(&call, code))
+            {
+                // Callback of a Jest `it`/`test` block: calls belong to the test
+                Some(test_name)
             } else {
                 // Arrow functions might not have a name, check parent for variable declaration
                 // Handle case: const ComponentName = () => { ... }
@@ -3039,6 +3193,42 @@ export { default as MyButton } from './Button';
         );
     }
 
+    #[test]
+    fn test_jest_blocks_are_test_symbols() {
+        let mut parser = TypeScriptParser::new().unwrap();
+        let file_id = FileId::new(1).unwrap();
+        let code = r#"
+            describe('sum', () => {
+                it('adds numbers', () => {
+                    expect(sum(1, 2)).toBe(3);
+                });
+                test.skip(`adds zero`, function () { sum(0, 2); });
+            });
+            it.todo('later');
+            const ok = /x/.test('x');
+        "#;
+
+        let mut counter = SymbolCounter::new();
+        let symbols = parser.parse(code, file_id, &mut counter);
+        let tests: Vec<&str> = symbols
+            .iter()
+            .filter(|s| s.is_test)
+            .map(|s| s.name.as_ref())
+            .collect();
+        assert_eq!(tests, vec!["adds numbers", "adds zero"]);
+
+        // Calls inside the callbacks are attributed to the test
+        let calls = parser.find_calls(code);
+        for test in ["adds numbers", "adds zero"] {
+            assert!(
+                calls
+                    .iter()
+                    .any(|(from, to, _)| *from == test && *to == "sum"),
+                "missing call from {test}"
+            );
+        }
+    }
+
     #[test]
     fn test_typescript_find_variable_types_new_expression() {
         let mut parser = TypeScriptParser::new().unwrap();
@@ -511,6 +511,8 @@ impl ResolutionScope for TypeScriptResolutionContext {
                 // Very permissive - almost anything can reference anything
                 true
             }
+            Routes => from_kind == Route && matches!(to_kind, Function | Method | Class),
+            RoutedBy => matches!(from_kind, Function | Method | Class) && to_kind == Route,
         }
     }
 
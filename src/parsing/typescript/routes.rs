@@ -0,0 +1,342 @@
+//! HTTP route extraction for Express, Fastify, NestJS and Next.js
+//!
+//! - Registrations: `app.post('/orders', auth, createOrder)`,
+//!   `router.route('/orders').get(list).post(create)`,
+//!   `fastify.route({ method: 'GET', url: '/orders', handler })`
+//! - NestJS decorators: `@Controller('orders')` with `@Get(':id')`
+//! - Next.js file routes: `app/**/route.ts` exports and `pages/api/**`
+
+use crate::parsing::route::{
+    self, ANY_METHOD, RouteEntry, arguments, find_calls, first_string, http_method,
+    http_methods_in, join_path, keyword_argument, path_argument,
+};
+use crate::parsing::test_detection::file_name;
+use crate::{Symbol, SymbolKind};
+use regex::Regex;
+use std::sync::LazyLock;
+
+static REGISTRATION_CALL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"([A-Za-z_$][\w$]*)\s*\.\s*(get|post|put|delete|patch|head|options|all)\s*\(")
+        .expect("Invalid regex")
+});
+
+static ROUTE_CALL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"([A-Za-z_$][\w$]*)\s*\.\s*route\s*\(").expect("Invalid regex"));
+
+static CHAINED_METHOD: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*\.\s*(get|post|put|delete|patch|head|options|all)\s*\(")
+        .expect("Invalid regex")
+});
+
+static DEFAULT_EXPORT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"export\s+default\s+(?:async\s+)?(?:function\s*\*?\s*)?([A-Za-z_$][\w$]*)")
+        .expect("Invalid regex")
+});
+
+/// Receivers whose `.get(url)` is an HTTP client call, not a route
+const CLIENT_RECEIVERS: [&str; 10] = [
+    "axios",
+    "http",
+    "https",
+    "client",
+    "request",
+    "superagent",
+    "fetch",
+    "cy",
+    "$",
+    "api",
+];
+
+pub fn extract_routes(source: &str, file_path: &str, symbols: &[Symbol]) -> Vec<RouteEntry> {
+    let mut routes = nest_routes(symbols);
+    routes.extend(registration_routes(source));
+    routes.extend(next_routes(source, file_path, symbols));
+    route::resolve_local_handlers(&mut routes, symbols);
+    routes
+}
+
+fn server_framework(source: &str) -> &'static str {
+    if source.contains("fastify") {
+        "fastify"
+    } else {
+        "express"
+    }
+}
+
+/// Method name of a registration call (`all` accepts every method)
+fn method_name(word: &str) -> &'static str {
+    http_method(word).unwrap_or(ANY_METHOD)
+}
+
+fn is_route_path(path: &str) -> bool {
+    path.starts_with('/') || path == "*"
+}
+
+fn registration_routes(source: &str) -> Vec<RouteEntry> {
+    let framework = server_framework(source);
+    let mut routes = Vec::new();
+
+    for call in find_calls(source, &REGISTRATION_CALL) {
+        let (Some(receiver), Some(method)) = (call.groups[0], call.groups[1]) else {
+            continue;
+        };
+        if CLIENT_RECEIVERS.contains(&receiver.to_lowercase().as_str()) {
+            continue;
+        }
+        let args = arguments(call.args);
+        if args.len() < 2 {
+            continue;
+        }
+        let Some(path) = route::string_literal(args[0]).filter(|p| is_route_path(p)) else {
+            continue;
+        };
+        let handler = args.last().copied().unwrap_or_default();
+        routes.push(
+            RouteEntry::new(method_name(method), &path, framework, call.line).with_handler(handler),
+        );
+    }
+
+    for call in find_calls(source, &ROUTE_CALL) {
+        let args = arguments(call.args);
+        let Some(first) = args.first() else {
+            continue;
+        };
+
+        if let Some(options) = first.strip_prefix('{').and_then(|o| o.strip_suffix('}')) {
+            // fastify.route({ method, url, handler })
+            let fields = arguments(options);
+            let Some(path) = keyword_argument(&fields, "url")
+                .or_else(|| keyword_argument(&fields, "path"))
+                .and_then(first_string)
+            else {
+                continue;
+            };
+            let methods = keyword_argument(&fields, "method")
+                .map(http_methods_in)
+                .unwrap_or_default();
+            let handler = keyword_argument(&fields, "handler")
+                .or_else(|| fields.iter().copied().find(|f| *f == "handler"))
+                .unwrap_or_default();
+            for method in if methods.is_empty() {
+                vec![ANY_METHOD]
+            } else {
+                methods
+            } {
+                routes.push(
+                    RouteEntry::new(method, &path, framework, call.line).with_handler(handler),
+                );
+            }
+            continue;
+        }
+
+        // router.route('/orders').get(list).post(create)
+        let Some(path) = route::string_literal(first).filter(|p| is_route_path(p)) else {
+            continue;
+        };
+        let mut rest = &source[call.end..];
+        while let Some(chained) = find_calls(rest, &CHAINED_METHOD).into_iter().next() {
+            if let Some(method) = chained.groups[0] {
+                let handler = arguments(chained.args).last().copied().unwrap_or_default();
+                routes.push(
+                    RouteEntry::new(method_name(method), &path, framework, call.line)
+                        .with_handler(handler),
+                );
+            }
+            rest = &rest[chained.end..];
+        }
+    }
+    routes
+}
+
+fn nest_routes(symbols: &[Symbol]) -> Vec<RouteEntry> {
+    let mut routes = Vec::new();
+    for symbol in symbols.iter().filter(|s| s.kind == SymbolKind::Method) {
+        let prefix = route::enclosing_class(symbols, symbol)
+            .and_then(|class| {
+                class
+                    .annotations
+                    .iter()
+                    .find(|a| a.short_name() == "Controller")
+            })
+            .map(|a| {
+                path_argument(a.arguments.as_deref().unwrap_or_default(), &["path"])
+                    .unwrap_or_default()
+            });
+        let Some(prefix) = prefix else {
+            continue;
+        };
+
+        for annotation in &symbol.annotations {
+            let method = match annotation.short_name() {
+                "All" => ANY_METHOD,
+                name => match http_method(name) {
+                    Some(method) => method,
+                    None => continue,
+                },
+            };
+            let path = path_argument(
+                annotation.arguments.as_deref().unwrap_or_default(),
+                &["path"],
+            )
+            .unwrap_or_default();
+            routes.push(
+                RouteEntry::new(
+                    method,
+                    &join_path(&prefix, &path),
+                    "nestjs",
+                    symbol.range.start_line,
+                )
+                .with_handler_symbol(symbol),
+            );
+        }
+    }
+    routes
+}
+
+/// Route path of a Next.js file below `app/` or `pages/`
+fn next_path<'a>(segments: impl Iterator<Item = &'a str>) -> String {
+    // Route groups `(name)` and parallel-route slots `@name` are not part of the URL
+    let is_group = |s: &str| s.starts_with('(') && s.ends_with(')');
+    let kept: Vec<&str> = segments
+        .filter(|s| !s.starts_with('@') && !is_group(s))
+        .collect();
+    format!("/{}", kept.join("/"))
+}
+
+fn next_routes(source: &str, file_path: &str, symbols: &[Symbol]) -> Vec<RouteEntry> {
+    let normalized = file_path.replace('\\', "/");
+    let segments: Vec<&str> = normalized.split('/').collect();
+    let stem = file_name(&normalized)
+        .rsplit_once('.')
+        .map_or(file_name(&normalized), |(stem, _)| stem);
+    let mut routes = Vec::new();
+
+    // App router: app/orders/[id]/route.ts exporting GET, POST, ...
+    if stem == "route" {
+        if let Some(app) = segments.iter().rposition(|s| *s == "app") {
+            let path = next_path(segments[app + 1..segments.len() - 1].iter().copied());
+            for symbol in symbols.iter().filter(|s| {
+                matches!(
+                    s.kind,
+                    SymbolKind::Function | SymbolKind::Variable | SymbolKind::Constant
+                )
+            }) {
+                if let Some(method) = http_method(&symbol.name).filter(|m| *m == &*symbol.name) {
+                    routes.push(
+                        RouteEntry::new(method, &path, "nextjs", symbol.range.start_line)
+                            .with_handler_symbol(symbol),
+                    );
+                }
+            }
+        }
+    }
+
+    // Pages router: pages/api/orders/[id].ts default export
+    if let Some(pages) = segments
+        .iter()
+        .rposition(|s| *s == "pages")
+        .filter(|i| segments.get(i + 1) == Some(&"api"))
+    {
+        let mut parts: Vec<&str> = segments[pages + 1..segments.len() - 1].to_vec();
+        if stem != "index" {
+            parts.push(stem);
+        }
+        let path = next_path(parts.into_iter());
+        if let Some(captures) = DEFAULT_EXPORT.captures(source) {
+            let line = source[..captures.get(0).map_or(0, |m| m.start())]
+                .matches('\n')
+                .count() as u32;
+            let handler = captures.get(1).map_or("", |m| m.as_str());
+            routes.push(RouteEntry::new(ANY_METHOD, &path, "nextjs", line).with_handler(handler));
+        }
+    }
+    routes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::TypeScriptParser;
+
+    fn parse(source: &str) -> Vec<Symbol> {
+        let mut parser = TypeScriptParser::new().unwrap();
+        route::parse_symbols(&mut parser, source)
+    }
+
+    fn signatures(routes: &[RouteEntry]) -> Vec<String> {
+        routes.iter().map(|r| r.signature()).collect()
+    }
+
+    #[test]
+    fn test_express_registrations() {
+        let source = r#"
+import express from 'express';
+const app = express();
+
+function createOrder(req, res) {}
+
+app.post('/orders', auth, createOrder);
+app.get('/orders/:id', (req, res) => res.send('ok'));
+router.route('/users').get(users.list).put(users.update);
+const data = await axios.get('/orders', config);
+"#;
+        let symbols = parse(source);
+        let routes = extract_routes(source, "src/server.ts", &symbols);
+
+        assert_eq!(
+            signatures(&routes),
+            vec![
+                "POST /orders -> createOrder (express)",
+                "GET /orders/:id -> <inline> (express)",
+                "GET /users -> users.list (express)",
+                "PUT /users -> users.update (express)",
+            ]
+        );
+        assert!(routes[0].handler_id.is_some());
+    }
+
+    #[test]
+    fn test_nest_controller() {
+        let source = r#"
+@Controller('orders')
+export class OrdersController {
+  @Get(':id')
+  findOne(id: string) {}
+
+  @Post()
+  create() {}
+}
+"#;
+        let symbols = parse(source);
+        let routes = extract_routes(source, "src/orders.controller.ts", &symbols);
+
+        assert_eq!(
+            signatures(&routes),
+            vec![
+                "GET /orders/:id -> findOne (nestjs)",
+                "POST /orders -> create (nestjs)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_next_file_routes() {
+        let source = "export async function GET(request: Request) {}\nexport async function POST(request: Request) {}\n";
+        let symbols = parse(source);
+        let routes = extract_routes(source, "web/app/(shop)/orders/[id]/route.ts", &symbols);
+        assert_eq!(
+            signatures(&routes),
+            vec![
+                "GET /orders/[id] -> GET (nextjs)",
+                "POST /orders/[id] -> POST (nextjs)",
+            ]
+        );
+
+        let source = "export default function handler(req, res) {}\n";
+        let routes = extract_routes(source, "pages/api/orders/index.ts", &parse(source));
+        assert_eq!(
+            signatures(&routes),
+            vec!["ANY /api/orders -> handler (nextjs)"]
+        );
+    }
+}
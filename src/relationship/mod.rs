@@ -34,6 +34,9 @@ pub enum RelationKind {
     DefinedIn,
     References,
     ReferencedBy,
+    /// HTTP route to the function that handles it
+    Routes,
+    RoutedBy,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -105,6 +108,8 @@ impl RelationKind {
             Self::DefinedIn => Self::Defines,
             Self::References => Self::ReferencedBy,
             Self::ReferencedBy => Self::References,
+            Self::Routes => Self::RoutedBy,
+            Self::RoutedBy => Self::Routes,
         }
     }
 
@@ -208,6 +213,7 @@ mod tests {
             RelationKind::References.inverse(),
             RelationKind::ReferencedBy
         );
+        assert_eq!(RelationKind::Routes.inverse(), RelationKind::RoutedBy);
     }
 
     #[test]
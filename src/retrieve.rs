@@ -24,7 +24,11 @@ use crate::io::{
     schema::{OutputData, OutputMetadata, UnifiedOutput, UnifiedOutputBuilder},
 };
 use crate::symbol::context::SymbolContext;
-use crate::{SimpleIndexer, Symbol};
+use crate::symbol::impact::parse_unified_diff;
+use crate::symbol::position::SourcePosition;
+use crate::symbol::source::{SourceOptions, read_source};
+use crate::symbol::trace::parse_stack_trace;
+use crate::{SimpleIndexer, Symbol, SymbolRef};
 use std::borrow::Cow;
 
 /// Execute retrieve symbol command
@@ -36,11 +40,11 @@ pub fn retrieve_symbol(
 ) -> ExitCode {
     let mut output = OutputManager::new(format);
 
-    // Check if name is a symbol_id (format: "symbol_id:123")
+    // Check if name is a symbol_id (format: "symbol_id:123" or a stable ID)
     let symbols = if let Some(id_str) = name.strip_prefix("symbol_id:") {
         // Direct symbol_id lookup
-        if let Ok(id) = id_str.parse::<u32>() {
-            match indexer.get_symbol(crate::SymbolId(id)) {
+        if let Ok(id) = id_str.parse::<SymbolRef>() {
+            match indexer.get_symbol_by_ref(&id) {
                 Some(sym) => vec![sym],
                 None => vec![],
             }
@@ -52,6 +56,40 @@ pub fn retrieve_symbol(
         indexer.find_symbols_by_name(name, language)
     };
 
+    // Library symbols are only consulted when the project has no match
+    let dependency_symbols = if symbols.is_empty() && !name.starts_with("symbol_id:") {
+        indexer
+            .dependency_layer()
+            .map(|layer| layer.find_symbols_by_name(name, language))
+            .unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+
+    if !dependency_symbols.is_empty() {
+        let mut extra = std::collections::HashMap::new();
+        extra.insert(Cow::Borrowed("layer"), serde_json::json!("dependencies"));
+
+        let unified = UnifiedOutputBuilder::items(dependency_symbols, EntityType::Symbol)
+            .with_metadata(OutputMetadata {
+                query: Some(Cow::Borrowed(name)),
+                tool: None,
+                timing_ms: None,
+                truncated: None,
+                extra,
+            })
+            .build();
+
+        eprintln!("Note: '{name}' is not defined in the project; showing dependency symbols");
+        return match output.unified(unified) {
+            Ok(code) => code,
+            Err(e) => {
+                eprintln!("Error writing output: {e}");
+                ExitCode::GeneralError
+            }
+        };
+    }
+
     if symbols.is_empty() {
         // Build not found output
         let unified = UnifiedOutput {
@@ -123,11 +161,11 @@ pub fn retrieve_callers(
 ) -> ExitCode {
     let mut output = OutputManager::new(format);
 
-    // Check if function is a symbol_id (format: "symbol_id:123")
+    // Check if function is a symbol_id (format: "symbol_id:123" or a stable ID)
     let (symbol, query_str) = if let Some(id_str) = function.strip_prefix("symbol_id:") {
         // Direct symbol_id lookup
-        if let Ok(id) = id_str.parse::<u32>() {
-            match indexer.get_symbol(crate::SymbolId(id)) {
+        if let Ok(id) = id_str.parse::<SymbolRef>() {
+            match indexer.get_symbol_by_ref(&id) {
                 Some(sym) => (sym, format!("symbol_id:{id}")),
                 None => {
                     let unified = UnifiedOutput {
@@ -265,11 +303,11 @@ pub fn retrieve_calls(
 ) -> ExitCode {
     let mut output = OutputManager::new(format);
 
-    // Check if function is a symbol_id (format: "symbol_id:123" or just "123" if numeric)
+    // Check if function is a symbol_id (format: "symbol_id:123" or a stable ID)
     let (symbol, query_str) = if let Some(id_str) = function.strip_prefix("symbol_id:") {
         // Direct symbol_id lookup
-        if let Ok(id) = id_str.parse::<u32>() {
-            match indexer.get_symbol(crate::SymbolId(id)) {
+        if let Ok(id) = id_str.parse::<SymbolRef>() {
+            match indexer.get_symbol_by_ref(&id) {
                 Some(sym) => (sym, format!("symbol_id:{id}")),
                 None => {
                     let unified = UnifiedOutput {
@@ -407,8 +445,23 @@ pub fn retrieve_implementations(
 ) -> ExitCode {
     let mut output = OutputManager::new(format);
 
-    // Find the trait symbol first
-    let trait_symbols = indexer.find_symbols_by_name(trait_name, language);
+    // Find the trait symbol first; `OrderService.Create` names an RPC by service
+    let mut trait_symbols = indexer.find_symbols_by_name(trait_name, language);
+    if trait_symbols.is_empty() {
+        if let Some((_, name)) = trait_name.rsplit_once('.') {
+            let suffix = format!(".{trait_name}");
+            trait_symbols = indexer
+                .find_symbols_by_name(name, language)
+                .into_iter()
+                .filter(|symbol| {
+                    symbol
+                        .module_path
+                        .as_deref()
+                        .is_some_and(|path| path == trait_name || path.ends_with(&suffix))
+                })
+                .collect();
+        }
+    }
     let implementations = if let Some(trait_symbol) = trait_symbols.first() {
         indexer.get_implementations(trait_symbol.id)
     } else {
@@ -456,6 +509,7 @@ pub fn retrieve_search(
     kind: Option<&str>,
     module: Option<&str>,
     language: Option<&str>,
+    annotation: Option<&str>,
     format: OutputFormat,
 ) -> ExitCode {
     let mut output = OutputManager::new(format);
@@ -474,15 +528,20 @@ pub fn retrieve_search(
         "module" => Some(crate::SymbolKind::Module),
         "typealias" => Some(crate::SymbolKind::TypeAlias),
         "enum" => Some(crate::SymbolKind::Enum),
+        "route" => Some(crate::SymbolKind::Route),
         _ => {
             eprintln!("Warning: Unknown symbol kind '{k}', ignoring filter");
             None
         }
     });
 
-    let search_results = indexer
-        .search(query, limit, kind_filter, module, language)
-        .unwrap_or_default();
+    let search_results = match annotation {
+        Some(annotation) => {
+            indexer.search_with_annotation(query, limit, kind_filter, module, language, annotation)
+        }
+        None => indexer.search(query, limit, kind_filter, module, language),
+    }
+    .unwrap_or_default();
 
     // Transform search results to SymbolContext with relationships
     use crate::symbol::context::ContextIncludes;
@@ -608,11 +667,11 @@ pub fn retrieve_describe(
 ) -> ExitCode {
     let mut output = OutputManager::new(format);
 
-    // Check if symbol_name is a symbol_id (format: "symbol_id:123")
+    // Check if symbol_name is a symbol_id (format: "symbol_id:123" or a stable ID)
     let (symbol, query_str) = if let Some(id_str) = symbol_name.strip_prefix("symbol_id:") {
         // Direct symbol_id lookup
-        if let Ok(id) = id_str.parse::<u32>() {
-            match indexer.get_symbol(crate::SymbolId(id)) {
+        if let Ok(id) = id_str.parse::<SymbolRef>() {
+            match indexer.get_symbol_by_ref(&id) {
                 Some(sym) => (sym, format!("symbol_id:{id}")),
                 None => {
                     let unified = UnifiedOutput {
@@ -766,3 +825,352 @@ pub fn retrieve_describe(
         }
     }
 }
+
+/// Execute retrieve source command
+///
+/// Each query is either a symbol name or `symbol_id:N`. Names that match
+/// several symbols return the source of every match.
+pub fn retrieve_source(
+    indexer: &SimpleIndexer,
+    queries: &[String],
+    language: Option<&str>,
+    options: SourceOptions,
+    format: OutputFormat,
+) -> ExitCode {
+    let mut output = OutputManager::new(format);
+    let workspace_root = indexer.settings().workspace_root.as_deref();
+
+    let mut sources = Vec::new();
+    for query in queries {
+        let symbols = if let Some(id_str) = query.strip_prefix("symbol_id:") {
+            match id_str.parse::<SymbolRef>() {
+                Ok(id) => indexer.get_symbol_by_ref(&id).into_iter().collect(),
+                Err(_) => {
+                    eprintln!("Invalid symbol_id format: {id_str}");
+                    return ExitCode::GeneralError;
+                }
+            }
+        } else {
+            indexer.find_symbols_by_name(query, language)
+        };
+
+        if symbols.is_empty() {
+            eprintln!("Symbol not found: {query}");
+        }
+
+        for symbol in symbols {
+            match read_source(&symbol, workspace_root, options) {
+                Ok(source) => sources.push(source),
+                Err(e) => eprintln!(
+                    "Warning: could not read source of {} from {}: {e}",
+                    symbol.name, symbol.file_path
+                ),
+            }
+        }
+    }
+
+    let query = queries.join(",");
+    let unified = UnifiedOutputBuilder::items(sources, EntityType::Symbol)
+        .with_metadata(OutputMetadata {
+            query: Some(Cow::Owned(query)),
+            tool: None,
+            timing_ms: None,
+            truncated: None,
+            extra: Default::default(),
+        })
+        .build();
+
+    match output.unified(unified) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("Error writing output: {e}");
+            ExitCode::GeneralError
+        }
+    }
+}
+
+/// Execute retrieve outline command
+pub fn retrieve_outline(
+    indexer: &SimpleIndexer,
+    file_path: &str,
+    format: OutputFormat,
+) -> ExitCode {
+    let mut output = OutputManager::new(format);
+
+    let data = match indexer.get_file_outline(file_path) {
+        Some(outline) => OutputData::Single {
+            item: Box::new(outline),
+        },
+        None => OutputData::Empty,
+    };
+    let (status, count, exit_code) = match data {
+        OutputData::Empty => (OutputStatus::NotFound, 0, ExitCode::NotFound),
+        _ => (OutputStatus::Success, 1, ExitCode::Success),
+    };
+
+    let unified = UnifiedOutput {
+        status,
+        entity_type: EntityType::File,
+        count,
+        data,
+        metadata: Some(OutputMetadata {
+            query: Some(Cow::Borrowed(file_path)),
+            tool: None,
+            timing_ms: None,
+            truncated: None,
+            extra: Default::default(),
+        }),
+        guidance: None,
+        exit_code,
+    };
+
+    match output.unified(unified) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("Error writing output: {e}");
+            ExitCode::GeneralError
+        }
+    }
+}
+
+/// Execute retrieve at command
+///
+/// Finds the innermost symbol containing `position` and, with
+/// `include_edges`, the relationships recorded on that line.
+pub fn retrieve_at(
+    indexer: &SimpleIndexer,
+    position: &SourcePosition,
+    include_edges: bool,
+    format: OutputFormat,
+) -> ExitCode {
+    let mut output = OutputManager::new(format);
+    let query = position.to_string();
+
+    let (status, count, data, exit_code) = match indexer.find_symbol_at(position, include_edges) {
+        Some(found) => (
+            OutputStatus::Success,
+            1,
+            OutputData::Single {
+                item: Box::new(found),
+            },
+            ExitCode::Success,
+        ),
+        None => (
+            OutputStatus::NotFound,
+            0,
+            OutputData::Empty,
+            ExitCode::NotFound,
+        ),
+    };
+
+    let unified = UnifiedOutput {
+        status,
+        entity_type: EntityType::Symbol,
+        count,
+        data,
+        metadata: Some(OutputMetadata {
+            query: Some(Cow::Owned(query)),
+            tool: None,
+            timing_ms: None,
+            truncated: None,
+            extra: Default::default(),
+        }),
+        guidance: None,
+        exit_code,
+    };
+
+    match output.unified(unified) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("Error writing output: {e}");
+            ExitCode::GeneralError
+        }
+    }
+}
+
+/// Resolve the frames of a pasted stack trace to indexed symbols
+///
+/// Frames outside the index are kept and marked; the status is
+/// `partial_success` when only some frames resolve.
+pub fn retrieve_trace(
+    indexer: &SimpleIndexer,
+    trace: &str,
+    max_callers: usize,
+    format: OutputFormat,
+) -> ExitCode {
+    let mut output = OutputManager::new(format);
+
+    let frames = indexer.resolve_stack_trace(parse_stack_trace(trace), max_callers);
+    let resolved = frames.iter().filter(|f| f.in_index).count();
+    let status = if resolved == 0 {
+        OutputStatus::NotFound
+    } else if resolved < frames.len() {
+        OutputStatus::PartialSuccess
+    } else {
+        OutputStatus::Success
+    };
+
+    let unified = UnifiedOutputBuilder::items(frames, EntityType::StackFrame)
+        .with_status(status)
+        .build();
+
+    match output.unified(unified) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("Error writing output: {e}");
+            ExitCode::GeneralError
+        }
+    }
+}
+
+/// Report the symbols changed by a unified diff and their impact radius
+pub fn retrieve_diff_impact(
+    indexer: &SimpleIndexer,
+    diff: &str,
+    max_depth: usize,
+    format: OutputFormat,
+) -> ExitCode {
+    let mut output = OutputManager::new(format);
+
+    let report = indexer.diff_impact(&parse_unified_diff(diff), max_depth);
+    let (status, exit_code) = if report.changed_count == 0 {
+        (OutputStatus::NotFound, ExitCode::NotFound)
+    } else {
+        (OutputStatus::Success, ExitCode::Success)
+    };
+    let count = report.changed_count + report.impacted_count;
+
+    let unified = UnifiedOutput {
+        status,
+        entity_type: EntityType::Impact,
+        count,
+        data: OutputData::Single {
+            item: Box::new(report),
+        },
+        metadata: None,
+        guidance: None,
+        exit_code,
+    };
+
+    match output.unified(unified) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("Error writing output: {e}");
+            ExitCode::GeneralError
+        }
+    }
+}
+
+/// Report the tests that reach a symbol through calls
+///
+/// `query` is a symbol name or `symbol_id:N`. Every symbol matching the name
+/// gets its own report; the status is `not_found` when no test reaches any
+/// of them.
+pub fn retrieve_tests(
+    indexer: &SimpleIndexer,
+    query: &str,
+    language: Option<&str>,
+    max_depth: usize,
+    format: OutputFormat,
+) -> ExitCode {
+    let mut output = OutputManager::new(format);
+
+    let symbols: Vec<Symbol> = if let Some(id_str) = query.strip_prefix("symbol_id:") {
+        match id_str.parse::<SymbolRef>() {
+            Ok(id) => indexer.get_symbol_by_ref(&id).into_iter().collect(),
+            Err(_) => {
+                eprintln!("Invalid symbol_id format: {id_str}");
+                return ExitCode::GeneralError;
+            }
+        }
+    } else {
+        indexer.find_symbols_by_name(query, language)
+    };
+
+    let reports: Vec<_> = symbols
+        .iter()
+        .filter_map(|symbol| indexer.find_tests_for(symbol.id, max_depth))
+        .collect();
+    let test_count: usize = reports.iter().map(|r| r.tests.len()).sum();
+    let (status, exit_code) = if test_count == 0 {
+        (OutputStatus::NotFound, ExitCode::NotFound)
+    } else {
+        (OutputStatus::Success, ExitCode::Success)
+    };
+
+    let unified = UnifiedOutput {
+        status,
+        entity_type: EntityType::Function,
+        count: test_count,
+        data: OutputData::Items { items: reports },
+        metadata: Some(OutputMetadata {
+            query: Some(Cow::Borrowed(query)),
+            tool: None,
+            timing_ms: None,
+            truncated: None,
+            extra: Default::default(),
+        }),
+        guidance: None,
+        exit_code,
+    };
+
+    match output.unified(unified) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("Error writing output: {e}");
+            ExitCode::GeneralError
+        }
+    }
+}
+
+/// List HTTP routes and their handlers
+///
+/// `method` and `path` narrow the table (see [`SimpleIndexer::find_routes`]);
+/// the status is `not_found` when no route matches.
+pub fn retrieve_routes(
+    indexer: &SimpleIndexer,
+    method: Option<&str>,
+    path: Option<&str>,
+    language: Option<&str>,
+    format: OutputFormat,
+) -> ExitCode {
+    let mut output = OutputManager::new(format);
+
+    let table = indexer.find_routes(method, path, language);
+    let (status, exit_code) = if table.routes.is_empty() {
+        (OutputStatus::NotFound, ExitCode::NotFound)
+    } else {
+        (OutputStatus::Success, ExitCode::Success)
+    };
+    let query = [method, path]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let unified = UnifiedOutput {
+        status,
+        entity_type: EntityType::Route,
+        count: table.routes.len(),
+        data: OutputData::Single {
+            item: Box::new(table),
+        },
+        metadata: (!query.is_empty()).then(|| OutputMetadata {
+            query: Some(Cow::Owned(query)),
+            tool: None,
+            timing_ms: None,
+            truncated: None,
+            extra: Default::default(),
+        }),
+        guidance: None,
+        exit_code,
+    };
+
+    match output.unified(unified) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("Error writing output: {e}");
+            ExitCode::GeneralError
+        }
+    }
+}
@@ -316,6 +316,11 @@ impl SimpleSemanticSearch {
         self.embeddings.len()
     }
 
+    /// Symbols that have an embedding
+    pub fn symbol_ids(&self) -> Vec<SymbolId> {
+        self.embeddings.keys().copied().collect()
+    }
+
     /// Clear all embeddings
     pub fn clear(&mut self) {
         self.embeddings.clear();
@@ -182,6 +182,27 @@ impl SemanticVectorStorage {
             })
     }
 
+    /// Rewrites the storage keeping only the embeddings of symbols `keep` accepts.
+    ///
+    /// An embedding saved more than once keeps its latest version. Vector IDs
+    /// are symbol IDs, so every kept embedding stays under its symbol's ID.
+    /// Returns the number of embeddings kept.
+    pub fn retain(
+        &mut self,
+        mut keep: impl FnMut(SymbolId) -> bool,
+    ) -> Result<usize, SemanticSearchError> {
+        self.storage
+            .compact(|vector_id| {
+                SymbolId::new(vector_id.get())
+                    .filter(|id| keep(*id))
+                    .map(|_| vector_id)
+            })
+            .map_err(|e| SemanticSearchError::StorageError {
+                message: format!("Failed to compact embeddings: {e}"),
+                suggestion: "Check disk space and file permissions".to_string(),
+            })
+    }
+
     /// Returns the number of embeddings stored.
     pub fn embedding_count(&self) -> usize {
         self.storage.vector_count()
@@ -0,0 +1,481 @@
+//! Portable index archives for `codanna index pack` and `codanna index unpack`
+//!
+//! An archive is a tar stream compressed as a single LZ4 frame. It starts with
+//! `manifest.json`, followed by every file of the index directory: Tantivy
+//! segments, the symbol cache, the semantic vectors, the resolver rules and the
+//! index metadata. Lock files and the journal of an unfinished run stay out.
+//!
+//! Symbols record their files relative to the workspace root, so Tantivy and
+//! the symbol cache move between checkouts unchanged. The JSON stores keep
+//! absolute paths (the indexed directories in `index.meta`, the tsconfig files
+//! of the resolvers); unpacking rewrites those under the root recorded in the
+//! manifest to the new workspace root, so an index built in `/ci/workspace`
+//! answers queries in `~/src/repo`.
+
+use crate::indexing::lock::LOCK_FILE;
+use crate::indexing::transaction::JOURNAL_FILE;
+use crate::storage::IndexMetadata;
+use crate::storage::migration::SCHEMA_VERSION;
+use crate::{IndexError, IndexResult};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Version of the archive layout, raised when older codanna can't unpack it
+pub const FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// Directory of the index files inside the archive
+const INDEX_DIR: &str = "index";
+
+/// Files owned by the process writing the index rather than part of its data
+const SKIPPED_FILES: &[&str] = &[
+    LOCK_FILE,
+    JOURNAL_FILE,
+    ".tantivy-writer.lock",
+    ".tantivy-meta.lock",
+];
+
+/// Where and from what an archived index was built
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackManifest {
+    /// Archive layout, see [`FORMAT_VERSION`]
+    pub format: u32,
+    pub codanna_version: String,
+    /// Schema the index was written with, see [`SCHEMA_VERSION`]
+    pub schema_version: u32,
+    /// Git commit checked out in the workspace, if it is a repository
+    pub commit: Option<String>,
+    /// Workspace root the stored absolute paths start with
+    pub root: PathBuf,
+    pub created_at: u64,
+    pub symbol_count: u32,
+    pub file_count: u32,
+}
+
+/// Result of [`unpack`]
+#[derive(Debug)]
+pub struct UnpackReport {
+    pub manifest: PackManifest,
+    /// Files written into the index directory
+    pub files: usize,
+    /// Absolute paths moved from the packed root to the new one
+    pub rewritten_paths: usize,
+}
+
+/// Write the index at `index_path`, built in `root`, to the archive `output`
+///
+/// The caller holds the index write lock, so no writer changes the files while
+/// they are read. The archive appears at `output` only once it is complete.
+pub fn pack(index_path: &Path, root: &Path, output: &Path) -> IndexResult<PackManifest> {
+    if !index_path.join("tantivy").join("meta.json").exists() {
+        return Err(IndexError::General(format!(
+            "No index to pack at {}; run 'codanna index' first",
+            index_path.display()
+        )));
+    }
+    if index_path.join(JOURNAL_FILE).exists() {
+        return Err(IndexError::IndexCorrupted {
+            reason: "an interrupted indexing run is not finished".to_string(),
+        });
+    }
+
+    let metadata = IndexMetadata::load(index_path)?;
+    let manifest = PackManifest {
+        format: FORMAT_VERSION,
+        codanna_version: env!("CARGO_PKG_VERSION").to_string(),
+        schema_version: metadata.schema_version,
+        commit: head_commit(root),
+        root: root.to_path_buf(),
+        created_at: crate::indexing::get_utc_timestamp(),
+        symbol_count: metadata.symbol_count,
+        file_count: metadata.file_count,
+    };
+
+    let persist_error = |e: std::io::Error| IndexError::PersistenceError {
+        path: output.to_path_buf(),
+        source: Box::new(e),
+    };
+    let mut partial = output.as_os_str().to_owned();
+    partial.push(".partial");
+    let partial = PathBuf::from(partial);
+    let written = File::create(&partial)
+        .and_then(|file| write_archive(&file, index_path, &manifest))
+        .and_then(|()| fs::rename(&partial, output));
+    if let Err(e) = written {
+        let _ = fs::remove_file(&partial);
+        return Err(persist_error(e));
+    }
+    Ok(manifest)
+}
+
+fn write_archive(file: &File, index_path: &Path, manifest: &PackManifest) -> std::io::Result<()> {
+    let encoder = lz4_flex::frame::FrameEncoder::new(BufWriter::new(file));
+    let mut archive = tar::Builder::new(encoder);
+
+    let json = serde_json::to_vec_pretty(manifest)?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(json.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(manifest.created_at);
+    header.set_cksum();
+    archive.append_data(&mut header, MANIFEST_FILE, json.as_slice())?;
+
+    for entry in WalkDir::new(index_path).sort_by_file_name() {
+        let entry = entry.map_err(std::io::Error::other)?;
+        let name = entry.file_name().to_string_lossy();
+        if !entry.file_type().is_file() || SKIPPED_FILES.contains(&name.as_ref()) {
+            continue;
+        }
+        let relative = entry
+            .path()
+            .strip_prefix(index_path)
+            .map_err(std::io::Error::other)?;
+        archive.append_path_with_name(entry.path(), Path::new(INDEX_DIR).join(relative))?;
+    }
+
+    let mut writer = archive
+        .into_inner()?
+        .finish()
+        .map_err(std::io::Error::other)?;
+    writer.flush()
+}
+
+/// Manifest of the archive at `path`, without unpacking the index
+pub fn read_manifest(path: &Path) -> IndexResult<PackManifest> {
+    let mut archive = open_archive(path)?;
+    let mut entries = archive.entries().map_err(|e| load_error(path, e))?;
+    manifest_entry(path, entries.next())
+}
+
+/// Replace the index at `index_path` with the one archived at `path`
+///
+/// Paths under the packed root are rewritten to `root`. An existing index is
+/// only replaced with `replace`; the caller holds its write lock.
+pub fn unpack(
+    path: &Path,
+    index_path: &Path,
+    root: &Path,
+    replace: bool,
+) -> IndexResult<UnpackReport> {
+    // Commands that skip loading still open an empty Tantivy index, so only
+    // the saved metadata marks an index worth keeping
+    if index_path.join("index.meta").exists() && !replace {
+        return Err(IndexError::General(format!(
+            "An index already exists at {}; use --force to replace it",
+            index_path.display()
+        )));
+    }
+
+    let mut archive = open_archive(path)?;
+    let mut entries = archive.entries().map_err(|e| load_error(path, e))?;
+    let manifest = manifest_entry(path, entries.next())?;
+    if manifest.format > FORMAT_VERSION {
+        return Err(IndexError::IndexCorrupted {
+            reason: format!(
+                "archive format {} comes from codanna {}; this version reads format {FORMAT_VERSION}",
+                manifest.format, manifest.codanna_version
+            ),
+        });
+    }
+    // Older schemas are migrated when the index is first loaded
+    if manifest.schema_version > SCHEMA_VERSION {
+        return Err(IndexError::SchemaMismatch {
+            found: manifest.schema_version,
+            expected: SCHEMA_VERSION,
+            reason: format!(
+                "the archive comes from codanna {}",
+                manifest.codanna_version
+            ),
+        });
+    }
+
+    // Unpack next to the index, so a failed or truncated archive leaves the
+    // current index untouched and the final move stays on one file system
+    let parent = index_path.parent().unwrap_or(Path::new("."));
+    let write_error = |source: std::io::Error| IndexError::FileWrite {
+        path: index_path.to_path_buf(),
+        source,
+    };
+    fs::create_dir_all(parent).map_err(write_error)?;
+    let staging = tempfile::Builder::new()
+        .prefix(".unpack-")
+        .tempdir_in(parent)
+        .map_err(write_error)?;
+    let mut files = 0;
+    for entry in entries {
+        let mut entry = entry.map_err(|e| load_error(path, e))?;
+        let entry_path = entry.path().map_err(|e| load_error(path, e))?;
+        if !entry_path.starts_with(INDEX_DIR) {
+            continue;
+        }
+        // unpack_in refuses entries that would land outside the staging directory
+        if entry
+            .unpack_in(staging.path())
+            .map_err(|e| load_error(path, e))?
+        {
+            files += 1;
+        }
+    }
+    let unpacked = staging.path().join(INDEX_DIR);
+    if !unpacked.join("tantivy").join("meta.json").exists() {
+        return Err(IndexError::IndexCorrupted {
+            reason: format!("{} holds no Tantivy index", path.display()),
+        });
+    }
+
+    let rewritten_paths = rewrite_json_stores(&unpacked, &manifest.root, root)?;
+    install(&unpacked, index_path).map_err(write_error)?;
+    Ok(UnpackReport {
+        manifest,
+        files,
+        rewritten_paths,
+    })
+}
+
+fn open_archive(path: &Path) -> IndexResult<tar::Archive<impl Read>> {
+    let file = File::open(path).map_err(|source| IndexError::FileRead {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let decoder = lz4_flex::frame::FrameDecoder::new(BufReader::new(file));
+    Ok(tar::Archive::new(decoder))
+}
+
+fn manifest_entry<R: Read>(
+    path: &Path,
+    entry: Option<std::io::Result<tar::Entry<'_, R>>>,
+) -> IndexResult<PackManifest> {
+    let not_an_archive = || IndexError::IndexCorrupted {
+        reason: format!("{} is not a codanna index archive", path.display()),
+    };
+    let mut entry = entry
+        .ok_or_else(not_an_archive)?
+        .map_err(|_| not_an_archive())?;
+    let is_manifest = entry
+        .path()
+        .is_ok_and(|name| name.as_ref() == Path::new(MANIFEST_FILE));
+    if !is_manifest {
+        return Err(not_an_archive());
+    }
+    let mut json = Vec::new();
+    entry
+        .read_to_end(&mut json)
+        .map_err(|e| load_error(path, e))?;
+    serde_json::from_slice(&json).map_err(|_| not_an_archive())
+}
+
+fn load_error(path: &Path, source: std::io::Error) -> IndexError {
+    IndexError::LoadError {
+        path: path.to_path_buf(),
+        source: Box::new(source),
+    }
+}
+
+/// Move the unpacked files into `index_path`, replacing its contents
+///
+/// The write lock file stays in place: the unpacking process holds it.
+fn install(unpacked: &Path, index_path: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(index_path)?;
+    for entry in fs::read_dir(index_path)? {
+        let entry = entry?;
+        if entry.file_name() == LOCK_FILE {
+            continue;
+        }
+        if entry.file_type()?.is_dir() {
+            fs::remove_dir_all(entry.path())?;
+        } else {
+            fs::remove_file(entry.path())?;
+        }
+    }
+    for entry in fs::read_dir(unpacked)? {
+        let entry = entry?;
+        fs::rename(entry.path(), index_path.join(entry.file_name()))?;
+    }
+    Ok(())
+}
+
+/// Rewrite absolute paths under `from` to `to` in the JSON stores of an index
+///
+/// Tantivy keeps its own `meta.json`, which holds no paths.
+fn rewrite_json_stores(index_path: &Path, from: &Path, to: &Path) -> IndexResult<usize> {
+    if from == to {
+        return Ok(0);
+    }
+    let mut rewritten = 0;
+    for entry in WalkDir::new(index_path)
+        .into_iter()
+        .filter_entry(|e| e.file_name() != "tantivy")
+        .filter_map(Result::ok)
+    {
+        let path = entry.path();
+        let is_json =
+            path.extension().is_some_and(|ext| ext == "json") || entry.file_name() == "index.meta";
+        if !entry.file_type().is_file() || !is_json {
+            continue;
+        }
+        let Ok(json) = fs::read_to_string(path) else {
+            continue;
+        };
+        let Ok(mut value) = serde_json::from_str::<Value>(&json) else {
+            continue;
+        };
+        let count = rebase_paths(&mut value, from, to);
+        if count == 0 {
+            continue;
+        }
+        let json = serde_json::to_string_pretty(&value).map_err(|e| {
+            IndexError::General(format!("Failed to serialize {}: {e}", path.display()))
+        })?;
+        fs::write(path, json).map_err(|source| IndexError::FileWrite {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        rewritten += count;
+    }
+    Ok(rewritten)
+}
+
+/// Rebase every string and object key in `value` that is a path under `from`
+fn rebase_paths(value: &mut Value, from: &Path, to: &Path) -> usize {
+    match value {
+        Value::String(text) => match rebase(text, from, to) {
+            Some(path) => {
+                *text = path;
+                1
+            }
+            None => 0,
+        },
+        Value::Array(items) => items
+            .iter_mut()
+            .map(|item| rebase_paths(item, from, to))
+            .sum(),
+        Value::Object(map) => {
+            let mut count = 0;
+            let entries = std::mem::take(map);
+            for (key, mut item) in entries {
+                count += rebase_paths(&mut item, from, to);
+                let key = match rebase(&key, from, to) {
+                    Some(path) => {
+                        count += 1;
+                        path
+                    }
+                    None => key,
+                };
+                map.insert(key, item);
+            }
+            count
+        }
+        _ => 0,
+    }
+}
+
+fn rebase(text: &str, from: &Path, to: &Path) -> Option<String> {
+    let path = Path::new(text);
+    if !path.is_absolute() {
+        return None;
+    }
+    let rest = path.strip_prefix(from).ok()?;
+    let rebased = if rest.as_os_str().is_empty() {
+        to.to_path_buf()
+    } else {
+        to.join(rest)
+    };
+    Some(rebased.to_string_lossy().into_owned())
+}
+
+/// Commit checked out in the repository containing `root`
+pub fn head_commit(root: &Path) -> Option<String> {
+    let repo = git2::Repository::discover(root).ok()?;
+    let commit = repo.head().ok()?.peel_to_commit().ok()?;
+    Some(commit.id().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Settings;
+    use crate::storage::IndexPersistence;
+    use crate::{SimpleIndexer, indexing::IndexWriteLock};
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    fn settings(root: &Path) -> Arc<Settings> {
+        Arc::new(Settings {
+            index_path: root.join(".codanna").join("index"),
+            workspace_root: Some(root.to_path_buf()),
+            ..Settings::default()
+        })
+    }
+
+    #[test]
+    fn test_index_moves_to_another_checkout() {
+        let ci = TempDir::new().unwrap();
+        let ci_root = ci.path().canonicalize().unwrap();
+        fs::create_dir_all(ci_root.join("src")).unwrap();
+        fs::write(ci_root.join("src").join("lib.rs"), "pub fn packed() {}\n").unwrap();
+
+        let ci_settings = settings(&ci_root);
+        let mut indexer = SimpleIndexer::with_settings(ci_settings.clone());
+        indexer
+            .index_directory(ci_root.join("src"), false, false)
+            .unwrap();
+        let persistence = IndexPersistence::new(ci_settings.index_path.clone());
+        persistence.save(&indexer).unwrap();
+        drop(indexer);
+        // Recorded by the startup sync from settings.toml in a real workspace
+        let mut metadata = IndexMetadata::load(&ci_settings.index_path).unwrap();
+        metadata.update_indexed_paths(vec![ci_root.join("src")]);
+        metadata.save(&ci_settings.index_path).unwrap();
+
+        let archive = ci_root.join("index.tar.lz4");
+        let _lock = IndexWriteLock::try_acquire(&ci_settings.index_path).unwrap();
+        let manifest = pack(&ci_settings.index_path, &ci_root, &archive).unwrap();
+        assert_eq!(manifest.root, ci_root);
+        assert!(manifest.symbol_count > 0);
+        assert_eq!(
+            read_manifest(&archive).unwrap().file_count,
+            manifest.file_count
+        );
+
+        let local = TempDir::new().unwrap();
+        let local_root = local.path().canonicalize().unwrap();
+        let local_settings = settings(&local_root);
+        let report = unpack(&archive, &local_settings.index_path, &local_root, false).unwrap();
+        assert!(report.files > 0);
+        assert!(report.rewritten_paths > 0);
+        assert!(!local_settings.index_path.join(LOCK_FILE).exists());
+
+        let metadata = IndexMetadata::load(&local_settings.index_path).unwrap();
+        assert_eq!(metadata.indexed_paths, Some(vec![local_root.join("src")]));
+        let unpacked = IndexPersistence::new(local_settings.index_path.clone())
+            .load_with_settings_lazy(local_settings.clone(), false, true)
+            .unwrap();
+        let found = unpacked.find_symbols_by_name("packed", None);
+        assert_eq!(found.len(), 1);
+        assert_eq!(&*found[0].file_path, "src/lib.rs");
+
+        // An existing index is only replaced on request
+        assert!(unpack(&archive, &local_settings.index_path, &local_root, false).is_err());
+        assert!(unpack(&archive, &local_settings.index_path, &local_root, true).is_ok());
+    }
+
+    #[test]
+    fn test_other_files_are_not_archives() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("index.tar.lz4");
+        fs::write(&path, b"not an archive").unwrap();
+        assert!(matches!(
+            read_manifest(&path),
+            Err(IndexError::IndexCorrupted { .. })
+        ));
+        assert!(matches!(
+            unpack(&path, &dir.path().join("index"), dir.path(), false),
+            Err(IndexError::IndexCorrupted { .. })
+        ));
+    }
+}
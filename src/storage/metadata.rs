@@ -1,6 +1,8 @@
 //! Metadata tracking for index state and data sources
 
 use crate::IndexResult;
+use crate::indexing::UnresolvedSummary;
+use crate::storage::migration::SCHEMA_VERSION;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -11,6 +13,11 @@ pub struct IndexMetadata {
     /// Version of the index format
     pub version: u32,
 
+    /// Version of the Tantivy schema the index was written with
+    /// Indexes saved before it was recorded read as the first version
+    #[serde(default = "first_schema_version")]
+    pub schema_version: u32,
+
     /// Current data source
     pub data_source: DataSource,
 
@@ -27,6 +34,15 @@ pub struct IndexMetadata {
     /// Used to detect config changes and auto-sync on load
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub indexed_paths: Option<Vec<PathBuf>>,
+
+    /// Relationship targets that could not be resolved, counted per file
+    /// Shown by `codanna stats --unresolved`
+    #[serde(default, skip_serializing_if = "UnresolvedSummary::is_empty")]
+    pub unresolved: UnresolvedSummary,
+}
+
+fn first_schema_version() -> u32 {
+    1
 }
 
 /// Describes where the index data came from
@@ -47,11 +63,13 @@ impl Default for IndexMetadata {
     fn default() -> Self {
         Self {
             version: 1,
+            schema_version: SCHEMA_VERSION,
             data_source: DataSource::Fresh,
             symbol_count: 0,
             file_count: 0,
             last_modified: crate::indexing::get_utc_timestamp(),
             indexed_paths: None,
+            unresolved: UnresolvedSummary::new(),
         }
     }
 }
@@ -0,0 +1,755 @@
+//! Schema versions of the on-disk index and the migrations between them
+//!
+//! `IndexSchema::build` defines the Tantivy fields of this release and
+//! [`SCHEMA_VERSION`] names that layout; `index.meta` records the version each
+//! index was written with. Before `IndexPersistence` opens an index, [`prepare`]
+//! compares the two:
+//!
+//! - Same version, same fields: nothing to do.
+//! - Additive changes are migrated in place. New relationship kinds need no
+//!   rewrite, since an older index never stores them. New fields do: Tantivy
+//!   addresses fields by position, so every document is copied by field name
+//!   into a fresh index with the current schema, which then replaces the old one.
+//! - Anything else fails with [`IndexError::SchemaMismatch`], which asks for
+//!   `codanna index --force`: a field that changed type or options, a change
+//!   listed as breaking in [`MIGRATIONS`], or an index from a newer release.
+
+use crate::config::Settings;
+use crate::indexing::lock::IndexWriteLock;
+use crate::parsing::Annotation;
+use crate::storage::IndexMetadata;
+use crate::storage::tantivy::{IndexSchema, register_tokenizers};
+use crate::types::{StableIdAssigner, StableSymbolId, SymbolKind};
+use crate::{IndexError, IndexResult};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tantivy::directory::MmapDirectory;
+use tantivy::schema::{Field, Schema, Value};
+use tantivy::{
+    DocAddress, Index, IndexSettings, IndexWriter, ReloadPolicy, Searcher, TantivyDocument,
+};
+
+/// Version of the schema built by `IndexSchema::build`
+///
+/// Raise it with every change to the schema or to what documents may contain,
+/// and describe the change in [`MIGRATIONS`].
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Changes that led to each schema version after the first, oldest first
+///
+/// Only breaking changes stop an older index from loading; new fields are
+/// found by comparing the schemas, the descriptions are shown when migrating.
+pub const MIGRATIONS: &[Migration] = &[Migration {
+    version: 2,
+    change: SchemaChange::Additive("stable symbol IDs"),
+}];
+
+/// How the schema changed on the way to `version`
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    pub version: u32,
+    pub change: SchemaChange,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum SchemaChange {
+    /// New fields or relationship kinds, e.g. "relationship kind `Overrides`"
+    Additive(&'static str),
+    /// A change existing documents can't be converted to
+    Breaking(&'static str),
+}
+
+/// Result of comparing an index on disk with this release's schema
+#[derive(Debug)]
+pub enum SchemaStatus {
+    Current,
+    Migrate(MigrationPlan),
+    Incompatible { found: u32, reason: String },
+}
+
+/// What migrating an index to [`SCHEMA_VERSION`] involves
+#[derive(Debug, Default)]
+pub struct MigrationPlan {
+    pub from: u32,
+    /// Fields of the current schema the index lacks
+    pub added_fields: Vec<String>,
+    /// Fields the current schema no longer has, dropped while copying
+    pub dropped_fields: Vec<String>,
+    /// Descriptions of the additive changes since `from`
+    pub changes: Vec<&'static str>,
+    /// Same fields at other positions, which the handles can't follow
+    pub reordered: bool,
+}
+
+impl MigrationPlan {
+    /// Whether the documents have to be copied into a new index
+    pub fn needs_rewrite(&self) -> bool {
+        self.reordered || !self.added_fields.is_empty() || !self.dropped_fields.is_empty()
+    }
+}
+
+/// Result of [`prepare`] when it migrated the index
+#[derive(Debug)]
+pub struct MigrationReport {
+    pub plan: MigrationPlan,
+    /// Documents copied into the new index, if it was rewritten
+    pub documents: Option<u64>,
+}
+
+impl fmt::Display for MigrationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Migrated index schema v{} to v{SCHEMA_VERSION}",
+            self.plan.from
+        )?;
+        let mut details = self
+            .plan
+            .changes
+            .iter()
+            .map(|c| c.to_string())
+            .collect::<Vec<_>>();
+        if !self.plan.added_fields.is_empty() {
+            details.push(format!(
+                "added fields {}",
+                self.plan.added_fields.join(", ")
+            ));
+        }
+        if !self.plan.dropped_fields.is_empty() {
+            details.push(format!(
+                "dropped fields {}",
+                self.plan.dropped_fields.join(", ")
+            ));
+        }
+        if let Some(documents) = self.documents {
+            details.push(format!("copied {documents} documents"));
+        }
+        if !details.is_empty() {
+            write!(f, " ({})", details.join("; "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Compare the index at `index_path` with the current schema
+pub fn check(index_path: &Path) -> IndexResult<SchemaStatus> {
+    let tantivy_path = index_path.join("tantivy");
+    if !tantivy_path.join("meta.json").exists() {
+        return Ok(SchemaStatus::Current);
+    }
+    let found = IndexMetadata::load(index_path)?.schema_version;
+    let on_disk = Index::open_in_dir(&tantivy_path)
+        .map_err(tantivy_error("open index"))?
+        .schema();
+    let (expected, _) = IndexSchema::build();
+    Ok(plan(found, &on_disk, &expected, MIGRATIONS))
+}
+
+/// Make the index at `index_path` readable by this release
+///
+/// Returns what was migrated, `None` if the index was current (or absent), and
+/// [`IndexError::SchemaMismatch`] if only a reindex can bring it up to date.
+pub fn prepare(index_path: &Path, settings: &Settings) -> IndexResult<Option<MigrationReport>> {
+    let plan = match check(index_path)? {
+        SchemaStatus::Current => return Ok(None),
+        SchemaStatus::Incompatible { found, reason } => {
+            return Err(IndexError::SchemaMismatch {
+                found,
+                expected: SCHEMA_VERSION,
+                reason,
+            });
+        }
+        SchemaStatus::Migrate(plan) => plan,
+    };
+
+    // A migration writes the index; a writer that was already running has
+    // migrated it by the time the lock is ours, so check again. Without a
+    // lock this process is the writer holding it.
+    let _lock = if IndexWriteLock::held_elsewhere(index_path) {
+        let timeout = Duration::from_secs(settings.indexing.lock_timeout_secs);
+        let lock = IndexWriteLock::acquire(index_path, timeout)?;
+        match check(index_path)? {
+            SchemaStatus::Migrate(_) => Some(lock),
+            _ => return prepare(index_path, settings),
+        }
+    } else {
+        IndexWriteLock::try_acquire(index_path)?
+    };
+
+    let documents = if plan.needs_rewrite() {
+        Some(rewrite(&index_path.join("tantivy"))?)
+    } else {
+        None
+    };
+
+    if index_path.join("index.meta").exists() {
+        let mut metadata = IndexMetadata::load(index_path)?;
+        metadata.schema_version = SCHEMA_VERSION;
+        metadata.save(index_path)?;
+    }
+
+    Ok(Some(MigrationReport { plan, documents }))
+}
+
+fn plan(found: u32, on_disk: &Schema, expected: &Schema, history: &[Migration]) -> SchemaStatus {
+    let incompatible = |reason: String| SchemaStatus::Incompatible { found, reason };
+    if found > SCHEMA_VERSION {
+        return incompatible("it was written by a newer release".to_string());
+    }
+
+    let mut changes = Vec::new();
+    for migration in history
+        .iter()
+        .filter(|m| m.version > found && m.version <= SCHEMA_VERSION)
+    {
+        match migration.change {
+            SchemaChange::Additive(change) => changes.push(change),
+            SchemaChange::Breaking(change) => {
+                return incompatible(format!("schema v{} {change}", migration.version));
+            }
+        }
+    }
+
+    let mut added_fields = Vec::new();
+    for (_, entry) in expected.fields() {
+        match on_disk.get_field(entry.name()) {
+            Ok(field) if on_disk.get_field_entry(field) != entry => {
+                return incompatible(format!(
+                    "field `{}` changed its type or options",
+                    entry.name()
+                ));
+            }
+            Ok(_) => {}
+            Err(_) => added_fields.push(entry.name().to_string()),
+        }
+    }
+    let dropped_fields: Vec<String> = on_disk
+        .fields()
+        .map(|(_, entry)| entry.name())
+        .filter(|name| expected.get_field(name).is_err())
+        .map(str::to_string)
+        .collect();
+
+    // A schema that is a reordering of the same fields still needs the copy
+    let reordered = added_fields.is_empty()
+        && dropped_fields.is_empty()
+        && expected
+            .fields()
+            .any(|(field, entry)| on_disk.get_field(entry.name()).ok() != Some(field));
+
+    if found == SCHEMA_VERSION && added_fields.is_empty() && dropped_fields.is_empty() && !reordered
+    {
+        return SchemaStatus::Current;
+    }
+    if reordered {
+        changes.push("field order");
+    }
+    SchemaStatus::Migrate(MigrationPlan {
+        from: found,
+        added_fields,
+        dropped_fields,
+        changes,
+        reordered,
+    })
+}
+
+/// Copy every document of the index at `tantivy_path` into a new index with
+/// the current schema and put it in the old one's place
+fn rewrite(tantivy_path: &Path) -> IndexResult<u64> {
+    let staging = sibling(tantivy_path, "migrating");
+    let _ = fs::remove_dir_all(&staging);
+    let copied = copy_documents(tantivy_path, &staging);
+    let copied = match copied {
+        Ok(copied) => copied,
+        Err(e) => {
+            let _ = fs::remove_dir_all(&staging);
+            return Err(e);
+        }
+    };
+
+    let replaced = sibling(tantivy_path, "replaced");
+    let _ = fs::remove_dir_all(&replaced);
+    let write_error = |source: std::io::Error| IndexError::FileWrite {
+        path: tantivy_path.to_path_buf(),
+        source,
+    };
+    fs::rename(tantivy_path, &replaced).map_err(write_error)?;
+    if let Err(e) = fs::rename(&staging, tantivy_path) {
+        let _ = fs::rename(&replaced, tantivy_path);
+        return Err(write_error(e));
+    }
+    let _ = fs::remove_dir_all(&replaced);
+    Ok(copied)
+}
+
+fn copy_documents(from: &Path, to: &Path) -> IndexResult<u64> {
+    let old = Index::open_in_dir(from).map_err(tantivy_error("open index"))?;
+    let old_schema = old.schema();
+    let reader = old
+        .reader_builder()
+        .reload_policy(ReloadPolicy::Manual)
+        .try_into()
+        .map_err(tantivy_error("open reader"))?;
+    let searcher = reader.searcher();
+
+    let (schema, fields) = IndexSchema::build();
+    let stable_ids = derive_stable_ids(&searcher, &old_schema)?;
+    let targets: HashMap<Field, Field> = old_schema
+        .fields()
+        .filter_map(|(field, entry)| Some((field, schema.get_field(entry.name()).ok()?)))
+        .collect();
+
+    fs::create_dir_all(to).map_err(|source| IndexError::FileWrite {
+        path: to.to_path_buf(),
+        source,
+    })?;
+    let directory = MmapDirectory::open(to).map_err(|e| IndexError::TantivyError {
+        operation: "open directory".to_string(),
+        cause: e.to_string(),
+    })?;
+    let index = Index::create(directory, schema, IndexSettings::default())
+        .map_err(tantivy_error("create index"))?;
+    register_tokenizers(&index);
+    let mut writer: IndexWriter<TantivyDocument> = index
+        .writer(WRITER_HEAP_BYTES)
+        .map_err(tantivy_error("create writer"))?;
+
+    let mut copied = 0;
+    for (segment_ord, segment) in searcher.segment_readers().iter().enumerate() {
+        let store = segment
+            .get_store_reader(1)
+            .map_err(|e| IndexError::TantivyError {
+                operation: "read segment".to_string(),
+                cause: e.to_string(),
+            })?;
+        for doc_id in segment.doc_ids_alive() {
+            let stored: TantivyDocument =
+                store.get(doc_id).map_err(tantivy_error("read document"))?;
+            let mut doc = TantivyDocument::new();
+            for (field, value) in stored.field_values() {
+                if let Some(&target) = targets.get(&field) {
+                    doc.add_field_value(target, value);
+                }
+            }
+
+            // Indexed fields that aren't stored can't be copied; derive them
+            // again from the stored ones. A new unstored field needs the same,
+            // or a breaking entry in MIGRATIONS.
+            let terms: Vec<String> = doc
+                .get_all(fields.annotations)
+                .filter_map(|v| v.as_str())
+                .filter_map(Annotation::parse)
+                .flat_map(|annotation| annotation.search_terms())
+                .collect();
+            for term in terms {
+                doc.add_text(fields.annotation_name, term);
+            }
+            if let Some(stable_id) = stable_ids.get(&DocAddress::new(segment_ord as u32, doc_id)) {
+                doc.add_text(fields.stable_id, stable_id.as_str());
+            }
+
+            writer
+                .add_document(doc)
+                .map_err(tantivy_error("copy document"))?;
+            copied += 1;
+        }
+    }
+    writer.commit().map_err(tantivy_error("commit"))?;
+    writer
+        .wait_merging_threads()
+        .map_err(tantivy_error("finish merges"))?;
+    Ok(copied)
+}
+
+/// Stable IDs for the symbol documents of an index that has none
+///
+/// Each file's symbols are numbered in source order, as indexing does.
+fn derive_stable_ids(
+    searcher: &Searcher,
+    schema: &Schema,
+) -> IndexResult<HashMap<DocAddress, StableSymbolId>> {
+    if schema.get_field("stable_id").is_ok() {
+        return Ok(HashMap::new());
+    }
+    let field = |name: &str| schema.get_field(name).ok();
+    let (Some(doc_type), Some(symbol_id), Some(name), Some(kind)) = (
+        field("doc_type"),
+        field("symbol_id"),
+        field("name"),
+        field("kind"),
+    ) else {
+        return Ok(HashMap::new());
+    };
+    let text = |doc: &TantivyDocument, name: &str| -> String {
+        field(name)
+            .and_then(|field| doc.get_first(field)?.as_str().map(str::to_string))
+            .unwrap_or_default()
+    };
+    let number = |doc: &TantivyDocument, name: &str| -> u64 {
+        field(name)
+            .and_then(|field| doc.get_first(field)?.as_u64())
+            .unwrap_or_default()
+    };
+
+    struct Definition {
+        position: (u64, u64, u64),
+        address: DocAddress,
+        language: String,
+        module_path: String,
+        name: String,
+        kind: SymbolKind,
+    }
+    let mut by_file: HashMap<String, Vec<Definition>> = HashMap::new();
+    for (segment_ord, segment) in searcher.segment_readers().iter().enumerate() {
+        let store = segment
+            .get_store_reader(1)
+            .map_err(|e| IndexError::TantivyError {
+                operation: "read segment".to_string(),
+                cause: e.to_string(),
+            })?;
+        for doc_id in segment.doc_ids_alive() {
+            let doc: TantivyDocument = store.get(doc_id).map_err(tantivy_error("read document"))?;
+            if doc.get_first(doc_type).and_then(|v| v.as_str()) != Some("symbol") {
+                continue;
+            }
+            let (Some(id), Some(kind)) = (
+                doc.get_first(symbol_id).and_then(|v| v.as_u64()),
+                doc.get_first(kind)
+                    .and_then(|v| v.as_str())
+                    .and_then(|kind| kind.parse::<SymbolKind>().ok()),
+            ) else {
+                continue;
+            };
+            let file_path = text(&doc, "file_path");
+            let module_path = Some(text(&doc, "module_path"))
+                .filter(|path| !path.is_empty())
+                .unwrap_or_else(|| file_path.clone());
+            by_file.entry(file_path).or_default().push(Definition {
+                position: (number(&doc, "line_number"), number(&doc, "column"), id),
+                address: DocAddress::new(segment_ord as u32, doc_id),
+                language: text(&doc, "language"),
+                module_path,
+                name: doc
+                    .get_first(name)
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                kind,
+            });
+        }
+    }
+
+    let mut stable_ids = HashMap::new();
+    for mut definitions in by_file.into_values() {
+        definitions.sort_by_key(|definition| definition.position);
+        let mut assigner = StableIdAssigner::new();
+        for definition in definitions {
+            let stable_id = assigner.assign(
+                &definition.language,
+                &definition.module_path,
+                &definition.name,
+                definition.kind,
+            );
+            stable_ids.insert(definition.address, stable_id);
+        }
+    }
+    Ok(stable_ids)
+}
+
+/// Heap of the writer filling the migrated index
+const WRITER_HEAP_BYTES: usize = 50_000_000;
+
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+fn tantivy_error(operation: &'static str) -> impl Fn(tantivy::TantivyError) -> IndexError {
+    move |e| IndexError::TantivyError {
+        operation: operation.to_string(),
+        cause: e.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indexing::SimpleIndexer;
+    use crate::storage::IndexPersistence;
+    use std::sync::Arc;
+    use tantivy::schema::{STORED, SchemaBuilder};
+    use tempfile::TempDir;
+
+    fn settings(root: &Path) -> Settings {
+        Settings {
+            index_path: root.join(".codanna/index"),
+            workspace_root: Some(root.to_path_buf()),
+            ..Settings::default()
+        }
+    }
+
+    fn write_file(root: &Path, name: &str, content: &str) -> PathBuf {
+        let path = root.join(name);
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    /// Index `root`, then replace its Tantivy index with one that lacks the
+    /// `relation_context` and `stable_id` fields, as an older release would
+    /// have written it. Returns the documents and the stable IDs indexing gave.
+    fn index_with_older_schema(
+        root: &Path,
+        settings: &Arc<Settings>,
+    ) -> (u64, Vec<(String, Option<StableSymbolId>)>) {
+        let file = write_file(
+            root,
+            "lib.rs",
+            "#[derive(Debug)]\npub struct Config;\n\nimpl Config {\n    pub fn new() -> Self {\n        Config\n    }\n}\n\npub struct Limits;\n\nimpl Limits {\n    pub fn new() -> Self {\n        Limits\n    }\n}\n\npub fn load() -> Config {\n    helper();\n    Config\n}\n\nfn helper() {}\n",
+        );
+        let mut indexer = SimpleIndexer::with_settings(settings.clone());
+        indexer.index_file(&file).unwrap();
+        let persistence = IndexPersistence::new(settings.index_path.clone());
+        persistence.save(&indexer).unwrap();
+        let stable_ids = stable_ids(&indexer);
+        drop(indexer);
+
+        let tantivy_path = settings.index_path.join("tantivy");
+        let current = Index::open_in_dir(&tantivy_path).unwrap();
+        let searcher = current
+            .reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .try_into()
+            .map(|reader: tantivy::IndexReader| reader.searcher())
+            .unwrap();
+
+        let mut builder = SchemaBuilder::default();
+        let (schema, _) = IndexSchema::build();
+        for (_, entry) in schema.fields() {
+            if !matches!(entry.name(), "relation_context" | "stable_id") {
+                builder.add_field(entry.clone());
+            }
+        }
+        let older = builder.build();
+
+        let staging = root.join("older");
+        fs::create_dir_all(&staging).unwrap();
+        let index = Index::create_in_dir(&staging, older.clone()).unwrap();
+        register_tokenizers(&index);
+        let mut writer: IndexWriter<TantivyDocument> = index.writer(15_000_000).unwrap();
+        let mut count = 0;
+        for segment in searcher.segment_readers() {
+            let store = segment.get_store_reader(1).unwrap();
+            for doc_id in segment.doc_ids_alive() {
+                let stored: TantivyDocument = store.get(doc_id).unwrap();
+                let mut doc = TantivyDocument::new();
+                for (field, value) in stored.field_values() {
+                    let name = schema.get_field_name(field);
+                    if let Ok(target) = older.get_field(name) {
+                        doc.add_field_value(target, value);
+                    }
+                }
+                writer.add_document(doc).unwrap();
+                count += 1;
+            }
+        }
+        writer.commit().unwrap();
+        drop(writer);
+        drop(searcher);
+        drop(current);
+
+        fs::remove_dir_all(&tantivy_path).unwrap();
+        fs::rename(&staging, &tantivy_path).unwrap();
+        (count, stable_ids)
+    }
+
+    fn stable_ids(indexer: &SimpleIndexer) -> Vec<(String, Option<StableSymbolId>)> {
+        let mut ids: Vec<_> = indexer
+            .get_all_symbols()
+            .into_iter()
+            .map(|symbol| (symbol.name.to_string(), symbol.stable_id))
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn test_plan_classifies_schema_changes() {
+        let (expected, _) = IndexSchema::build();
+        assert!(matches!(
+            plan(SCHEMA_VERSION, &expected, &expected, MIGRATIONS),
+            SchemaStatus::Current
+        ));
+
+        let mut builder = SchemaBuilder::default();
+        for (_, entry) in expected.fields() {
+            if entry.name() != "signature" {
+                builder.add_field(entry.clone());
+            }
+        }
+        let missing_field = builder.build();
+        match plan(SCHEMA_VERSION, &missing_field, &expected, MIGRATIONS) {
+            SchemaStatus::Migrate(plan) => {
+                assert_eq!(plan.added_fields, vec!["signature".to_string()]);
+                assert!(plan.needs_rewrite());
+            }
+            other => panic!("expected a migration, got {other:?}"),
+        }
+
+        let mut builder = SchemaBuilder::default();
+        for (_, entry) in expected.fields() {
+            if entry.name() == "module_path" {
+                builder.add_text_field("module_path", STORED);
+            } else {
+                builder.add_field(entry.clone());
+            }
+        }
+        let changed_field = builder.build();
+        assert!(matches!(
+            plan(SCHEMA_VERSION, &changed_field, &expected, MIGRATIONS),
+            SchemaStatus::Incompatible { .. }
+        ));
+
+        assert!(matches!(
+            plan(SCHEMA_VERSION + 1, &expected, &expected, MIGRATIONS),
+            SchemaStatus::Incompatible { .. }
+        ));
+
+        // A release that only learned new relationship kinds bumps the version
+        // without touching the documents
+        let history = [Migration {
+            version: SCHEMA_VERSION,
+            change: SchemaChange::Additive("relationship kind `Overrides`"),
+        }];
+        match plan(SCHEMA_VERSION - 1, &expected, &expected, &history) {
+            SchemaStatus::Migrate(plan) => {
+                assert!(!plan.needs_rewrite());
+                assert_eq!(plan.changes, vec!["relationship kind `Overrides`"]);
+            }
+            other => panic!("expected a migration, got {other:?}"),
+        }
+
+        let history = [Migration {
+            version: SCHEMA_VERSION,
+            change: SchemaChange::Breaking("stores symbol ids as u64"),
+        }];
+        assert!(matches!(
+            plan(SCHEMA_VERSION - 1, &expected, &expected, &history),
+            SchemaStatus::Incompatible { .. }
+        ));
+    }
+
+    #[test]
+    fn test_first_version_fields_keep_their_positions() {
+        const V1_FIELDS: &[&str] = &[
+            "doc_type",
+            "symbol_id",
+            "file_path",
+            "line_number",
+            "column",
+            "end_line",
+            "end_column",
+            "name",
+            "name_text",
+            "doc_comment",
+            "signature",
+            "context",
+            "module_path",
+            "kind",
+            "visibility",
+            "scope_context",
+            "language",
+            "from_symbol_id",
+            "to_symbol_id",
+            "relation_kind",
+            "relation_weight",
+            "relation_line",
+            "relation_column",
+            "relation_context",
+            "file_id",
+            "file_hash",
+            "file_timestamp",
+            "meta_key",
+            "meta_value",
+            "cluster_id",
+            "vector_id",
+            "has_vector",
+            "import_file_id",
+            "import_path",
+            "import_alias",
+            "import_is_glob",
+            "import_is_type_only",
+        ];
+        let (schema, _) = IndexSchema::build();
+        let names: Vec<&str> = schema.fields().map(|(_, entry)| entry.name()).collect();
+        assert_eq!(&names[..V1_FIELDS.len()], V1_FIELDS);
+    }
+
+    #[test]
+    fn test_prepare_adds_missing_fields_and_keeps_documents() {
+        let temp = TempDir::new().unwrap();
+        let settings = Arc::new(settings(temp.path()));
+        let (documents, indexed_stable_ids) = index_with_older_schema(temp.path(), &settings);
+
+        let report = prepare(&settings.index_path, &settings)
+            .unwrap()
+            .expect("older index should be migrated");
+        assert_eq!(
+            report.plan.added_fields,
+            vec!["relation_context".to_string(), "stable_id".to_string()]
+        );
+        assert_eq!(report.documents, Some(documents));
+        assert!(matches!(
+            check(&settings.index_path).unwrap(),
+            SchemaStatus::Current
+        ));
+        assert!(!settings.index_path.join("tantivy.migrating").exists());
+
+        let indexer = IndexPersistence::new(settings.index_path.clone())
+            .load_with_settings(settings.clone(), false)
+            .unwrap();
+        assert!(!indexer.find_symbols_by_name("Config", None).is_empty());
+        let annotated = indexer
+            .search_with_annotation("", 10, None, None, None, "derive")
+            .unwrap();
+        assert!(!annotated.is_empty(), "annotation terms are derived again");
+        let load = indexer.find_symbols_by_name("load", None)[0].id;
+        let called = indexer.get_called_functions(load);
+        assert!(called.iter().any(|s| s.name.as_ref() == "helper"));
+
+        // Stable IDs are derived the way indexing derives them
+        assert!(indexed_stable_ids.iter().all(|(_, id)| id.is_some()));
+        assert_eq!(stable_ids(&indexer), indexed_stable_ids);
+    }
+
+    #[test]
+    fn test_newer_schema_needs_reindex() {
+        let temp = TempDir::new().unwrap();
+        let settings = Arc::new(settings(temp.path()));
+        let file = write_file(temp.path(), "lib.rs", "pub fn only() {}\n");
+        let mut indexer = SimpleIndexer::with_settings(settings.clone());
+        indexer.index_file(&file).unwrap();
+        let persistence = IndexPersistence::new(settings.index_path.clone());
+        persistence.save(&indexer).unwrap();
+        drop(indexer);
+
+        let mut metadata = IndexMetadata::load(&settings.index_path).unwrap();
+        assert_eq!(metadata.schema_version, SCHEMA_VERSION);
+        metadata.schema_version = SCHEMA_VERSION + 1;
+        metadata.save(&settings.index_path).unwrap();
+
+        let Err(err) = persistence.load_with_settings(settings.clone(), false) else {
+            panic!("an index from a newer release should not load");
+        };
+        assert!(matches!(err, IndexError::SchemaMismatch { .. }));
+        assert_eq!(
+            crate::io::ExitCode::from_error(&err),
+            crate::io::ExitCode::IndexCorrupted
+        );
+        assert!(err.to_string().contains("needs a reindex"));
+    }
+}
@@ -8,6 +8,8 @@
 //! - [`DocumentIndex`]: Tantivy ベースの全文検索
 //! - [`IndexMetadata`]: インデックスメタデータ管理
 //! - [`MetadataKey`]: メタデータキーの定義
+//! - [`migration`]: `index.meta` のスキーマバージョン検出と、追加的な変更を含む旧インデックスの移行
+//! - [`archive`]: `codanna index pack` / `unpack` で別のチェックアウトへ運ぶ圧縮アーカイブ
 //!
 //! # 使用例
 //!
@@ -19,10 +21,12 @@
 //! let persistence = IndexPersistence::new(path.to_path_buf());
 //! ```
 
+pub mod archive;
 pub mod error;
 pub mod memory;
 pub mod metadata;
 pub mod metadata_keys;
+pub mod migration;
 pub mod persistence;
 pub mod symbol_cache;
 pub mod tantivy;
@@ -3,6 +3,7 @@
 //! This module manages metadata and ensures Tantivy index exists.
 //! All actual data is stored in Tantivy.
 
+use crate::storage::migration::{self, SCHEMA_VERSION};
 use crate::storage::{DataSource, IndexMetadata};
 use crate::{IndexError, IndexResult, Settings, SimpleIndexer};
 use std::path::PathBuf;
@@ -33,6 +34,7 @@ impl IndexPersistence {
             IndexMetadata::load(&self.base_path).unwrap_or_else(|_| IndexMetadata::new());
 
         metadata.update_counts(indexer.symbol_count() as u32, indexer.file_count());
+        metadata.schema_version = SCHEMA_VERSION;
 
         // Update indexed paths for sync detection on next load
         let indexed_paths: Vec<PathBuf> = indexer.get_indexed_paths().iter().cloned().collect();
@@ -46,6 +48,7 @@ impl IndexPersistence {
             }
         }
         metadata.update_indexed_paths(indexed_paths);
+        metadata.unresolved = indexer.unresolved_summary().clone();
 
         // Update metadata to reflect Tantivy
         metadata.data_source = DataSource::Tantivy {
@@ -75,6 +78,9 @@ impl IndexPersistence {
                 .map_err(|e| IndexError::General(format!("Failed to save semantic search: {e}")))?;
         }
 
+        // Every store now holds the run's results
+        indexer.finish_index_journal()?;
+
         Ok(())
     }
 
@@ -102,6 +108,12 @@ impl IndexPersistence {
         info: bool,
         skip_trait_resolver: bool,
     ) -> IndexResult<SimpleIndexer> {
+        // Bring an index written by an older schema up to this one first;
+        // opening it as is would read its fields through the wrong handles
+        if let Some(report) = migration::prepare(&self.base_path, &settings)? {
+            eprintln!("{report}");
+        }
+
         // Load metadata to understand data sources
         let metadata = IndexMetadata::load(&self.base_path).ok();
 
@@ -194,6 +206,9 @@ impl IndexPersistence {
                         );
                     }
                 }
+
+                // Unresolved counts of files that are not re-indexed carry over
+                indexer.set_unresolved_summary(meta.unresolved.clone());
             }
 
             Ok(indexer)
@@ -8,19 +8,20 @@ use crate::indexing::retry::{
     backoff_with_jitter_ms, is_windows_transient_io_error, is_writer_killed,
     normalized_heap_bytes, windows_error_retry_class, WindowsIoRetryClass,
 };
+use crate::parsing::Annotation;
 use crate::relationship::RelationshipMetadata;
 use crate::vector::{ClusterId, EmbeddingGenerator, SegmentOrdinal, VectorId, VectorSearchEngine};
 use crate::{FileId, RelationKind, Relationship, SymbolId, SymbolKind};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 use std::sync::{Arc, RwLock};
 use tantivy::DocId;
 use tantivy::{
-    Index, IndexReader, IndexSettings, IndexWriter, ReloadPolicy, TantivyDocument as Document,
-    Term,
-    collector::TopDocs,
+    Index, IndexReader, IndexSettings, IndexWriter, Order, ReloadPolicy, Searcher,
+    TantivyDocument as Document, Term,
+    collector::{DocSetCollector, TopDocs},
     directory::MmapDirectory,
     query::{BooleanQuery, FuzzyTermQuery, Occur, Query, QueryParser, TermQuery},
     schema::{
@@ -201,6 +202,12 @@ pub struct IndexSchema {
     pub import_alias: Field,        // Optional alias
     pub import_is_glob: Field,      // Boolean (0/1) for glob imports
     pub import_is_type_only: Field, // Boolean (0/1) for type-only imports (TypeScript)
+
+    // Symbol fields added after the first schema version
+    pub is_test: Field,         // Boolean (0/1) for test functions and test blocks
+    pub annotations: Field,     // Attributes/decorators as written, one value each
+    pub annotation_name: Field, // Lowercased annotation names and segments for filtering
+    pub stable_id: Field,       // Identifier derived from the definition, kept across reindexes
 }
 
 impl IndexSchema {
@@ -290,6 +297,13 @@ impl IndexSchema {
         let import_is_glob = builder.add_u64_field("import_is_glob", STORED);
         let import_is_type_only = builder.add_u64_field("import_is_type_only", STORED);
 
+        // New fields go last. Tantivy addresses fields by position, so a field
+        // inserted in between moves every later one in existing indexes.
+        let is_test = builder.add_u64_field("is_test", indexed_u64_options.clone());
+        let annotations = builder.add_text_field("annotations", STORED);
+        let annotation_name = builder.add_text_field("annotation_name", STRING);
+        let stable_id = builder.add_text_field("stable_id", STRING | STORED);
+
         let schema = builder.build();
         let index_schema = IndexSchema {
             doc_type,
@@ -329,6 +343,10 @@ impl IndexSchema {
             import_alias,
             import_is_glob,
             import_is_type_only,
+            is_test,
+            annotations,
+            annotation_name,
+            stable_id,
         };
 
         (schema, index_schema)
@@ -501,6 +519,8 @@ pub struct SearchResult {
     pub score: f32,
     pub highlights: Vec<TextHighlight>,
     pub context: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub annotations: Vec<Annotation>,
 }
 
 /// Highlighted text region
@@ -561,6 +581,14 @@ impl std::fmt::Debug for DocumentIndex {
     }
 }
 
+/// Register the tokenizers the schema's text fields name
+pub(crate) fn register_tokenizers(index: &Index) {
+    // Register custom tokenizer for partial matching (ngram with min_gram=3, max_gram=10)
+    // This allows "Archive" to match "ArchiveAppService"
+    let ngram_tokenizer = TextAnalyzer::builder(NgramTokenizer::new(3, 10, false).unwrap()).build();
+    index.tokenizers().register("ngram", ngram_tokenizer);
+}
+
 impl DocumentIndex {
     /// Create a new document index
     pub fn new(
@@ -580,17 +608,26 @@ impl DocumentIndex {
 
         // Create or open the index
         let index = if index_path.join("meta.json").exists() {
-            Index::open_in_dir(&index_path)?
+            let index = Index::open_in_dir(&index_path)?;
+            // Field handles are positional, so an index written by an older schema
+            // cannot be used with this one until `migration::prepare` rewrites it
+            let existing = index.schema();
+            if schema
+                .fields()
+                .any(|(field, entry)| existing.get_field(entry.name()).ok() != Some(field))
+            {
+                return Err(StorageError::SchemaError(
+                    "index was created by an older version; re-index with `codanna index --force`"
+                        .to_string(),
+                ));
+            }
+            index
         } else {
             let dir = MmapDirectory::open(&index_path)?;
             Index::create(dir, schema, IndexSettings::default())?
         };
 
-        // Register custom tokenizer for partial matching (ngram with min_gram=3, max_gram=10)
-        // This allows "Archive" to match "ArchiveAppService"
-        let ngram_tokenizer =
-            TextAnalyzer::builder(NgramTokenizer::new(3, 10, false).unwrap()).build();
-        index.tokenizers().register("ngram", ngram_tokenizer);
+        register_tokenizers(&index);
 
         let reader = index
             .reader_builder()
@@ -1100,7 +1137,9 @@ impl DocumentIndex {
         {
             let writer_lock = self.writer.lock().map_err(|_| StorageError::LockPoisoned)?;
             if writer_lock.is_some() {
-                return Ok(());
+                drop(writer_lock);
+                // commit_batchはwriterを残してカウンタだけクリアするので、コミット済みの値から再開
+                return self.resume_pending_counters();
             }
         }
 
@@ -1168,6 +1207,47 @@ impl DocumentIndex {
         visibility: crate::Visibility,
         scope_context: Option<crate::ScopeContext>,
         language_id: Option<&str>, // Language identifier for the symbol
+    ) -> StorageResult<()> {
+        self.add_symbol_document(
+            symbol_id,
+            name,
+            kind,
+            file_id,
+            file_path,
+            (line, column, end_line, end_column),
+            doc_comment,
+            signature,
+            module_path,
+            context,
+            visibility,
+            scope_context,
+            language_id,
+            false,
+            &[],
+            None,
+        )
+    }
+
+    /// Add a symbol document including its test flag, annotations and stable ID
+    #[allow(clippy::too_many_arguments)]
+    fn add_symbol_document(
+        &self,
+        symbol_id: SymbolId,
+        name: &str,
+        kind: SymbolKind,
+        file_id: FileId,
+        file_path: &str,
+        (line, column, end_line, end_column): (u32, u16, u32, u16),
+        doc_comment: Option<&str>,
+        signature: Option<&str>,
+        module_path: &str,
+        context: Option<&str>,
+        visibility: crate::Visibility,
+        scope_context: Option<crate::ScopeContext>,
+        language_id: Option<&str>,
+        is_test: bool,
+        annotations: &[Annotation],
+        stable_id: Option<&crate::StableSymbolId>,
     ) -> StorageResult<()> {
         let mut writer_lock = self.writer.lock().map_err(|_| StorageError::LockPoisoned)?;
         let writer = writer_lock.as_mut().ok_or(StorageError::NoActiveBatch)?;
@@ -1214,6 +1294,17 @@ impl DocumentIndex {
         } else {
             doc.add_text(self.schema.language, "");
         }
+        doc.add_u64(self.schema.is_test, is_test as u64);
+
+        for annotation in annotations {
+            doc.add_text(self.schema.annotations, annotation.to_string());
+            for term in annotation.search_terms() {
+                doc.add_text(self.schema.annotation_name, term);
+            }
+        }
+        if let Some(stable_id) = stable_id {
+            doc.add_text(self.schema.stable_id, stable_id.as_str());
+        }
 
         // Add default vector fields - these will be updated later if vectors are generated
         if self.has_vector_support() {
@@ -1363,6 +1454,19 @@ impl DocumentIndex {
         Ok(())
     }
 
+    /// 保持しているwriterを破棄し、Tantivyのwriterロックを解放する
+    ///
+    /// commit_batchは次のバッチのためにwriterを残すため、常駐プロセス（ファイル監視）は
+    /// 書き込み後にこれを呼び、他のプロセスがwriterを作成できるようにする。
+    /// コミットされていない変更は破棄される。
+    pub fn release_writer(&self) -> StorageResult<()> {
+        let writer = self.writer.lock().map_err(|_| StorageError::LockPoisoned)?.take();
+        if let Some(writer) = writer {
+            writer.wait_merging_threads()?;
+        }
+        Ok(())
+    }
+
     /// Remove documents for a specific file
     pub fn remove_file_documents(&self, file_path: &str) -> StorageResult<()> {
         // Use existing batch writer if available, otherwise create temporary one
@@ -1394,6 +1498,30 @@ impl DocumentIndex {
         kind_filter: Option<SymbolKind>,
         module_filter: Option<&str>,
         language_filter: Option<&str>,
+    ) -> StorageResult<Vec<SearchResult>> {
+        self.search_with_annotation(
+            query_str,
+            limit,
+            kind_filter,
+            module_filter,
+            language_filter,
+            None,
+        )
+    }
+
+    /// Search for documents, optionally keeping only symbols with an annotation
+    ///
+    /// The annotation filter matches a full name (`app.route`, `tokio::main`)
+    /// or any segment of it (`route`, `main`), case-insensitively. With a filter
+    /// and an empty query, every annotated symbol matching the filters is returned.
+    pub fn search_with_annotation(
+        &self,
+        query_str: &str,
+        limit: usize,
+        kind_filter: Option<SymbolKind>,
+        module_filter: Option<&str>,
+        language_filter: Option<&str>,
+        annotation_filter: Option<&str>,
     ) -> StorageResult<Vec<SearchResult>> {
         let searcher = self.reader.searcher();
 
@@ -1461,14 +1589,17 @@ impl DocumentIndex {
         // 1. Main query (ngram partial matching)
         // 2. Fuzzy on ngram tokens (typos in short queries)
         // 3. Fuzzy on whole word (typos in full symbol names)
-        all_clauses.push((
-            Occur::Must,
-            Box::new(BooleanQuery::new(vec![
-                (Occur::Should, main_query),
-                (Occur::Should, Box::new(fuzzy_ngram_query)),
-                (Occur::Should, Box::new(fuzzy_whole_word_query)),
-            ])),
-        ));
+        // An annotation filter alone lists its symbols without a text query
+        if !query_str.trim().is_empty() || annotation_filter.is_none() {
+            all_clauses.push((
+                Occur::Must,
+                Box::new(BooleanQuery::new(vec![
+                    (Occur::Should, main_query),
+                    (Occur::Should, Box::new(fuzzy_ngram_query)),
+                    (Occur::Should, Box::new(fuzzy_whole_word_query)),
+                ])),
+            ));
+        }
 
         // Add mandatory filters.
         all_clauses.push((
@@ -1504,6 +1635,15 @@ impl DocumentIndex {
             ));
         }
 
+        if let Some(annotation) = annotation_filter {
+            let name = annotation.trim_start_matches(['@', '#', '[']).to_lowercase();
+            let term = Term::from_field_text(self.schema.annotation_name, &name);
+            all_clauses.push((
+                Occur::Must,
+                Box::new(TermQuery::new(term, IndexRecordOption::Basic)),
+            ));
+        }
+
         let final_query = BooleanQuery::new(all_clauses);
 
         let top_docs = searcher.search(&final_query, &TopDocs::with_limit(limit))?;
@@ -1565,16 +1705,7 @@ impl DocumentIndex {
                 .and_then(|v| v.as_str())
                 .unwrap_or("Unknown");
 
-            let kind = match kind_str {
-                "Function" => SymbolKind::Function,
-                "Struct" => SymbolKind::Struct,
-                "Trait" => SymbolKind::Trait,
-                "Method" => SymbolKind::Method,
-                "Field" => SymbolKind::Field,
-                "Module" => SymbolKind::Module,
-                "Constant" => SymbolKind::Constant,
-                _ => SymbolKind::Function, // Default fallback
-            };
+            let kind = SymbolKind::from_str_with_default(kind_str);
 
             let module_path = doc
                 .get_first(self.schema.module_path)
@@ -1582,6 +1713,12 @@ impl DocumentIndex {
                 .unwrap_or("")
                 .to_string();
 
+            let annotations = doc
+                .get_all(self.schema.annotations)
+                .filter_map(|v| v.as_str())
+                .filter_map(Annotation::parse)
+                .collect();
+
             results.push(SearchResult {
                 symbol_id,
                 name,
@@ -1595,6 +1732,7 @@ impl DocumentIndex {
                 score,
                 highlights: Vec::new(), // TODO: Implement highlighting
                 context,
+                annotations,
             });
         }
 
@@ -1642,6 +1780,27 @@ impl DocumentIndex {
         }
     }
 
+    /// Find a symbol by its stable ID
+    pub fn find_symbol_by_stable_id(
+        &self,
+        id: &crate::StableSymbolId,
+    ) -> StorageResult<Option<crate::Symbol>> {
+        let searcher = self.reader.searcher();
+        let query = TermQuery::new(
+            Term::from_field_text(self.schema.stable_id, id.as_str()),
+            IndexRecordOption::Basic,
+        );
+
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(1))?;
+
+        if let Some((_score, doc_address)) = top_docs.first() {
+            let doc = searcher.doc::<Document>(*doc_address)?;
+            Ok(Some(self.document_to_symbol(&doc)?))
+        } else {
+            Ok(None)
+        }
+    }
+
     /// Find a symbol by its ID with language filter
     pub fn find_symbol_by_id_with_language(
         &self,
@@ -1718,7 +1877,70 @@ impl DocumentIndex {
 
         let final_query = BooleanQuery::new(query_clauses);
 
-        let top_docs = searcher.search(&final_query, &TopDocs::with_limit(100))?;
+        self.search_symbols_by_id(&searcher, &final_query, 100)
+    }
+
+    /// Find symbols by file ID
+    pub fn find_symbols_by_file(&self, file_id: FileId) -> StorageResult<Vec<crate::Symbol>> {
+        let searcher = self.reader.searcher();
+        let query = BooleanQuery::from(vec![
+            (
+                Occur::Must,
+                Box::new(TermQuery::new(
+                    Term::from_field_text(self.schema.doc_type, "symbol"),
+                    IndexRecordOption::Basic,
+                )) as Box<dyn Query>,
+            ),
+            (
+                Occur::Must,
+                Box::new(TermQuery::new(
+                    Term::from_field_u64(self.schema.file_id, file_id.0 as u64),
+                    IndexRecordOption::Basic,
+                )) as Box<dyn Query>,
+            ),
+        ]);
+
+        self.search_symbols_by_id(&searcher, &query, 1000)
+    }
+
+    /// Find symbols of one kind (e.g. all `Route` symbols), optionally of one language
+    pub fn find_symbols_by_kind(
+        &self,
+        kind: crate::SymbolKind,
+        language_filter: Option<&str>,
+        limit: usize,
+    ) -> StorageResult<Vec<crate::Symbol>> {
+        let searcher = self.reader.searcher();
+        let mut query_clauses = vec![
+            (
+                Occur::Must,
+                Box::new(TermQuery::new(
+                    Term::from_field_text(self.schema.doc_type, "symbol"),
+                    IndexRecordOption::Basic,
+                )) as Box<dyn Query>,
+            ),
+            (
+                Occur::Must,
+                Box::new(TermQuery::new(
+                    Term::from_field_text(self.schema.kind, &format!("{kind:?}")),
+                    IndexRecordOption::Basic,
+                )) as Box<dyn Query>,
+            ),
+        ];
+
+        if let Some(lang) = language_filter {
+            query_clauses.push((
+                Occur::Must,
+                Box::new(TermQuery::new(
+                    Term::from_field_text(self.schema.language, lang),
+                    IndexRecordOption::Basic,
+                )),
+            ));
+        }
+
+        let query = BooleanQuery::new(query_clauses);
+
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(limit))?;
         let mut symbols = Vec::new();
 
         for (_score, doc_address) in top_docs {
@@ -1729,8 +1951,12 @@ impl DocumentIndex {
         Ok(symbols)
     }
 
-    /// Find symbols by file ID
-    pub fn find_symbols_by_file(&self, file_id: FileId) -> StorageResult<Vec<crate::Symbol>> {
+    /// Find symbols carrying an annotation (full name or segment, e.g. `pyfunction`)
+    pub fn find_symbols_by_annotation(
+        &self,
+        annotation: &str,
+        limit: usize,
+    ) -> StorageResult<Vec<crate::Symbol>> {
         let searcher = self.reader.searcher();
         let query = BooleanQuery::from(vec![
             (
@@ -1743,13 +1969,16 @@ impl DocumentIndex {
             (
                 Occur::Must,
                 Box::new(TermQuery::new(
-                    Term::from_field_u64(self.schema.file_id, file_id.0 as u64),
+                    Term::from_field_text(
+                        self.schema.annotation_name,
+                        &annotation.to_lowercase(),
+                    ),
                     IndexRecordOption::Basic,
                 )) as Box<dyn Query>,
             ),
         ]);
 
-        let top_docs = searcher.search(&query, &TopDocs::with_limit(1000))?;
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(limit))?;
         let mut symbols = Vec::new();
 
         for (_score, doc_address) in top_docs {
@@ -1774,16 +2003,30 @@ impl DocumentIndex {
             )) as Box<dyn Query>,
         )]);
 
-        let top_docs = searcher.search(&query, &TopDocs::with_limit(limit))?;
-
-        let mut symbols = Vec::new();
-
-        for (_score, doc_address) in top_docs {
-            let doc = searcher.doc::<Document>(doc_address)?;
-            symbols.push(self.document_to_symbol(&doc)?);
-        }
+        self.search_symbols_by_id(&searcher, &query, limit)
+    }
 
-        Ok(symbols)
+    /// Symbols matching `query` with the lowest IDs, in ID order
+    ///
+    /// These queries score every match alike, and `TopDocs` breaks ties in
+    /// segment order, which merges reshuffle. Ranking by the `symbol_id` fast
+    /// field instead keeps both the order and the cut at `limit` stable.
+    fn search_symbols_by_id(
+        &self,
+        searcher: &Searcher,
+        query: &dyn Query,
+        limit: usize,
+    ) -> StorageResult<Vec<crate::Symbol>> {
+        let collector =
+            TopDocs::with_limit(limit).order_by_fast_field::<u64>("symbol_id", Order::Asc);
+        searcher
+            .search(query, &collector)?
+            .into_iter()
+            .map(|(_, doc_address)| {
+                let doc = searcher.doc::<Document>(doc_address)?;
+                self.document_to_symbol(&doc)
+            })
+            .collect()
     }
 
     /// Convert a Tantivy document to a Symbol
@@ -1960,6 +2203,19 @@ impl DocumentIndex {
                             .and_then(|registry| registry.find_language_id(lang_str))
                     })
             },
+            is_test: doc
+                .get_first(self.schema.is_test)
+                .and_then(|v| v.as_u64())
+                .is_some_and(|v| v == 1),
+            annotations: doc
+                .get_all(self.schema.annotations)
+                .filter_map(|v| v.as_str())
+                .filter_map(Annotation::parse)
+                .collect(),
+            stable_id: doc
+                .get_first(self.schema.stable_id)
+                .and_then(|v| v.as_str())
+                .and_then(|s| s.parse().ok()),
         })
     }
 
@@ -2012,6 +2268,32 @@ impl DocumentIndex {
         }
     }
 
+    /// Restart pending counters cleared by a commit from the committed metadata
+    ///
+    /// Without this, the next batch on the same writer would read the
+    /// committed counter for every file and hand out the same IDs again.
+    fn resume_pending_counters(&self) -> StorageResult<()> {
+        let mut symbol_guard = self
+            .pending_symbol_counter
+            .lock()
+            .map_err(|_| StorageError::LockPoisoned)?;
+        if symbol_guard.is_none() {
+            let committed = self.query_metadata(MetadataKey::SymbolCounter)?.unwrap_or(0) as u32;
+            *symbol_guard = Some(committed + 1);
+        }
+        drop(symbol_guard);
+
+        let mut file_guard = self
+            .pending_file_counter
+            .lock()
+            .map_err(|_| StorageError::LockPoisoned)?;
+        if file_guard.is_none() {
+            let committed = self.query_metadata(MetadataKey::FileCounter)?.unwrap_or(0) as u32;
+            *file_guard = Some(committed + 1);
+        }
+        Ok(())
+    }
+
     /// Get next file ID
     pub fn get_next_file_id(&self) -> StorageResult<u32> {
         // During batch operations, use and increment the pending counter
@@ -2441,16 +2723,18 @@ impl DocumentIndex {
 
     /// Index a symbol from a Symbol struct
     pub fn index_symbol(&self, symbol: &crate::Symbol, file_path: &str) -> StorageResult<()> {
-        self.add_document(
+        self.add_symbol_document(
             symbol.id,
             &symbol.name,
             symbol.kind,
             symbol.file_id,
             file_path,
-            symbol.range.start_line,
-            symbol.range.start_column,
-            symbol.range.end_line,
-            symbol.range.end_column,
+            (
+                symbol.range.start_line,
+                symbol.range.start_column,
+                symbol.range.end_line,
+                symbol.range.end_column,
+            ),
             symbol.doc_comment.as_ref().map(|s| s.as_ref()),
             symbol.signature.as_ref().map(|s| s.as_ref()),
             symbol
@@ -2476,6 +2760,9 @@ impl DocumentIndex {
             // This should be tested with real workloads to ensure we maintain our performance targets.
             symbol.scope_context.clone(),
             symbol.language_id.as_ref().map(|id| id.as_str()),
+            symbol.is_test,
+            &symbol.annotations,
+            symbol.stable_id.as_ref(),
         )
     }
 
@@ -2717,6 +3004,8 @@ impl DocumentIndex {
                 "DefinedIn" => RelationKind::DefinedIn,
                 "References" => RelationKind::References,
                 "ReferencedBy" => RelationKind::ReferencedBy,
+                "Routes" => RelationKind::Routes,
+                "RoutedBy" => RelationKind::RoutedBy,
                 _ => continue, // Skip unknown relation kinds
             };
 
@@ -2806,6 +3095,61 @@ impl DocumentIndex {
         Ok(files)
     }
 
+    /// File IDs that import documents belong to
+    pub(crate) fn query_import_file_ids(&self) -> StorageResult<HashSet<FileId>> {
+        let searcher = self.reader.searcher();
+        let query = TermQuery::new(
+            Term::from_field_text(self.schema.doc_type, "import"),
+            IndexRecordOption::Basic,
+        );
+
+        let mut file_ids = HashSet::new();
+        for doc_address in searcher.search(&query, &DocSetCollector)? {
+            let doc: Document = searcher.doc(doc_address)?;
+            if let Some(file_id) = doc
+                .get_first(self.schema.import_file_id)
+                .and_then(|v| v.as_u64())
+                .and_then(|id| FileId::new(id as u32))
+            {
+                file_ids.insert(file_id);
+            }
+        }
+
+        Ok(file_ids)
+    }
+
+    /// Merge all segments into one and delete the files no segment uses
+    ///
+    /// Merging drops deleted documents for good. Returns the number of
+    /// segments before the merge.
+    pub fn merge_segments(&self) -> StorageResult<usize> {
+        let mut writer_lock = self.lock_writer_safe()?;
+        let mut own_writer = None;
+        let writer = match writer_lock.as_mut() {
+            Some(writer) => writer,
+            None => own_writer.insert(self.create_writer_with_retry()?),
+        };
+
+        let segments = self.index.searchable_segment_ids()?;
+        let has_deletes = self
+            .reader
+            .searcher()
+            .segment_readers()
+            .iter()
+            .any(|segment| segment.has_deletes());
+        if segments.len() > 1 || has_deletes {
+            writer.merge(&segments).wait()?;
+        }
+        writer.garbage_collect_files().wait()?;
+
+        drop(writer_lock);
+        if let Some(writer) = own_writer {
+            writer.wait_merging_threads()?;
+        }
+        self.reader.reload()?;
+        Ok(segments.len())
+    }
+
     /// Count symbols in Tantivy index
     #[allow(dead_code)]
     pub(crate) fn count_symbol_documents(&self) -> StorageResult<u64> {
@@ -3214,6 +3558,78 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_symbol_limits_cut_by_id_across_segments() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings = crate::config::Settings::default();
+        let index = DocumentIndex::new(temp_dir.path(), &settings).unwrap();
+
+        // One segment per symbol, written from the highest ID down
+        for id in (1..=8).rev() {
+            index.start_batch().unwrap();
+            index
+                .add_document(
+                    SymbolId::new(id).unwrap(),
+                    "handler",
+                    SymbolKind::Function,
+                    FileId::new(1).unwrap(),
+                    "src/lib.rs",
+                    id,
+                    0,
+                    id,
+                    1,
+                    None,
+                    None,
+                    "crate",
+                    None,
+                    crate::Visibility::Public,
+                    None,
+                    None,
+                )
+                .unwrap();
+            index.commit_batch().unwrap();
+        }
+
+        let ids = |symbols: Vec<crate::Symbol>| -> Vec<u32> {
+            symbols.iter().map(|symbol| symbol.id.value()).collect()
+        };
+        assert_eq!(ids(index.get_all_symbols(3).unwrap()), [1, 2, 3]);
+        let all: Vec<u32> = (1..=8).collect();
+        assert_eq!(ids(index.find_symbols_by_name("handler", None).unwrap()), all);
+        assert_eq!(ids(index.find_symbols_by_file(FileId::new(1).unwrap()).unwrap()), all);
+    }
+
+    #[test]
+    fn test_reused_writer_does_not_repeat_ids() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings = crate::config::Settings::default();
+        let index = DocumentIndex::new(temp_dir.path(), &settings).unwrap();
+
+        index.start_batch().unwrap();
+        let first_symbol = index.get_next_symbol_id().unwrap();
+        let first_file = index.get_next_file_id().unwrap();
+        index
+            .store_metadata(MetadataKey::SymbolCounter, first_symbol as u64)
+            .unwrap();
+        index
+            .store_metadata(MetadataKey::FileCounter, first_file as u64)
+            .unwrap();
+        index.commit_batch().unwrap();
+
+        // The commit keeps the writer, so this batch reuses it
+        index.start_batch().unwrap();
+        let symbols = [
+            index.get_next_symbol_id().unwrap(),
+            index.get_next_symbol_id().unwrap(),
+        ];
+        let files = [
+            index.get_next_file_id().unwrap(),
+            index.get_next_file_id().unwrap(),
+        ];
+        assert_eq!(symbols, [first_symbol + 1, first_symbol + 2]);
+        assert_eq!(files, [first_file + 1, first_file + 2]);
+    }
+
     #[test]
     fn test_vector_metadata_creation() {
         // Test creating metadata without vector
@@ -4520,4 +4936,63 @@ mod tests {
         let imports_after = index.get_imports_for_file(file_id).unwrap();
         assert_eq!(imports_after.len(), 0, "Imports should be deleted");
     }
+
+    #[test]
+    fn test_annotations_are_stored_and_filterable() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings = crate::config::Settings::default();
+        let index = DocumentIndex::new(temp_dir.path(), &settings).unwrap();
+        let file_id = FileId::new(1).unwrap();
+
+        let symbol = |id: u32, name: &str, annotations: &[&str]| {
+            let mut symbol = crate::Symbol::new(
+                SymbolId::new(id).unwrap(),
+                name,
+                SymbolKind::Function,
+                file_id,
+                crate::Range::new(id, 0, id + 2, 0),
+            );
+            symbol.annotations = annotations
+                .iter()
+                .filter_map(|a| Annotation::parse(a))
+                .collect();
+            symbol
+        };
+
+        index.start_batch().unwrap();
+        for sym in [
+            symbol(
+                1,
+                "create_order",
+                &["app.route(\"/orders\", methods=[\"POST\"])"],
+            ),
+            symbol(2, "main", &["tokio::main"]),
+            symbol(3, "order_total", &[]),
+        ] {
+            index.index_symbol(&sym, "src/app.py").unwrap();
+        }
+        index.commit_batch().unwrap();
+
+        let found = index
+            .find_symbol_by_id(SymbolId::new(1).unwrap())
+            .unwrap()
+            .unwrap();
+        assert_eq!(&*found.annotations[0].name, "app.route");
+        assert_eq!(
+            found.annotations[0].arguments.as_deref(),
+            Some("\"/orders\", methods=[\"POST\"]")
+        );
+
+        let routes = index
+            .search_with_annotation("", 10, None, None, None, Some("@Route"))
+            .unwrap();
+        assert_eq!(routes.len(), 1);
+        assert_eq!(routes[0].name, "create_order");
+        assert_eq!(routes[0].annotations.len(), 1);
+
+        let mains = index
+            .search_with_annotation("order", 10, None, None, None, Some("tokio::main"))
+            .unwrap();
+        assert!(mains.is_empty());
+    }
 }
@@ -102,6 +102,19 @@ impl SymbolContext {
             output.push_str(&format!("{indent}Module: {module}\n"));
         }
 
+        if !self.symbol.annotations.is_empty() {
+            let annotations: Vec<String> = self
+                .symbol
+                .annotations
+                .iter()
+                .map(|a| a.to_string())
+                .collect();
+            output.push_str(&format!(
+                "{indent}Annotations: {}\n",
+                annotations.join(", ")
+            ));
+        }
+
         if let Some(sig) = self.symbol.as_signature() {
             output.push_str(&format!("{indent}Signature:\n"));
             Self::write_multiline(output, sig, indent, 2);
@@ -0,0 +1,346 @@
+//! Change impact of a unified diff
+//!
+//! Parses `git diff` style unified diffs into the changed lines of each file.
+//! Changed lines are mapped to the innermost indexed symbol containing them,
+//! and the impact radius of those symbols (callers, users, implementors) is
+//! reported grouped by file. Line numbers refer to the new side of the diff,
+//! so the index should reflect the changed tree.
+
+use crate::SymbolKind;
+use serde::Serialize;
+use std::fmt;
+
+/// Changed lines of one file in a diff
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileDiff {
+    /// New path, or the old path for deleted files
+    pub path: String,
+    /// File was removed; every symbol it had is changed
+    pub deleted: bool,
+    /// One-based lines in the new file that were added, or where lines were removed
+    pub lines: Vec<u32>,
+}
+
+fn diff_path(header: &str) -> Option<String> {
+    // Some tools append a tab and timestamp after the path
+    let path = header.split('\t').next().unwrap_or(header).trim();
+    if path == "/dev/null" {
+        return None;
+    }
+    let path = path
+        .strip_prefix("a/")
+        .or_else(|| path.strip_prefix("b/"))
+        .unwrap_or(path);
+    Some(path.to_string())
+}
+
+/// Parse `@@ -a,b +c,d @@` into (old count, new start, new count)
+fn hunk_header(line: &str) -> Option<(u32, u32, u32)> {
+    let ranges = line.strip_prefix("@@ ")?.split(" @@").next()?;
+    let mut parts = ranges.split_whitespace();
+    let old = parts.next()?.strip_prefix('-')?;
+    let new = parts.next()?.strip_prefix('+')?;
+
+    let count = |range: &str| -> Option<(u32, u32)> {
+        match range.split_once(',') {
+            Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+            None => Some((range.parse().ok()?, 1)),
+        }
+    };
+    let (_, old_count) = count(old)?;
+    let (new_start, new_count) = count(new)?;
+    Some((old_count, new_start, new_count))
+}
+
+/// Parse a unified diff into per-file changed lines.
+///
+/// Files are returned in diff order. Binary and mode-only changes have no
+/// hunks and are skipped unless the file was deleted.
+pub fn parse_unified_diff(text: &str) -> Vec<FileDiff> {
+    let mut files: Vec<FileDiff> = Vec::new();
+    let mut old_path: Option<String> = None;
+    // Lines left in the current hunk: (old side, new side)
+    let mut remaining = (0u32, 0u32);
+    let mut new_line = 0u32;
+
+    for line in text.lines() {
+        if remaining.0 > 0 || remaining.1 > 0 {
+            let Some(file) = files.last_mut() else {
+                remaining = (0, 0);
+                continue;
+            };
+            match line.chars().next() {
+                Some('+') => {
+                    file.lines.push(new_line);
+                    new_line += 1;
+                    remaining.1 = remaining.1.saturating_sub(1);
+                }
+                Some('-') => {
+                    // Removed lines are attributed to the line now in their place
+                    file.lines.push(new_line.max(1));
+                    remaining.0 = remaining.0.saturating_sub(1);
+                }
+                Some('\\') => {}
+                _ => {
+                    new_line += 1;
+                    remaining.0 = remaining.0.saturating_sub(1);
+                    remaining.1 = remaining.1.saturating_sub(1);
+                }
+            }
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix("--- ") {
+            old_path = diff_path(header);
+        } else if let Some(header) = line.strip_prefix("+++ ") {
+            let (path, deleted) = match diff_path(header) {
+                Some(path) => (Some(path), false),
+                None => (old_path.take(), true),
+            };
+            if let Some(path) = path {
+                files.push(FileDiff {
+                    path,
+                    deleted,
+                    lines: Vec::new(),
+                });
+            }
+        } else if let Some((old_count, new_start, new_count)) = hunk_header(line) {
+            remaining = (old_count, new_count);
+            new_line = new_start;
+        }
+    }
+
+    files.retain(|f| f.deleted || !f.lines.is_empty());
+    for file in &mut files {
+        file.lines.sort_unstable();
+        file.lines.dedup();
+    }
+    files
+}
+
+/// Whether a symbol was edited by the diff or is affected through relationships
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImpactKind {
+    Changed,
+    Impacted,
+}
+
+/// A changed or impacted symbol
+#[derive(Debug, Clone, Serialize)]
+pub struct ImpactEntry {
+    pub impact: ImpactKind,
+    pub symbol_id: u32,
+    pub name: String,
+    pub kind: SymbolKind,
+    /// One-based first line
+    pub start_line: u32,
+    /// One-based last line
+    pub end_line: u32,
+    /// Changed symbols this one is reached from
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub via: Vec<String>,
+}
+
+/// Changed and impacted symbols of one file
+#[derive(Debug, Clone, Serialize)]
+pub struct FileImpact {
+    pub file_path: String,
+    pub symbols: Vec<ImpactEntry>,
+}
+
+/// Impact of a diff, grouped by file
+#[derive(Debug, Clone, Serialize)]
+pub struct DiffImpact {
+    /// One-line description, suitable as a PR comment heading
+    pub summary: String,
+    pub depth: usize,
+    pub changed_count: usize,
+    pub impacted_count: usize,
+    pub files: Vec<FileImpact>,
+    /// Files in the diff that are not in the index
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unindexed_files: Vec<String>,
+}
+
+impl DiffImpact {
+    /// Assemble the report; files and entries are sorted for stable output
+    pub fn new(depth: usize, mut files: Vec<FileImpact>, unindexed_files: Vec<String>) -> Self {
+        files.retain(|f| !f.symbols.is_empty());
+        files.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        for file in &mut files {
+            file.symbols
+                .sort_by_key(|e| (e.impact == ImpactKind::Impacted, e.start_line, e.symbol_id));
+        }
+
+        let count_of = |impact: ImpactKind| {
+            let symbols = files
+                .iter()
+                .flat_map(|f| &f.symbols)
+                .filter(|e| e.impact == impact)
+                .count();
+            let file_count = files
+                .iter()
+                .filter(|f| f.symbols.iter().any(|e| e.impact == impact))
+                .count();
+            (symbols, file_count)
+        };
+        let (changed_count, changed_files) = count_of(ImpactKind::Changed);
+        let (impacted_count, impacted_files) = count_of(ImpactKind::Impacted);
+
+        let plural = |n: usize, word: &str| {
+            if n == 1 {
+                format!("{n} {word}")
+            } else {
+                format!("{n} {word}s")
+            }
+        };
+        let summary = format!(
+            "{} in {} {} {} in {}",
+            plural(changed_count, "changed symbol"),
+            plural(changed_files, "file"),
+            if changed_count == 1 {
+                "affects"
+            } else {
+                "affect"
+            },
+            plural(impacted_count, "symbol"),
+            plural(impacted_files, "file"),
+        );
+
+        Self {
+            summary,
+            depth,
+            changed_count,
+            impacted_count,
+            files,
+            unindexed_files,
+        }
+    }
+}
+
+impl fmt::Display for DiffImpact {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (depth {})", self.summary, self.depth)?;
+        for file in &self.files {
+            write!(f, "\n\n{}", file.file_path)?;
+            for entry in &file.symbols {
+                let label = match entry.impact {
+                    ImpactKind::Changed => "changed ",
+                    ImpactKind::Impacted => "impacted",
+                };
+                write!(
+                    f,
+                    "\n  {label} {:?} {} L{}-{} [symbol_id:{}]",
+                    entry.kind, entry.name, entry.start_line, entry.end_line, entry.symbol_id
+                )?;
+                if !entry.via.is_empty() {
+                    write!(f, " (via {})", entry.via.join(", "))?;
+                }
+            }
+        }
+        if !self.unindexed_files.is_empty() {
+            write!(f, "\n\nNot indexed: {}", self.unindexed_files.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_unified_diff() {
+        let diff = "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -2,3 +2,4 @@ pub fn add(a: i32, b: i32) -> i32 {
+     let x = 1;
+-    a + b
++    let y = 2;
++    a + b + y
+ }
+@@ -20,3 +21,2 @@ impl Calc {
+ fn keep() {}
+-fn removed() {}
+ fn tail() {}
+diff --git a/old.py b/old.py
+deleted file mode 100644
+--- a/old.py
++++ /dev/null
+@@ -1,2 +0,0 @@
+-def gone():
+-    pass
+diff --git a/logo.png b/logo.png
+Binary files a/logo.png and b/logo.png differ
+";
+        let files = parse_unified_diff(diff);
+        assert_eq!(
+            files,
+            vec![
+                FileDiff {
+                    path: "src/lib.rs".to_string(),
+                    deleted: false,
+                    lines: vec![3, 4, 22],
+                },
+                FileDiff {
+                    path: "old.py".to_string(),
+                    deleted: true,
+                    lines: vec![1],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hunk_header_without_counts() {
+        assert_eq!(hunk_header("@@ -5 +5 @@"), Some((1, 5, 1)));
+        assert_eq!(hunk_header("@@ -0,0 +1,3 @@"), Some((0, 1, 3)));
+        assert_eq!(hunk_header("not a hunk"), None);
+    }
+
+    #[test]
+    fn test_report_summary_and_order() {
+        let entry = |impact, id, line| ImpactEntry {
+            impact,
+            symbol_id: id,
+            name: format!("s{id}"),
+            kind: SymbolKind::Function,
+            start_line: line,
+            end_line: line + 2,
+            via: Vec::new(),
+        };
+        let report = DiffImpact::new(
+            2,
+            vec![
+                FileImpact {
+                    file_path: "src/b.rs".to_string(),
+                    symbols: vec![entry(ImpactKind::Impacted, 3, 1)],
+                },
+                FileImpact {
+                    file_path: "src/a.rs".to_string(),
+                    symbols: vec![
+                        entry(ImpactKind::Impacted, 2, 1),
+                        entry(ImpactKind::Changed, 1, 10),
+                    ],
+                },
+                FileImpact {
+                    file_path: "src/empty.rs".to_string(),
+                    symbols: Vec::new(),
+                },
+            ],
+            Vec::new(),
+        );
+
+        assert_eq!(
+            report.summary,
+            "1 changed symbol in 1 file affects 2 symbols in 2 files"
+        );
+        assert_eq!(report.files.len(), 2);
+        assert_eq!(report.files[0].file_path, "src/a.rs");
+        assert_eq!(report.files[0].symbols[0].impact, ImpactKind::Changed);
+    }
+}
@@ -19,9 +19,19 @@
 //! ```
 
 pub mod context;
-
+pub mod impact;
+pub mod outline;
+pub mod position;
+pub mod routes;
+pub mod source;
+pub mod test_map;
+pub mod trace;
+
+use crate::parsing::annotation::Annotation;
 use crate::parsing::registry::LanguageId;
-use crate::types::{CompactString, FileId, Range, SymbolId, SymbolKind, compact_string};
+use crate::types::{
+    CompactString, FileId, Range, StableSymbolId, SymbolId, SymbolKind, compact_string,
+};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
@@ -92,6 +102,15 @@ pub struct Symbol {
     /// This field enables language-specific filtering in searches.
     /// It's Optional for backward compatibility - existing indexes will have None.
     pub language_id: Option<LanguageId>,
+    /// Whether this symbol is a test (e.g. `#[test]`, `test_*`, `@Test`, Jest `it`)
+    #[serde(default)]
+    pub is_test: bool,
+    /// Attributes, decorators and annotations (e.g. `#[derive(Debug)]`, `@app.route("/")`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub annotations: Vec<Annotation>,
+    /// Identifier derived from the definition, unchanged by a reindex
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stable_id: Option<StableSymbolId>,
 }
 
 #[repr(C, align(32))]
@@ -130,6 +149,9 @@ impl Symbol {
             visibility: Visibility::Private,
             scope_context: None, // Default to None for backward compatibility
             language_id: None,   // Default to None for backward compatibility
+            is_test: false,
+            annotations: Vec::new(),
+            stable_id: None,
         }
     }
 
@@ -343,6 +365,7 @@ impl CompactSymbol {
             11 => SymbolKind::Parameter,
             12 => SymbolKind::TypeAlias,
             13 => SymbolKind::Macro,
+            14 => SymbolKind::Route,
             _ => return None,
         };
 
@@ -359,6 +382,9 @@ impl CompactSymbol {
             visibility: Visibility::Private,
             scope_context: None, // CompactSymbol doesn't store scope info yet
             language_id: None,   // CompactSymbol doesn't store language info yet
+            is_test: false,
+            annotations: Vec::new(),
+            stable_id: None,
         })
     }
 }
@@ -463,6 +489,7 @@ mod tests {
             SymbolKind::Parameter,
             SymbolKind::TypeAlias,
             SymbolKind::Macro,
+            SymbolKind::Route,
         ];
 
         let mut string_table = StringTable::new();
@@ -0,0 +1,346 @@
+//! Nested symbol outline for a single file
+//!
+//! Builds a tree (modules > classes > methods > fields) from the flat list of
+//! symbols stored for a file. A symbol's parent is resolved from, in order:
+//!
+//! 1. an explicit `Defines` relationship (e.g. a Rust `impl` method defined by
+//!    its struct),
+//! 2. the `parent_name` recorded in [`ScopeContext::Local`],
+//! 3. the innermost symbol whose range encloses it.
+//!
+//! Parameters and local variables are left out; they are noise in an outline.
+
+use crate::display::tables::TableBuilder;
+use crate::types::Range;
+use crate::{Symbol, SymbolId, SymbolKind, Visibility};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+
+use super::ScopeContext;
+
+/// A symbol and its nested children
+#[derive(Debug, Clone, Serialize)]
+pub struct OutlineNode {
+    pub symbol_id: u32,
+    pub name: String,
+    pub kind: SymbolKind,
+    pub range: Range,
+    pub visibility: Visibility,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<OutlineNode>,
+}
+
+/// Outline of one indexed file
+#[derive(Debug, Clone, Serialize)]
+pub struct FileOutline {
+    pub file_path: String,
+    pub file_id: u32,
+    pub symbol_count: usize,
+    pub symbols: Vec<OutlineNode>,
+}
+
+/// Whether a symbol belongs in an outline
+pub(crate) fn is_outline_symbol(symbol: &Symbol) -> bool {
+    match &symbol.scope_context {
+        Some(ScopeContext::Parameter) => false,
+        Some(ScopeContext::Local { .. }) => !matches!(
+            symbol.kind,
+            SymbolKind::Variable | SymbolKind::Constant | SymbolKind::Parameter
+        ),
+        _ => symbol.kind != SymbolKind::Parameter,
+    }
+}
+
+fn encloses(outer: &Range, inner: &Range) -> bool {
+    let starts_before =
+        (outer.start_line, outer.start_column) <= (inner.start_line, inner.start_column);
+    let ends_after = (outer.end_line, outer.end_column) >= (inner.end_line, inner.end_column);
+    starts_before && ends_after && outer != inner
+}
+
+/// Build the outline tree for the symbols of one file.
+///
+/// `defined_by` maps a symbol to the symbol that defines it (from `Defines`
+/// relationships). Entries pointing outside `symbols` are ignored.
+pub fn build_outline(
+    file_path: &str,
+    file_id: u32,
+    mut symbols: Vec<Symbol>,
+    defined_by: &HashMap<SymbolId, SymbolId>,
+) -> FileOutline {
+    symbols.retain(is_outline_symbol);
+    // Source order; enclosing symbols sort before the symbols they contain
+    symbols.sort_by(|a, b| {
+        (a.range.start_line, a.range.start_column)
+            .cmp(&(b.range.start_line, b.range.start_column))
+            .then(
+                (b.range.end_line, b.range.end_column).cmp(&(a.range.end_line, a.range.end_column)),
+            )
+    });
+
+    let index_of: HashMap<SymbolId, usize> =
+        symbols.iter().enumerate().map(|(i, s)| (s.id, i)).collect();
+
+    let parents: Vec<Option<usize>> = symbols
+        .iter()
+        .enumerate()
+        .map(|(i, symbol)| {
+            if let Some(parent) = defined_by
+                .get(&symbol.id)
+                .and_then(|id| index_of.get(id))
+                .filter(|&&p| p != i)
+            {
+                return Some(*parent);
+            }
+
+            // Innermost enclosing symbols first
+            let mut enclosing = (0..i)
+                .rev()
+                .filter(|&j| encloses(&symbols[j].range, &symbol.range));
+
+            match &symbol.scope_context {
+                Some(ScopeContext::Local {
+                    parent_name: Some(parent_name),
+                    ..
+                }) => {
+                    let mut candidates = enclosing.clone();
+                    candidates
+                        .find(|&j| symbols[j].name == *parent_name)
+                        .or_else(|| enclosing.next())
+                }
+                _ => enclosing.next(),
+            }
+        })
+        .collect();
+
+    // Symbols on a cycle of relationships become roots so every node is reachable
+    let parents: Vec<Option<usize>> = (0..symbols.len())
+        .map(|i| {
+            let mut seen = vec![i];
+            let mut current = parents[i];
+            while let Some(p) = current {
+                if p == i {
+                    return None;
+                }
+                if seen.contains(&p) {
+                    break;
+                }
+                seen.push(p);
+                current = parents[p];
+            }
+            parents[i]
+        })
+        .collect();
+
+    let mut children: HashMap<usize, Vec<usize>> = HashMap::new();
+    let mut roots = Vec::new();
+    for (i, parent) in parents.iter().enumerate() {
+        match parent {
+            Some(p) => children.entry(*p).or_default().push(i),
+            None => roots.push(i),
+        }
+    }
+
+    fn build_node(
+        i: usize,
+        symbols: &[Symbol],
+        children: &HashMap<usize, Vec<usize>>,
+    ) -> OutlineNode {
+        let symbol = &symbols[i];
+        OutlineNode {
+            symbol_id: symbol.id.value(),
+            name: symbol.name.to_string(),
+            kind: symbol.kind,
+            range: symbol.range,
+            visibility: symbol.visibility,
+            signature: symbol.signature.as_deref().map(str::to_string),
+            children: children
+                .get(&i)
+                .map(|c| {
+                    c.iter()
+                        .map(|&j| build_node(j, symbols, children))
+                        .collect()
+                })
+                .unwrap_or_default(),
+        }
+    }
+
+    FileOutline {
+        file_path: file_path.to_string(),
+        file_id,
+        symbol_count: symbols.len(),
+        symbols: roots
+            .into_iter()
+            .map(|i| build_node(i, &symbols, &children))
+            .collect(),
+    }
+}
+
+impl FileOutline {
+    /// Visit every node depth-first with its tree-drawing prefix
+    fn walk<'a>(&'a self, mut visit: impl FnMut(String, &'a OutlineNode)) {
+        fn walk_nodes<'a>(
+            nodes: &'a [OutlineNode],
+            indent: &str,
+            visit: &mut impl FnMut(String, &'a OutlineNode),
+        ) {
+            for (i, node) in nodes.iter().enumerate() {
+                let last = i + 1 == nodes.len();
+                let branch = if last { "└── " } else { "├── " };
+                visit(format!("{indent}{branch}"), node);
+                let child_indent = format!("{indent}{}", if last { "    " } else { "│   " });
+                walk_nodes(&node.children, &child_indent, visit);
+            }
+        }
+
+        walk_nodes(&self.symbols, "", &mut visit);
+    }
+
+    /// Compact plain-text tree, one symbol per line
+    pub fn format_tree(&self) -> String {
+        let mut output = format!("{} ({} symbols)\n", self.file_path, self.symbol_count);
+        self.walk(|prefix, node| {
+            output.push_str(&format!(
+                "{prefix}{:?} {} [{}] L{}-{} [symbol_id:{}]\n",
+                node.kind,
+                node.name,
+                format_visibility(node.visibility),
+                node.range.start_line + 1,
+                node.range.end_line + 1,
+                node.symbol_id
+            ));
+        });
+        output
+    }
+}
+
+fn format_visibility(visibility: Visibility) -> &'static str {
+    match visibility {
+        Visibility::Public => "pub",
+        Visibility::Crate => "crate",
+        Visibility::Module => "module",
+        Visibility::Private => "private",
+    }
+}
+
+impl fmt::Display for FileOutline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut table = TableBuilder::new().set_headers(vec![
+            "Symbol",
+            "Kind",
+            "Lines",
+            "Visibility",
+            "Signature",
+            "ID",
+        ]);
+
+        let mut rows = Vec::new();
+        self.walk(|prefix, node| {
+            let signature = node
+                .signature
+                .as_deref()
+                .and_then(|s| s.lines().next())
+                .unwrap_or_default()
+                .to_string();
+            rows.push(vec![
+                format!("{prefix}{}", node.name),
+                format!("{:?}", node.kind),
+                format!("{}-{}", node.range.start_line + 1, node.range.end_line + 1),
+                format_visibility(node.visibility).to_string(),
+                signature,
+                node.symbol_id.to_string(),
+            ]);
+        });
+        for row in rows {
+            table = table.add_row(row);
+        }
+
+        writeln!(f, "{} ({} symbols)", self.file_path, self.symbol_count)?;
+        write!(f, "{}", table.build())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FileId;
+
+    fn symbol(id: u32, name: &str, kind: SymbolKind, range: Range) -> Symbol {
+        Symbol::new(
+            SymbolId::new(id).unwrap(),
+            name,
+            kind,
+            FileId::new(1).unwrap(),
+            range,
+        )
+    }
+
+    #[test]
+    fn test_nesting_by_range() {
+        let symbols = vec![
+            symbol(3, "method", SymbolKind::Method, Range::new(2, 4, 4, 5))
+                .with_scope(ScopeContext::ClassMember),
+            symbol(1, "Parser", SymbolKind::Class, Range::new(0, 0, 10, 1)),
+            symbol(2, "field", SymbolKind::Field, Range::new(1, 4, 1, 20))
+                .with_scope(ScopeContext::ClassMember),
+            symbol(4, "helper", SymbolKind::Function, Range::new(12, 0, 14, 1)),
+        ];
+
+        let outline = build_outline("src/parser.py", 1, symbols, &HashMap::new());
+        assert_eq!(outline.symbol_count, 4);
+        assert_eq!(outline.symbols.len(), 2);
+
+        let class = &outline.symbols[0];
+        assert_eq!(class.name, "Parser");
+        let children: Vec<_> = class.children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(children, vec!["field", "method"]);
+        assert_eq!(outline.symbols[1].name, "helper");
+    }
+
+    #[test]
+    fn test_defines_relationship_and_locals() {
+        let symbols = vec![
+            symbol(1, "Calc", SymbolKind::Struct, Range::new(0, 0, 0, 16)),
+            symbol(2, "double", SymbolKind::Method, Range::new(3, 4, 6, 5))
+                .with_scope(ScopeContext::ClassMember),
+            symbol(3, "tmp", SymbolKind::Variable, Range::new(4, 8, 4, 20)).with_scope(
+                ScopeContext::Local {
+                    hoisted: false,
+                    parent_name: Some("double".into()),
+                    parent_kind: Some(SymbolKind::Method),
+                },
+            ),
+            symbol(4, "x", SymbolKind::Parameter, Range::new(3, 20, 3, 26))
+                .with_scope(ScopeContext::Parameter),
+        ];
+        let defined_by = HashMap::from([(SymbolId(2), SymbolId(1))]);
+
+        let outline = build_outline("src/lib.rs", 1, symbols, &defined_by);
+        assert_eq!(outline.symbol_count, 2);
+        assert_eq!(outline.symbols.len(), 1);
+        assert_eq!(outline.symbols[0].children[0].name, "double");
+        assert!(outline.symbols[0].children[0].children.is_empty());
+    }
+
+    #[test]
+    fn test_tree_rendering() {
+        let symbols = vec![
+            symbol(1, "outer", SymbolKind::Module, Range::new(0, 0, 9, 1)),
+            symbol(2, "first", SymbolKind::Function, Range::new(1, 4, 2, 5)),
+            symbol(3, "second", SymbolKind::Function, Range::new(3, 4, 4, 5)),
+        ];
+        let outline = build_outline("src/lib.rs", 1, symbols, &HashMap::new());
+
+        let tree = outline.format_tree();
+        assert!(tree.contains("└── Module outer"));
+        assert!(tree.contains("    ├── Function first"));
+        assert!(tree.contains("    └── Function second"));
+
+        let table = outline.to_string();
+        assert!(table.contains("Signature"));
+        assert!(table.contains("├── first"));
+    }
+}
@@ -0,0 +1,243 @@
+//! Symbol lookup by source position
+//!
+//! Diagnostics, stack traces and review comments point at `file:line[:col]`.
+//! These helpers parse such positions and pick the innermost indexed symbol
+//! whose [`Range`] contains them.
+
+use crate::relationship::RelationKind;
+use crate::types::Range;
+use crate::{Symbol, SymbolKind};
+use serde::Serialize;
+use std::fmt;
+
+/// A `file:line[:column]` position as written by compilers and editors
+///
+/// `line` and `column` are one-based, matching what tools print.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SourcePosition {
+    pub file_path: String,
+    pub line: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<u16>,
+}
+
+impl SourcePosition {
+    /// Parse `path:line` or `path:line:column`
+    ///
+    /// Numbers are taken from the right so paths containing `:` (such as
+    /// Windows drive letters) are kept intact.
+    pub fn parse(input: &str) -> Option<Self> {
+        let (rest, last) = input.rsplit_once(':')?;
+        let last: u32 = last.trim().parse().ok()?;
+
+        // Either "path:line:column" or "path:line"
+        let with_column = rest
+            .rsplit_once(':')
+            .filter(|(path, _)| !path.is_empty())
+            .and_then(|(path, line)| Some((path, line.trim().parse::<u32>().ok()?)));
+        if let Some((path, line)) = with_column {
+            return Some(Self {
+                file_path: path.to_string(),
+                line,
+                column: Some(u16::try_from(last).ok()?),
+            });
+        }
+
+        (!rest.is_empty()).then(|| Self {
+            file_path: rest.to_string(),
+            line: last,
+            column: None,
+        })
+    }
+
+    /// Zero-based line as stored in [`Range`]
+    pub fn zero_based_line(&self) -> u32 {
+        self.line.saturating_sub(1)
+    }
+
+    /// Zero-based column as stored in [`Range`]
+    pub fn zero_based_column(&self) -> Option<u16> {
+        self.column.map(|c| c.saturating_sub(1))
+    }
+}
+
+impl fmt::Display for SourcePosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.column {
+            Some(column) => write!(f, "{}:{}:{}", self.file_path, self.line, column),
+            None => write!(f, "{}:{}", self.file_path, self.line),
+        }
+    }
+}
+
+/// Lines spanned by a range, then columns for single-line ranges
+fn range_size(range: &Range) -> (u32, u16) {
+    let lines = range.end_line.saturating_sub(range.start_line);
+    let columns = if lines == 0 {
+        range.end_column.saturating_sub(range.start_column)
+    } else {
+        0
+    };
+    (lines, columns)
+}
+
+/// Find the innermost symbol containing a zero-based position.
+///
+/// Without a column the whole line is matched and parameters and local
+/// variables are skipped, so a line lookup lands on the enclosing definition.
+pub fn innermost_symbol_at(symbols: &[Symbol], line: u32, column: Option<u16>) -> Option<&Symbol> {
+    symbols
+        .iter()
+        .filter(|s| match column {
+            Some(column) => s.range.contains(line, column),
+            None => {
+                s.range.start_line <= line
+                    && line <= s.range.end_line
+                    && super::outline::is_outline_symbol(s)
+            }
+        })
+        // Prefer the smallest range; modules span whole files and lose ties
+        .min_by_key(|s| (range_size(&s.range), s.kind == SymbolKind::Module))
+}
+
+/// Relationship recorded at a position (e.g. the call on a diagnostic line)
+#[derive(Debug, Clone, Serialize)]
+pub struct PositionEdge {
+    pub kind: RelationKind,
+    pub target_id: u32,
+    pub target_name: String,
+    pub target_kind: SymbolKind,
+    pub target_file_path: String,
+    /// One-based line of the edge
+    pub line: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<u16>,
+}
+
+/// Result of a position lookup
+#[derive(Debug, Clone, Serialize)]
+pub struct SymbolAtPosition {
+    pub position: SourcePosition,
+    pub symbol: Symbol,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub edges: Vec<PositionEdge>,
+}
+
+impl fmt::Display for SymbolAtPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {:?} {} at {}:{}-{} [symbol_id:{}]",
+            self.position,
+            self.symbol.kind,
+            self.symbol.name,
+            self.symbol.file_path,
+            self.symbol.range.start_line + 1,
+            self.symbol.range.end_line + 1,
+            self.symbol.id.value()
+        )?;
+        if let Some(sig) = &self.symbol.signature {
+            write!(f, "\n  Signature: {sig}")?;
+        }
+        for edge in &self.edges {
+            write!(
+                f,
+                "\n  {:?} -> {:?} {} at {} [symbol_id:{}]",
+                edge.kind,
+                edge.target_kind,
+                edge.target_name,
+                edge.target_file_path,
+                edge.target_id
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FileId, ScopeContext, SymbolId};
+
+    fn symbol(id: u32, name: &str, kind: SymbolKind, range: Range) -> Symbol {
+        Symbol::new(
+            SymbolId::new(id).unwrap(),
+            name,
+            kind,
+            FileId::new(1).unwrap(),
+            range,
+        )
+    }
+
+    #[test]
+    fn test_parse_position() {
+        assert_eq!(
+            SourcePosition::parse("src/foo.rs:120"),
+            Some(SourcePosition {
+                file_path: "src/foo.rs".to_string(),
+                line: 120,
+                column: None,
+            })
+        );
+        assert_eq!(
+            SourcePosition::parse("src/foo.rs:120:8"),
+            Some(SourcePosition {
+                file_path: "src/foo.rs".to_string(),
+                line: 120,
+                column: Some(8),
+            })
+        );
+        assert_eq!(
+            SourcePosition::parse(r"C:\src\foo.rs:3:1").map(|p| p.file_path),
+            Some(r"C:\src\foo.rs".to_string())
+        );
+        assert!(SourcePosition::parse("src/foo.rs").is_none());
+        assert!(SourcePosition::parse(":12").is_none());
+    }
+
+    #[test]
+    fn test_innermost_symbol() {
+        let symbols = vec![
+            symbol(1, "module", SymbolKind::Module, Range::new(0, 0, 50, 0)),
+            symbol(2, "Parser", SymbolKind::Class, Range::new(2, 0, 20, 1)),
+            symbol(3, "parse", SymbolKind::Method, Range::new(5, 4, 10, 5)),
+            symbol(4, "input", SymbolKind::Parameter, Range::new(5, 13, 5, 24))
+                .with_scope(ScopeContext::Parameter),
+        ];
+
+        let found = innermost_symbol_at(&symbols, 7, None).unwrap();
+        assert_eq!(found.name.as_ref(), "parse");
+
+        // A line lookup skips the parameter, a column lookup finds it
+        assert_eq!(
+            innermost_symbol_at(&symbols, 5, None)
+                .unwrap()
+                .name
+                .as_ref(),
+            "parse"
+        );
+        assert_eq!(
+            innermost_symbol_at(&symbols, 5, Some(15))
+                .unwrap()
+                .name
+                .as_ref(),
+            "input"
+        );
+
+        assert_eq!(
+            innermost_symbol_at(&symbols, 15, None)
+                .unwrap()
+                .name
+                .as_ref(),
+            "Parser"
+        );
+        assert_eq!(
+            innermost_symbol_at(&symbols, 30, None)
+                .unwrap()
+                .name
+                .as_ref(),
+            "module"
+        );
+        assert!(innermost_symbol_at(&symbols, 60, None).is_none());
+    }
+}
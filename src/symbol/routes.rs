@@ -0,0 +1,174 @@
+//! HTTP routes and the symbols that handle them
+//!
+//! Route symbols (`SymbolKind::Route`, named `METHOD /path`) are created
+//! during indexing by the framework extractors in [`crate::parsing::route`]
+//! and linked to their handlers with `RelationKind::Routes`. This module
+//! turns them into a route table that can be filtered by method and path,
+//! answering questions like "what handles POST /orders?".
+
+use crate::parsing::route::{ANY_METHOD, path_matches};
+use serde::Serialize;
+use std::fmt;
+
+/// Handler symbol a route dispatches to
+#[derive(Debug, Clone, Serialize)]
+pub struct RouteHandler {
+    pub symbol_id: u32,
+    pub name: String,
+    pub file_path: String,
+    /// One-based first line
+    pub line: u32,
+}
+
+/// One registered route
+#[derive(Debug, Clone, Serialize)]
+pub struct RouteInfo {
+    pub symbol_id: u32,
+    pub method: String,
+    pub path: String,
+    /// Handler as written at the registration; `None` for inline handlers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub handler: Option<String>,
+    pub framework: String,
+    pub file_path: String,
+    /// One-based line of the registration
+    pub line: u32,
+    /// Resolved handler symbol
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub handler_symbol: Option<RouteHandler>,
+}
+
+impl RouteInfo {
+    /// Whether the route accepts `method` (`ANY` routes accept every method)
+    pub fn matches_method(&self, method: &str) -> bool {
+        self.method == ANY_METHOD || self.method.eq_ignore_ascii_case(method)
+    }
+
+    /// Whether the route matches a path filter
+    ///
+    /// A filter starting with `/` is matched segment by segment against the
+    /// pattern, so `/orders/42` finds `/orders/{id}`; anything else is a
+    /// case-insensitive substring of the pattern.
+    pub fn matches_path(&self, filter: &str) -> bool {
+        if filter.starts_with('/') {
+            self.path.eq_ignore_ascii_case(filter) || path_matches(&self.path, filter)
+        } else {
+            self.path.to_lowercase().contains(&filter.to_lowercase())
+        }
+    }
+}
+
+/// Routes of the index, sorted by path then method
+#[derive(Debug, Clone, Serialize)]
+pub struct RouteTable {
+    /// One-line description
+    pub summary: String,
+    pub routes: Vec<RouteInfo>,
+}
+
+impl RouteTable {
+    pub fn new(mut routes: Vec<RouteInfo>) -> Self {
+        routes.sort_by(|a, b| {
+            a.path
+                .cmp(&b.path)
+                .then(a.method.cmp(&b.method))
+                .then(a.file_path.cmp(&b.file_path))
+                .then(a.line.cmp(&b.line))
+        });
+        let handled = routes.iter().filter(|r| r.handler_symbol.is_some()).count();
+        let summary = match routes.len() {
+            0 => "No routes found".to_string(),
+            1 => format!("1 route, {handled} with a resolved handler"),
+            n => format!("{n} routes, {handled} with a resolved handler"),
+        };
+        Self { summary, routes }
+    }
+}
+
+impl fmt::Display for RouteTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.summary)?;
+        let width = self
+            .routes
+            .iter()
+            .map(|r| r.method.len() + 1 + r.path.len())
+            .max()
+            .unwrap_or(0);
+        for route in &self.routes {
+            let name = format!("{} {}", route.method, route.path);
+            write!(f, "\n  {name:<width$}  -> ")?;
+            match (&route.handler_symbol, &route.handler) {
+                (Some(handler), _) => write!(
+                    f,
+                    "{} {}:{} [symbol_id:{}]",
+                    handler.name, handler.file_path, handler.line, handler.symbol_id
+                )?,
+                (None, Some(handler)) => write!(f, "{handler} (unresolved)")?,
+                (None, None) => write!(f, "<inline>")?,
+            }
+            write!(
+                f,
+                "  ({}, {}:{})",
+                route.framework, route.file_path, route.line
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route(id: u32, method: &str, path: &str, handler: Option<&str>) -> RouteInfo {
+        RouteInfo {
+            symbol_id: id,
+            method: method.to_string(),
+            path: path.to_string(),
+            handler: handler.map(str::to_string),
+            framework: "axum".to_string(),
+            file_path: "src/app.rs".to_string(),
+            line: id,
+            handler_symbol: handler.map(|name| RouteHandler {
+                symbol_id: id + 100,
+                name: name.to_string(),
+                file_path: "src/handlers.rs".to_string(),
+                line: 3,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_route_filters() {
+        let any = route(1, "ANY", "/orders/{id}", None);
+        assert!(any.matches_method("delete"));
+        assert!(any.matches_path("/orders/42"));
+        assert!(any.matches_path("ORDERS"));
+        assert!(!any.matches_path("/orders"));
+
+        let post = route(2, "POST", "/orders", Some("create"));
+        assert!(post.matches_method("post"));
+        assert!(!post.matches_method("GET"));
+    }
+
+    #[test]
+    fn test_table_order_and_display() {
+        let table = RouteTable::new(vec![
+            route(3, "POST", "/orders", Some("create")),
+            route(2, "GET", "/orders", None),
+            route(1, "GET", "/health", Some("health")),
+        ]);
+
+        assert_eq!(table.summary, "3 routes, 2 with a resolved handler");
+        let names: Vec<String> = table
+            .routes
+            .iter()
+            .map(|r| format!("{} {}", r.method, r.path))
+            .collect();
+        assert_eq!(names, vec!["GET /health", "GET /orders", "POST /orders"]);
+
+        let text = table.to_string();
+        assert!(text.contains("POST /orders  -> create src/handlers.rs:3 [symbol_id:103]"));
+        assert!(text.contains("GET /orders   -> <inline>  (axum, src/app.rs:2)"));
+    }
+}
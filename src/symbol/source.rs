@@ -0,0 +1,336 @@
+//! Source text extraction for indexed symbols
+//!
+//! Symbols store their location as a [`Range`] against a path that is relative
+//! to the workspace root. These helpers resolve that path and slice the exact
+//! source text back out of the file on disk.
+
+use crate::types::Range;
+use crate::{Symbol, SymbolKind};
+use serde::Serialize;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Options controlling what [`symbol_source`] returns
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SourceOptions {
+    /// Number of whole lines to include before and after the symbol
+    pub context_lines: usize,
+    /// Include the symbol's doc comment
+    pub include_doc: bool,
+}
+
+/// Exact source text of a symbol with optional surrounding context
+#[derive(Debug, Clone, Serialize)]
+pub struct SymbolSource {
+    pub symbol_id: u32,
+    pub name: String,
+    pub kind: SymbolKind,
+    pub file_path: String,
+    pub range: Range,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doc_comment: Option<String>,
+    pub source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context_before: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context_after: Option<String>,
+}
+
+/// Resolve a stored symbol path to a readable path on disk.
+///
+/// Indexed paths are stored relative to the workspace root. Absolute paths
+/// (and relative paths when no workspace root is known) are returned unchanged.
+pub fn resolve_source_path(file_path: &str, workspace_root: Option<&Path>) -> PathBuf {
+    let path = Path::new(file_path);
+    match workspace_root {
+        Some(root) if path.is_relative() => root.join(path),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Extract the text covered by `range` from `content`.
+///
+/// Lines are zero-based; columns are byte offsets as reported by tree-sitter.
+/// Columns that fall outside a line or inside a multi-byte character are
+/// clamped so the result is always valid UTF-8.
+pub fn extract_range(content: &str, range: &Range) -> String {
+    let start_line = range.start_line as usize;
+    let end_line = range.end_line as usize;
+
+    let mut output = String::new();
+    for (idx, line) in content.lines().enumerate().skip(start_line) {
+        if idx > end_line {
+            break;
+        }
+
+        let from = if idx == start_line {
+            clamp_to_char_boundary(line, range.start_column as usize)
+        } else {
+            0
+        };
+        let to = if idx == end_line {
+            clamp_to_char_boundary(line, range.end_column as usize)
+        } else {
+            line.len()
+        };
+
+        if idx > start_line {
+            output.push('\n');
+        }
+        if from < to {
+            output.push_str(&line[from..to]);
+        }
+    }
+
+    output
+}
+
+/// Read the source text of `symbol` from disk.
+pub fn read_symbol_source(
+    symbol: &Symbol,
+    workspace_root: Option<&Path>,
+) -> std::io::Result<String> {
+    let path = resolve_source_path(&symbol.file_path, workspace_root);
+    let content = std::fs::read_to_string(path)?;
+    Ok(extract_range(&content, &symbol.range))
+}
+
+/// Build a [`SymbolSource`] for `symbol` from the content of its file.
+pub fn symbol_source(symbol: &Symbol, content: &str, options: SourceOptions) -> SymbolSource {
+    let start_line = symbol.range.start_line as usize;
+    let end_line = symbol.range.end_line as usize;
+
+    let (context_before, context_after) = if options.context_lines > 0 {
+        let lines: Vec<&str> = content.lines().collect();
+        let before_start = start_line.saturating_sub(options.context_lines);
+        let after_end = (end_line + 1 + options.context_lines).min(lines.len());
+        let before = lines
+            .get(before_start..start_line.min(lines.len()))
+            .filter(|l| !l.is_empty())
+            .map(|l| l.join("\n"));
+        let after = lines
+            .get((end_line + 1).min(after_end)..after_end)
+            .filter(|l| !l.is_empty())
+            .map(|l| l.join("\n"));
+        (before, after)
+    } else {
+        (None, None)
+    };
+
+    SymbolSource {
+        symbol_id: symbol.id.value(),
+        name: symbol.name.to_string(),
+        kind: symbol.kind,
+        file_path: symbol.file_path.to_string(),
+        range: symbol.range,
+        doc_comment: options
+            .include_doc
+            .then(|| symbol.doc_comment.as_deref().map(str::to_string))
+            .flatten(),
+        source: extract_range(content, &symbol.range),
+        context_before,
+        context_after,
+    }
+}
+
+/// Read the source of `symbol` from disk and build a [`SymbolSource`].
+pub fn read_source(
+    symbol: &Symbol,
+    workspace_root: Option<&Path>,
+    options: SourceOptions,
+) -> std::io::Result<SymbolSource> {
+    let path = resolve_source_path(&symbol.file_path, workspace_root);
+    let content = std::fs::read_to_string(path)?;
+    Ok(symbol_source(symbol, &content, options))
+}
+
+impl fmt::Display for SymbolSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} {} at {}:{}-{} [symbol_id:{}]",
+            self.kind,
+            self.name,
+            self.file_path,
+            self.range.start_line + 1,
+            self.range.end_line + 1,
+            self.symbol_id
+        )?;
+
+        if let Some(doc) = &self.doc_comment {
+            for line in doc.lines() {
+                write!(f, "\n  {line}")?;
+            }
+        }
+
+        // Number every line; the first source line is re-indented to its column
+        let before = context_lines(self.context_before.as_deref());
+        let after = context_lines(self.context_after.as_deref());
+        let first_line = self.range.start_line as usize + 1 - before.len();
+        let indent = " ".repeat(self.range.start_column as usize);
+        let source = format!("{indent}{}", self.source);
+
+        let last_line = self.range.end_line as usize + 1 + after.len();
+        let width = last_line.to_string().len();
+        for (offset, line) in before
+            .iter()
+            .copied()
+            .chain(source.split('\n'))
+            .chain(after.iter().copied())
+            .enumerate()
+        {
+            write!(f, "\n{:>width$} | {line}", first_line + offset)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Lines of a context block, keeping a blank line next to the symbol that
+/// `str::lines` would drop
+fn context_lines(context: Option<&str>) -> Vec<&str> {
+    context
+        .map(|context| context.split('\n').collect())
+        .unwrap_or_default()
+}
+
+fn clamp_to_char_boundary(line: &str, column: usize) -> usize {
+    let mut column = column.min(line.len());
+    while !line.is_char_boundary(column) {
+        column -= 1;
+    }
+    column
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "fn outer() {\n    fn inner() {}\n}\n";
+
+    #[test]
+    fn test_extract_single_line() {
+        let range = Range::new(1, 4, 1, 17);
+        assert_eq!(extract_range(SOURCE, &range), "fn inner() {}");
+    }
+
+    #[test]
+    fn test_extract_multi_line() {
+        let range = Range::new(0, 0, 2, 1);
+        assert_eq!(
+            extract_range(SOURCE, &range),
+            "fn outer() {\n    fn inner() {}\n}"
+        );
+    }
+
+    #[test]
+    fn test_extract_clamps_columns() {
+        let range = Range::new(0, 3, 0, 200);
+        assert_eq!(extract_range("fn é() {}", &range), "é() {}");
+
+        // Column inside the two-byte 'é' is moved back to its start
+        let range = Range::new(0, 4, 0, 200);
+        assert_eq!(extract_range("fn é() {}", &range), "é() {}");
+    }
+
+    #[test]
+    fn test_symbol_source_with_context() {
+        use crate::{FileId, SymbolId};
+
+        let content = "// header\nfn outer() {\n    fn inner() {}\n}\n// footer\n";
+        let symbol = Symbol::new(
+            SymbolId::new(7).unwrap(),
+            "inner",
+            SymbolKind::Function,
+            FileId::new(1).unwrap(),
+            Range::new(2, 4, 2, 17),
+        )
+        .with_doc("Inner helper");
+
+        let plain = symbol_source(&symbol, content, SourceOptions::default());
+        assert_eq!(plain.source, "fn inner() {}");
+        assert!(plain.doc_comment.is_none());
+        assert!(plain.context_before.is_none());
+        assert!(plain.context_after.is_none());
+
+        let options = SourceOptions {
+            context_lines: 1,
+            include_doc: true,
+        };
+        let source = symbol_source(&symbol, content, options);
+        assert_eq!(source.doc_comment.as_deref(), Some("Inner helper"));
+        assert_eq!(source.context_before.as_deref(), Some("fn outer() {"));
+        assert_eq!(source.context_after.as_deref(), Some("}"));
+
+        let text = source.to_string();
+        assert!(text.contains("2 | fn outer() {"));
+        assert!(text.contains("3 |     fn inner() {}"));
+        assert!(text.contains("4 | }"));
+    }
+
+    #[test]
+    fn test_symbol_source_numbers_blank_context_lines() {
+        use crate::{FileId, SymbolId};
+
+        let content = "use std::fmt;\n\nfn main() {\n}\n\n// end\n";
+        let symbol = Symbol::new(
+            SymbolId::new(2).unwrap(),
+            "main",
+            SymbolKind::Function,
+            FileId::new(1).unwrap(),
+            Range::new(2, 0, 3, 1),
+        );
+        let options = SourceOptions {
+            context_lines: 2,
+            include_doc: false,
+        };
+        let source = symbol_source(&symbol, content, options);
+        assert_eq!(source.context_before.as_deref(), Some("use std::fmt;\n"));
+        assert_eq!(source.context_after.as_deref(), Some("\n// end"));
+
+        let text = source.to_string();
+        assert!(text.contains("1 | use std::fmt;"));
+        assert!(text.contains("\n2 | \n"));
+        assert!(text.contains("3 | fn main() {"));
+        assert!(text.contains("4 | }"));
+        assert!(text.contains("6 | // end"));
+    }
+
+    #[test]
+    fn test_symbol_source_context_at_file_edges() {
+        use crate::{FileId, SymbolId};
+
+        let symbol = Symbol::new(
+            SymbolId::new(1).unwrap(),
+            "main",
+            SymbolKind::Function,
+            FileId::new(1).unwrap(),
+            Range::new(0, 0, 0, 12),
+        );
+        let options = SourceOptions {
+            context_lines: 5,
+            include_doc: false,
+        };
+        let source = symbol_source(&symbol, "fn main() {}", options);
+        assert_eq!(source.source, "fn main() {}");
+        assert!(source.context_before.is_none());
+        assert!(source.context_after.is_none());
+    }
+
+    #[test]
+    fn test_resolve_source_path() {
+        let root = Path::new("/workspace");
+        assert_eq!(
+            resolve_source_path("src/lib.rs", Some(root)),
+            PathBuf::from("/workspace/src/lib.rs")
+        );
+        assert_eq!(
+            resolve_source_path("/abs/lib.rs", Some(root)),
+            PathBuf::from("/abs/lib.rs")
+        );
+        assert_eq!(
+            resolve_source_path("src/lib.rs", None),
+            PathBuf::from("src/lib.rs")
+        );
+    }
+}
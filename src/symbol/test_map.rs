@@ -0,0 +1,150 @@
+//! Tests that exercise a symbol
+//!
+//! Symbols flagged as tests during indexing (`#[test]`, `test_*`, `TestXxx`,
+//! `@Test`, Jest `it`/`test` blocks) are found by walking callers backwards
+//! from a symbol. The result lists each reachable test with the call chain
+//! that leads to the symbol, so only the relevant tests need to run after a
+//! change.
+
+use serde::Serialize;
+use std::fmt;
+
+/// A test that reaches the queried symbol through calls
+#[derive(Debug, Clone, Serialize)]
+pub struct TestEntry {
+    pub symbol_id: u32,
+    pub name: String,
+    pub file_path: String,
+    /// One-based first line
+    pub line: u32,
+    /// Number of calls between the test and the symbol; 0 for the symbol itself
+    pub depth: usize,
+    /// Functions between the test and the symbol, outermost first
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub via: Vec<String>,
+}
+
+/// Tests reachable from a symbol, grouped by file
+#[derive(Debug, Clone, Serialize)]
+pub struct TestsForSymbol {
+    /// One-line description
+    pub summary: String,
+    pub symbol_id: u32,
+    pub name: String,
+    pub file_path: String,
+    pub depth: usize,
+    pub tests: Vec<TestEntry>,
+    /// Distinct files containing the tests, for passing to a test runner
+    pub test_files: Vec<String>,
+}
+
+impl TestsForSymbol {
+    /// Assemble the report; tests are sorted by file and line
+    pub fn new(
+        symbol_id: u32,
+        name: String,
+        file_path: String,
+        depth: usize,
+        mut tests: Vec<TestEntry>,
+    ) -> Self {
+        tests.sort_by(|a, b| {
+            a.file_path
+                .cmp(&b.file_path)
+                .then(a.line.cmp(&b.line))
+                .then(a.symbol_id.cmp(&b.symbol_id))
+        });
+        let mut test_files: Vec<String> = tests.iter().map(|t| t.file_path.clone()).collect();
+        test_files.dedup();
+
+        let summary = match (tests.len(), test_files.len()) {
+            (0, _) => format!("No tests reach {name}"),
+            (1, _) => format!("1 test in 1 file reaches {name}"),
+            (n, 1) => format!("{n} tests in 1 file reach {name}"),
+            (n, files) => format!("{n} tests in {files} files reach {name}"),
+        };
+
+        Self {
+            summary,
+            symbol_id,
+            name,
+            file_path,
+            depth,
+            tests,
+            test_files,
+        }
+    }
+}
+
+impl fmt::Display for TestsForSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} [symbol_id:{}] (depth {})",
+            self.summary, self.symbol_id, self.depth
+        )?;
+        let mut current_file: Option<&str> = None;
+        for test in &self.tests {
+            if current_file != Some(test.file_path.as_str()) {
+                write!(f, "\n\n{}", test.file_path)?;
+                current_file = Some(&test.file_path);
+            }
+            write!(
+                f,
+                "\n  {} L{} [symbol_id:{}]",
+                test.name, test.line, test.symbol_id
+            )?;
+            if !test.via.is_empty() {
+                write!(f, " (via {})", test.via.join(" -> "))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: u32, file: &str, line: u32, via: &[&str]) -> TestEntry {
+        TestEntry {
+            symbol_id: id,
+            name: format!("test_{id}"),
+            file_path: file.to_string(),
+            line,
+            depth: via.len() + 1,
+            via: via.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_report_order_and_files() {
+        let report = TestsForSymbol::new(
+            1,
+            "add".to_string(),
+            "src/lib.rs".to_string(),
+            5,
+            vec![
+                entry(4, "tests/b.rs", 3, &[]),
+                entry(3, "tests/a.rs", 9, &["double"]),
+                entry(2, "tests/a.rs", 2, &[]),
+            ],
+        );
+
+        assert_eq!(report.summary, "3 tests in 2 files reach add");
+        assert_eq!(report.test_files, vec!["tests/a.rs", "tests/b.rs"]);
+        let ids: Vec<u32> = report.tests.iter().map(|t| t.symbol_id).collect();
+        assert_eq!(ids, vec![2, 3, 4]);
+        assert!(
+            report
+                .to_string()
+                .contains("test_3 L9 [symbol_id:3] (via double)")
+        );
+    }
+
+    #[test]
+    fn test_report_without_tests() {
+        let report = TestsForSymbol::new(1, "add".to_string(), "src/lib.rs".to_string(), 5, vec![]);
+        assert_eq!(report.summary, "No tests reach add");
+        assert!(report.test_files.is_empty());
+    }
+}
@@ -0,0 +1,641 @@
+//! Stack trace parsing and frame resolution
+//!
+//! Parses the stack traces developers paste into issues and chats:
+//!
+//! - Python tracebacks (`File "app/x.py", line 12, in handler`)
+//! - Rust panics and `RUST_BACKTRACE` output (`3: app::run` / `at ./src/main.rs:10:5`)
+//! - Go panics (`main.(*Server).Handle(...)` / `\t/src/app/server.go:42 +0x1d`)
+//! - Java and Kotlin exceptions (`at com.acme.Foo.bar(Foo.java:12)`)
+//! - Node.js stacks (`at Foo.bar (/app/src/foo.js:10:5)`)
+//!
+//! Frames keep the order they appear in the input. Each frame is later mapped
+//! to an indexed symbol by position (see [`super::position`]) or, when paths do
+//! not match the index, by its qualified function name.
+
+use crate::{Symbol, SymbolKind};
+use regex::Regex;
+use serde::Serialize;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use super::ScopeContext;
+
+static PYTHON_FRAME: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"^\s*File "(?P<file>[^"]+)", line (?P<line>\d+)(?:, in (?P<func>.+?))?\s*$"#)
+        .expect("Invalid regex")
+});
+static RUST_PANIC: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"panicked at (?:'.*', )?(?P<file>[^\s'"]+?):(?P<line>\d+):(?P<col>\d+)"#)
+        .expect("Invalid regex")
+});
+static RUST_FRAME: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*\d+:\s+(?:0x[0-9a-fA-F]+ - )?(?P<func>\S.*?)\s*$").expect("Invalid regex")
+});
+static RUST_LOCATION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*at (?P<file>.+?):(?P<line>\d+)(?::(?P<col>\d+))?\s*$").expect("Invalid regex")
+});
+static RUST_HASH: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"::h[0-9a-f]{16}$").expect("Invalid regex"));
+static RUST_TRAIT_IMPL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<(?P<ty>[^<>]+?) as [^<>]+>").expect("Invalid regex"));
+static JVM_FRAME: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*at (?P<func>[\w$.<>/@\-]+)\((?:(?P<file>[^():]+):(?P<line>\d+)|[^()]*)\)\s*$")
+        .expect("Invalid regex")
+});
+static NODE_FRAME: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*at (?:(?P<func>.+?) \()?(?P<file>[^()\s]+?):(?P<line>\d+):(?P<col>\d+)\)?\s*$")
+        .expect("Invalid regex")
+});
+static GO_FUNC: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:created by )?(?P<func>(?:[\w\-.~/]|\(\*?\w+\)\.)+)(?:\([^()]*\))?(?: in goroutine \d+)?\s*$")
+        .expect("Invalid regex")
+});
+static GO_LOCATION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s+(?P<file>\S+\.go):(?P<line>\d+)(?: \+0x[0-9a-f]+)?\s*$")
+        .expect("Invalid regex")
+});
+
+/// Stack trace dialect a frame was parsed from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TraceFormat {
+    Python,
+    Rust,
+    Go,
+    /// Java and Kotlin
+    Jvm,
+    Node,
+}
+
+impl fmt::Display for TraceFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            TraceFormat::Python => "python",
+            TraceFormat::Rust => "rust",
+            TraceFormat::Go => "go",
+            TraceFormat::Jvm => "jvm",
+            TraceFormat::Node => "node",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A single frame from a stack trace
+///
+/// `line` and `column` are one-based as printed by the runtime. For JVM frames
+/// the file path is rebuilt from the package (`com/acme/Foo.java`), since the
+/// trace only carries the file name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StackFrame {
+    pub index: usize,
+    pub format: TraceFormat,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub function: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<u16>,
+}
+
+impl StackFrame {
+    /// Qualified function name split into segments, innermost last.
+    ///
+    /// Compiler noise is removed: Rust symbol hashes and closures, Go receiver
+    /// parentheses and `funcN` closures, JVM lambdas, `$default` bridges and
+    /// constructors (`<init>` resolves to the class), Node `async`/`new`.
+    /// Anonymous frames such as Python `<module>` give no segments.
+    pub fn name_segments(&self) -> Vec<String> {
+        let Some(function) = self.function.as_deref().map(str::trim) else {
+            return Vec::new();
+        };
+
+        let segments: Vec<String> = match self.format {
+            TraceFormat::Python => vec![function.to_string()],
+            TraceFormat::Rust => {
+                let function = RUST_HASH.replace(function, "");
+                let function = RUST_TRAIT_IMPL.replace_all(&function, "$ty");
+                function
+                    .split("::")
+                    .map(|s| s.trim_matches(|c| c == '<' || c == '>' || c == '&'))
+                    .filter(|s| !s.is_empty() && !s.starts_with('{'))
+                    .map(str::to_string)
+                    .collect()
+            }
+            TraceFormat::Go => {
+                let function = function.rsplit('/').next().unwrap_or(function);
+                function
+                    .split('.')
+                    .map(|s| {
+                        s.trim_start_matches("(*")
+                            .trim_matches(|c| c == '(' || c == ')')
+                    })
+                    .filter(|s| !s.is_empty() && !is_go_closure(s))
+                    .map(str::to_string)
+                    .collect()
+            }
+            TraceFormat::Jvm => {
+                let function = function.rsplit('/').next().unwrap_or(function);
+                let mut segments = Vec::new();
+                for segment in function.split('.') {
+                    if let Some(rest) = segment.strip_prefix("lambda$") {
+                        segments.extend(rest.split('$').next().map(str::to_string));
+                        continue;
+                    }
+                    segments.extend(
+                        segment
+                            .split('$')
+                            .filter(|p| {
+                                !p.is_empty()
+                                    && !p.chars().all(|c| c.is_ascii_digit())
+                                    && *p != "default"
+                            })
+                            .map(str::to_string),
+                    );
+                }
+                segments
+            }
+            TraceFormat::Node => {
+                let function = function
+                    .split(" [as ")
+                    .next()
+                    .unwrap_or(function)
+                    .trim_start_matches("async ")
+                    .trim_start_matches("new ");
+                function.split('.').map(str::to_string).collect()
+            }
+        };
+
+        // Constructors and anonymous frames (`<module>`, `<anonymous>`, `<init>`)
+        segments
+            .into_iter()
+            .filter(|s| !s.starts_with('<'))
+            .collect()
+    }
+
+    /// File name of the frame, if any
+    pub fn file_name(&self) -> Option<&str> {
+        let path = self.file_path.as_deref()?;
+        path.rsplit(['/', '\\']).next()
+    }
+}
+
+impl fmt::Display for StackFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.index)?;
+        if let Some(path) = &self.file_path {
+            write!(f, " {path}")?;
+            if let Some(line) = self.line {
+                write!(f, ":{line}")?;
+            }
+        }
+        if let Some(function) = &self.function {
+            write!(f, " in {function}")?;
+        }
+        write!(f, " [{}]", self.format)
+    }
+}
+
+fn is_go_closure(segment: &str) -> bool {
+    segment.chars().all(|c| c.is_ascii_digit())
+        || segment
+            .strip_prefix("func")
+            .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Rebuild `com/acme/Foo.java` from `com.acme.Foo.bar` and `Foo.java`
+fn jvm_file_path(function: &str, file: &str) -> String {
+    let function = function.rsplit('/').next().unwrap_or(function);
+    let package: Vec<&str> = function
+        .split('.')
+        .take_while(|s| s.chars().next().is_some_and(|c| c.is_lowercase()))
+        .collect();
+    if package.is_empty() {
+        file.to_string()
+    } else {
+        format!("{}/{file}", package.join("/"))
+    }
+}
+
+fn capture_u32(caps: &regex::Captures, name: &str) -> Option<u32> {
+    caps.name(name).and_then(|m| m.as_str().parse().ok())
+}
+
+fn capture_u16(caps: &regex::Captures, name: &str) -> Option<u16> {
+    caps.name(name).and_then(|m| m.as_str().parse().ok())
+}
+
+fn capture_string(caps: &regex::Captures, name: &str) -> Option<String> {
+    caps.name(name).map(|m| m.as_str().to_string())
+}
+
+/// Parse every recognised frame from pasted stack trace text.
+///
+/// Lines that are not frames (exception messages, `Caused by:`, source
+/// excerpts) are skipped, so several traces and formats may be mixed.
+pub fn parse_stack_trace(text: &str) -> Vec<StackFrame> {
+    let mut frames: Vec<StackFrame> = Vec::new();
+    // A Rust or Go function line waiting for its location line
+    let mut pending: Option<(TraceFormat, String)> = None;
+
+    let mut push = |format, function, file_path, line, column| {
+        frames.push(StackFrame {
+            index: 0,
+            format,
+            function,
+            file_path,
+            line,
+            column,
+        });
+    };
+
+    for text_line in text.lines() {
+        let text_line = text_line.trim_end();
+
+        if let Some((format, function)) = pending.take() {
+            let location = match format {
+                TraceFormat::Rust => RUST_LOCATION.captures(text_line),
+                _ => GO_LOCATION.captures(text_line),
+            };
+            if let Some(caps) = location {
+                push(
+                    format,
+                    Some(function),
+                    capture_string(&caps, "file"),
+                    capture_u32(&caps, "line"),
+                    capture_u16(&caps, "col"),
+                );
+                continue;
+            }
+            // Rust backtraces omit locations for frames without debug info;
+            // a Go function line without a location is not a frame
+            if format == TraceFormat::Rust {
+                push(format, Some(function), None, None, None);
+            }
+        }
+
+        if let Some(caps) = PYTHON_FRAME.captures(text_line) {
+            push(
+                TraceFormat::Python,
+                capture_string(&caps, "func"),
+                capture_string(&caps, "file"),
+                capture_u32(&caps, "line"),
+                None,
+            );
+        } else if let Some(caps) = RUST_PANIC.captures(text_line) {
+            push(
+                TraceFormat::Rust,
+                None,
+                capture_string(&caps, "file"),
+                capture_u32(&caps, "line"),
+                capture_u16(&caps, "col"),
+            );
+        } else if let Some(caps) = JVM_FRAME.captures(text_line) {
+            let function = capture_string(&caps, "func").unwrap_or_default();
+            let file_path = caps
+                .name("file")
+                .map(|file| jvm_file_path(&function, file.as_str()));
+            push(
+                TraceFormat::Jvm,
+                Some(function),
+                file_path,
+                capture_u32(&caps, "line"),
+                None,
+            );
+        } else if let Some(caps) = NODE_FRAME.captures(text_line) {
+            let file_path =
+                capture_string(&caps, "file").map(|f| f.trim_start_matches("file://").to_string());
+            push(
+                TraceFormat::Node,
+                capture_string(&caps, "func"),
+                file_path,
+                capture_u32(&caps, "line"),
+                capture_u16(&caps, "col"),
+            );
+        } else if let Some(caps) = RUST_FRAME.captures(text_line) {
+            pending = capture_string(&caps, "func").map(|f| (TraceFormat::Rust, f));
+        } else if let Some(caps) = GO_FUNC.captures(text_line) {
+            // Go function lines look like `pkg.Func(...)`; require a package qualifier
+            pending = capture_string(&caps, "func")
+                .filter(|f| f.contains('.'))
+                .map(|f| (TraceFormat::Go, f));
+        }
+    }
+    if let Some((TraceFormat::Rust, function)) = pending {
+        push(TraceFormat::Rust, Some(function), None, None, None);
+    }
+
+    for (index, frame) in frames.iter_mut().enumerate() {
+        frame.index = index;
+    }
+    frames
+}
+
+/// Pick the indexed file a trace path refers to.
+///
+/// Traces carry absolute paths from other machines, container paths or bare
+/// file names, so paths are compared component-wise from the end. Returns the
+/// unique indexed path sharing the longest suffix, and whether every component
+/// of the trace path matched.
+pub fn match_trace_path<'a>(path: &str, indexed: &'a [PathBuf]) -> Option<(&'a Path, bool)> {
+    let wanted: Vec<&str> = path
+        .split(['/', '\\'])
+        .filter(|c| !c.is_empty() && *c != ".")
+        .collect();
+
+    let mut best: Option<(&Path, usize)> = None;
+    let mut tied = false;
+    for candidate in indexed {
+        let matched = candidate
+            .components()
+            .rev()
+            .zip(wanted.iter().rev())
+            .take_while(|(c, w)| c.as_os_str() == **w)
+            .count();
+        if matched == 0 {
+            continue;
+        }
+        match best {
+            Some((_, best_matched)) if matched < best_matched => {}
+            Some((_, best_matched)) if matched == best_matched => tied = true,
+            _ => {
+                best = Some((candidate.as_path(), matched));
+                tied = false;
+            }
+        }
+    }
+
+    match best {
+        Some((path, matched)) if !tied => Some((path, matched == wanted.len())),
+        _ => None,
+    }
+}
+
+/// Unqualified part of a symbol name (`Shape.area` -> `area`)
+pub(crate) fn short_name(name: &str) -> &str {
+    name.rsplit(['.', ':']).next().unwrap_or(name)
+}
+
+/// Whether a symbol kind can appear as a stack frame
+pub(crate) fn is_frame_kind(kind: SymbolKind) -> bool {
+    matches!(
+        kind,
+        SymbolKind::Function
+            | SymbolKind::Method
+            | SymbolKind::Macro
+            | SymbolKind::Class
+            | SymbolKind::Struct
+    )
+}
+
+/// How well a symbol matches the qualifier segments of a frame function.
+///
+/// Counts qualifiers found in the module path, file path, enclosing type
+/// (`parent`) or scope parent, plus one when the file name agrees.
+pub(crate) fn qualifier_score(
+    symbol: &Symbol,
+    qualifiers: &[String],
+    parent: Option<&str>,
+    frame_file: Option<&str>,
+) -> usize {
+    let mut tokens: Vec<&str> = Vec::new();
+    if let Some(module_path) = symbol.module_path.as_deref() {
+        tokens.extend(module_path.split([':', '.']).filter(|s| !s.is_empty()));
+    }
+    tokens.extend(
+        symbol
+            .file_path
+            .split(['/', '\\'])
+            .map(|c| c.split('.').next().unwrap_or(c)),
+    );
+    tokens.extend(parent);
+    if let Some(ScopeContext::Local {
+        parent_name: Some(parent_name),
+        ..
+    }) = &symbol.scope_context
+    {
+        tokens.push(parent_name);
+    }
+
+    let found = qualifiers
+        .iter()
+        .filter(|q| tokens.contains(&q.as_str()))
+        .count();
+    let same_file =
+        frame_file.is_some_and(|name| symbol.file_path.rsplit(['/', '\\']).next() == Some(name));
+    found + usize::from(same_file)
+}
+
+/// How a frame was mapped to a symbol
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FrameMatch {
+    /// File and line fall inside the symbol's range
+    Position,
+    /// Qualified function name matched an indexed symbol
+    Name,
+}
+
+/// A function calling a frame's symbol
+#[derive(Debug, Clone, Serialize)]
+pub struct FrameCaller {
+    pub symbol_id: u32,
+    pub name: String,
+    pub kind: SymbolKind,
+    pub file_path: String,
+    /// One-based start line of the caller
+    pub line: u32,
+}
+
+/// A stack frame with the indexed symbol it maps to
+#[derive(Debug, Clone, Serialize)]
+pub struct ResolvedFrame {
+    #[serde(flatten)]
+    pub frame: StackFrame,
+    /// False for frames in libraries, the runtime or unindexed files
+    pub in_index: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matched_by: Option<FrameMatch>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<Symbol>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub callers: Vec<FrameCaller>,
+    /// Equally good name matches when the frame is ambiguous
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub candidates: Vec<u32>,
+}
+
+impl fmt::Display for ResolvedFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.frame)?;
+
+        let Some(symbol) = &self.symbol else {
+            if self.candidates.is_empty() {
+                return write!(f, " (outside index)");
+            }
+            let ids: Vec<String> = self
+                .candidates
+                .iter()
+                .map(|id| format!("symbol_id:{id}"))
+                .collect();
+            return write!(f, " (ambiguous: {})", ids.join(", "));
+        };
+
+        let matched_by = match self.matched_by {
+            Some(FrameMatch::Name) => "by name",
+            _ => "by position",
+        };
+        write!(
+            f,
+            "\n   {:?} {} at {}:{}-{} [symbol_id:{}] ({matched_by})",
+            symbol.kind,
+            symbol.name,
+            symbol.file_path,
+            symbol.range.start_line + 1,
+            symbol.range.end_line + 1,
+            symbol.id.value()
+        )?;
+        if let Some(sig) = &symbol.signature {
+            let sig = sig.lines().next().unwrap_or_default();
+            write!(f, "\n   Signature: {sig}")?;
+        }
+        if let Some(doc) = symbol.doc_comment.as_deref().and_then(|d| d.lines().next()) {
+            write!(f, "\n   Doc: {doc}")?;
+        }
+        if !self.callers.is_empty() {
+            let callers: Vec<String> = self
+                .callers
+                .iter()
+                .map(|c| format!("{} [symbol_id:{}]", c.name, c.symbol_id))
+                .collect();
+            write!(f, "\n   Called by: {}", callers.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn locations(frames: &[StackFrame]) -> Vec<(Option<&str>, Option<u32>)> {
+        frames
+            .iter()
+            .map(|f| (f.file_path.as_deref(), f.line))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_python_and_node() {
+        let python = r#"Traceback (most recent call last):
+  File "/srv/app/main.py", line 10, in <module>
+    run()
+  File "/srv/app/app/service.py", line 42, in handle
+    raise ValueError("bad")
+ValueError: bad"#;
+        let frames = parse_stack_trace(python);
+        assert_eq!(
+            locations(&frames),
+            vec![
+                (Some("/srv/app/main.py"), Some(10)),
+                (Some("/srv/app/app/service.py"), Some(42))
+            ]
+        );
+        assert!(frames[0].name_segments().is_empty());
+        assert_eq!(frames[1].name_segments(), vec!["handle"]);
+
+        let node = "TypeError: x is undefined\n    at Parser.parse (/app/src/parser.js:12:7)\n    at async main (file:///app/src/index.js:3:1)\n    at /app/src/anon.js:1:2";
+        let frames = parse_stack_trace(node);
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[0].format, TraceFormat::Node);
+        assert_eq!(frames[0].name_segments(), vec!["Parser", "parse"]);
+        assert_eq!(frames[0].column, Some(7));
+        assert_eq!(frames[1].file_path.as_deref(), Some("/app/src/index.js"));
+        assert_eq!(frames[1].name_segments(), vec!["main"]);
+        assert_eq!(frames[2].function, None);
+    }
+
+    #[test]
+    fn test_parse_rust_backtrace() {
+        let rust = "thread 'main' panicked at src/parser.rs:88:14:\nindex out of bounds\nstack backtrace:\n   0: rust_begin_unwind\n             at /rustc/abc/library/std/src/panicking.rs:645:5\n   1: core::panicking::panic_fmt\n   2: <app::Parser as app::Parse>::parse::h0123456789abcdef\n             at ./src/parser.rs:88:14\n   3: app::main::{{closure}}\n             at ./src/main.rs:5:9";
+        let frames = parse_stack_trace(rust);
+        assert_eq!(
+            locations(&frames),
+            vec![
+                (Some("src/parser.rs"), Some(88)),
+                (Some("/rustc/abc/library/std/src/panicking.rs"), Some(645)),
+                (None, None),
+                (Some("./src/parser.rs"), Some(88)),
+                (Some("./src/main.rs"), Some(5)),
+            ]
+        );
+        assert!(frames.iter().all(|f| f.format == TraceFormat::Rust));
+        assert_eq!(frames[3].name_segments(), vec!["app", "Parser", "parse"]);
+        assert_eq!(frames[4].name_segments(), vec!["app", "main"]);
+        assert_eq!(frames[4].index, 4);
+    }
+
+    #[test]
+    fn test_parse_go_and_jvm() {
+        let go = "panic: boom\n\ngoroutine 1 [running]:\nexample.com/app/server.(*Server).Handle.func1(0xc000010000)\n\t/home/dev/app/server/server.go:42 +0x1d\nmain.main()\n\t/home/dev/app/main.go:9 +0x25\nexit status 2";
+        let frames = parse_stack_trace(go);
+        assert_eq!(
+            locations(&frames),
+            vec![
+                (Some("/home/dev/app/server/server.go"), Some(42)),
+                (Some("/home/dev/app/main.go"), Some(9))
+            ]
+        );
+        assert_eq!(
+            frames[0].name_segments(),
+            vec!["server", "Server", "Handle"]
+        );
+
+        let jvm = "java.lang.IllegalStateException: boom\n\tat com.acme.service.OrderService.lambda$place$0(OrderService.java:57)\n\tat com.acme.UtilsKt.helper$default(Utils.kt:12)\n\tat java.base/java.lang.Thread.run(Thread.java:833)\n\tat com.acme.Native.call(Native Method)\nCaused by: java.io.IOException\n\t... 3 more";
+        let frames = parse_stack_trace(jvm);
+        assert_eq!(
+            locations(&frames),
+            vec![
+                (Some("com/acme/service/OrderService.java"), Some(57)),
+                (Some("com/acme/Utils.kt"), Some(12)),
+                (Some("java/lang/Thread.java"), Some(833)),
+                (None, None),
+            ]
+        );
+        assert_eq!(
+            frames[0].name_segments(),
+            vec!["com", "acme", "service", "OrderService", "place"]
+        );
+        assert_eq!(frames[1].name_segments().last().unwrap(), "helper");
+    }
+
+    #[test]
+    fn test_match_trace_path() {
+        let indexed = vec![
+            PathBuf::from("src/main.rs"),
+            PathBuf::from("src/parser/mod.rs"),
+            PathBuf::from("tools/gen/mod.rs"),
+            PathBuf::from("app/src/main/java/com/acme/service/OrderService.java"),
+        ];
+
+        assert_eq!(
+            match_trace_path("/home/ci/build/src/main.rs", &indexed),
+            Some((Path::new("src/main.rs"), false))
+        );
+        assert_eq!(
+            match_trace_path("./src/parser/mod.rs", &indexed),
+            Some((Path::new("src/parser/mod.rs"), true))
+        );
+        assert_eq!(
+            match_trace_path("com/acme/service/OrderService.java", &indexed).map(|(p, _)| p),
+            Some(Path::new(
+                "app/src/main/java/com/acme/service/OrderService.java"
+            ))
+        );
+        // Two files share the best suffix
+        assert_eq!(match_trace_path("mod.rs", &indexed), None);
+        assert_eq!(match_trace_path("lib/other.rs", &indexed), None);
+    }
+}
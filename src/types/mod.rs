@@ -19,8 +19,10 @@
 //! assert!(range.contains(12, 10));
 //! ```
 
+mod stable_id;
 mod symbol_counter;
 
+pub use stable_id::{StableIdAssigner, StableSymbolId, SymbolRef};
 pub use symbol_counter::SymbolCounter;
 
 use serde::{Deserialize, Serialize};
@@ -179,6 +181,8 @@ pub enum SymbolKind {
     TypeAlias,
     /// マクロ
     Macro,
+    /// HTTP ルート（例: `POST /orders`）
+    Route,
 }
 
 impl SymbolId {
@@ -257,6 +261,7 @@ impl FromStr for SymbolKind {
             "Parameter" => Ok(SymbolKind::Parameter),
             "TypeAlias" => Ok(SymbolKind::TypeAlias),
             "Macro" => Ok(SymbolKind::Macro),
+            "Route" => Ok(SymbolKind::Route),
             _ => Err("Unknown symbol kind"),
         }
     }
@@ -338,9 +343,10 @@ mod tests {
             SymbolKind::Parameter,
             SymbolKind::TypeAlias,
             SymbolKind::Macro,
+            SymbolKind::Route,
         ];
 
-        assert_eq!(kinds.len(), 14);
+        assert_eq!(kinds.len(), 15);
     }
 
     #[test]
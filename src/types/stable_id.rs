@@ -0,0 +1,285 @@
+//! Stable symbol identifiers that survive a reindex.
+//!
+//! [`SymbolId`] is handed out by a counter in indexing order, so the same
+//! function gets another number after `codanna index --force`. A
+//! [`StableSymbolId`] is derived from what names the definition instead:
+//! language, module path, name, kind, and a disambiguator for definitions that
+//! share all four, such as two `new` methods in one Rust module. The
+//! disambiguator is the definition's position among those, in source order.
+//!
+//! The ID reads `<language>.<hash>`, e.g. `rust.4f1c9e0a7b3d2c58`, where the
+//! hash is the first 64 bits of a SHA-256 over the five parts.
+
+use super::{SymbolId, SymbolKind};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// Hex digits of the hash part
+const HASH_LEN: usize = 16;
+
+/// Identifier of a symbol derived from its definition rather than indexing order
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct StableSymbolId(Box<str>);
+
+impl StableSymbolId {
+    /// Derive the ID of a definition
+    ///
+    /// `module_path` is the file path for languages without module paths.
+    /// `disambiguator` counts the earlier definitions in the same file that
+    /// share the other four parts.
+    pub fn derive(
+        language: &str,
+        module_path: &str,
+        name: &str,
+        kind: SymbolKind,
+        disambiguator: u32,
+    ) -> Self {
+        let kind = format!("{kind:?}");
+        let disambiguator = disambiguator.to_string();
+        let mut hasher = Sha256::new();
+        for part in [language, module_path, name, &kind, &disambiguator] {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        let hash: String = hasher.finalize()[..HASH_LEN / 2]
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+        let language = if language.is_empty() {
+            "unknown"
+        } else {
+            language
+        };
+        Self(format!("{language}.{hash}").into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for StableSymbolId {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (language, hash) = s
+            .rsplit_once('.')
+            .ok_or("Stable symbol ID must look like <language>.<hash>")?;
+        let valid_language = !language.is_empty()
+            && language
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        let valid_hash =
+            hash.len() == HASH_LEN && hash.chars().all(|c| matches!(c, '0'..='9' | 'a'..='f'));
+        if valid_language && valid_hash {
+            Ok(Self(s.into()))
+        } else {
+            Err("Stable symbol ID must look like <language>.<hash>")
+        }
+    }
+}
+
+impl TryFrom<String> for StableSymbolId {
+    type Error = &'static str;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<StableSymbolId> for String {
+    fn from(id: StableSymbolId) -> Self {
+        id.0.into()
+    }
+}
+
+impl fmt::Display for StableSymbolId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Hands out stable IDs to the definitions of one file
+///
+/// Feed the definitions in source order so the disambiguators come out the
+/// same on every reindex.
+#[derive(Debug, Default)]
+pub struct StableIdAssigner {
+    seen: HashMap<StableSymbolId, u32>,
+}
+
+impl StableIdAssigner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stable ID of the next definition with these parts
+    pub fn assign(
+        &mut self,
+        language: &str,
+        module_path: &str,
+        name: &str,
+        kind: SymbolKind,
+    ) -> StableSymbolId {
+        let first = StableSymbolId::derive(language, module_path, name, kind, 0);
+        let count = self.seen.entry(first.clone()).or_insert(0);
+        let disambiguator = *count;
+        *count += 1;
+        if disambiguator == 0 {
+            first
+        } else {
+            StableSymbolId::derive(language, module_path, name, kind, disambiguator)
+        }
+    }
+}
+
+/// A symbol as named after `symbol_id:`, by numeric or stable ID
+///
+/// Numeric IDs only hold within one index; stable IDs keep naming the same
+/// definition after a reindex.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SymbolRef {
+    Id(SymbolId),
+    Stable(StableSymbolId),
+}
+
+impl FromStr for SymbolRef {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let s = s.strip_prefix("symbol_id:").unwrap_or(s);
+        if let Ok(id) = s.parse::<u32>() {
+            return Ok(Self::Id(SymbolId(id)));
+        }
+        s.parse()
+            .map(Self::Stable)
+            .map_err(|_| "symbol_id must be a number or a stable ID like rust.4f1c9e0a7b3d2c58")
+    }
+}
+
+impl From<SymbolId> for SymbolRef {
+    fn from(id: SymbolId) -> Self {
+        Self::Id(id)
+    }
+}
+
+impl From<StableSymbolId> for SymbolRef {
+    fn from(id: StableSymbolId) -> Self {
+        Self::Stable(id)
+    }
+}
+
+impl fmt::Display for SymbolRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Id(id) => write!(f, "{}", id.value()),
+            Self::Stable(id) => write!(f, "{id}"),
+        }
+    }
+}
+
+impl Serialize for SymbolRef {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Id(id) => serializer.serialize_u32(id.value()),
+            Self::Stable(id) => serializer.serialize_str(id.as_str()),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SymbolRef {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Id(u32),
+            Text(String),
+        }
+
+        match Raw::deserialize(deserializer)? {
+            Raw::Id(id) => Ok(Self::Id(SymbolId(id))),
+            Raw::Text(text) => text.parse().map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_derive_is_deterministic_and_parses_back() {
+        let id = StableSymbolId::derive(
+            "rust",
+            "crate::config::load",
+            "load",
+            SymbolKind::Function,
+            0,
+        );
+        assert_eq!(
+            id,
+            StableSymbolId::derive(
+                "rust",
+                "crate::config::load",
+                "load",
+                SymbolKind::Function,
+                0
+            )
+        );
+        assert!(id.as_str().starts_with("rust."));
+        assert_eq!(id.as_str().parse::<StableSymbolId>().unwrap(), id);
+
+        let method =
+            StableSymbolId::derive("rust", "crate::config::load", "load", SymbolKind::Method, 0);
+        assert_ne!(id, method);
+        assert!("rust.xyz".parse::<StableSymbolId>().is_err());
+        assert!("4f1c9e0a7b3d2c58".parse::<StableSymbolId>().is_err());
+    }
+
+    #[test]
+    fn test_assigner_disambiguates_in_order() {
+        let mut assigner = StableIdAssigner::new();
+        let first = assigner.assign("rust", "crate::shapes::new", "new", SymbolKind::Method);
+        let second = assigner.assign("rust", "crate::shapes::new", "new", SymbolKind::Method);
+        assert_ne!(first, second);
+        assert_eq!(
+            first,
+            StableSymbolId::derive("rust", "crate::shapes::new", "new", SymbolKind::Method, 0)
+        );
+        assert_eq!(
+            second,
+            StableSymbolId::derive("rust", "crate::shapes::new", "new", SymbolKind::Method, 1)
+        );
+    }
+
+    #[test]
+    fn test_symbol_ref_accepts_numeric_and_stable_ids() {
+        assert_eq!(
+            "symbol_id:1771".parse::<SymbolRef>().unwrap(),
+            SymbolRef::Id(SymbolId(1771))
+        );
+        let stable = StableSymbolId::derive("python", "app.models", "User", SymbolKind::Class, 0);
+        assert_eq!(
+            stable.as_str().parse::<SymbolRef>().unwrap(),
+            SymbolRef::Stable(stable.clone())
+        );
+        assert!("parse_file".parse::<SymbolRef>().is_err());
+
+        let refs: Vec<SymbolRef> =
+            serde_json::from_str(&format!("[12, \"34\", \"{stable}\"]")).unwrap();
+        assert_eq!(
+            refs,
+            vec![
+                SymbolRef::Id(SymbolId(12)),
+                SymbolRef::Id(SymbolId(34)),
+                SymbolRef::Stable(stable)
+            ]
+        );
+        assert_eq!(serde_json::to_string(&refs[0]).unwrap(), "12");
+    }
+}
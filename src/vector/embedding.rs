@@ -527,6 +527,7 @@ pub fn create_symbol_text(
         crate::types::SymbolKind::Constant => "constant",
         crate::types::SymbolKind::Module => "module",
         crate::types::SymbolKind::Macro => "macro",
+        crate::types::SymbolKind::Route => "route",
         crate::types::SymbolKind::Interface => "interface",
         crate::types::SymbolKind::Class => "class",
         crate::types::SymbolKind::Field => "field",
@@ -16,6 +16,7 @@
 //! - Memory usage: 4 bytes per dimension per vector
 //! - Startup time: <1ms (mmap is lazy-loaded by OS)
 
+use std::collections::HashMap;
 use std::fs::{File, OpenOptions};
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
@@ -326,6 +327,59 @@ impl MmapVectorStorage {
         Ok(vectors)
     }
 
+    /// Rewrites the storage file without the vectors `remap` drops.
+    ///
+    /// `remap` returns the ID a vector is stored under afterwards, or `None` to
+    /// drop it. Entries that end up with the same ID collapse into the one
+    /// written last, the one callers collecting [`Self::read_all_vectors`] into
+    /// a map keep. The new file replaces the old one once it is complete.
+    ///
+    /// Returns the number of vectors kept.
+    pub fn compact(
+        &mut self,
+        mut remap: impl FnMut(VectorId) -> Option<VectorId>,
+    ) -> Result<usize, VectorStorageError> {
+        if !self.exists() {
+            return Ok(0);
+        }
+
+        let mut slots: HashMap<VectorId, usize> = HashMap::new();
+        let mut kept: Vec<(VectorId, Vec<f32>)> = Vec::new();
+        for (id, vector) in self.read_all_vectors()? {
+            let Some(id) = remap(id) else {
+                continue;
+            };
+            match slots.get(&id) {
+                Some(&slot) => kept[slot].1 = vector,
+                None => {
+                    slots.insert(id, kept.len());
+                    kept.push((id, vector));
+                }
+            }
+        }
+        self.invalidate_cache();
+
+        let mut rewritten = Self {
+            path: self.path.with_extension("vec.compact"),
+            mmap: None,
+            dimension: self.dimension,
+            vector_count: 0,
+            segment: self.segment,
+        };
+        let written = rewritten
+            .initialize()
+            .and_then(|()| rewritten.append_vectors(&kept))
+            .and_then(|()| rewritten.update_metadata(kept.len()))
+            .and_then(|()| Ok(std::fs::rename(&rewritten.path, &self.path)?));
+        if let Err(e) = written {
+            let _ = std::fs::remove_file(&rewritten.path);
+            return Err(e);
+        }
+
+        self.vector_count = kept.len();
+        Ok(kept.len())
+    }
+
     /// Returns the number of vectors stored.
     #[must_use]
     pub fn vector_count(&self) -> usize {
@@ -603,6 +657,45 @@ mod tests {
         assert!(storage.write_batch(&wrong_vectors).is_err());
     }
 
+    #[test]
+    fn test_compact_drops_and_remaps_vectors() {
+        let temp_dir = TempDir::new().unwrap();
+        let dimension = VectorDimension::new(2).unwrap();
+        let mut storage =
+            MmapVectorStorage::open_or_create(temp_dir.path(), SegmentOrdinal::new(0), dimension)
+                .unwrap();
+
+        let id = |n| VectorId::new(n).unwrap();
+        storage
+            .write_batch(&[
+                (id(1), &[1.0, 1.0]),
+                (id(2), &[2.0, 2.0]),
+                (id(3), &[3.0, 3.0]),
+            ])
+            .unwrap();
+        // A later write of the same ID supersedes the first one
+        storage.write_batch(&[(id(1), &[1.5, 1.5])]).unwrap();
+        let size_before = storage.file_size().unwrap();
+
+        let kept = storage
+            .compact(|vector_id| match vector_id.get() {
+                2 => None,
+                3 => Some(id(20)),
+                _ => Some(vector_id),
+            })
+            .unwrap();
+        assert_eq!(kept, 2);
+        assert!(storage.file_size().unwrap() < size_before);
+
+        let mut reopened =
+            MmapVectorStorage::open(temp_dir.path(), SegmentOrdinal::new(0)).unwrap();
+        assert_eq!(reopened.vector_count(), 2);
+        assert_eq!(reopened.read_vector(id(1)), Some(vec![1.5, 1.5]));
+        assert_eq!(reopened.read_vector(id(2)), None);
+        assert_eq!(reopened.read_vector(id(20)), Some(vec![3.0, 3.0]));
+        assert!(!temp_dir.path().join("segment_0.vec.compact").exists());
+    }
+
     #[test]
     fn test_persistence_across_instances() {
         let temp_dir = TempDir::new().unwrap();
@@ -1,2 +1,25 @@
 // This module contains common test utilities
-// Currently empty as unused infrastructure was removed
+// Each test crate that includes it uses only some of them
+#![allow(dead_code)]
+
+use codanna::SimpleIndexer;
+use codanna::config::Settings;
+use std::path::Path;
+use std::sync::Arc;
+use tempfile::TempDir;
+
+/// Index every file under the workspace `root`, keeping the index in a
+/// temporary directory that lives as long as the returned guard
+pub fn index_workspace(root: &Path) -> (TempDir, SimpleIndexer) {
+    let index_dir = TempDir::new().unwrap();
+    let settings = Settings {
+        workspace_root: Some(root.to_path_buf()),
+        index_path: index_dir.path().join("index"),
+        ..Default::default()
+    };
+    let mut indexer = SimpleIndexer::with_settings(Arc::new(settings));
+    indexer
+        .index_directory(root, false, false)
+        .expect("Failed to index directory");
+    (index_dir, indexer)
+}
@@ -1,10 +1,10 @@
 use std::sync::Arc;
 
-use codanna::SimpleIndexer;
 use codanna::config::{SemanticSearchConfig, Settings};
 use codanna::mcp::{
     AnalyzeImpactRequest, CodeIntelligenceServer, SemanticSearchWithContextRequest,
 };
+use codanna::{SimpleIndexer, SymbolRef};
 use rmcp::handler::server::wrapper::Parameters;
 use rmcp::model::RawContent;
 use tempfile::TempDir;
@@ -90,7 +90,7 @@ async fn test_gdscript_semantic_search_and_analyze_impact() {
         .split("[symbol_id:")
         .nth(1)
         .and_then(|rest| rest.split(']').next())
-        .and_then(|digits| digits.parse::<u32>().ok())
+        .and_then(|id| id.parse::<SymbolRef>().ok())
         .expect("semantic output should expose symbol_id for apply_damage");
 
     let impact_result = server
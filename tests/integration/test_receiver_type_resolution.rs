@@ -0,0 +1,116 @@
+use crate::common::index_workspace;
+use codanna::SimpleIndexer;
+use std::path::Path;
+use tempfile::TempDir;
+
+/// `(name, line)` of every function `caller` calls, 1-based lines
+fn callees(indexer: &SimpleIndexer, caller: &str, language: &str) -> Vec<(String, u32)> {
+    let symbols = indexer.find_symbols_by_name(caller, Some(language));
+    assert_eq!(symbols.len(), 1, "expected one '{caller}' symbol");
+    let mut callees: Vec<_> = indexer
+        .get_called_functions(symbols[0].id)
+        .into_iter()
+        .map(|s| (s.name.to_string(), s.range.start_line + 1))
+        .collect();
+    callees.sort();
+    callees.dedup();
+    callees
+}
+
+/// Method calls resolve through the receiver's inferred type, not the first
+/// method with a matching name
+///
+/// ```python
+/// doc = Document()
+/// doc.save()        # Document.save, not Invoice.save
+/// receipt = Receipt()
+/// receipt.total()   # inherited from Invoice
+/// ```
+#[test]
+fn test_python_receiver_type_picks_method() {
+    let code = r#"class Invoice:
+    def save(self):
+        return "invoice"
+
+    def total(self):
+        return 0
+
+
+class Document:
+    def save(self):
+        return "document"
+
+
+class Receipt(Invoice):
+    def refund(self):
+        return self.total()
+
+
+def archive():
+    doc = Document()
+    doc.save()
+
+
+def bill():
+    invoice = Invoice()
+    invoice.save()
+
+
+def settle():
+    receipt = Receipt()
+    receipt.total()
+"#;
+
+    let workspace = TempDir::new().unwrap();
+    std::fs::write(workspace.path().join("models.py"), code).unwrap();
+    let (_index_dir, indexer) = index_workspace(workspace.path());
+
+    let archive = callees(&indexer, "archive", "python");
+    assert!(archive.contains(&("Document.save".to_string(), 10)));
+    assert!(!archive.contains(&("Invoice.save".to_string(), 2)));
+
+    let bill = callees(&indexer, "bill", "python");
+    assert!(bill.contains(&("Invoice.save".to_string(), 2)));
+    assert!(!bill.contains(&("Document.save".to_string(), 10)));
+
+    assert!(callees(&indexer, "settle", "python").contains(&("Invoice.total".to_string(), 5)));
+    assert_eq!(
+        callees(&indexer, "Receipt.refund", "python"),
+        vec![("Invoice.total".to_string(), 5)]
+    );
+}
+
+/// `self.method()` and calls on typed locals in the Rust multi-module
+/// fixture resolve to the method of the receiver's type
+#[test]
+fn test_rust_fixture_receiver_calls() {
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples/rust/multi_module");
+    let workspace = TempDir::new().unwrap();
+    copy_dir(&fixture, workspace.path());
+    let (_index_dir, indexer) = index_workspace(workspace.path());
+
+    // self.validate_user(user) in AuthService::register_user
+    assert!(
+        callees(&indexer, "register_user", "rust").contains(&("validate_user".to_string(), 85))
+    );
+    // let db = DatabaseConnection::new(..); db.connect()
+    assert!(
+        callees(&indexer, "test_database_connection", "rust")
+            .contains(&("connect".to_string(), 29))
+    );
+    // processor.process_batch(..) -> self.process(..)
+    assert!(callees(&indexer, "process_batch", "rust").contains(&("process".to_string(), 66)));
+}
+
+fn copy_dir(from: &Path, to: &Path) {
+    for entry in std::fs::read_dir(from).unwrap() {
+        let entry = entry.unwrap();
+        let target = to.join(entry.file_name());
+        if entry.file_type().unwrap().is_dir() {
+            std::fs::create_dir_all(&target).unwrap();
+            copy_dir(&entry.path(), &target);
+        } else {
+            std::fs::copy(entry.path(), target).unwrap();
+        }
+    }
+}
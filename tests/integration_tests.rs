@@ -4,6 +4,10 @@
 // Re-export the integration test modules
 // Each test file in integration/ needs to be included here
 
+// Include the common test utilities
+#[path = "common/mod.rs"]
+mod common;
+
 #[path = "integration/test_mcp_schema.rs"]
 mod test_mcp_schema;
 
@@ -51,3 +55,6 @@ mod test_gdscript_mcp;
 
 #[path = "integration/test_kotlin_semantic_search.rs"]
 mod test_kotlin_semantic_search;
+
+#[path = "integration/test_receiver_type_resolution.rs"]
+mod test_receiver_type_resolution;